@@ -0,0 +1,47 @@
+use std::path::{Path, PathBuf};
+
+use codeowners::runner::{RunConfig, teams_for_files_from_codeowners};
+use criterion::{Criterion, criterion_group, criterion_main};
+
+const GITHUB_TEAMS: [&str; 3] = ["@PaymentsTeam", "@PayrollTeam", "@UX"];
+
+/// A generated CODEOWNERS file with `sections` shuffled sections of `lines_per_section` lines, some disabled.
+fn generated_codeowners_file(sections: usize, lines_per_section: usize) -> String {
+    let mut file = String::from("# STOP! - DO NOT EDIT THIS FILE MANUALLY\n#\n# https://help.github.com/en/articles/about-code-owners\n\n");
+    for section in 0..sections {
+        file.push_str(&format!("\n# Section {}\n", section));
+        for line in 0..lines_per_section {
+            // Visit the lines out of order so each section needs sorting
+            let index = (line * 7919) % lines_per_section;
+            let disabled = if index % 13 == 0 { "# " } else { "" };
+            let team = GITHUB_TEAMS[index % GITHUB_TEAMS.len()];
+            file.push_str(&format!("{}/packs/p{}/dir{}/**/** {}\n", disabled, section, index, team));
+        }
+    }
+    file
+}
+
+fn codeowners_parser(c: &mut Criterion) {
+    let project_root = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/valid_project");
+    let codeowners_file = tempfile::NamedTempFile::new().unwrap();
+    std::fs::write(codeowners_file.path(), generated_codeowners_file(10, 15_000)).unwrap();
+    let run_config = RunConfig {
+        config_path: project_root.join("config/code_ownership.yml"),
+        project_root,
+        codeowners_file_path: Some(PathBuf::from(codeowners_file.path())),
+        no_cache: true,
+        ..Default::default()
+    };
+    let file_paths = vec!["packs/p3/dir42/app/models/thing.rb".to_string(), "unowned/file.rb".to_string()];
+
+    c.bench_function("teams_for_files_from_codeowners on 150k lines", |b| {
+        b.iter(|| teams_for_files_from_codeowners(&run_config, &file_paths).unwrap())
+    });
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().sample_size(10);
+    targets = codeowners_parser
+}
+criterion_main!(benches);
@@ -8,7 +8,7 @@ use std::{
     sync::Mutex,
 };
 
-use super::{Caching, FileOwnerCacheEntry};
+use super::{Caching, FileOwnerCacheEntry, ResolvedOwners};
 
 #[derive(Debug)]
 pub struct GlobalCache {
@@ -38,7 +38,23 @@ impl Caching for GlobalCache {
             && let Ok(mut cache) = cache_mutex.lock()
             && let Ok(timestamp) = get_file_timestamp(path)
         {
-            cache.insert(path.to_path_buf(), FileOwnerCacheEntry { timestamp, owner });
+            cache.insert(
+                path.to_path_buf(),
+                FileOwnerCacheEntry {
+                    timestamp,
+                    owner,
+                    resolved_owners: None,
+                },
+            );
+        }
+    }
+
+    fn write_resolved_owners(&self, path: &Path, resolved_owners: ResolvedOwners) {
+        if let Some(cache_mutex) = self.file_owner_cache.as_ref()
+            && let Ok(mut cache) = cache_mutex.lock()
+            && let Some(entry) = cache.get_mut(path)
+        {
+            entry.resolved_owners = Some(resolved_owners);
         }
     }
 
@@ -65,6 +81,13 @@ impl Caching for GlobalCache {
         tracing::debug!("Deleting cache file: {}", cache_path.display());
         fs::remove_file(cache_path).change_context(Error::Io)
     }
+
+    fn entry_count(&self) -> usize {
+        self.file_owner_cache
+            .as_ref()
+            .and_then(|cache_mutex| cache_mutex.lock().ok().map(|cache| cache.len()))
+            .unwrap_or(0)
+    }
 }
 
 impl GlobalCache {
@@ -117,6 +140,7 @@ mod tests {
     use tempfile::tempdir;
 
     use super::*;
+    use crate::ownership::{file_owner_finder::Owner, mapper::Source};
 
     #[test]
     fn test_cache_dir() -> Result<(), Error> {
@@ -137,7 +161,8 @@ mod tests {
             cache_entry,
             Some(FileOwnerCacheEntry {
                 timestamp,
-                owner: Some("owner 1".to_owned())
+                owner: Some("owner 1".to_owned()),
+                resolved_owners: None,
             })
         );
 
@@ -151,7 +176,8 @@ mod tests {
             cache_entry,
             Some(FileOwnerCacheEntry {
                 timestamp,
-                owner: Some("owner 1".to_owned())
+                owner: Some("owner 1".to_owned()),
+                resolved_owners: None,
             })
         );
 
@@ -161,6 +187,37 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_resolved_owners() -> Result<(), Error> {
+        let temp_dir = tempdir().change_context(Error::Io)?;
+        let cache_dir = "test-codeowners-cache";
+        let cache = GlobalCache::new(temp_dir.path().to_path_buf(), cache_dir.to_owned())?;
+        let file_path = PathBuf::from("tests/fixtures/valid_project/ruby/app/models/bank_account.rb");
+        let resolved_owners = ResolvedOwners {
+            fingerprint: 42,
+            owners: vec![Owner {
+                sources: vec![Source::AnnotatedFile],
+                team_name: "Payments".to_owned(),
+            }],
+        };
+
+        // Only files with an entry can hold resolved owners
+        cache.write_resolved_owners(&file_path, resolved_owners.clone());
+        assert_eq!(cache.get_file_owner(&file_path)?, None);
+
+        cache.write_file_owner(&file_path, Some("Payments".to_owned()));
+        cache.write_resolved_owners(&file_path, resolved_owners.clone());
+        cache.persist_cache()?;
+        let cache = GlobalCache::new(temp_dir.path().to_path_buf(), cache_dir.to_owned())?;
+        let cache_entry = cache.get_file_owner(&file_path)?.expect("entry was not persisted");
+        assert_eq!(cache_entry.resolved_owners, Some(resolved_owners));
+
+        // Re-reading the file's owner starts its resolution over
+        cache.write_file_owner(&file_path, Some("Payments".to_owned()));
+        assert_eq!(cache.get_file_owner(&file_path)?.and_then(|entry| entry.resolved_owners), None);
+        Ok(())
+    }
+
     #[test]
     fn test_corrupted_cache() -> Result<(), Error> {
         let temp_dir = tempdir().change_context(Error::Io)?;
@@ -1,4 +1,4 @@
-use crate::project::Error;
+use crate::{ownership::file_owner_finder::Owner, project::Error};
 use enum_dispatch::enum_dispatch;
 use error_stack::Result;
 use file::GlobalCache;
@@ -18,12 +18,24 @@ pub enum Cache {
 pub trait Caching {
     fn get_file_owner(&self, path: &Path) -> Result<Option<FileOwnerCacheEntry>, Error>;
     fn write_file_owner(&self, path: &Path, owner: Option<String>);
+    /// Attaches `resolved_owners` to the entry `write_file_owner` (or the loaded cache) holds for `path`.
+    fn write_resolved_owners(&self, path: &Path, resolved_owners: ResolvedOwners);
     fn persist_cache(&self) -> Result<(), Error>;
     fn delete_cache(&self) -> Result<(), Error>;
+    fn entry_count(&self) -> usize;
 }
 
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize, PartialEq)]
 pub struct FileOwnerCacheEntry {
     timestamp: u64,
     pub owner: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resolved_owners: Option<ResolvedOwners>,
+}
+
+/// Every team the mappers assigned a file to, as of the mapper state `fingerprint` identifies.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, PartialEq)]
+pub struct ResolvedOwners {
+    pub fingerprint: u64,
+    pub owners: Vec<Owner>,
 }
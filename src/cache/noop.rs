@@ -2,7 +2,7 @@ use crate::project::Error;
 use error_stack::Result;
 use std::path::Path;
 
-use super::{Caching, FileOwnerCacheEntry};
+use super::{Caching, FileOwnerCacheEntry, ResolvedOwners};
 
 #[derive(Default)]
 pub struct NoopCache {}
@@ -16,6 +16,10 @@ impl Caching for NoopCache {
         // noop
     }
 
+    fn write_resolved_owners(&self, _path: &Path, _resolved_owners: ResolvedOwners) {
+        // noop
+    }
+
     fn persist_cache(&self) -> Result<(), Error> {
         Ok(())
     }
@@ -23,4 +27,8 @@ impl Caching for NoopCache {
     fn delete_cache(&self) -> Result<(), Error> {
         Ok(())
     }
+
+    fn entry_count(&self) -> usize {
+        0
+    }
 }
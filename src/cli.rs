@@ -1,8 +1,9 @@
-use clap::{Parser, Subcommand};
+use clap::{ArgGroup, Parser, Subcommand, ValueEnum};
 use codeowners::runner::RunConfig;
 use codeowners::runner::{self, Error as RunnerError, RunResult};
 use error_stack::{Result, ResultExt};
 use path_clean::PathClean;
+use std::io::{Read, Write};
 use std::path::{Path, PathBuf};
 
 #[derive(Subcommand, Debug)]
@@ -17,13 +18,108 @@ enum Command {
             help = "Find the owner from the CODEOWNERS file and just return the team name and yml path"
         )]
         from_codeowners: bool,
+        #[arg(
+            long,
+            default_value = "false",
+            requires = "from_codeowners",
+            help = "With --from-codeowners, list every matching CODEOWNERS pattern with its line number, in file order; GitHub applies the last"
+        )]
+        all: bool,
+        #[arg(
+            long,
+            value_name = "REF",
+            requires = "from_codeowners",
+            conflicts_with_all = ["all", "format", "uniform"],
+            help = "With --from-codeowners, resolve against the CODEOWNERS file as committed at this git ref, without checking it out"
+        )]
+        at: Option<String>,
+        #[arg(
+            long,
+            default_value = "false",
+            conflicts_with = "from_codeowners",
+            help = "Derive the owner from annotations, packages, directories, and team files, ignoring `for_file_default_mode`"
+        )]
+        derived: bool,
         #[arg(short, long, default_value = "false", help = "Output the result in JSON format")]
         json: bool,
+        #[arg(
+            long,
+            value_enum,
+            default_value_t = ForFileFormat::Text,
+            conflicts_with_all = ["json", "trace", "all", "team_file"],
+            help = "Output format; `line` prints `path<TAB>team<TAB>github_team<TAB>source` on one line"
+        )]
+        format: ForFileFormat,
+        #[arg(
+            long,
+            default_value = "false",
+            conflicts_with_all = ["json", "format"],
+            help = "For a directory, also report whether every owned file inside has the directory's owner, or a per-team breakdown"
+        )]
+        uniform: bool,
+        #[arg(
+            long,
+            default_value = "false",
+            conflicts_with_all = ["from_codeowners", "json"],
+            help = "Print every mapper's verdict in evaluation order, followed by the chosen owner and why"
+        )]
+        trace: bool,
+        #[arg(
+            long,
+            default_value = "false",
+            conflicts_with_all = ["from_codeowners", "trace", "format", "team_file"],
+            help = "Derive the owner, letting a team whose strongest claim outranks the others' win, and list the claims it beat by priority"
+        )]
+        explain: bool,
+        #[arg(
+            long,
+            value_name = "PATH",
+            conflicts_with_all = ["from_codeowners", "trace"],
+            help = "Resolve as if this team file were part of the project, replacing any team of the same name. Repeatable"
+        )]
+        team_file: Vec<PathBuf>,
+        #[arg(
+            long,
+            value_enum,
+            default_value_t = TeamFormat::At,
+            help = "Print GitHub team handles as written (`at`, e.g. @org/team) or without the leading @ (`plain`)"
+        )]
+        team_format: TeamFormat,
+        #[arg(
+            long,
+            value_enum,
+            default_value_t = TeamNameFrom::Name,
+            help = "Print the team file's `name` (`name`) or its GitHub team handle (`github`) as the team name"
+        )]
+        team_name_from: TeamNameFrom,
+        #[arg(
+            long,
+            default_value = "false",
+            help = "Print the team file path (and the file path, with `--format line`) as absolute instead of relative to the project root"
+        )]
+        absolute_paths: bool,
+        #[arg(
+            long,
+            value_name = "PACK_DIR",
+            help = "Treat the path as relative to this package directory (itself relative to the project root) instead of the project root"
+        )]
+        package_relative: Option<PathBuf>,
         name: String,
     },
 
+    #[clap(about = "Print every file a team owns, one per line, as soon as each resolves")]
+    OwnersOf { team: String },
+
     #[clap(about = "Finds code ownership information for a given team", visible_alias = "t")]
-    ForTeam { name: String },
+    ForTeam {
+        name: String,
+        #[arg(
+            long,
+            default_value = "false",
+            help = "Follow each glob with the source it came from (annotation, package, team YML, ...)"
+        )]
+        with_source: bool,
+    },
 
     #[clap(
         about = "Generate the CODEOWNERS file and save it to '--codeowners-file-path'.",
@@ -32,30 +128,397 @@ enum Command {
     Generate {
         #[arg(long, short, default_value = "false", help = "Skip staging the CODEOWNERS file")]
         skip_stage: bool,
+        #[arg(
+            long,
+            value_name = "DIR",
+            help = "Only regenerate the lines for files under this directory (relative to the project root), keeping the rest of the CODEOWNERS file"
+        )]
+        path: Option<PathBuf>,
+        #[arg(
+            long,
+            value_name = "PATH",
+            conflicts_with = "path",
+            help = "Also write a JSON file (relative to the project root) mapping each generated glob to its team, GitHub team, and source kind"
+        )]
+        sidecar: Option<PathBuf>,
+        #[arg(
+            long,
+            default_value = "false",
+            conflicts_with_all = ["path", "sidecar"],
+            help = "Write the fewest CODEOWNERS patterns that give every tracked file the same owner, merging uniformly owned directories"
+        )]
+        minimize: bool,
+        #[arg(
+            long,
+            value_name = "SECTION",
+            conflicts_with_all = ["skip_stage", "path", "sidecar", "minimize", "audit_log"],
+            help = "Print only this section to stdout, leaving the CODEOWNERS file alone, for debugging which section a line comes from: default, extension, annotation, team-glob, directory, package-yml, package-json, cargo-toml, team-yml, team-gem, or override. Repeatable"
+        )]
+        only: Vec<String>,
+        #[arg(
+            long,
+            value_name = "GLOB",
+            help = "Treat files matching this glob as unowned for this run, on top of `unowned_globs`. Repeatable"
+        )]
+        exclude: Vec<String>,
+        #[arg(
+            long,
+            value_name = "PATH",
+            help = "Append each file's resolved owner to this NDJSON file (relative to the project root), for auditing"
+        )]
+        audit_log: Option<PathBuf>,
     },
 
     #[clap(
         about = "Validate the validity of the CODEOWNERS file. A validation failure will exit with a failure code and a detailed output of the validation errors.",
         visible_alias = "v"
     )]
+    #[command(group(ArgGroup::new("mode").multiple(false)))]
     Validate {
         #[arg(help = "Optional list of files to validate ownership for (fast mode for git hooks)")]
         files: Vec<String>,
+        #[arg(
+            long,
+            conflicts_with = "files",
+            help = "Only report errors for files owned by this team, plus its team, package, and directory files"
+        )]
+        team: Option<String>,
+        #[arg(
+            long,
+            default_value = "false",
+            conflicts_with_all = ["files", "team"],
+            help = "Read the files to validate from stdin, one path per line, instead of as arguments"
+        )]
+        stdin_paths: bool,
+        #[arg(
+            long,
+            default_value = "false",
+            conflicts_with_all = ["files", "team", "stdin_paths"],
+            help = "Like --stdin-paths, but NUL-separated, as `git diff -z --name-only` prints them"
+        )]
+        stdin_paths0: bool,
+        #[arg(
+            long,
+            default_value = "false",
+            group = "mode",
+            conflicts_with_all = ["files", "team"],
+            help = "Annotate unowned files with the team owning their directory or nearest package"
+        )]
+        fix: bool,
+        #[arg(
+            long,
+            default_value = "false",
+            group = "mode",
+            conflicts_with_all = ["files", "team"],
+            help = "Also fail when a file's @team annotation names a different team than its package or .codeowner directory"
+        )]
+        consistent_annotations: bool,
+        #[arg(
+            long,
+            default_value = "false",
+            group = "mode",
+            conflicts_with_all = ["files", "team"],
+            help = "Only re-resolve owners for files changed since the cache was written, for repeated runs in a dev loop"
+        )]
+        since_cache: bool,
+        #[arg(
+            long,
+            default_value = "false",
+            group = "mode",
+            conflicts_with_all = ["files", "team"],
+            help = "Also fail when an annotation in any tracked file, even one outside owned_globs, names an unknown team"
+        )]
+        check_all_annotations: bool,
+        #[arg(
+            long,
+            default_value = "false",
+            conflicts_with_all = ["files", "team", "mode", "format"],
+            help = "Same as `--format json`"
+        )]
+        json: bool,
+        #[arg(
+            long,
+            value_enum,
+            default_value_t = ValidateFormat::Text,
+            conflicts_with_all = ["files", "team", "mode"],
+            help = "Output format; `json` prints validation errors and warnings as JSON, `github-annotations` (or `github`) prints each error as a GitHub Actions `::error` workflow command, `sarif` prints a SARIF 2.1.0 log for code scanning"
+        )]
+        format: ValidateFormat,
+        #[arg(
+            long,
+            short,
+            default_value = "false",
+            requires = "fix",
+            help = "Apply every suggested annotation without prompting"
+        )]
+        yes: bool,
+        #[arg(
+            long,
+            value_enum,
+            default_value_t = InputFormat::Args,
+            conflicts_with_all = ["files", "team", "mode", "json", "format", "stdin_paths", "stdin_paths0"],
+            help = "How the files to validate are given; `json` reads a JSON array of paths from stdin or --input-file"
+        )]
+        input_format: InputFormat,
+        #[arg(
+            long,
+            value_name = "PATH",
+            requires = "input_format",
+            help = "Read the JSON array of paths from this file instead of stdin"
+        )]
+        input_file: Option<PathBuf>,
+        #[arg(
+            long,
+            default_value = "false",
+            conflicts_with_all = ["mode", "json", "format"],
+            help = "Stop at the first error and report only it, for quicker feedback on large changesets"
+        )]
+        fail_fast: bool,
+        #[arg(
+            long,
+            default_value = "false",
+            conflicts_with = "fail_fast",
+            help = "Report every error (the default)"
+        )]
+        collect_all: bool,
+        #[arg(
+            long,
+            value_name = "GLOB",
+            help = "Treat files matching this glob as unowned for this run, on top of `unowned_globs`. Repeatable"
+        )]
+        exclude: Vec<String>,
+        #[arg(
+            long,
+            value_name = "PATH",
+            help = "Append each file's resolved owner to this NDJSON file (relative to the project root), for auditing"
+        )]
+        audit_log: Option<PathBuf>,
     },
 
     #[clap(about = "Chains both `generate` and `validate` commands.", visible_alias = "gv")]
     GenerateAndValidate {
         #[arg(long, short, default_value = "false", help = "Skip staging the CODEOWNERS file")]
         skip_stage: bool,
+        #[arg(
+            long,
+            default_value = "false",
+            conflicts_with_all = ["skip_stage", "audit_log"],
+            help = "Write nothing: report whether the CODEOWNERS file would change, and validate as if it had been regenerated"
+        )]
+        dry_run: bool,
         #[arg(help = "Optional list of files to validate ownership for (fast mode for git hooks)")]
         files: Vec<String>,
+        #[arg(
+            long,
+            value_name = "GLOB",
+            help = "Treat files matching this glob as unowned for this run, on top of `unowned_globs`. Repeatable"
+        )]
+        exclude: Vec<String>,
+        #[arg(
+            long,
+            value_name = "PATH",
+            help = "Append each file's resolved owner to this NDJSON file (relative to the project root), for auditing"
+        )]
+        audit_log: Option<PathBuf>,
+    },
+
+    #[clap(about = "Write the generated ownership in another tool's format, without touching the CODEOWNERS file.")]
+    Export {
+        #[arg(
+            long,
+            value_enum,
+            help = "`review-rules`: YAML mapping each CODEOWNERS pattern to the GitHub team slugs whose review branch protection should require. `tsv`: one `path<TAB>team_name<TAB>github_team<TAB>source_kind` line per owned file, under a header"
+        )]
+        format: ExportFormat,
+        #[arg(help = "File to write, relative to the project root")]
+        path: PathBuf,
+    },
+
+    #[clap(about = "Re-sort the existing CODEOWNERS file into canonical order, keeping its entries (unlike `generate`).")]
+    Normalize {
+        #[arg(long, default_value = "false", help = "Print the normalized file instead of writing it back")]
+        stdout: bool,
+    },
+
+    #[clap(about = "Check team files for dead configuration. With no check given, runs all of them.")]
+    LintTeams {
+        #[arg(
+            long,
+            default_value = "false",
+            help = "Report owned_globs and subtracted_globs entries that match no tracked files"
+        )]
+        orphaned_globs: bool,
+        #[arg(
+            long,
+            default_value = "false",
+            help = "Report teams that own no file and no glob in the generated CODEOWNERS besides their own team file"
+        )]
+        unused: bool,
+        #[arg(
+            long,
+            default_value = "false",
+            help = "Report subtracted_globs entries that remove a whole owned glob, or overlap none and do nothing"
+        )]
+        subtracted_globs: bool,
+        #[arg(long, default_value = "false", help = "Output the findings in JSON format")]
+        json: bool,
+    },
+
+    #[clap(about = "Print every team's name, GitHub team, and team file, sorted by name.")]
+    ListTeams {
+        #[arg(
+            long,
+            default_value = "false",
+            help = "Output a JSON array that also counts each team's owned_globs and owned_gems"
+        )]
+        json: bool,
+    },
+
+    #[clap(about = "Check the config, team files, CODEOWNERS path, git, and owned_globs, reporting what to fix.")]
+    Doctor,
+
+    #[clap(about = "Print the JSON Schema of a command's --json output.")]
+    Schema {
+        #[command(subcommand)]
+        command: SchemaCommand,
     },
 
     #[clap(about = "Delete the cache file.", visible_alias = "d")]
     DeleteCache,
 
+    #[clap(about = "Read every tracked owned file into the cache and save it, so later runs (e.g. other CI jobs) start warm.")]
+    WarmCache,
+
     #[clap(about = "Compare the CODEOWNERS file to the for-file command.", hide = true)]
-    CrosscheckOwners,
+    CrosscheckOwners {
+        #[arg(short, long, default_value = "false", help = "Output the summary in JSON format")]
+        json: bool,
+    },
+}
+
+impl Command {
+    fn exclude_globs(&self) -> Vec<String> {
+        match self {
+            Command::Generate { exclude, .. } | Command::Validate { exclude, .. } | Command::GenerateAndValidate { exclude, .. } => {
+                exclude.clone()
+            }
+            _ => vec![],
+        }
+    }
+
+    fn audit_log(&self) -> Option<PathBuf> {
+        match self {
+            Command::Generate { audit_log, .. } | Command::Validate { audit_log, .. } | Command::GenerateAndValidate { audit_log, .. } => {
+                audit_log.clone()
+            }
+            _ => None,
+        }
+    }
+
+    fn team_format(&self) -> runner::TeamFormat {
+        match self {
+            Command::ForFile { team_format, .. } => (*team_format).into(),
+            _ => runner::TeamFormat::default(),
+        }
+    }
+
+    fn team_name_from(&self) -> runner::TeamNameSource {
+        match self {
+            Command::ForFile { team_name_from, .. } => (*team_name_from).into(),
+            _ => runner::TeamNameSource::default(),
+        }
+    }
+
+    fn absolute_paths(&self) -> bool {
+        matches!(self, Command::ForFile { absolute_paths: true, .. })
+    }
+
+    // `for-file --package-relative <dir> <path>` asks about `<dir>/<path>`
+    fn with_package_relative_name(mut self) -> Self {
+        if let Command::ForFile {
+            package_relative: Some(pack_dir),
+            name,
+            ..
+        } = &mut self
+        {
+            *name = pack_dir.join(&*name).to_string_lossy().into_owned();
+        }
+        self
+    }
+
+    /// `validate --json` is shorthand for `--format json`
+    fn with_json_as_format(mut self) -> Self {
+        if let Command::Validate { json, format, .. } = &mut self
+            && *json
+        {
+            *json = false;
+            *format = ValidateFormat::Json;
+        }
+        self
+    }
+}
+
+#[derive(Subcommand, Debug)]
+enum SchemaCommand {
+    #[clap(about = "Schema of `for-file --json` output.")]
+    ForFile,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+enum ForFileFormat {
+    Text,
+    Line,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+enum ValidateFormat {
+    Text,
+    Json,
+    #[value(alias = "github")]
+    GithubAnnotations,
+    Sarif,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+enum ExportFormat {
+    ReviewRules,
+    Tsv,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+enum InputFormat {
+    Args,
+    Json,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+enum TeamFormat {
+    At,
+    Plain,
+}
+
+impl From<TeamFormat> for runner::TeamFormat {
+    fn from(team_format: TeamFormat) -> Self {
+        match team_format {
+            TeamFormat::At => runner::TeamFormat::At,
+            TeamFormat::Plain => runner::TeamFormat::Plain,
+        }
+    }
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+enum TeamNameFrom {
+    Name,
+    Github,
+}
+
+impl From<TeamNameFrom> for runner::TeamNameSource {
+    fn from(team_name_from: TeamNameFrom) -> Self {
+        match team_name_from {
+            TeamNameFrom::Name => runner::TeamNameSource::Name,
+            TeamNameFrom::Github => runner::TeamNameSource::Github,
+        }
+    }
 }
 
 /// A CLI to validate and generate Github's CODEOWNERS file.
@@ -72,45 +535,102 @@ struct Args {
     #[arg(long, default_value = "./config/code_ownership.yml")]
     config_path: PathBuf,
 
-    /// Path for the root of the project
-    #[arg(long, default_value = ".")]
-    project_root: PathBuf,
+    /// Path for the root of the project [default: with an absolute --config-path, the directory holding its config/ directory; otherwise the nearest ancestor of the current directory containing the config file, else .]
+    #[arg(long)]
+    project_root: Option<PathBuf>,
 
     /// Run without the cache (good for CI, testing)
     #[arg(long)]
     no_cache: bool,
+
+    /// Only walk this many directory levels below the project root; files nested deeper are not considered (overrides max_depth from the config)
+    #[arg(long)]
+    max_depth: Option<usize>,
+
+    /// Number of threads used to walk and process the project [default: RAYON_NUM_THREADS, else one per core]
+    #[arg(long)]
+    threads: Option<usize>,
+
+    /// Override a config key without editing the config file, e.g. `vendored_gems_path=vendor/gems`. List keys take `[a, b]` or a single value. Repeatable
+    #[arg(long, value_name = "KEY=VALUE")]
+    config_override: Vec<String>,
+
+    /// Color validation errors and warnings: `auto` only when printing to a terminal and `NO_COLOR` is unset
+    #[arg(long, value_enum, default_value_t = ColorChoice::Auto)]
+    color: ColorChoice,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub(crate) enum ColorChoice {
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    /// Whether to color output written to a stream, given whether that stream is a terminal
+    pub(crate) fn enabled(self, is_terminal: bool) -> bool {
+        match self {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => is_terminal && std::env::var_os("NO_COLOR").is_none_or(|no_color| no_color.is_empty()),
+        }
+    }
 }
 
 impl Args {
+    /// `--project-root` when given. Otherwise an absolute `--config-path` names the project it belongs to, and a
+    /// relative one is looked for from the current directory up.
     fn absolute_project_root(&self) -> Result<PathBuf, RunnerError> {
-        self.project_root.canonicalize().change_context(RunnerError::Io(format!(
+        let project_root = match &self.project_root {
+            Some(project_root) => project_root.clone(),
+            None if self.config_path.is_absolute() => project_root_of_config(&self.config_path),
+            None => self.discover_project_root()?,
+        };
+        project_root.canonicalize().change_context(RunnerError::Io(format!(
             "Can't canonicalize project root: {}",
-            &self.project_root.to_string_lossy()
+            &project_root.to_string_lossy()
         )))
     }
 
-    fn absolute_config_path(&self) -> Result<PathBuf, RunnerError> {
-        Ok(self.absolute_path(&self.config_path)?.clean())
+    /// Walks up from the current directory looking for the config file (the same way git finds `.git`),
+    /// falling back to the current directory when no ancestor contains it.
+    fn discover_project_root(&self) -> Result<PathBuf, RunnerError> {
+        let current_dir = std::env::current_dir().change_context(RunnerError::Io("Can't read current directory".to_string()))?;
+        let project_root = current_dir
+            .ancestors()
+            .find(|dir| dir.join(&self.config_path).is_file())
+            .unwrap_or(&current_dir)
+            .to_path_buf();
+        Ok(project_root)
     }
 
-    fn absolute_codeowners_path(&self) -> Result<Option<PathBuf>, RunnerError> {
-        match &self.codeowners_file_path {
-            Some(path) => Ok(Some(self.absolute_path(path)?.clean())),
-            None => Ok(None),
-        }
+    fn absolute_config_path(&self, project_root: &Path) -> PathBuf {
+        project_root.join(&self.config_path).clean()
     }
 
-    fn absolute_path(&self, path: &Path) -> Result<PathBuf, RunnerError> {
-        Ok(self.absolute_project_root()?.join(path))
+    fn absolute_codeowners_path(&self, project_root: &Path) -> Option<PathBuf> {
+        self.codeowners_file_path.as_ref().map(|path| project_root.join(path).clean())
     }
 }
 
-pub fn cli() -> Result<RunResult, RunnerError> {
+/// The directory holding `config_path`'s `config/` directory, as in the default layout, or else the directory
+/// holding the config file itself.
+fn project_root_of_config(config_path: &Path) -> PathBuf {
+    let config_dir = config_path.parent().unwrap_or(config_path);
+    match config_dir.file_name() {
+        Some(name) if name == "config" => config_dir.parent().unwrap_or(config_dir),
+        _ => config_dir,
+    }
+    .to_path_buf()
+}
+
+pub fn cli() -> Result<(RunResult, ColorChoice), RunnerError> {
     let args = Args::parse();
 
-    let config_path = args.absolute_config_path()?;
-    let codeowners_file_path = args.absolute_codeowners_path()?;
     let project_root = args.absolute_project_root()?;
+    let config_path = args.absolute_config_path(&project_root);
+    let codeowners_file_path = args.absolute_codeowners_path(&project_root);
 
     let run_config = RunConfig {
         config_path,
@@ -118,21 +638,244 @@ pub fn cli() -> Result<RunResult, RunnerError> {
         project_root,
         no_cache: args.no_cache,
         executable_name: None,
+        max_depth: args.max_depth,
+        threads: args.threads,
+        config_overrides: args.config_override,
+        exclude_globs: args.command.exclude_globs(),
+        team_format: args.command.team_format(),
+        team_name_from: args.command.team_name_from(),
+        absolute_paths: args.command.absolute_paths(),
+        audit_log: args.command.audit_log(),
     };
 
-    let runner_result = match args.command {
-        Command::Validate { files } => runner::validate(&run_config, files),
-        Command::Generate { skip_stage } => runner::generate(&run_config, !skip_stage),
-        Command::GenerateAndValidate { files, skip_stage } => runner::generate_and_validate(&run_config, files, !skip_stage),
+    let runner_result = match args.command.with_package_relative_name().with_json_as_format() {
+        Command::Validate { fix: true, yes, .. } => runner::validate_fix(&run_config, |suggestion| yes || confirm_annotation(suggestion)),
+        Command::Validate {
+            format: ValidateFormat::Json,
+            ..
+        } => runner::validate_json(&run_config),
+        Command::Validate {
+            format: ValidateFormat::GithubAnnotations,
+            ..
+        } => runner::validate_github_annotations(&run_config),
+        Command::Validate {
+            format: ValidateFormat::Sarif,
+            ..
+        } => runner::validate_sarif(&run_config),
+        Command::Validate {
+            consistent_annotations: true,
+            ..
+        } => runner::validate_consistent_annotations(&run_config),
+        Command::Validate { since_cache: true, .. } => runner::validate_since_cache(&run_config),
+        Command::Validate {
+            check_all_annotations: true,
+            ..
+        } => runner::validate_all_annotations(&run_config),
+        Command::Validate {
+            team: Some(team),
+            fail_fast: true,
+            ..
+        } => runner::validate_team_fail_fast(&run_config, &team),
+        Command::Validate { team: Some(team), .. } => runner::validate_team(&run_config, &team),
+        Command::Validate {
+            input_format: InputFormat::Json,
+            input_file,
+            fail_fast,
+            ..
+        } => validate_file_list(&run_config, read_json_file_list(input_file.as_deref()), fail_fast),
+        Command::Validate {
+            stdin_paths,
+            stdin_paths0,
+            fail_fast,
+            ..
+        } if stdin_paths || stdin_paths0 => {
+            validate_file_list(&run_config, read_stdin_paths(if stdin_paths0 { '\0' } else { '\n' }), fail_fast)
+        }
+        Command::Validate {
+            files, fail_fast: true, ..
+        } => runner::validate_fail_fast(&run_config, files),
+        Command::Validate { files, .. } => runner::validate(&run_config, files),
+        Command::Generate {
+            skip_stage,
+            path: Some(path),
+            ..
+        } => runner::generate_subtree(&run_config, &path, !skip_stage),
+        Command::Generate {
+            skip_stage,
+            sidecar: Some(sidecar),
+            ..
+        } => runner::generate_with_sidecar(&run_config, &sidecar, !skip_stage),
+        Command::Generate {
+            skip_stage,
+            minimize: true,
+            ..
+        } => runner::generate_minimized(&run_config, !skip_stage),
+        Command::Generate { only, .. } if !only.is_empty() => runner::generate_sections(&run_config, &only),
+        Command::Generate { skip_stage, .. } => runner::generate(&run_config, !skip_stage),
+        Command::GenerateAndValidate { files, dry_run: true, .. } => runner::generate_and_validate_dry_run(&run_config, files),
+        Command::GenerateAndValidate { files, skip_stage, .. } => runner::generate_and_validate(&run_config, files, !skip_stage),
+        Command::ForFile {
+            name,
+            from_codeowners,
+            trace,
+            explain,
+            format,
+            team_file,
+            ..
+        } if run_config.project_root.join(&name).is_dir()
+            && (from_codeowners || trace || explain || format == ForFileFormat::Line || !team_file.is_empty()) =>
+        {
+            RunResult {
+                io_errors: vec!["--from-codeowners, --trace, --explain, --format line and --team-file only apply to files".to_owned()],
+                ..Default::default()
+            }
+        }
+        Command::ForFile {
+            name,
+            at: Some(git_ref),
+            json,
+            ..
+        } => runner::for_file_at_ref(&run_config, &name, &git_ref, json),
+        Command::ForFile { name, uniform, json, .. } if run_config.project_root.join(&name).is_dir() => {
+            runner::for_directory(&run_config, &name, uniform, json)
+        }
+        Command::ForFile { uniform: true, .. } => RunResult {
+            io_errors: vec!["--uniform only applies to directories".to_owned()],
+            ..Default::default()
+        },
+        Command::ForFile { name, trace: true, .. } => runner::for_file_trace(&run_config, &name),
+        Command::ForFile {
+            name, explain: true, json, ..
+        } => runner::for_file_explained(&run_config, &name, json),
+        Command::ForFile {
+            name,
+            format: ForFileFormat::Line,
+            from_codeowners: false,
+            derived: false,
+            ..
+        } => runner::for_file_line_in_default_mode(&run_config, &name),
+        Command::ForFile {
+            name,
+            format: ForFileFormat::Line,
+            from_codeowners,
+            ..
+        } => runner::for_file_line(&run_config, &name, from_codeowners),
+        Command::ForFile { name, all: true, json, .. } => runner::for_file_all_codeowners_patterns(&run_config, &name, json),
+        Command::ForFile {
+            name,
+            from_codeowners: false,
+            derived: false,
+            json,
+            team_file,
+            ..
+        } if team_file.is_empty() => runner::for_file_in_default_mode(&run_config, &name, json),
         Command::ForFile {
             name,
             from_codeowners,
             json,
-        } => runner::for_file(&run_config, &name, from_codeowners, json),
-        Command::ForTeam { name } => runner::for_team(&run_config, &name),
+            team_file,
+            ..
+        } if team_file.is_empty() => runner::for_file(&run_config, &name, from_codeowners, json),
+        Command::ForFile { name, json, team_file, .. } => runner::for_file_with_team_files(&run_config, &name, &team_file, json),
+        Command::OwnersOf { team } => runner::stream_team_files(&run_config, &team, |file| {
+            let mut stdout = std::io::stdout().lock();
+            let _ = writeln!(stdout, "{}", file);
+            let _ = stdout.flush();
+        }),
+        Command::ForTeam { name, with_source: true } => runner::for_team_with_sources(&run_config, &name),
+        Command::ForTeam { name, .. } => runner::for_team(&run_config, &name),
+        // With no check given, run all of them
+        Command::LintTeams {
+            orphaned_globs: false,
+            unused: false,
+            subtracted_globs: false,
+            json,
+        } => runner::lint_teams(&run_config, runner::LintChecks::all(), json),
+        Command::LintTeams {
+            orphaned_globs,
+            unused,
+            subtracted_globs,
+            json,
+        } => runner::lint_teams(
+            &run_config,
+            runner::LintChecks {
+                orphaned_globs,
+                unused,
+                subtracted_globs,
+            },
+            json,
+        ),
+        Command::Export {
+            format: ExportFormat::ReviewRules,
+            path,
+        } => runner::export_review_rules(&run_config, &path),
+        Command::Export {
+            format: ExportFormat::Tsv,
+            path,
+        } => runner::export_tsv(&run_config, &path),
+        Command::ListTeams { json } => runner::list_teams(&run_config, json),
+        Command::Doctor => runner::doctor(&run_config),
+        Command::Normalize { stdout } => runner::normalize(&run_config, stdout),
+        Command::Schema {
+            command: SchemaCommand::ForFile,
+        } => runner::for_file_schema(),
         Command::DeleteCache => runner::delete_cache(&run_config),
-        Command::CrosscheckOwners => runner::crosscheck_owners(&run_config),
+        Command::WarmCache => runner::warm_cache(&run_config),
+        Command::CrosscheckOwners { json } => runner::crosscheck_owners(&run_config, json),
+    };
+
+    Ok((runner_result, args.color))
+}
+
+fn validate_file_list(run_config: &RunConfig, files: std::result::Result<Vec<String>, String>, fail_fast: bool) -> RunResult {
+    match files {
+        // Unlike no positional files, an empty list means there's nothing to validate rather than the whole project
+        Ok(files) if files.is_empty() => RunResult::default(),
+        Ok(files) if fail_fast => runner::validate_fail_fast(run_config, files),
+        Ok(files) => runner::validate(run_config, files),
+        Err(err) => RunResult {
+            io_errors: vec![err],
+            ..Default::default()
+        },
+    }
+}
+
+/// Paths given as a JSON array of strings, e.g. a CI system's list of changed files, read from `input_file` or stdin.
+fn read_json_file_list(input_file: Option<&Path>) -> std::result::Result<Vec<String>, String> {
+    let (source, content) = match input_file {
+        Some(path) => (
+            path.display().to_string(),
+            std::fs::read_to_string(path).map_err(|err| format!("Can't read {}: {}", path.display(), err))?,
+        ),
+        None => {
+            let mut content = String::new();
+            std::io::stdin()
+                .read_to_string(&mut content)
+                .map_err(|err| format!("Can't read stdin: {}", err))?;
+            ("stdin".to_owned(), content)
+        }
     };
+    serde_json::from_str(&content).map_err(|err| format!("Expected a JSON array of file paths from {}: {}", source, err))
+}
+
+fn confirm_annotation(suggestion: &runner::SuggestedAnnotation) -> bool {
+    eprint!("Annotate {} with `{}`? [y/N] ", suggestion.path.display(), suggestion.annotation());
+    let _ = std::io::stderr().flush();
+
+    let mut answer = String::new();
+    if std::io::stdin().read_line(&mut answer).is_err() {
+        return false;
+    }
+    matches!(answer.trim().to_lowercase().as_str(), "y" | "yes")
+}
 
-    Ok(runner_result)
+/// Paths read from stdin, separated by `delimiter`. Empty entries (e.g. after a trailing separator) are skipped.
+fn read_stdin_paths(delimiter: char) -> std::result::Result<Vec<String>, String> {
+    let content = std::io::read_to_string(std::io::stdin()).map_err(|err| format!("Can't read file paths from stdin: {}", err))?;
+    Ok(content
+        .split(delimiter)
+        .map(|path| path.strip_suffix('\r').unwrap_or(path))
+        .filter(|path| !path.is_empty())
+        .map(str::to_owned)
+        .collect())
 }
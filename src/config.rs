@@ -1,5 +1,14 @@
+use fast_glob::glob_match;
+
+use crate::path_utils::to_slash_str;
+use path_clean::PathClean;
 use serde::Deserialize;
-use std::{fs::File, path::Path};
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    path::{Path, PathBuf},
+    time::Duration,
+};
 
 #[derive(Deserialize, Debug, Clone)]
 pub struct Config {
@@ -11,6 +20,10 @@ pub struct Config {
     #[serde(alias = "js_package_paths", default = "javascript_package_paths")]
     pub javascript_package_paths: Vec<String>,
 
+    /// Directories whose `Cargo.toml` may name an owner under `[package.metadata]`. None by default.
+    #[serde(default)]
+    pub cargo_package_paths: Vec<String>,
+
     #[serde(default = "team_file_glob")]
     pub team_file_glob: Vec<String>,
 
@@ -31,6 +44,116 @@ pub struct Config {
 
     #[serde(default = "default_codeowners_path")]
     pub codeowners_path: String,
+
+    #[serde(default)]
+    pub package_discovery: PackageDiscovery,
+
+    /// How `for-file` resolves owners when neither `--from-codeowners` nor `--derived` is passed.
+    #[serde(default)]
+    pub for_file_default_mode: ForFileMode,
+
+    #[serde(default)]
+    pub ignored_owners: Vec<String>,
+
+    #[serde(default)]
+    pub max_depth: Option<usize>,
+
+    #[serde(default)]
+    pub case_insensitive_paths: bool,
+
+    /// Own files inside git submodules (listed in `.gitmodules`) too; by default they're left out of the project.
+    #[serde(default)]
+    pub include_submodules: bool,
+
+    #[serde(default)]
+    pub strict_annotations: bool,
+
+    #[serde(default = "default_annotation_header_lines")]
+    pub annotation_header_lines: usize,
+
+    /// Words that may introduce an ownership annotation, e.g. `squad` for `# @squad Payroll`. `validate --fix` writes the first.
+    #[serde(default = "default_annotation_keywords")]
+    pub annotation_keywords: Vec<String>,
+
+    /// Files matching these globs must get their owner from a package or directory, not an `@team` annotation.
+    #[serde(default)]
+    pub annotation_forbidden_globs: Vec<String>,
+
+    /// Team owning every owned file with an extension (`proto: ApiTeam`) that no other mapper claims.
+    #[serde(default, deserialize_with = "deserialize_extension_owners")]
+    pub extension_owners: BTreeMap<String, String>,
+
+    /// `package.json` field (e.g. `maintainers`) whose first person owns a javascript package without a `metadata.owner`.
+    #[serde(default)]
+    pub owner_field: Option<String>,
+
+    /// Team owning every team file, instead of each team owning the file that defines it.
+    #[serde(default)]
+    pub team_files_owner: Option<String>,
+
+    /// Team owning every owned file that no other mapper claims, including `extension_owners`.
+    #[serde(default)]
+    pub default_owner: Option<String>,
+
+    /// Manual owners that win over every mapper for the files their glob matches.
+    #[serde(default)]
+    pub overrides: Vec<OwnershipOverride>,
+
+    /// Hand-maintained CODEOWNERS rules (relative to the project root) appended verbatim below the generated sections.
+    #[serde(default)]
+    pub extra_codeowners_file: Option<PathBuf>,
+
+    /// Make `generate` fail instead of writing a CODEOWNERS file that owns nothing but the team files.
+    #[serde(default)]
+    pub fail_on_empty_generation: bool,
+
+    #[serde(default = "default_git_timeout_secs")]
+    pub git_timeout_secs: u64,
+
+    /// Directory `team_file_glob` is resolved against instead of the project root, so several projects in a
+    /// monorepo can share one set of team files. Relative paths are resolved against the monorepo root.
+    #[serde(default)]
+    pub teams_root: Option<PathBuf>,
+}
+
+/// How directories containing a `package.yml`/`package.json`/`Cargo.toml` are recognized as packages.
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PackageDiscovery {
+    /// Only directories matching `ruby_package_paths`/`javascript_package_paths`/`cargo_package_paths` are packages.
+    #[default]
+    Glob,
+    /// Any directory below the project root containing a package manifest is a package.
+    Anywhere,
+}
+
+impl PackageDiscovery {
+    /// Whether `package_dir` (relative to the project root) is treated as a package when it contains a manifest.
+    pub fn is_package_dir(&self, package_dir: &Path, package_paths: &[String]) -> bool {
+        match self {
+            PackageDiscovery::Glob => to_slash_str(package_dir).is_some_and(|dir| package_paths.iter().any(|glob| glob_match(glob, &*dir))),
+            // The project root itself has no parent and is never a package
+            PackageDiscovery::Anywhere => package_dir.parent().is_some(),
+        }
+    }
+}
+
+/// An `overrides` entry: `team` owns the owned files matching `glob`, whatever else claims them.
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct OwnershipOverride {
+    pub glob: String,
+    pub team: String,
+}
+
+/// Where `for-file` looks up a file's owner.
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ForFileMode {
+    /// Derive the owner from annotations, packages, directories, and team files.
+    #[default]
+    Derived,
+    /// Match the file against the committed CODEOWNERS file only.
+    Codeowners,
 }
 
 #[allow(dead_code)]
@@ -87,6 +210,18 @@ fn default_ignore_dirs() -> Vec<String> {
     ]
 }
 
+fn default_annotation_header_lines() -> usize {
+    5
+}
+
+pub(crate) fn default_annotation_keywords() -> Vec<String> {
+    vec!["team".to_owned()]
+}
+
+pub(crate) fn default_git_timeout_secs() -> u64 {
+    30
+}
+
 fn default_codeowners_path() -> String {
     ".github".to_string()
 }
@@ -96,6 +231,180 @@ impl Config {
         let file = File::open(path).map_err(|e| format!("Can't open config file: {} ({})", path.to_string_lossy(), e))?;
         serde_yaml::from_reader(file).map_err(|e| format!("Can't parse config file: {} ({})", path.to_string_lossy(), e))
     }
+
+    /// Whether `relative_dir` is the root of a gem directly under `vendored_gems_path`.
+    pub fn is_vendored_gem_root(&self, relative_dir: &Path) -> bool {
+        relative_dir.parent() == Some(Path::new(&self.vendored_gems_path))
+    }
+
+    /// The directory `team_file_glob` is relative to: `teams_root` when set, otherwise the project root.
+    /// A relative `teams_root` is joined to the nearest ancestor of the project root containing `.git`.
+    pub fn resolve_teams_root(&self, project_root: &Path) -> PathBuf {
+        match &self.teams_root {
+            None => project_root.to_path_buf(),
+            Some(teams_root) if teams_root.is_absolute() => teams_root.clean(),
+            Some(teams_root) => {
+                let monorepo_root = project_root
+                    .ancestors()
+                    .find(|dir| dir.join(".git").exists())
+                    .unwrap_or(project_root);
+                monorepo_root.join(teams_root).clean()
+            }
+        }
+    }
+
+    /// Common misconfigurations that don't stop a run but likely make it do something other than intended.
+    pub fn validate(&self, project_root: &Path) -> Vec<String> {
+        let mut warnings = Vec::new();
+
+        if self.owned_globs.is_empty() {
+            warnings.push("`owned_globs` is empty, so no file is owned".to_owned());
+        }
+
+        let teams_root = self.resolve_teams_root(project_root);
+        for team_file_glob in &self.team_file_glob {
+            let matches = crate::project::team_file_paths(&teams_root, std::slice::from_ref(team_file_glob));
+            match matches {
+                Ok(paths) if paths.is_empty() => warnings.push(format!(
+                    "`team_file_glob` entry `{}` matches no files under {}",
+                    team_file_glob,
+                    teams_root.display()
+                )),
+                Ok(_) => {}
+                Err(err) => warnings.push(format!("`team_file_glob` entry `{}` is not a valid glob: {}", team_file_glob, err)),
+            }
+        }
+
+        // The default is assumed to be fine when the project has no vendored gems
+        if self.vendored_gems_path != vendored_gems_path() && !project_root.join(&self.vendored_gems_path).is_dir() {
+            warnings.push(format!(
+                "`vendored_gems_path` `{}` does not exist under {}",
+                self.vendored_gems_path,
+                project_root.display()
+            ));
+        }
+
+        for ruby_path in &self.ruby_package_paths {
+            for javascript_path in &self.javascript_package_paths {
+                if ruby_path == javascript_path || glob_match(ruby_path, javascript_path) || glob_match(javascript_path, ruby_path) {
+                    warnings.push(format!(
+                        "`ruby_package_paths` entry `{}` overlaps `javascript_package_paths` entry `{}`",
+                        ruby_path, javascript_path
+                    ));
+                }
+            }
+        }
+
+        if self.annotation_keywords.is_empty() {
+            warnings.push("`annotation_keywords` is empty, so the default `team` is used".to_owned());
+        }
+
+        for glob in self.owned_globs.iter().filter(|glob| self.unowned_globs.contains(glob)) {
+            warnings.push(format!(
+                "`{}` is listed in both `owned_globs` and `unowned_globs`, so it owns nothing",
+                glob
+            ));
+        }
+
+        // The walk never enters a top-level `ignore_dirs` directory, so globs aimed inside one match nothing
+        for glob in &self.owned_globs {
+            let Some((first_segment, _)) = glob.split_once('/') else {
+                continue;
+            };
+            if first_segment == "**" {
+                continue;
+            }
+            for ignore_dir in self
+                .ignore_dirs
+                .iter()
+                .filter(|ignore_dir| glob_match(first_segment, ignore_dir.as_str()))
+            {
+                warnings.push(format!(
+                    "`owned_globs` entry `{}` reaches into `{}`, which `ignore_dirs` skips, so files there are never owned",
+                    glob, ignore_dir
+                ));
+            }
+        }
+
+        warnings
+    }
+
+    /// The keyword `validate --fix` writes annotations with: the first of `annotation_keywords`.
+    pub fn annotation_keyword(&self) -> &str {
+        self.annotation_keywords.first().map_or("team", String::as_str)
+    }
+
+    pub fn git_timeout(&self) -> Duration {
+        Duration::from_secs(self.git_timeout_secs)
+    }
+
+    /// Applies `key=value` overrides on top of the loaded config, in order.
+    pub fn apply_overrides(&mut self, overrides: &[String]) -> std::result::Result<(), String> {
+        for config_override in overrides {
+            let (key, value) = config_override
+                .split_once('=')
+                .ok_or_else(|| format!("Invalid config override `{}`: expected key=value", config_override))?;
+            self.apply_override(key.trim(), value.trim())
+                .map_err(|e| format!("Invalid config override `{}`: {}", config_override, e))?;
+        }
+        Ok(())
+    }
+
+    fn apply_override(&mut self, key: &str, value: &str) -> std::result::Result<(), String> {
+        match key {
+            "owned_globs" => self.owned_globs = parse_list_override(value)?,
+            "ruby_package_paths" | "pack_paths" => self.ruby_package_paths = parse_list_override(value)?,
+            "javascript_package_paths" | "js_package_paths" => self.javascript_package_paths = parse_list_override(value)?,
+            "cargo_package_paths" => self.cargo_package_paths = parse_list_override(value)?,
+            "team_file_glob" => self.team_file_glob = parse_list_override(value)?,
+            "unowned_globs" => self.unowned_globs = parse_list_override(value)?,
+            "ignore_dirs" => self.ignore_dirs = parse_list_override(value)?,
+            "ignored_owners" => self.ignored_owners = parse_list_override(value)?,
+            "annotation_keywords" => self.annotation_keywords = parse_list_override(value)?,
+            "annotation_forbidden_globs" => self.annotation_forbidden_globs = parse_list_override(value)?,
+            "extension_owners" => {
+                let extension_owners: BTreeMap<String, String> = serde_yaml::from_str(value).map_err(|e| e.to_string())?;
+                self.extension_owners = normalize_extension_owners(extension_owners);
+            }
+            "vendored_gems_path" | "unbuilt_gems_path" => self.vendored_gems_path = value.to_owned(),
+            "cache_directory" => self.cache_directory = value.to_owned(),
+            "executable_name" => self.executable_name = value.to_owned(),
+            "codeowners_path" => self.codeowners_path = value.to_owned(),
+            "teams_root" => self.teams_root = Some(PathBuf::from(value)),
+            "owner_field" => self.owner_field = Some(value.to_owned()).filter(|field| !field.is_empty()),
+            "team_files_owner" => self.team_files_owner = Some(value.to_owned()).filter(|team| !team.is_empty()),
+            "default_owner" => self.default_owner = Some(value.to_owned()).filter(|team| !team.is_empty()),
+            "overrides" => self.overrides = serde_yaml::from_str(value).map_err(|e| e.to_string())?,
+            "extra_codeowners_file" => self.extra_codeowners_file = Some(PathBuf::from(value)).filter(|path| !path.as_os_str().is_empty()),
+            _ => return Err(format!("unknown config key `{}`", key)),
+        }
+        Ok(())
+    }
+}
+
+/// Extensions may be written with or without the leading dot.
+fn normalize_extension_owners(extension_owners: BTreeMap<String, String>) -> BTreeMap<String, String> {
+    extension_owners
+        .into_iter()
+        .map(|(extension, owner)| (extension.trim_start_matches('.').to_owned(), owner))
+        .collect()
+}
+
+fn deserialize_extension_owners<'de, D>(deserializer: D) -> std::result::Result<BTreeMap<String, String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    BTreeMap::deserialize(deserializer).map(normalize_extension_owners)
+}
+
+/// List-valued overrides take a YAML flow sequence (`[a, b]`), since globs may themselves contain commas.
+/// Anything else is treated as a single-element list.
+fn parse_list_override(value: &str) -> std::result::Result<Vec<String>, String> {
+    if value.starts_with('[') {
+        serde_yaml::from_str(value).map_err(|e| e.to_string())
+    } else {
+        Ok(vec![value.to_owned()])
+    }
 }
 
 #[cfg(test)]
@@ -135,6 +444,66 @@ mod tests {
         );
         assert_eq!(config.vendored_gems_path, "vendored/");
         assert_eq!(config.executable_name, "codeowners generate");
+        assert_eq!(config.package_discovery, PackageDiscovery::Glob);
+        assert_eq!(config.for_file_default_mode, ForFileMode::Derived);
+        assert!(config.ignored_owners.is_empty());
+        assert!(!config.case_insensitive_paths);
+        assert!(!config.strict_annotations);
+        assert_eq!(config.annotation_header_lines, 5);
+        assert!(config.annotation_forbidden_globs.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_package_discovery_can_be_set_to_anywhere() -> Result<(), Box<dyn Error>> {
+        let temp_dir = tempdir()?;
+        let config_path = temp_dir.path().join("config.yml");
+        let config_str = indoc! {"
+            ---
+            owned_globs:
+              - \"**/*.rb\"
+            package_discovery: anywhere
+        "};
+        fs::write(&config_path, config_str)?;
+        let config_file = File::open(&config_path)?;
+        let config: Config = serde_yaml::from_reader(config_file)?;
+        assert_eq!(config.package_discovery, PackageDiscovery::Anywhere);
+        assert!(
+            config
+                .package_discovery
+                .is_package_dir(Path::new("lib/engines/billing"), &config.ruby_package_paths)
+        );
+        assert!(!config.package_discovery.is_package_dir(Path::new(""), &config.ruby_package_paths));
+        Ok(())
+    }
+
+    #[test]
+    fn test_apply_overrides() -> Result<(), Box<dyn Error>> {
+        let mut config: Config = serde_yaml::from_str("owned_globs:\n  - \"**/*.rb\"\n")?;
+        config.apply_overrides(&[
+            "owned_globs=[\"{app,lib}/**/*.rb\", \"spec/**/*.rb\"]".to_owned(),
+            "unowned_globs=db/**/*".to_owned(),
+            "vendored_gems_path=vendor/gems".to_owned(),
+            "annotation_keywords=[team, squad]".to_owned(),
+        ])?;
+        assert_eq!(config.owned_globs, vec!["{app,lib}/**/*.rb", "spec/**/*.rb"]);
+        assert_eq!(config.unowned_globs, vec!["db/**/*"]);
+        assert_eq!(config.vendored_gems_path, "vendor/gems");
+        assert_eq!(config.annotation_keywords, vec!["team", "squad"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_apply_overrides_rejects_unknown_keys() -> Result<(), Box<dyn Error>> {
+        let mut config: Config = serde_yaml::from_str("owned_globs:\n  - \"**/*.rb\"\n")?;
+        assert_eq!(
+            config.apply_overrides(&["owned_glob=app/**".to_owned()]),
+            Err("Invalid config override `owned_glob=app/**`: unknown config key `owned_glob`".to_owned())
+        );
+        assert_eq!(
+            config.apply_overrides(&["owned_globs".to_owned()]),
+            Err("Invalid config override `owned_globs`: expected key=value".to_owned())
+        );
         Ok(())
     }
 
@@ -203,4 +572,111 @@ mod tests {
         assert_eq!(config.codeowners_path, "docs");
         Ok(())
     }
+
+    fn config_for_validation(temp_dir: &Path, extra: &str) -> Result<Config, Box<dyn Error>> {
+        fs::create_dir_all(temp_dir.join("config/teams"))?;
+        fs::write(temp_dir.join("config/teams/payments.yml"), "name: Payments\n")?;
+        Ok(serde_yaml::from_str(&format!("---\nowned_globs:\n  - \"**/*.rb\"\n{}", extra))?)
+    }
+
+    #[test]
+    fn test_validate_accepts_a_sane_config() -> Result<(), Box<dyn Error>> {
+        let temp_dir = tempdir()?;
+        let config = config_for_validation(temp_dir.path(), "")?;
+        assert!(config.validate(temp_dir.path()).is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate_flags_empty_owned_globs() -> Result<(), Box<dyn Error>> {
+        let temp_dir = tempdir()?;
+        let config = Config {
+            owned_globs: vec![],
+            ..config_for_validation(temp_dir.path(), "")?
+        };
+        assert_eq!(
+            config.validate(temp_dir.path()),
+            vec!["`owned_globs` is empty, so no file is owned"]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate_flags_team_file_glob_matching_nothing() -> Result<(), Box<dyn Error>> {
+        let temp_dir = tempdir()?;
+        let config = config_for_validation(temp_dir.path(), "team_file_glob:\n  - config/teams/**/*.yml\n  - teams/*.yml\n")?;
+        assert_eq!(
+            config.validate(temp_dir.path()),
+            vec![format!(
+                "`team_file_glob` entry `teams/*.yml` matches no files under {}",
+                temp_dir.path().display()
+            )]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate_flags_missing_vendored_gems_path() -> Result<(), Box<dyn Error>> {
+        let temp_dir = tempdir()?;
+        let config = config_for_validation(temp_dir.path(), "vendored_gems_path: gems\n")?;
+        assert_eq!(
+            config.validate(temp_dir.path()),
+            vec![format!(
+                "`vendored_gems_path` `gems` does not exist under {}",
+                temp_dir.path().display()
+            )]
+        );
+
+        fs::create_dir_all(temp_dir.path().join("gems"))?;
+        assert!(config.validate(temp_dir.path()).is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate_flags_overlapping_package_paths() -> Result<(), Box<dyn Error>> {
+        let temp_dir = tempdir()?;
+        let config = config_for_validation(
+            temp_dir.path(),
+            "ruby_package_paths:\n  - packs/*\njavascript_package_paths:\n  - packs/ui\n  - frontend/*\n",
+        )?;
+        assert_eq!(
+            config.validate(temp_dir.path()),
+            vec!["`ruby_package_paths` entry `packs/*` overlaps `javascript_package_paths` entry `packs/ui`"]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate_flags_globs_both_owned_and_unowned() -> Result<(), Box<dyn Error>> {
+        let temp_dir = tempdir()?;
+        let config = config_for_validation(temp_dir.path(), "unowned_globs:\n  - \"**/*.rb\"\n")?;
+        assert_eq!(
+            config.validate(temp_dir.path()),
+            vec!["`**/*.rb` is listed in both `owned_globs` and `unowned_globs`, so it owns nothing"]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate_flags_owned_globs_inside_ignore_dirs() -> Result<(), Box<dyn Error>> {
+        let temp_dir = tempdir()?;
+        let config = config_for_validation(
+            temp_dir.path(),
+            "  - \"db/seeds/**/*.rb\"\n  - \"{app,log}/**/*.rb\"\n  - \"**/tmp/*.rb\"\n",
+        )?;
+        assert_eq!(
+            config.validate(temp_dir.path()),
+            vec![
+                "`owned_globs` entry `db/seeds/**/*.rb` reaches into `db`, which `ignore_dirs` skips, so files there are never owned",
+                "`owned_globs` entry `{app,log}/**/*.rb` reaches into `log`, which `ignore_dirs` skips, so files there are never owned",
+            ]
+        );
+
+        let config = Config {
+            ignore_dirs: vec![".git".to_owned()],
+            ..config
+        };
+        assert!(config.validate(temp_dir.path()).is_empty());
+        Ok(())
+    }
 }
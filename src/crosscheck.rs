@@ -1,45 +1,138 @@
-use std::path::Path;
+use std::{collections::BTreeMap, path::Path};
+
+use itertools::Itertools;
+use serde::Serialize;
 
 use crate::{
     cache::Cache,
     config::Config,
-    ownership::file_owner_resolver::find_file_owners,
+    ownership::file_owner_resolver::BatchResolver,
     project::Project,
     project_builder::ProjectBuilder,
     runner::{RunConfig, RunResult, config_from_run_config, team_for_file_from_codeowners},
 };
 
-pub fn crosscheck_owners(run_config: &RunConfig, cache: &Cache) -> RunResult {
-    match do_crosscheck_owners(run_config, cache) {
-        Ok(mismatches) if mismatches.is_empty() => RunResult {
-            info_messages: vec!["Success! All files match between CODEOWNERS and for-file command.".to_string()],
-            ..Default::default()
-        },
-        Ok(mismatches) => RunResult {
-            validation_errors: mismatches,
-            ..Default::default()
-        },
-        Err(err) => RunResult {
-            io_errors: vec![err],
-            ..Default::default()
-        },
+/// Totals over every checked file. `sources` counts the source kind of each file's winning fast-path owner.
+#[derive(Debug, Serialize)]
+struct CrosscheckSummary {
+    total: usize,
+    matches: usize,
+    mismatches: usize,
+    sources: BTreeMap<&'static str, usize>,
+    mismatched_files: Vec<Mismatch>,
+}
+
+/// A file whose owner in the CODEOWNERS file differs from the one the fast path resolves.
+#[derive(Debug, Serialize)]
+struct Mismatch {
+    path: String,
+    codeowners_owner: String,
+    fast_owner: String,
+}
+
+impl std::fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "- {}: CODEOWNERS={} fast={}", self.path, self.codeowners_owner, self.fast_owner)
+    }
+}
+
+impl CrosscheckSummary {
+    fn new() -> Self {
+        Self {
+            total: 0,
+            matches: 0,
+            mismatches: 0,
+            sources: crate::ownership::source_kinds()
+                .into_iter()
+                .chain(["unowned"])
+                .map(|kind| (kind, 0))
+                .collect(),
+            mismatched_files: Vec::new(),
+        }
+    }
+
+    fn summary_line(&self) -> String {
+        let sources = self.sources.iter().map(|(kind, count)| format!("{}={}", kind, count)).join(", ");
+        format!(
+            "Checked {} files: {} matches, {} mismatches. Winning sources: {}",
+            self.total, self.matches, self.mismatches, sources
+        )
     }
 }
 
-fn do_crosscheck_owners(run_config: &RunConfig, cache: &Cache) -> Result<Vec<String>, String> {
+pub fn crosscheck_owners(run_config: &RunConfig, cache: &Cache, json: bool) -> RunResult {
+    let summary = match do_crosscheck_owners(run_config, cache) {
+        Ok(result) => result,
+        Err(err) => {
+            return RunResult {
+                io_errors: vec![err],
+                ..Default::default()
+            };
+        }
+    };
+
+    if json {
+        let json = match serde_json::to_string_pretty(&summary) {
+            Ok(json) => json,
+            Err(err) => {
+                return RunResult {
+                    io_errors: vec![err.to_string()],
+                    ..Default::default()
+                };
+            }
+        };
+        // Mismatches still fail the run, with the JSON as the only output
+        return if summary.mismatched_files.is_empty() {
+            RunResult {
+                info_messages: vec![json],
+                ..Default::default()
+            }
+        } else {
+            RunResult {
+                validation_errors: vec![json],
+                ..Default::default()
+            }
+        };
+    }
+
+    let info_messages = if summary.mismatched_files.is_empty() {
+        vec![
+            "Success! All files match between CODEOWNERS and for-file command.".to_string(),
+            summary.summary_line(),
+        ]
+    } else {
+        vec![summary.summary_line()]
+    };
+
+    RunResult {
+        info_messages,
+        validation_errors: summary.mismatched_files.iter().map(|mismatch| mismatch.to_string()).collect(),
+        ..Default::default()
+    }
+}
+
+fn do_crosscheck_owners(run_config: &RunConfig, cache: &Cache) -> Result<CrosscheckSummary, String> {
     let config = load_config(run_config)?;
     let project = build_project(&config, run_config, cache)?;
 
-    let mut mismatches: Vec<String> = Vec::new();
+    let mut summary = CrosscheckSummary::new();
+    let mut resolver = BatchResolver::new(&run_config.project_root, &config)?;
     for file in &project.files {
-        let (codeowners_team, fast_display) = owners_for_file(&file.path, run_config, &config)?;
+        let (codeowners_team, fast_display, source_kind) = owners_for_file(&file.path, run_config, &mut resolver)?;
         let codeowners_display = codeowners_team.clone().unwrap_or_else(|| "Unowned".to_string());
-        if !is_match(codeowners_team.as_deref(), &fast_display) {
-            mismatches.push(format_mismatch(&project, &file.path, &codeowners_display, &fast_display));
+        summary.total += 1;
+        *summary.sources.entry(source_kind).or_default() += 1;
+        if is_match(codeowners_team.as_deref(), &fast_display) {
+            summary.matches += 1;
+        } else {
+            summary.mismatches += 1;
+            summary
+                .mismatched_files
+                .push(format_mismatch(&project, &file.path, codeowners_display, fast_display));
         }
     }
 
-    Ok(mismatches)
+    Ok(summary)
 }
 
 fn load_config(run_config: &RunConfig) -> Result<Config, String> {
@@ -49,18 +142,27 @@ fn load_config(run_config: &RunConfig) -> Result<Config, String> {
 fn build_project(config: &Config, run_config: &RunConfig, cache: &Cache) -> Result<Project, String> {
     use crate::runner::resolve_codeowners_file_path;
     let codeowners_file_path = resolve_codeowners_file_path(run_config, config);
-    let mut project_builder = ProjectBuilder::new(config, run_config.project_root.clone(), codeowners_file_path, cache);
+    let mut project_builder =
+        ProjectBuilder::new(config, run_config.project_root.clone(), codeowners_file_path, cache).with_threads(run_config.threads);
     project_builder.build().map_err(|e| e.to_string())
 }
 
-fn owners_for_file(path: &Path, run_config: &RunConfig, config: &Config) -> Result<(Option<String>, String), String> {
+fn owners_for_file(
+    path: &Path,
+    run_config: &RunConfig,
+    resolver: &mut BatchResolver,
+) -> Result<(Option<String>, String, &'static str), String> {
     let file_path_str = path.to_string_lossy().to_string();
 
     let codeowners_team = team_for_file_from_codeowners(run_config, &file_path_str)
         .map_err(|e| e.to_string())?
         .map(|t| t.name);
 
-    let fast_owners = find_file_owners(&run_config.project_root, config, Path::new(&file_path_str))?;
+    let fast_owners = resolver.file_owners(Path::new(&file_path_str));
+    let source_kind = fast_owners
+        .first()
+        .and_then(|owner| owner.sources.first())
+        .map_or("unowned", |source| source.kind());
     let fast_display = match fast_owners.len() {
         0 => "Unowned".to_string(),
         1 => fast_owners[0].team.name.clone(),
@@ -70,7 +172,7 @@ fn owners_for_file(path: &Path, run_config: &RunConfig, config: &Config) -> Resu
         }
     };
 
-    Ok((codeowners_team, fast_display))
+    Ok((codeowners_team, fast_display, source_kind))
 }
 
 fn is_match(codeowners_team: Option<&str>, fast_display: &str) -> bool {
@@ -81,7 +183,10 @@ fn is_match(codeowners_team: Option<&str>, fast_display: &str) -> bool {
     }
 }
 
-fn format_mismatch(project: &Project, file_path: &Path, codeowners_display: &str, fast_display: &str) -> String {
-    let rel = project.relative_path(file_path).to_string_lossy().to_string();
-    format!("- {}: CODEOWNERS={} fast={}", rel, codeowners_display, fast_display)
+fn format_mismatch(project: &Project, file_path: &Path, codeowners_display: String, fast_display: String) -> Mismatch {
+    Mismatch {
+        path: project.relative_path(file_path).to_string_lossy().to_string(),
+        codeowners_owner: codeowners_display,
+        fast_owner: fast_display,
+    }
 }
@@ -0,0 +1,148 @@
+use std::{path::Path, time::Duration};
+
+use crate::{
+    config::{Config, default_git_timeout_secs},
+    git::run_git,
+    ownership::file_owner_resolver::load_teams,
+    path_utils::{glob_matches, relative_to, to_slash_string_lossy},
+    runner::{RunConfig, RunResult, config_from_run_config, resolve_codeowners_file_path},
+    team_lint::tracked_file_paths,
+};
+
+/// The outcome of one setup check: what was found when it passes, what to fix when it fails.
+struct Check {
+    name: &'static str,
+    outcome: Result<String, String>,
+}
+
+impl Check {
+    fn new(name: &'static str, outcome: Result<String, String>) -> Self {
+        Self { name, outcome }
+    }
+
+    fn line(&self) -> String {
+        match &self.outcome {
+            Ok(detail) => format!("[pass] {}: {}", self.name, detail),
+            Err(fix) => format!("[FAIL] {}: {}", self.name, fix),
+        }
+    }
+}
+
+/// Checks the setup pieces ownership depends on and reports each one, so a new project can see what's missing.
+pub fn doctor(run_config: &RunConfig) -> RunResult {
+    let checks = run_checks(run_config);
+    let failures = checks.iter().filter(|check| check.outcome.is_err()).count();
+    let mut report: Vec<String> = checks.iter().map(Check::line).collect();
+
+    if failures == 0 {
+        report.push(format!("All {} checks passed.", checks.len()));
+        return RunResult {
+            info_messages: vec![report.join("\n")],
+            ..Default::default()
+        };
+    }
+    report.push(format!("{} of {} checks failed.", failures, checks.len()));
+    RunResult {
+        validation_errors: vec![report.join("\n")],
+        ..Default::default()
+    }
+}
+
+fn run_checks(run_config: &RunConfig) -> Vec<Check> {
+    let project_root = &run_config.project_root;
+    let config = config_from_run_config(run_config).map_err(|e| e.current_context().to_string());
+    let mut checks = vec![Check::new(
+        "config",
+        config
+            .as_ref()
+            .map(|_| format!("{} parses", run_config.config_path.display()))
+            .map_err(|e| format!("{}. Create it with at least `owned_globs`", e)),
+    )];
+
+    let config = match config {
+        Ok(config) => config,
+        // Every other check needs the config
+        Err(_) => {
+            checks.push(Check::new(
+                "git",
+                check_git(project_root, Duration::from_secs(default_git_timeout_secs())),
+            ));
+            return checks;
+        }
+    };
+
+    checks.push(Check::new("teams", check_teams(project_root, &config)));
+    checks.push(Check::new("codeowners file", check_codeowners_writable(run_config, &config)));
+    checks.push(Check::new("git", check_git(project_root, config.git_timeout())));
+    checks.push(Check::new("owned_globs", check_owned_globs(project_root, &config)));
+    checks
+}
+
+fn check_teams(project_root: &Path, config: &Config) -> Result<String, String> {
+    match load_teams(project_root, config)?.len() {
+        0 => Err(format!(
+            "no team files match `team_file_glob` ({}). Add a team YML such as config/teams/my_team.yml with `name` and `github.team`",
+            config.team_file_glob.join(", ")
+        )),
+        1 => Ok("found 1 team".to_owned()),
+        count => Ok(format!("found {} teams", count)),
+    }
+}
+
+fn check_codeowners_writable(run_config: &RunConfig, config: &Config) -> Result<String, String> {
+    let codeowners_file_path = resolve_codeowners_file_path(run_config, config);
+    // `generate` creates the file and its missing directories, so check the nearest thing that exists
+    let existing = codeowners_file_path
+        .ancestors()
+        .find(|path| path.exists())
+        .ok_or_else(|| format!("{} has no existing parent directory", codeowners_file_path.display()))?;
+    let readonly = std::fs::metadata(existing)
+        .map_err(|e| format!("can't read {}: {}", existing.display(), e))?
+        .permissions()
+        .readonly();
+    if readonly {
+        return Err(format!(
+            "{} is read-only, so `generate` can't write {}. Fix its permissions or pass --codeowners-file-path",
+            existing.display(),
+            codeowners_file_path.display()
+        ));
+    }
+    Ok(format!("{} is writable", codeowners_file_path.display()))
+}
+
+fn check_git(project_root: &Path, timeout: Duration) -> Result<String, String> {
+    let output = run_git(&["--version"], project_root, timeout).map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        return Err(format!(
+            "`git --version` failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_owned())
+}
+
+fn check_owned_globs(project_root: &Path, config: &Config) -> Result<String, String> {
+    let owned_files = tracked_file_paths(project_root, config)?
+        .iter()
+        .map(|path| to_slash_string_lossy(relative_to(project_root, path)))
+        .filter(|file| {
+            config
+                .owned_globs
+                .iter()
+                .any(|glob| glob_matches(glob, file, config.case_insensitive_paths))
+                && !config
+                    .unowned_globs
+                    .iter()
+                    .any(|glob| glob_matches(glob, file, config.case_insensitive_paths))
+        })
+        .count();
+    match owned_files {
+        0 => Err(format!(
+            "no files match `owned_globs` ({}) outside `unowned_globs`. Check the globs are relative to {}",
+            config.owned_globs.join(", "),
+            project_root.display()
+        )),
+        1 => Ok("1 file to own".to_owned()),
+        count => Ok(format!("{} files to own", count)),
+    }
+}
@@ -0,0 +1,115 @@
+use std::{
+    fmt,
+    io::{self, Read},
+    path::Path,
+    process::{Command, Output, Stdio},
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use wait_timeout::ChildExt;
+
+#[derive(Debug)]
+pub(crate) enum GitError {
+    NotFound,
+    TimedOut(Duration),
+    Io(String),
+}
+
+impl fmt::Display for GitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GitError::NotFound => write!(
+                f,
+                "`git` executable not found on PATH. Install git, or use `--skip-stage` to skip staging"
+            ),
+            GitError::TimedOut(timeout) => write!(
+                f,
+                "`git` did not finish within {}s. Raise `git_timeout_secs` in the config if the repository is large",
+                timeout.as_secs()
+            ),
+            GitError::Io(message) => write!(f, "Failed to run `git`: {}", message),
+        }
+    }
+}
+
+/// Runs `git` with `args` in `current_dir`, killing it if it hasn't exited within `timeout`.
+pub(crate) fn run_git(args: &[&str], current_dir: &Path, timeout: Duration) -> Result<Output, GitError> {
+    run_with_timeout("git", args, current_dir, timeout)
+}
+
+fn run_with_timeout(program: &str, args: &[&str], current_dir: &Path, timeout: Duration) -> Result<Output, GitError> {
+    // A missing working directory also fails to spawn with `NotFound`, which would be misreported as a missing binary
+    if !current_dir.is_dir() {
+        return Err(GitError::Io(format!("{} is not a directory", current_dir.display())));
+    }
+
+    let mut child = Command::new(program)
+        .args(args)
+        .current_dir(current_dir)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| match e.kind() {
+            io::ErrorKind::NotFound => GitError::NotFound,
+            _ => GitError::Io(e.to_string()),
+        })?;
+
+    // Drain the pipes while waiting so a chatty child can't block on a full pipe buffer
+    let stdout = drain(child.stdout.take());
+    let stderr = drain(child.stderr.take());
+
+    let status = match child.wait_timeout(timeout).map_err(|e| GitError::Io(e.to_string()))? {
+        Some(status) => status,
+        None => {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(GitError::TimedOut(timeout));
+        }
+    };
+
+    Ok(Output {
+        status,
+        stdout: stdout.join().unwrap_or_default(),
+        stderr: stderr.join().unwrap_or_default(),
+    })
+}
+
+fn drain<R: Read + Send + 'static>(pipe: Option<R>) -> JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut buffer = Vec::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut buffer);
+        }
+        buffer
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_missing_program_is_not_found() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let result = run_with_timeout("definitely-not-git-xyz", &["status"], tmp_dir.path(), Duration::from_secs(5));
+        assert!(matches!(result, Err(GitError::NotFound)), "{:?}", result);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_slow_program_times_out() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let result = run_with_timeout("sleep", &["5"], tmp_dir.path(), Duration::from_millis(100));
+        assert!(matches!(result, Err(GitError::TimedOut(_))), "{:?}", result);
+    }
+
+    #[test]
+    fn test_run_git_captures_output() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let output = run_git(&["--version"], tmp_dir.path(), Duration::from_secs(30)).unwrap();
+        assert!(output.status.success());
+        assert!(String::from_utf8_lossy(&output.stdout).starts_with("git version"));
+    }
+}
@@ -2,10 +2,13 @@ pub mod cache;
 pub(crate) mod common_test;
 pub mod config;
 pub mod crosscheck;
+pub mod doctor;
+pub(crate) mod git;
 pub mod ownership;
 pub mod path_utils;
 pub(crate) mod project;
 pub mod project_builder;
 pub mod project_file_builder;
 pub mod runner;
+pub mod team_lint;
 pub(crate) mod tracked_files;
@@ -1,19 +1,29 @@
 mod cli;
+use std::io::IsTerminal;
 use std::process;
 
 use codeowners::runner::{Error as RunnerError, RunResult};
 use error_stack::Result;
 
-use crate::cli::cli;
+use crate::cli::{ColorChoice, cli};
 
 fn main() -> Result<(), RunnerError> {
     install_logger();
-    maybe_print_errors(cli()?)?;
+    let (result, color) = cli()?;
+    maybe_print_errors(result, color)?;
 
     Ok(())
 }
 
-fn maybe_print_errors(result: RunResult) -> Result<(), RunnerError> {
+fn maybe_print_errors(result: RunResult, color: ColorChoice) -> Result<(), RunnerError> {
+    let warning_prefix = if color.enabled(std::io::stderr().is_terminal()) {
+        "\x1b[33mWarning:\x1b[0m"
+    } else {
+        "Warning:"
+    };
+    for warning in result.warnings {
+        eprintln!("{} {}", warning_prefix, warning);
+    }
     if !result.info_messages.is_empty() {
         for msg in result.info_messages {
             println!("{}", msg);
@@ -23,8 +33,13 @@ fn maybe_print_errors(result: RunResult) -> Result<(), RunnerError> {
         for msg in result.io_errors {
             eprintln!("{}", msg);
         }
+        let highlight = color.enabled(std::io::stdout().is_terminal());
         for msg in result.validation_errors {
-            println!("{}", msg);
+            if highlight {
+                println!("{}", highlight_categories(&msg));
+            } else {
+                println!("{}", msg);
+            }
         }
         process::exit(1);
     }
@@ -32,6 +47,26 @@ fn maybe_print_errors(result: RunResult) -> Result<(), RunnerError> {
     Ok(())
 }
 
+/// Highlights validation error categories in red. A category starts a blank-line-separated block and, unlike the
+/// path heading a multiple-owners entry, isn't followed by an indented line.
+fn highlight_categories(errors: &str) -> String {
+    let lines: Vec<&str> = errors.split('\n').collect();
+    lines
+        .iter()
+        .enumerate()
+        .map(|(index, line)| {
+            let after_blank = index > 0 && lines[index - 1].is_empty();
+            let before_indented = lines.get(index + 1).is_some_and(|next| next.starts_with(' '));
+            if after_blank && !line.is_empty() && !line.starts_with(['-', ' ']) && !before_indented {
+                format!("\x1b[1;31m{}\x1b[0m", line)
+            } else {
+                line.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
 fn install_logger() {
     tracing_subscriber::fmt()
         .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
@@ -1,7 +1,9 @@
 use file_owner_finder::FileOwnerFinder;
 use itertools::Itertools;
 use mapper::{OwnerMatcher, Source, TeamName};
+use rayon::prelude::{IntoParallelRefIterator, ParallelIterator};
 use std::{
+    collections::HashSet,
     error::Error,
     fmt::{self, Display},
     path::Path,
@@ -9,30 +11,51 @@ use std::{
 };
 use tracing::{info, instrument};
 
+pub mod annotation_fixer;
+pub mod annotations;
+pub mod audit_log;
 pub(crate) mod codeowners_file_parser;
+mod codeowners_minimizer;
 pub(crate) mod codeowners_query;
 mod file_generator;
-mod file_owner_finder;
+pub(crate) mod file_owner_finder;
 pub mod file_owner_resolver;
 pub(crate) mod mapper;
 mod validator;
 
 use crate::{
+    cache::Cache,
+    ownership::audit_log::AuditLog,
     ownership::mapper::DirectoryMapper,
-    project::{Project, Team},
+    project::{Project, ProjectFile, Team},
+    project_file_builder::build_project_file_without_cache,
 };
 
+pub use file_generator::{ReviewRule, SidecarEntry};
 pub use validator::Errors as ValidatorErrors;
+pub use validator::ResolutionCounts;
+pub use validator::ValidationEntry;
 
 use self::{
     codeowners_file_parser::parse_for_team,
     file_generator::FileGenerator,
-    mapper::{JavascriptPackageMapper, Mapper, RubyPackageMapper, TeamFileMapper, TeamGemMapper, TeamGlobMapper, TeamYmlMapper},
+    mapper::{
+        CargoPackageMapper, DefaultOwnerMapper, ExtensionMapper, JavascriptPackageMapper, Mapper, OverrideMapper, RubyPackageMapper,
+        TeamFileMapper, TeamGemMapper, TeamGlobMapper, TeamYmlMapper,
+    },
     validator::Validator,
 };
 
 pub struct Ownership {
     project: Arc<Project>,
+    audit_log: Option<Arc<AuditLog>>,
+}
+
+/// A top-of-file annotation that has no effect because its file matches `unowned_globs`.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SuppressedAnnotation {
+    pub path: std::path::PathBuf,
+    pub team: String,
 }
 #[derive(Debug, Clone)]
 pub struct FileOwner {
@@ -56,15 +79,50 @@ impl TeamOwnership {
     }
 }
 
+/// One row of `export --format tsv`: an owned file and a team that owns it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IndexEntry {
+    pub path: String,
+    pub team_name: String,
+    pub github_team: String,
+    /// The `Source::kind` of the team's strongest claim on the file.
+    pub source_kind: &'static str,
+}
+
+/// The source behind each of a `TeamOwnership`'s globs, in the same order; `None` when no mapper produces the glob anymore.
+pub type GlobSources = Vec<Option<Source>>;
+
+impl FileOwner {
+    /// The order a file's owners are listed in, shared by the project-wide and single-file lookups so both agree:
+    /// the priority of the owner's strongest source, then team name, then that source's description (which names
+    /// the glob, package, or directory behind it).
+    pub fn cmp_precedence(&self, other: &Self) -> std::cmp::Ordering {
+        self.precedence_key().cmp(&other.precedence_key())
+    }
+
+    /// The priority of the owner's strongest source (lower wins), or `u8::MAX` without any.
+    pub fn priority(&self) -> u8 {
+        self.sources.iter().map(Source::priority).min().unwrap_or(u8::MAX)
+    }
+
+    fn precedence_key(&self) -> (u8, &str, String) {
+        let (priority, description) = self
+            .sources
+            .iter()
+            .map(|source| (source.priority(), source.to_string()))
+            .min()
+            .unwrap_or((u8::MAX, String::new()));
+        (priority, &self.team.name, description)
+    }
+}
+
 impl Display for FileOwner {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let sources = if self.sources.is_empty() {
             "".to_string()
         } else {
-            let sources_str = self
-                .sources
+            let sources_str = Source::normalize(self.sources.clone())
                 .iter()
-                .sorted_by_key(|source| source.to_string())
                 .map(|source| source.to_string())
                 .collect::<Vec<_>>()
                 .join("\n- ");
@@ -113,33 +171,202 @@ impl Ownership {
     pub fn build(project: Project) -> Self {
         Self {
             project: Arc::new(project),
+            audit_log: None,
+        }
+    }
+
+    /// Records every file the validator resolves to `audit_log`.
+    pub fn with_audit_log(mut self, audit_log: Arc<AuditLog>) -> Self {
+        self.audit_log = Some(audit_log);
+        self
+    }
+
+    /// Resolves every file's owners only so they're recorded to the audit log, for commands (like `generate`)
+    /// that don't otherwise resolve files one by one. Does nothing without an audit log.
+    pub fn record_resolutions(&self) {
+        if self.audit_log.is_some() {
+            self.validator(false).record_resolutions();
+        }
+    }
+
+    fn validator(&self, consistent_annotations: bool) -> Validator {
+        Validator {
+            project: self.project.clone(),
+            mappers: self.mappers(),
+            file_generator: self.file_generator(),
+            executable_name: self.project.executable_name.clone(),
+            consistent_annotations,
+            audit_log: self.audit_log.clone(),
+            fail_fast: false,
+            annotated_files_outside_project: vec![],
         }
     }
 
     #[instrument(level = "debug", skip_all)]
     pub fn validate(&self) -> Result<(), ValidatorErrors> {
         info!("validating file ownership");
+        let validator = self.validator(false);
+
+        validator.validate()
+    }
+
+    /// Like `validate`, but stops at the first error instead of collecting every one.
+    #[instrument(level = "debug", skip_all)]
+    pub fn validate_fail_fast(&self) -> Result<(), ValidatorErrors> {
+        info!("validating file ownership, stopping at the first error");
         let validator = Validator {
-            project: self.project.clone(),
-            mappers: self.mappers(),
-            file_generator: FileGenerator { mappers: self.mappers() },
-            executable_name: self.project.executable_name.clone(),
+            fail_fast: true,
+            ..self.validator(false)
         };
 
         validator.validate()
     }
 
+    /// Like `validate`, but also reports annotations naming unknown teams in `annotated_files` the project leaves out,
+    /// such as files outside `owned_globs`, before they're moved somewhere owned.
+    #[instrument(level = "debug", skip_all)]
+    pub fn validate_all_annotations(&self, annotated_files: Vec<ProjectFile>) -> Result<(), ValidatorErrors> {
+        info!("validating file ownership and the annotations of files outside the project");
+        let project_files: HashSet<&Path> = self.project.files.iter().map(|file| file.path.as_path()).collect();
+        let validator = Validator {
+            annotated_files_outside_project: annotated_files
+                .into_iter()
+                .filter(|file| !project_files.contains(file.path.as_path()))
+                .collect(),
+            ..self.validator(false)
+        };
+
+        validator.validate()
+    }
+
+    /// Like `validate`, but only runs the mappers for files whose owners `cache` doesn't already hold.
+    #[instrument(level = "debug", skip_all)]
+    pub fn validate_since_cache(&self, cache: &Cache) -> (Result<(), ValidatorErrors>, ResolutionCounts) {
+        info!("validating file ownership, reusing cached owners");
+        let validator = self.validator(false);
+
+        validator.validate_since_cache(cache)
+    }
+
+    /// Like `validate`, but also requires each file's `@team` annotation to match its package or directory owner.
+    #[instrument(level = "debug", skip_all)]
+    pub fn validate_consistent_annotations(&self) -> Result<(), ValidatorErrors> {
+        info!("validating file ownership with consistent annotations");
+        let validator = self.validator(true);
+
+        validator.validate()
+    }
+
+    #[instrument(level = "debug", skip_all)]
+    pub fn validate_codeowners_file(&self) -> Result<(), ValidatorErrors> {
+        info!("validating the CODEOWNERS file is up to date");
+        let validator = self.validator(false);
+
+        validator.validate_codeowners_file_is_fresh()
+    }
+
+    /// Like `validate`, but as if the generated CODEOWNERS file had been written, for `generate-and-validate --dry-run`.
+    #[instrument(level = "debug", skip_all)]
+    pub fn validate_generated(&self) -> Result<(), ValidatorErrors> {
+        info!("validating file ownership against the generated CODEOWNERS file");
+        self.validate().or_else(ValidatorErrors::without_stale_codeowners)
+    }
+
+    /// Those of `file_paths` (relative to the project root, `/`-separated) that no line of the generated CODEOWNERS
+    /// file gives an owner.
+    pub fn unowned_in_generated_file(&self, file_paths: &[String]) -> Vec<String> {
+        let owners = codeowners_minimizer::owners_by_file(&self.generate_file(), file_paths);
+        file_paths
+            .iter()
+            .filter(|file_path| owners[file_path.as_str()].is_none())
+            .cloned()
+            .collect()
+    }
+
+    /// Annotated files whose annotation is ignored because they match `unowned_globs`, sorted by path. Reads the
+    /// first `header_lines` of every such file.
+    pub fn suppressed_annotations(&self, header_lines: usize) -> Vec<SuppressedAnnotation> {
+        self.project
+            .unowned_glob_files
+            .par_iter()
+            .filter_map(|path| {
+                let file = build_project_file_without_cache(path, &self.project.annotation_parser, header_lines);
+                let team = file.owner.filter(|owner| !self.project.is_ignored_owner(owner))?;
+                Some(SuppressedAnnotation {
+                    path: self.project.relative_path(path).to_path_buf(),
+                    team,
+                })
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+            .sorted_by(|a, b| a.path.cmp(&b.path))
+            .collect()
+    }
+
+    /// Project files no mapper claims, relative to the project root.
+    pub fn unowned_files(&self) -> Vec<std::path::PathBuf> {
+        let validator = self.validator(false);
+
+        validator.unowned_files()
+    }
+
+    /// Every owned project file with its owners, one entry per owner, in path order.
+    pub fn ownership_index(&self) -> Vec<IndexEntry> {
+        let validator = self.validator(false);
+
+        validator
+            .owned_files()
+            .into_iter()
+            .flat_map(|(path, owners)| {
+                let path = crate::path_utils::to_slash_string_lossy(&path);
+                owners.into_iter().map(move |owner| {
+                    // Mappers name some teams by their GitHub team, so go through the team for both columns
+                    let (team_name, github_team) = match self.project.get_team(&owner.team_name) {
+                        Some(team) => (team.name, team.github_team),
+                        None => (owner.team_name, String::new()),
+                    };
+                    IndexEntry {
+                        path: path.clone(),
+                        team_name,
+                        github_team,
+                        source_kind: owner.sources.iter().min_by_key(|source| source.priority()).map_or("", Source::kind),
+                    }
+                })
+            })
+            .collect()
+    }
+
+    #[instrument(level = "debug", skip_all)]
+    pub fn validate_team(&self, team_name: &str) -> Result<(), ValidatorErrors> {
+        info!("validating file ownership for team {}", team_name);
+        let validator = self.validator(false);
+
+        validator.validate_team(team_name)
+    }
+
+    /// Like `validate_team`, but stops at the first error the team is responsible for.
+    #[instrument(level = "debug", skip_all)]
+    pub fn validate_team_fail_fast(&self, team_name: &str) -> Result<(), ValidatorErrors> {
+        info!("validating file ownership for team {}, stopping at the first error", team_name);
+        let validator = Validator {
+            fail_fast: true,
+            ..self.validator(false)
+        };
+
+        validator.validate_team(team_name)
+    }
+
     #[instrument(level = "debug", skip_all)]
     pub fn for_file(&self, file_path: &Path) -> Result<Vec<FileOwner>, ValidatorErrors> {
         info!("getting file ownership for {}", file_path.display());
         let owner_matchers: Vec<OwnerMatcher> = self.mappers().iter().flat_map(|mapper| mapper.owner_matchers()).collect();
         let file_owner_finder = FileOwnerFinder {
             owner_matchers: &owner_matchers,
+            case_insensitive_paths: self.project.case_insensitive_paths,
         };
         let owners = file_owner_finder.find(Path::new(file_path));
         Ok(owners
             .iter()
-            .sorted_by_key(|owner| owner.team_name.to_lowercase())
             .map(|owner| match self.project.get_team(&owner.team_name) {
                 Some(team) => FileOwner {
                     team: team.clone(),
@@ -151,6 +378,7 @@ impl Ownership {
                 },
                 None => FileOwner::default(),
             })
+            .sorted_by(FileOwner::cmp_precedence)
             .collect())
     }
 
@@ -163,30 +391,221 @@ impl Ownership {
         parse_for_team(team.github_team, &codeowners_file)
     }
 
+    /// Like `for_team`, but pairs each glob with the source that produced it: the mapper named by the
+    /// glob's section, asked which of its matchers assigns the glob's path to the team.
+    #[instrument(level = "debug", skip_all)]
+    pub fn for_team_with_sources(&self, team_name: &str) -> Result<Vec<(TeamOwnership, GlobSources)>, Box<dyn Error>> {
+        let team_ownerships = self.for_team(team_name)?;
+        let team_name = &self.project.get_team(team_name).ok_or("Team not found")?.name;
+        let mappers = self.mappers();
+
+        Ok(team_ownerships
+            .into_iter()
+            .map(|team_ownership| {
+                let section_name = team_ownership.heading.trim_start_matches('#').trim();
+                let owner_matchers = mappers
+                    .iter()
+                    .find(|mapper| mapper.name() == section_name)
+                    .map(|mapper| mapper.owner_matchers())
+                    .unwrap_or_default();
+                let sources = team_ownership
+                    .globs
+                    .iter()
+                    .map(|glob| {
+                        let path = glob.trim_start_matches('/').replace("\\[", "[").replace("\\]", "]");
+                        // A glob matcher that is exactly this line wins over a broader one (e.g. a parent `.codeowner`)
+                        let exact_match = owner_matchers.iter().find_map(|owner_matcher| match owner_matcher {
+                            OwnerMatcher::Glob {
+                                glob,
+                                team_name: owner,
+                                source,
+                                ..
+                            } if *glob == path && owner == team_name => Some(source.clone()),
+                            _ => None,
+                        });
+                        exact_match.or_else(|| {
+                            owner_matchers.iter().find_map(|owner_matcher| {
+                                match owner_matcher.owner_for(Path::new(&path), self.project.case_insensitive_paths) {
+                                    (Some(owner), source) if owner == team_name => Some(source.clone()),
+                                    _ => None,
+                                }
+                            })
+                        })
+                    })
+                    .collect();
+                (team_ownership, sources)
+            })
+            .collect())
+    }
+
+    /// Teams with no enabled line in the generated CODEOWNERS besides the one for their own team file, sorted by
+    /// name. Such a team owns no file and no glob, so it may be dead.
+    pub fn unused_teams(&self) -> Vec<Team> {
+        let owning_teams: HashSet<TeamName> = self.ownership_entries().into_iter().map(|entry| entry.team_name).collect();
+        self.project
+            .teams
+            .iter()
+            .filter(|team| !owning_teams.contains(&team.name))
+            .sorted_by(|a, b| a.name.cmp(&b.name))
+            .cloned()
+            .collect()
+    }
+
+    /// Whether the generated CODEOWNERS would have an enabled line besides those for the team files, which every
+    /// team gets no matter what the config matches.
+    pub fn has_ownership_entries(&self) -> bool {
+        !self.ownership_entries().is_empty()
+    }
+
+    fn ownership_entries(&self) -> Vec<Entry> {
+        self.mappers()
+            .iter()
+            .filter(|mapper| mapper.source_kind() != "yml")
+            .flat_map(|mapper| mapper.entries())
+            .filter(|entry| !entry.disabled)
+            .collect()
+    }
+
+    /// Whether any team file lives under `relative_dir`.
+    pub fn has_team_files_under(&self, relative_dir: &Path) -> bool {
+        let dir = self.project.base_path.join(relative_dir);
+        self.project.teams.iter().any(|team| team.path.starts_with(&dir))
+    }
+
     #[instrument(level = "debug", skip_all)]
     pub fn generate_file(&self) -> String {
         info!("generating codeowners file");
-        let file_generator = FileGenerator { mappers: self.mappers() };
-        file_generator.generate_file()
+        self.file_generator().generate_file()
+    }
+
+    /// `generate_file` with only the sections named in `sections` (see `Mapper::section_name`), to debug which
+    /// section a line comes from. The appendix is left out.
+    pub fn generate_sections_file(&self, sections: &[String]) -> Result<String, String> {
+        let section_names = section_names();
+        if let Some(unknown) = sections.iter().find(|section| !section_names.contains(&section.as_str())) {
+            return Err(format!(
+                "Unknown section `{}`; expected one of {}",
+                unknown,
+                section_names.join(", ")
+            ));
+        }
+        Ok(FileGenerator {
+            only_sections: Some(sections.to_vec()),
+            ..self.file_generator()
+        }
+        .generate_file())
+    }
+
+    /// `generate_file` with the generated sections replaced by the fewest lines that still give each of
+    /// `file_paths` (relative to the project root, `/`-separated) the same owners. The appendix is kept as is.
+    /// Every file is re-resolved against the result, so a mismatch is reported rather than written.
+    #[instrument(level = "debug", skip_all)]
+    pub fn generate_minimized_file(&self, file_paths: &[String]) -> Result<String, String> {
+        info!("generating minimized codeowners file");
+        let generated_sections = FileGenerator {
+            appendix: None,
+            ..self.file_generator()
+        }
+        .generate_file();
+        let owners = codeowners_minimizer::owners_by_file(&generated_sections, file_paths);
+
+        let mut lines = FileGenerator::disclaimer();
+        lines.push("# Minimized ownership".to_owned());
+        lines.append(&mut codeowners_minimizer::minimized_lines(&owners));
+        lines.push("".to_owned());
+        if let Some(appendix) = &self.project.codeowners_appendix {
+            lines.push(appendix.clone());
+        }
+        let minimized = lines.join("\n");
+
+        let expected = codeowners_minimizer::owners_by_file(&self.generate_file(), file_paths);
+        let actual = codeowners_minimizer::owners_by_file(&minimized, file_paths);
+        if let Some(file_path) = file_paths
+            .iter()
+            .find(|file_path| expected[file_path.as_str()] != actual[file_path.as_str()])
+        {
+            return Err(format!(
+                "Minimizing CODEOWNERS would change the owner of {} from {} to {}",
+                file_path,
+                expected[file_path.as_str()].as_deref().unwrap_or("nobody"),
+                actual[file_path.as_str()].as_deref().unwrap_or("nobody")
+            ));
+        }
+        Ok(minimized)
+    }
+
+    /// The entries behind `generate_file`'s lines, for the `generate --sidecar` JSON file.
+    pub fn generate_sidecar(&self) -> Vec<SidecarEntry> {
+        self.file_generator().sidecar_entries(&self.project.teams_by_name)
+    }
+
+    /// The rules behind `export --format review-rules`, one per uncommented `generate_file` line.
+    pub fn review_rules(&self) -> Vec<ReviewRule> {
+        self.file_generator().review_rules(&self.project.teams_by_name)
+    }
+
+    fn file_generator(&self) -> FileGenerator {
+        FileGenerator {
+            mappers: self.mappers(),
+            appendix: self.project.codeowners_appendix.clone(),
+            only_sections: None,
+        }
     }
 
     fn mappers(&self) -> Vec<Box<dyn Mapper>> {
-        vec![
-            Box::new(TeamFileMapper::build(self.project.clone())),
-            Box::new(TeamGlobMapper::build(self.project.clone())),
-            Box::new(DirectoryMapper::build(self.project.clone())),
-            Box::new(RubyPackageMapper::build(self.project.clone())),
-            Box::new(JavascriptPackageMapper::build(self.project.clone())),
-            Box::new(TeamYmlMapper::build(self.project.clone())),
-            Box::new(TeamGemMapper::build(self.project.clone())),
-        ]
+        mappers_for(&self.project)
     }
 }
 
+/// Every mapper over `project`, in the order `generate` writes their sections.
+fn mappers_for(project: &Arc<Project>) -> Vec<Box<dyn Mapper>> {
+    vec![
+        Box::new(DefaultOwnerMapper::build(project.clone())),
+        Box::new(ExtensionMapper::build(project.clone())),
+        Box::new(TeamFileMapper::build(project.clone())),
+        Box::new(TeamGlobMapper::build(project.clone())),
+        Box::new(DirectoryMapper::build(project.clone())),
+        Box::new(RubyPackageMapper::build(project.clone())),
+        Box::new(JavascriptPackageMapper::build(project.clone())),
+        Box::new(CargoPackageMapper::build(project.clone())),
+        Box::new(TeamYmlMapper::build(project.clone())),
+        Box::new(TeamGemMapper::build(project.clone())),
+        Box::new(OverrideMapper::build(project.clone())),
+    ]
+}
+
+/// Every mapper's section heading (see `Mapper::name`), in the order `generate` writes them.
+pub(crate) fn section_headings() -> Vec<String> {
+    mappers_for(&Arc::new(Project::default()))
+        .iter()
+        .map(|mapper| mapper.name())
+        .collect()
+}
+
+/// Every mapper's `Mapper::section_name`, as `generate --only` and `exclude_from_sections` take them, in section order.
+pub(crate) fn section_names() -> Vec<&'static str> {
+    mappers_for(&Arc::new(Project::default()))
+        .iter()
+        .map(|mapper| mapper.section_name())
+        .collect()
+}
+
+/// Every mapper's `Mapper::source_kind`, in section order. Several package mappers share one kind.
+pub(crate) fn source_kinds() -> Vec<&'static str> {
+    mappers_for(&Arc::new(Project::default()))
+        .iter()
+        .map(|mapper| mapper.source_kind())
+        .unique()
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::common_test::tests::build_ownership_with_all_mappers;
+    use crate::common_test::tests::{
+        DEFAULT_CODE_OWNERSHIP_YML, TestConfig, TestProjectFile, build_ownership, build_ownership_with_all_mappers,
+    };
+    use crate::config::Config;
 
     #[test]
     fn test_for_file_owner() -> Result<(), Box<dyn Error>> {
@@ -198,6 +617,42 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_for_file_orders_owners_like_the_single_file_resolver() -> Result<(), Box<dyn Error>> {
+        let temp_dir = tempfile::tempdir()?;
+        let team_file = |name: &str| TestProjectFile {
+            relative_path: format!("config/teams/{}.yml", name.to_lowercase()),
+            content: format!(
+                "name: {}\ngithub:\n  team: \"@{}\"\n  members:\n    - user\nowned_globs:\n  - app/shared/**/*\n",
+                name, name
+            ),
+        };
+        let files = vec![
+            team_file("Foo"),
+            team_file("Bar"),
+            TestProjectFile {
+                relative_path: "app/shared/.codeowner".to_owned(),
+                content: "Baz".to_owned(),
+            },
+            TestProjectFile {
+                relative_path: "app/shared/helper.rb".to_owned(),
+                content: "class Helper; end\n".to_owned(),
+            },
+        ];
+        let ownership = build_ownership(TestConfig::new(temp_dir.path().to_path_buf(), files))?;
+        let config: Config = serde_yaml::from_str(DEFAULT_CODE_OWNERSHIP_YML)?;
+        let file_path = Path::new("app/shared/helper.rb");
+
+        let team_names = |owners: Vec<FileOwner>| owners.into_iter().map(|owner| owner.team.name).collect::<Vec<_>>();
+        let expected = vec!["Baz", "Bar", "Foo"];
+        assert_eq!(team_names(ownership.for_file(file_path).unwrap()), expected);
+        assert_eq!(
+            team_names(file_owner_resolver::find_file_owners(temp_dir.path(), &config, file_path)?),
+            expected
+        );
+        Ok(())
+    }
+
     #[test]
     fn test_for_file_no_owner() -> Result<(), Box<dyn Error>> {
         let ownership = build_ownership_with_all_mappers()?;
@@ -221,4 +676,48 @@ mod tests {
         assert!(team_ownership.is_err(), "Team not found");
         Ok(())
     }
+
+    #[test]
+    fn test_generate_file_is_independent_of_traversal_order() -> Result<(), Box<dyn Error>> {
+        let ownership = build_ownership_with_all_mappers()?;
+        let expected = ownership.generate_file();
+
+        fn shuffled<T: Clone>(items: &[T], rotation: usize) -> Vec<T> {
+            let mut items = items.to_vec();
+            items.reverse();
+            if !items.is_empty() {
+                let len = items.len();
+                items.rotate_left(rotation % len);
+            }
+            items
+        }
+
+        let project = &ownership.project;
+        for rotation in 0..3 {
+            let shuffled_project = Project {
+                base_path: project.base_path.clone(),
+                files: shuffled(&project.files, rotation),
+                packages: shuffled(&project.packages, rotation),
+                vendored_gems: shuffled(&project.vendored_gems, rotation),
+                teams: shuffled(&project.teams, rotation),
+                codeowners_file_path: project.codeowners_file_path.clone(),
+                directory_codeowner_files: shuffled(&project.directory_codeowner_files, rotation),
+                teams_by_name: project.teams_by_name.clone(),
+                executable_name: project.executable_name.clone(),
+                ignored_owners: project.ignored_owners.clone(),
+                strict_annotation_lines: project.strict_annotation_lines,
+                annotation_parser: project.annotation_parser.clone(),
+                annotation_forbidden_globs: project.annotation_forbidden_globs.clone(),
+                extension_owners: project.extension_owners.clone(),
+                team_files_owner: project.team_files_owner.clone(),
+                default_owner: project.default_owner.clone(),
+                overrides: project.overrides.clone(),
+                codeowners_appendix: project.codeowners_appendix.clone(),
+                unowned_glob_files: vec![],
+                case_insensitive_paths: false,
+            };
+            assert_eq!(Ownership::build(shuffled_project).generate_file(), expected);
+        }
+        Ok(())
+    }
 }
@@ -0,0 +1,129 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{config::Config, project_file_builder::read_file_header};
+
+use super::file_owner_resolver::BatchResolver;
+
+/// An annotation `validate --fix` proposes for an unowned file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SuggestedAnnotation {
+    pub path: PathBuf,
+    pub team_name: String,
+    /// Keyword the annotation is written with (see `annotation_keywords` in the config).
+    pub keyword: String,
+}
+
+impl SuggestedAnnotation {
+    /// The header line written to the file, in the comment syntax of its extension.
+    pub fn annotation(&self) -> String {
+        let file_name = self.path.file_name().map(|name| name.to_string_lossy()).unwrap_or_default();
+        let extension = file_name.rsplit('.').next().unwrap_or_default();
+        match extension {
+            "js" | "jsx" | "ts" | "tsx" | "mjs" | "cjs" => format!("// @{} {}", self.keyword, self.team_name),
+            "erb" => format!("<%# @{}: {} %>", self.keyword, self.team_name),
+            "html" | "vue" | "svelte" => format!("<!-- @{} {} -->", self.keyword, self.team_name),
+            _ => format!("# @{} {}", self.keyword, self.team_name),
+        }
+    }
+
+    /// Inserts the annotation at the top of the file, below a shebang if there is one.
+    pub fn apply(&self, project_root: &Path) -> std::io::Result<()> {
+        let path = project_root.join(&self.path);
+        let content = fs::read_to_string(&path)?;
+        let annotation = self.annotation();
+        let annotated = match content.split_once('\n') {
+            Some((shebang, rest)) if shebang.starts_with("#!") => format!("{}\n{}\n{}", shebang, annotation, rest),
+            None if content.starts_with("#!") => format!("{}\n{}\n", content, annotation),
+            _ => format!("{}\n{}", annotation, content),
+        };
+        fs::write(&path, annotated)
+    }
+}
+
+/// Splits `unowned_files` into the annotations to suggest and the files no owner could be found for.
+pub fn suggest_annotations(
+    project_root: &Path,
+    config: &Config,
+    unowned_files: &[PathBuf],
+) -> Result<(Vec<SuggestedAnnotation>, Vec<PathBuf>), String> {
+    let resolver = BatchResolver::new(project_root, config)?;
+    let mut suggestions = Vec::new();
+    let mut without_suggestion = Vec::new();
+    for path in unowned_files {
+        // Annotating a binary file would corrupt it
+        if read_file_header(&project_root.join(path)).is_none() {
+            without_suggestion.push(path.clone());
+            continue;
+        }
+        match resolver.suggest_owner(path) {
+            Some(team_name) => suggestions.push(SuggestedAnnotation {
+                path: path.clone(),
+                team_name,
+                keyword: config.annotation_keyword().to_owned(),
+            }),
+            None => without_suggestion.push(path.clone()),
+        }
+    }
+    Ok((suggestions, without_suggestion))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn suggestion(path: &str) -> SuggestedAnnotation {
+        SuggestedAnnotation {
+            path: PathBuf::from(path),
+            team_name: "Payroll".to_owned(),
+            keyword: "team".to_owned(),
+        }
+    }
+
+    #[test]
+    fn test_annotation_uses_comment_syntax_of_extension() {
+        assert_eq!(suggestion("app/models/payroll.rb").annotation(), "# @team Payroll");
+        assert_eq!(suggestion("frontend/index.tsx").annotation(), "// @team Payroll");
+        assert_eq!(suggestion("app/views/show.html.erb").annotation(), "<%# @team: Payroll %>");
+        assert_eq!(suggestion("public/index.html").annotation(), "<!-- @team Payroll -->");
+        assert_eq!(suggestion("Rakefile").annotation(), "# @team Payroll");
+
+        let squad = SuggestedAnnotation {
+            keyword: "squad".to_owned(),
+            ..suggestion("app/views/show.html.erb")
+        };
+        assert_eq!(squad.annotation(), "<%# @squad: Payroll %>");
+    }
+
+    #[test]
+    fn test_apply_inserts_annotation_at_top() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        fs::write(temp_dir.path().join("payroll.rb"), "class Payroll\nend\n").unwrap();
+
+        suggestion("payroll.rb").apply(temp_dir.path()).unwrap();
+        assert_eq!(
+            fs::read_to_string(temp_dir.path().join("payroll.rb")).unwrap(),
+            "# @team Payroll\nclass Payroll\nend\n"
+        );
+    }
+
+    #[test]
+    fn test_apply_preserves_shebang() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        fs::write(temp_dir.path().join("run.rb"), "#!/usr/bin/env ruby\nputs 1\n").unwrap();
+        fs::write(temp_dir.path().join("bare.rb"), "#!/usr/bin/env ruby").unwrap();
+
+        suggestion("run.rb").apply(temp_dir.path()).unwrap();
+        suggestion("bare.rb").apply(temp_dir.path()).unwrap();
+        assert_eq!(
+            fs::read_to_string(temp_dir.path().join("run.rb")).unwrap(),
+            "#!/usr/bin/env ruby\n# @team Payroll\nputs 1\n"
+        );
+        assert_eq!(
+            fs::read_to_string(temp_dir.path().join("bare.rb")).unwrap(),
+            "#!/usr/bin/env ruby\n# @team Payroll\n"
+        );
+    }
+}
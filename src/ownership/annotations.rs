@@ -0,0 +1,227 @@
+use regex::Regex;
+
+use crate::config::Config;
+
+/// A top-of-file ownership annotation such as `# @team Payroll`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OwnerAnnotation {
+    pub team: String,
+    /// 1-based line the annotation was found on.
+    pub line: usize,
+}
+
+/// Recognizes ownership annotations written with any of the config's `annotation_keywords`, e.g. `@team Foo`,
+/// `@team: Foo` and `team: Foo` for the default `team`, behind `#`, `//`, `<!--` or `<%#` comments. Built once per
+/// run, as compiling the patterns per file would be wasted work.
+#[derive(Debug, Clone)]
+pub struct AnnotationParser {
+    /// Also accepts forms like `# team Foo`, only on the first line after any shebang, where annotations always went
+    first_line_regex: Regex,
+    /// Requires `@team` or `team:` followed by whitespace, so prose such as `# teammates use this` further down the
+    /// header isn't read as an annotation
+    regex: Regex,
+}
+
+impl AnnotationParser {
+    pub fn new(keywords: &[String]) -> Self {
+        // No keywords at all would make every comment an annotation
+        if keywords.is_empty() {
+            return Self::default();
+        }
+        let keywords = keywords.iter().map(|keyword| regex::escape(keyword)).collect::<Vec<_>>().join("|");
+        let first_line_regex = Regex::new(&format!(r#"^(?:#|//|<!--|<%#)\s*(?:@?(?:{keywords}):?\s*)(.*?)\s*(?:-->|%>)?$"#))
+            .expect("error compiling regular expression");
+        let regex = Regex::new(&format!(
+            r#"^(?:#|//|<!--|<%#)\s*(?:@(?:{keywords})\b:?|(?:{keywords}):)\s+(.*?)\s*(?:-->|%>)?$"#
+        ))
+        .expect("error compiling regular expression");
+        Self { first_line_regex, regex }
+    }
+
+    pub fn from_config(config: &Config) -> Self {
+        Self::new(&config.annotation_keywords)
+    }
+
+    /// The first owner annotated within the first `header_lines` lines of `content` (`annotation_header_lines`), so
+    /// a shebang, magic comment, or license header may come before it.
+    pub fn parse_owner(&self, content: &str, header_lines: usize) -> Option<OwnerAnnotation> {
+        self.annotations(content, header_lines).next()
+    }
+
+    /// Every distinct team annotated within the first `header_lines` lines of `content`, in order of appearance.
+    pub(crate) fn annotated_teams(&self, content: &str, header_lines: usize) -> Vec<String> {
+        let mut teams: Vec<String> = Vec::new();
+        for annotation in self.annotations(content, header_lines) {
+            if !teams.contains(&annotation.team) {
+                teams.push(annotation.team);
+            }
+        }
+        teams
+    }
+
+    fn annotations<'a>(&'a self, content: &'a str, header_lines: usize) -> impl Iterator<Item = OwnerAnnotation> + 'a {
+        let first_line = if content.starts_with("#!") { 1 } else { 0 };
+        content.lines().take(header_lines).enumerate().filter_map(move |(index, line)| {
+            let regex = if index == first_line { &self.first_line_regex } else { &self.regex };
+            regex.captures(line).and_then(|cap| cap.get(1)).map(|m| OwnerAnnotation {
+                team: m.as_str().to_string(),
+                line: index + 1,
+            })
+        })
+    }
+}
+
+impl Default for AnnotationParser {
+    fn default() -> Self {
+        Self::new(&crate::config::default_annotation_keywords())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    fn parse_owner(content: &str, header_lines: usize) -> Option<OwnerAnnotation> {
+        AnnotationParser::default().parse_owner(content, header_lines)
+    }
+
+    fn annotated_teams(content: &str, header_lines: usize) -> Vec<String> {
+        AnnotationParser::default().annotated_teams(content, header_lines)
+    }
+
+    fn team(content: &str) -> Option<String> {
+        parse_owner(content, 5).map(|annotation| annotation.team)
+    }
+
+    #[test]
+    fn test_parse_owner_forms() {
+        let cases = [
+            ("// @team Foo", "Foo"),
+            ("// @team Foo Bar", "Foo Bar"),
+            ("// @team: Zoo Foo", "Zoo Foo"),
+            ("# @team Payroll", "Payroll"),
+            ("# @team: Bap Hap", "Bap Hap"),
+            ("<!-- @team: Zoink Err -->", "Zoink Err"),
+            ("<!-- @team Blast Off -->", "Blast Off"),
+            ("<%# @team: Zap %>", "Zap"),
+            ("<%# @team: Zap Zip%>", "Zap Zip"),
+            // `team:` without the @
+            ("# team: MyTeam", "MyTeam"),
+            ("// team: MyTeam", "MyTeam"),
+            ("<!-- team: MyTeam -->", "MyTeam"),
+            ("<%# team: MyTeam %>", "MyTeam"),
+        ];
+        for (line, expected) in cases {
+            assert_eq!(team(&format!("{line}\nputs 1\n")).as_deref(), Some(expected), "{line}");
+        }
+    }
+
+    #[test]
+    fn test_parse_owner_line() {
+        assert_eq!(
+            parse_owner("# @team Foo\nputs 1\n", 5),
+            Some(OwnerAnnotation {
+                team: "Foo".to_owned(),
+                line: 1
+            })
+        );
+        assert_eq!(
+            parse_owner("#!/usr/bin/env ruby\n// @team Foo\n", 5),
+            Some(OwnerAnnotation {
+                team: "Foo".to_owned(),
+                line: 2
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_owner_below_header_comments() {
+        assert_eq!(team("# frozen_string_literal: true\n# @team Payroll\n").as_deref(), Some("Payroll"));
+        assert_eq!(team("#!/usr/bin/env ruby\n\n# @team Foo\n").as_deref(), Some("Foo"));
+        assert_eq!(
+            parse_owner("# Copyright Acme\n# License: MIT\n\n# @team Foo\n# @team Bar\n", 5),
+            Some(OwnerAnnotation {
+                team: "Foo".to_owned(),
+                line: 4
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_owner_ignores_annotations_past_the_header() {
+        let content = "# frozen_string_literal: true\n\nclass Foo\n  def bar; end\nend\n# @team Foo\n";
+        assert_eq!(team(content), None);
+        assert_eq!(parse_owner(content, 6).map(|annotation| annotation.team).as_deref(), Some("Foo"));
+        assert_eq!(parse_owner("# frozen_string_literal: true\n# @team Foo\n", 1), None);
+        assert_eq!(team("#!/usr/bin/env ruby"), None);
+        assert_eq!(team(""), None);
+    }
+
+    #[test]
+    fn test_parse_owner_ignores_prose_below_the_first_line() {
+        assert_eq!(
+            team("# frozen_string_literal: true\n\n# teammates use this helper to compute payroll\n"),
+            None
+        );
+        assert_eq!(
+            team("# frozen_string_literal: true\n# teammates use this helper\n# @team Payroll\n").as_deref(),
+            Some("Payroll")
+        );
+        assert_eq!(team("# frozen_string_literal: true\n# team Payroll\n"), None);
+        assert_eq!(team("# frozen_string_literal: true\n// @teams Payroll\n"), None);
+        assert_eq!(team("# frozen_string_literal: true\n# @team:Payroll\n"), None);
+        // The header of a generated CODEOWNERS file
+        assert_eq!(
+            team(
+                "# STOP! - DO NOT EDIT THIS FILE MANUALLY\n#\n# CODEOWNERS is used for GitHub to suggest code/file owners to various GitHub\n# teams. This is useful when developers create Pull Requests since the\n"
+            ),
+            None
+        );
+        // The first line keeps accepting every form it always has
+        assert_eq!(team("# team Payroll\n").as_deref(), Some("Payroll"));
+        assert_eq!(team("#!/usr/bin/env ruby\n# team Payroll\n").as_deref(), Some("Payroll"));
+    }
+
+    #[test]
+    fn test_annotated_teams() {
+        let content = "# @team Foo\n# frozen_string_literal: true\n// @team Bar\n# @team Foo\n\n# @team Baz\n";
+        assert_eq!(annotated_teams(content, 5), vec!["Foo", "Bar"]);
+        assert_eq!(annotated_teams(content, 6), vec!["Foo", "Bar", "Baz"]);
+        assert_eq!(annotated_teams(content, 1), vec!["Foo"]);
+        assert!(annotated_teams("class Foo; end\n", 5).is_empty());
+    }
+
+    #[test]
+    fn test_custom_annotation_keywords() {
+        let squad = indoc! {"
+            # @squad Payroll
+            class Paycheck; end
+        "};
+        let squad_html = indoc! {"
+            <!-- squad: Payroll -->
+            <p>Paycheck</p>
+        "};
+        let team = indoc! {"
+            // @team Payroll
+            export class Paycheck {}
+        "};
+        let owner = indoc! {"
+            # @owner: Payroll
+            class Paycheck; end
+        "};
+
+        let parser = AnnotationParser::new(&["team".to_owned(), "squad".to_owned()]);
+        let owner_of = |content: &str| parser.parse_owner(content, 5).map(|annotation| annotation.team);
+        assert_eq!(owner_of(squad).as_deref(), Some("Payroll"));
+        assert_eq!(owner_of(squad_html).as_deref(), Some("Payroll"));
+        assert_eq!(owner_of(team).as_deref(), Some("Payroll"));
+        assert_eq!(owner_of(owner), None);
+
+        let owner_only = AnnotationParser::new(&["owner".to_owned()]);
+        let owner_of = |content: &str| owner_only.parse_owner(content, 5).map(|annotation| annotation.team);
+        assert_eq!(owner_of(team), None);
+        assert_eq!(owner_of(owner).as_deref(), Some("Payroll"));
+    }
+}
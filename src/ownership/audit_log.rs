@@ -0,0 +1,92 @@
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use itertools::Itertools;
+
+use super::file_owner_finder::Owner;
+use crate::path_utils::to_slash_string_lossy;
+
+/// Appends one NDJSON record per resolved file, for runs started with `--audit-log`.
+///
+/// Records are written from the resolver's worker threads as files resolve, so their order isn't stable. Write
+/// errors don't interrupt the run; the first one is kept and reported by `finish`.
+#[derive(Debug)]
+pub struct AuditLog {
+    inner: Mutex<AuditLogInner>,
+}
+
+#[derive(Debug)]
+struct AuditLogInner {
+    writer: BufWriter<File>,
+    error: Option<io::Error>,
+}
+
+#[derive(Debug, serde::Serialize)]
+struct AuditRecord {
+    path: String,
+    /// Comma-separated teams when the file has several owners, `None` when it has none
+    team: Option<String>,
+    /// The chosen owner's highest-priority source kind, `multiple` on a conflict
+    source: Option<&'static str>,
+    /// Seconds since the Unix epoch
+    timestamp: u64,
+}
+
+impl AuditLog {
+    /// Opens `path` for appending, creating it (and its parent directories) when missing.
+    pub fn open(path: &Path) -> io::Result<Self> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self {
+            inner: Mutex::new(AuditLogInner {
+                writer: BufWriter::new(file),
+                error: None,
+            }),
+        })
+    }
+
+    pub fn record(&self, relative_path: &Path, owners: &[Owner]) {
+        let (team, source) = match owners {
+            [] => (None, None),
+            [owner] => (
+                Some(owner.team_name.clone()),
+                Some(owner.sources.first().map_or("unowned", |source| source.kind())),
+            ),
+            many => (
+                Some(many.iter().map(|owner| owner.team_name.as_str()).sorted().join(",")),
+                Some("multiple"),
+            ),
+        };
+        let record = AuditRecord {
+            path: to_slash_string_lossy(relative_path),
+            team,
+            source,
+            timestamp: SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_secs()),
+        };
+
+        let mut inner = self.inner.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        if inner.error.is_some() {
+            return;
+        }
+        let written = serde_json::to_writer(&mut inner.writer, &record)
+            .map_err(io::Error::from)
+            .and_then(|_| inner.writer.write_all(b"\n"));
+        if let Err(err) = written {
+            inner.error = Some(err);
+        }
+    }
+
+    /// Flushes buffered records, returning the first error hit while writing them.
+    pub fn finish(&self) -> io::Result<()> {
+        let mut inner = self.inner.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Some(err) = inner.error.take() {
+            return Err(err);
+        }
+        inner.writer.flush()
+    }
+}
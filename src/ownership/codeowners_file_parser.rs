@@ -1,32 +1,77 @@
 use crate::{
-    ownership::{FileGenerator, TeamOwnership},
-    project::Team,
+    ownership::{FileGenerator, TeamOwnership, section_headings},
+    path_utils::to_slash_string_lossy,
+    project::{Team, team_file_paths},
 };
 use fast_glob::glob_match;
-use memoize::memoize;
 use rayon::prelude::*;
-use regex::Regex;
-use std::{collections::HashMap, error::Error, fs, io::Error as IoError, path::PathBuf};
+use std::{
+    collections::HashMap,
+    error::Error,
+    fs::File,
+    io::{BufRead, BufReader, Error as IoError},
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
 
 use super::file_generator::compare_lines;
 
 pub struct Parser {
-    pub project_root: PathBuf,
+    /// Base directory of `team_file_globs`
+    pub teams_root: PathBuf,
     pub codeowners_file_path: PathBuf,
     pub team_file_globs: Vec<String>,
+    /// Teams keyed by GitHub team name, loaded on first use and kept for this parser's lifetime only, so a
+    /// later parser sees edits to the team files
+    teams_by_name: OnceLock<Result<HashMap<String, Team>, String>>,
 }
 
 impl Parser {
+    pub fn new(teams_root: PathBuf, codeowners_file_path: PathBuf, team_file_globs: Vec<String>) -> Self {
+        Self {
+            teams_root,
+            codeowners_file_path,
+            team_file_globs,
+            teams_by_name: OnceLock::new(),
+        }
+    }
+
     pub fn teams_from_files_paths(&self, file_paths: &[PathBuf]) -> Result<HashMap<String, Option<Team>>, Box<dyn Error>> {
+        self.teams_from_entries(file_paths, || {
+            parse_codeowners_entries(self.codeowners_file_path.to_string_lossy().into_owned())
+        })
+    }
+
+    /// Like `teams_from_files_paths`, but matches against `codeowners_file` instead of the file at
+    /// `codeowners_file_path`, e.g. a CODEOWNERS read from another revision.
+    pub fn teams_from_codeowners_content(
+        &self,
+        codeowners_file: &str,
+        file_paths: &[PathBuf],
+    ) -> Result<HashMap<String, Option<Team>>, Box<dyn Error>> {
+        self.teams_from_entries(file_paths, || {
+            codeowners_entries_by_priority(codeowners_file.as_bytes()).unwrap_or_else(|e| {
+                eprintln!("Error reading codeowners file: {}", e);
+                vec![]
+            })
+        })
+    }
+
+    fn teams_from_entries(
+        &self,
+        file_paths: &[PathBuf],
+        codeowners_entries: impl FnOnce() -> Vec<(String, String)>,
+    ) -> Result<HashMap<String, Option<Team>>, Box<dyn Error>> {
         let file_inputs: Vec<(String, String)> = file_paths
             .iter()
             .map(|path| {
-                let file_path_str = path
+                let original = path
                     .to_str()
-                    .ok_or(IoError::new(std::io::ErrorKind::InvalidInput, "Invalid file path"))?;
-                let original = file_path_str.to_string();
+                    .ok_or(IoError::new(std::io::ErrorKind::InvalidInput, "Invalid file path"))?
+                    .to_string();
+                let file_path_str = to_slash_string_lossy(path);
                 let prefixed = if file_path_str.starts_with('/') {
-                    original.clone()
+                    file_path_str
                 } else {
                     format!("/{}", file_path_str)
                 };
@@ -38,9 +83,8 @@ impl Parser {
             return Ok(HashMap::new());
         }
 
-        let codeowners_entries = parse_codeowners_entries(self.codeowners_file_path.to_string_lossy().into_owned());
-
-        let teams_by_name = teams_by_github_team_name(self.absolute_team_files_globs());
+        let teams_by_name = self.teams_by_name()?;
+        let codeowners_entries = codeowners_entries();
 
         let result: HashMap<String, Option<Team>> = file_inputs
             .par_iter()
@@ -56,63 +100,132 @@ impl Parser {
         Ok(result)
     }
 
-    fn absolute_team_files_globs(&self) -> Vec<String> {
-        self.team_file_globs
-            .iter()
-            .map(|glob| format!("{}/{}", self.project_root.display(), glob))
-            .collect()
+    fn teams_by_name(&self) -> Result<&HashMap<String, Team>, Box<dyn Error>> {
+        self.teams_by_name
+            .get_or_init(|| teams_by_github_team_name(&self.teams_root, &self.team_file_globs))
+            .as_ref()
+            .map_err(|e| e.clone().into())
     }
 }
 
-#[memoize]
-fn teams_by_github_team_name(team_file_glob: Vec<String>) -> HashMap<String, Team> {
-    let mut teams = HashMap::new();
-    for glob in team_file_glob {
-        match glob::glob(&glob) {
-            Ok(paths) => {
-                for path in paths.filter_map(Result::ok) {
-                    let team = match Team::from_team_file_path(path.clone()) {
-                        Ok(team) => team,
-                        Err(e) => {
-                            eprintln!("Error parsing team file: {e:?}, path: {}", path.display());
-                            continue;
-                        }
-                    };
-                    teams.insert(team.github_team.clone(), team);
-                }
-            }
-            Err(e) => {
-                eprintln!("Failed to read glob pattern '{}': {}", glob, e);
-                continue;
-            }
-        }
+/// Fails when the team files can't be listed, or the teams they define can't be told apart or resolved into a
+/// hierarchy. A team file that doesn't parse is reported and skipped.
+fn teams_by_github_team_name(teams_root: &Path, team_file_globs: &[String]) -> Result<HashMap<String, Team>, String> {
+    let paths = team_file_paths(teams_root, team_file_globs).map_err(|e| format!("Failed to read team file glob pattern: {}", e))?;
+
+    let mut teams = Vec::new();
+    for path in paths {
+        match Team::from_team_file_path(path.clone()) {
+            Ok(team) => teams.push(team),
+            Err(e) => eprintln!("Error parsing team file: {e:?}, path: {}", path.display()),
+        };
     }
 
-    teams
+    Team::ensure_unique(&teams).map_err(|e| format!("Failed to load teams: {e:?}"))?;
+    let teams = Team::resolve_parents(teams).map_err(|e| format!("Failed to resolve team parents: {e:?}"))?;
+    Ok(teams.into_iter().map(|team| (team.github_team.clone(), team)).collect())
 }
 
-fn build_codeowners_lines_in_priority(codeowners_file_path: String) -> Vec<String> {
-    let codeowners_file = match fs::read_to_string(codeowners_file_path) {
-        Ok(codeowners_file) => codeowners_file,
+fn parse_codeowners_entries(codeowners_file_path: String) -> Vec<(String, String)> {
+    match File::open(&codeowners_file_path).and_then(|file| codeowners_entries_by_priority(BufReader::new(file))) {
+        Ok(entries) => entries,
         Err(e) => {
-            // we can't return the error because it's not cloneable
             eprintln!("Error reading codeowners file: {}", e);
-            return vec![];
+            vec![]
         }
-    };
-    stripped_lines_by_priority(&codeowners_file)
+    }
 }
 
-fn parse_codeowners_entries(codeowners_file_path: String) -> Vec<(String, String)> {
-    build_codeowners_lines_in_priority(codeowners_file_path)
-        .iter()
-        .filter_map(|line| {
-            line.split_once(' ')
-                .map(|(glob, team_name)| (glob.to_string(), team_name.to_string()))
+/// The `(glob, owner)` pairs of a CODEOWNERS file, highest priority first: later sections before earlier ones,
+/// and each section's lines in reverse `compare_lines` order. Disabled `# /path` lines count as entries. The file
+/// is read a line at a time and only the current section is held unparsed, so very large files never sit in
+/// memory whole.
+fn codeowners_entries_by_priority(reader: impl BufRead) -> std::io::Result<Vec<(String, String)>> {
+    let mut entries = Vec::new();
+    let mut section_lines: Vec<String> = Vec::new();
+    let mut in_section = false;
+    let mut previous_line_blank = true;
+
+    for raw_line in reader.lines() {
+        let raw_line = raw_line?;
+        let is_heading = raw_line.starts_with('#') && !raw_line.starts_with("# /") && previous_line_blank;
+        previous_line_blank = raw_line.trim().is_empty();
+        let line = match raw_line.strip_prefix("# /") {
+            Some(rest) => format!("/{}", rest),
+            None => raw_line,
+        };
+        if line.is_empty() {
+            continue;
+        }
+
+        if is_heading {
+            // Lines above the first heading belong to the first section
+            if in_section {
+                push_section_entries(&mut entries, &mut section_lines);
+            }
+            in_section = true;
+        } else if !line.starts_with('#') {
+            section_lines.push(line);
+        }
+    }
+    if in_section {
+        push_section_entries(&mut entries, &mut section_lines);
+    }
+
+    entries.reverse();
+    Ok(entries)
+}
+
+fn push_section_entries(entries: &mut Vec<(String, String)>, section_lines: &mut Vec<String>) {
+    section_lines.sort_by(compare_lines);
+    entries.extend(section_lines.drain(..).filter_map(|line| {
+        line.split_once(' ')
+            .map(|(glob, team_name)| (glob.to_string(), team_name.to_string()))
+    }));
+}
+
+/// A CODEOWNERS line whose pattern matches a path.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct PatternMatch {
+    /// 1-based line number in the CODEOWNERS file.
+    pub line_number: usize,
+    pub glob: String,
+    pub owners: Vec<String>,
+}
+
+/// Every line of `codeowners_file` whose pattern matches `path` (a `/`-prefixed path relative to the project
+/// root), in file order. Comments and disabled `# /path` lines are skipped as GitHub skips them; GitHub applies
+/// the last match.
+pub fn matching_patterns(codeowners_file: &str, path: &str) -> Vec<PatternMatch> {
+    codeowners_file
+        .lines()
+        .enumerate()
+        .filter_map(|(index, line)| {
+            let (glob, owners) = parse_rule(line)?;
+            if !glob_match(glob, path) {
+                return None;
+            }
+            Some(PatternMatch {
+                line_number: index + 1,
+                glob: glob.to_owned(),
+                owners: owners.into_iter().map(str::to_owned).collect(),
+            })
         })
         .collect()
 }
 
+/// The pattern and owners of a CODEOWNERS rule, up to any trailing comment. `None` for blank lines and comments,
+/// including disabled `# /path` lines.
+pub(crate) fn parse_rule(line: &str) -> Option<(&str, Vec<&str>)> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+    let mut tokens = line.split_whitespace();
+    let glob = tokens.next()?;
+    Some((glob, tokens.take_while(|token| !token.starts_with('#')).collect()))
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 struct Section {
     heading: String,
@@ -130,50 +243,107 @@ impl Section {
     }
 }
 
-fn codeowner_sections(codeowners_file: &str) -> Result<Vec<Section>, Box<dyn Error>> {
-    let un_ignore = Regex::new(r"^# \/")?;
-    let mut iter = codeowners_file.lines().peekable();
-    let mut sections = Vec::new();
-    let mut current_section = None;
-    let mut current_lines = Vec::new();
+/// Whether `line` heads a section: the first comment of a block of lines (at the start of the file or after a
+/// blank line) that isn't a disabled `# /path` entry. Any other comment is free-form and is dropped by the parsers,
+/// so hand-written notes inside a section neither split it nor become a section of their own.
+fn is_section_heading(line: &str, previous_line: Option<&str>) -> bool {
+    line.starts_with('#') && !line.starts_with("# /") && previous_line.is_none_or(|previous| previous.trim().is_empty())
+}
 
-    while let Some(line) = iter.next() {
-        let line = un_ignore.replace(line, "/").to_string();
-        if line.is_empty() {
-            continue;
+/// Re-sorts an existing CODEOWNERS file into the layout `generate` produces while keeping its entries.
+/// Sections follow `section_headings`, with unrecognized sections after them in their original order, and
+/// lines within a section are sorted with `compare_lines`. Disabled (`# /path`) lines stay disabled, and free-form
+/// comments inside a section are dropped, as `generate` would.
+pub fn normalize(codeowners_file: &str) -> String {
+    render_sections(sections_with_disabled_lines(codeowners_file))
+}
+
+/// Replaces the lines of `codeowners_file` under `subtree` (a `/`-separated directory relative to the
+/// project root) with those of `generated` under it, keeping every other line as is. Returns `None`
+/// when `codeowners_file` has lines outside the sections `generate` writes, since those can't be merged safely.
+pub fn merge_subtree(codeowners_file: &str, generated: &str, subtree: &str) -> Option<String> {
+    let prefix = format!("/{}/", subtree.trim_matches('/'));
+    let is_under_subtree = |line: &String| {
+        let path = line.strip_prefix("# ").unwrap_or(line).split(' ').next().unwrap_or_default();
+        path.replace("\\[", "[").replace("\\]", "]").starts_with(&prefix)
+    };
+
+    let mut sections = sections_with_disabled_lines(codeowners_file);
+    let section_headings = section_headings();
+    if sections.iter().any(|section| {
+        !section_headings
+            .iter()
+            .any(|heading| heading == section.heading.trim_start_matches('#').trim())
+    }) {
+        return None;
+    }
+    for section in &mut sections {
+        section.lines.retain(|line| !is_under_subtree(line));
+    }
+
+    for generated_section in sections_with_disabled_lines(generated) {
+        let lines: Vec<String> = generated_section.lines.into_iter().filter(&is_under_subtree).collect();
+        match sections.iter_mut().find(|section| section.heading == generated_section.heading) {
+            Some(section) => *section = Section::new(section.heading.clone(), [std::mem::take(&mut section.lines), lines].concat()),
+            None => sections.push(Section::new(generated_section.heading, lines)),
         }
+    }
+    sections.retain(|section| !section.lines.is_empty());
+
+    Some(render_sections(sections))
+}
 
-        if line.starts_with('#') {
-            if iter
-                .peek()
-                .map(|next| next.starts_with('/') || next.starts_with("# /"))
-                .unwrap_or(false)
-            {
-                if let Some(section_name) = current_section.take() {
-                    sections.push(Section::new(section_name, std::mem::take(&mut current_lines)));
+fn sections_with_disabled_lines(codeowners_file: &str) -> Vec<Section> {
+    let input: String = codeowners_file.replace(&FileGenerator::disclaimer().join("\n"), "");
+    let mut sections = Vec::new();
+    let mut current_section: Option<String> = None;
+    let mut current_lines = Vec::new();
+    let mut previous_line = None;
+
+    for line in input.trim_start().lines().map(str::trim_end) {
+        let is_heading = is_section_heading(line, previous_line);
+        previous_line = Some(line);
+        match line {
+            "" => continue,
+            disabled if disabled.starts_with("# /") => current_lines.push(disabled.to_owned()),
+            heading if is_heading => {
+                if current_section.is_some() || !current_lines.is_empty() {
+                    sections.push(Section::new(
+                        current_section.take().unwrap_or_default(),
+                        std::mem::take(&mut current_lines),
+                    ));
                 }
-                current_section = Some(line);
+                current_section = Some(heading.to_owned());
             }
-        } else {
-            current_lines.push(line);
+            _comment if line.starts_with('#') => {}
+            entry => current_lines.push(entry.to_owned()),
         }
     }
-
-    if let Some(section_name) = current_section {
-        sections.push(Section::new(section_name, current_lines));
+    if current_section.is_some() || !current_lines.is_empty() {
+        sections.push(Section::new(current_section.unwrap_or_default(), current_lines));
     }
-
-    Ok(sections)
+    sections
 }
 
-fn stripped_lines_by_priority(codeowners_file: &str) -> Vec<String> {
-    let mut lines = Vec::new();
-    let sections = codeowner_sections(codeowners_file).unwrap_or_default();
+fn render_sections(mut sections: Vec<Section>) -> String {
+    let section_headings = section_headings();
+    sections.sort_by_key(|section| {
+        let name = section.heading.trim_start_matches('#').trim();
+        section_headings
+            .iter()
+            .position(|heading| heading == name)
+            .unwrap_or(section_headings.len())
+    });
+
+    let mut lines = FileGenerator::disclaimer();
     for section in sections {
+        if !section.heading.is_empty() {
+            lines.push(section.heading);
+        }
         lines.extend(section.lines);
+        lines.push("".to_owned());
     }
-    lines.reverse();
-    lines
+    lines.join("\n")
 }
 
 pub fn parse_for_team(team_name: String, codeowners_file: &str) -> Result<Vec<TeamOwnership>, Box<dyn Error>> {
@@ -182,14 +352,18 @@ pub fn parse_for_team(team_name: String, codeowners_file: &str) -> Result<Vec<Te
     let input: String = codeowners_file.replace(&FileGenerator::disclaimer().join("\n"), "");
     let error_message = "CODEOWNERS out of date. Run `codeowners generate` to update the CODEOWNERS file";
 
+    let mut previous_line = None;
     for line in input.trim_start().lines() {
+        let is_heading = is_section_heading(line, previous_line);
+        previous_line = Some(line);
         match line {
-            comment if comment.starts_with("#") => {
+            heading if is_heading => {
                 if let Some(section) = current_section.take() {
                     output.push(section);
                 }
-                current_section = Some(TeamOwnership::new(comment.to_string()));
+                current_section = Some(TeamOwnership::new(heading.to_string()));
             }
+            _comment if line.starts_with('#') => {}
             "" => {
                 if let Some(section) = current_section.take() {
                     output.push(section);
@@ -219,6 +393,7 @@ mod tests {
 
     use super::*;
     use indoc::indoc;
+    use regex::Regex;
 
     #[test]
     fn test_parse_for_team_trims_header() -> Result<(), Box<dyn Error>> {
@@ -372,37 +547,244 @@ mod tests {
             # Second Section
             path/to/owned @Foo
         "};
+        // Only the first comment of a block heads a section; the second is a free-form comment inside it
         let team_ownership = parse_for_team("@Foo".to_string(), codeownership_file)?;
+        vecs_match(
+            &team_ownership,
+            &vec![TeamOwnership {
+                heading: "# First Section".to_string(),
+                globs: vec!["path/to/owned".to_string()],
+            }],
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_normalize_sorts_shuffled_file() {
+        let shuffled = indoc! {"
+            # Team owned gems
+            /gems/pets/**/** @Pets
+
+            # Team-specific owned globs
+            /ruby/app/models/bank_account.rb @Payments
+            /javascript/packages/items/**/** @Payroll
+            # /ruby/app/legacy/**/** @Legacy
+            /ruby/app/models/**/** @Payroll
+
+            # Annotations at the top of file
+            /ruby/app/views/foos/new.html.erb @Payroll
+            /javascript/packages/list/page-admin.tsx @Payroll
+        "};
+
+        let mut expected = FileGenerator::disclaimer();
+        expected.extend(
+            [
+                "# Annotations at the top of file",
+                "/javascript/packages/list/page-admin.tsx @Payroll",
+                "/ruby/app/views/foos/new.html.erb @Payroll",
+                "",
+                "# Team-specific owned globs",
+                "/javascript/packages/items/**/** @Payroll",
+                "# /ruby/app/legacy/**/** @Legacy",
+                "/ruby/app/models/**/** @Payroll",
+                "/ruby/app/models/bank_account.rb @Payments",
+                "",
+                "# Team owned gems",
+                "/gems/pets/**/** @Pets",
+                "",
+            ]
+            .map(String::from),
+        );
+        let normalized = normalize(shuffled);
+        assert_eq!(normalized, expected.join("\n"));
+        assert_eq!(normalize(&normalized), normalized);
+    }
+
+    #[test]
+    fn test_free_form_comments_inside_sections() -> Result<(), Box<dyn Error>> {
+        let file = indoc! {"
+            # Team-specific owned globs
+            # Payments asked for this one, see the handbook
+            /b/** @Foo
+            # Temporarily owned by @Foo
+            # /c/** @Bar
+            /a/** @Foo
+
+            # Owner in .codeowner
+            /dir/**/** @Bar
+        "};
+
+        assert_eq!(entry_lines(file), vec!["/dir/**/** @Bar", "/c/** @Bar", "/b/** @Foo", "/a/** @Foo"]);
+
+        let team_ownership = parse_for_team("@Foo".to_string(), file)?;
         vecs_match(
             &team_ownership,
             &vec![
                 TeamOwnership {
-                    heading: "# First Section".to_string(),
-                    globs: vec![],
+                    heading: "# Team-specific owned globs".to_string(),
+                    globs: vec!["/b/**".to_string(), "/a/**".to_string()],
                 },
                 TeamOwnership {
-                    heading: "# Second Section".to_string(),
-                    globs: vec!["path/to/owned".to_string()],
+                    heading: "# Owner in .codeowner".to_string(),
+                    globs: vec![],
                 },
             ],
         );
+
+        let normalized = normalize(file);
+        let body: Vec<&str> = normalized.lines().skip(FileGenerator::disclaimer().len()).collect();
+        assert_eq!(
+            body,
+            vec![
+                "# Team-specific owned globs",
+                "/a/** @Foo",
+                "/b/** @Foo",
+                "# /c/** @Bar",
+                "",
+                "# Owner in .codeowner",
+                "/dir/**/** @Bar"
+            ]
+        );
+        assert_eq!(normalize(&normalized), normalized);
+
+        let merged = merge_subtree(file, &normalized, "a").expect("free-form comments are not unknown sections");
+        assert_eq!(merged, normalized);
         Ok(())
     }
 
     #[test]
-    fn test_stripped_lines_by_priority() -> Result<(), Box<dyn Error>> {
+    fn test_matching_patterns_in_file_order() {
+        let file = indoc! {"
+            # Team-specific owned globs
+            /app/** @Platform
+            # /app/models/** @Disabled
+            /app/models/*.rb @Models @Reviewers # shared with reviewers
+            /lib/** @Lib
+
+            # Owner in .codeowner
+            /app/models/**/** @Deep
+        "};
+
+        let matches = matching_patterns(file, "/app/models/user.rb");
+        assert_eq!(
+            matches,
+            vec![
+                PatternMatch {
+                    line_number: 2,
+                    glob: "/app/**".to_owned(),
+                    owners: vec!["@Platform".to_owned()],
+                },
+                PatternMatch {
+                    line_number: 4,
+                    glob: "/app/models/*.rb".to_owned(),
+                    owners: vec!["@Models".to_owned(), "@Reviewers".to_owned()],
+                },
+                PatternMatch {
+                    line_number: 8,
+                    glob: "/app/models/**/**".to_owned(),
+                    owners: vec!["@Deep".to_owned()],
+                },
+            ]
+        );
+        assert!(matching_patterns(file, "/config/routes.rb").is_empty());
+    }
+
+    #[test]
+    fn test_normalize_keeps_unknown_sections_last() {
+        let file = indoc! {"
+            # Hand written
+            /b @Foo
+            /a @Foo
+
+            # Owner in .codeowner
+            /dir/**/** @Bar
+        "};
+
+        let normalized = normalize(file);
+        let body: Vec<&str> = normalized.lines().skip(FileGenerator::disclaimer().len()).collect();
+        assert_eq!(
+            body,
+            vec![
+                "# Owner in .codeowner",
+                "/dir/**/** @Bar",
+                "",
+                "# Hand written",
+                "/a @Foo",
+                "/b @Foo"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_merge_subtree_replaces_only_lines_under_subtree() {
+        let existing = normalize(indoc! {"
+            # Annotations at the top of file
+            /packs/bar/app/bar.rb @Bar
+            /packs/foo/app/old.rb @Foo
+
+            # Owner in .codeowner
+            /packs/**/** @Bar
+            # /packs/foo/legacy/**/** @Legacy
+        "});
+        let generated = normalize(indoc! {"
+            # Annotations at the top of file
+            /packs/foo/app/new.rb @Foo
+
+            # Owner in .codeowner
+            /packs/**/** @Bar
+
+            # Owner metadata key in package.yml
+            /packs/foo/**/** @Foo
+            /packs/foobar/**/** @Bar
+        "});
+
+        let merged = merge_subtree(&existing, &generated, "packs/foo").unwrap();
+        let body: Vec<&str> = merged.lines().skip(FileGenerator::disclaimer().len()).collect();
+        assert_eq!(
+            body,
+            vec![
+                "# Annotations at the top of file",
+                "/packs/bar/app/bar.rb @Bar",
+                "/packs/foo/app/new.rb @Foo",
+                "",
+                "# Owner in .codeowner",
+                "/packs/**/** @Bar",
+                "",
+                "# Owner metadata key in package.yml",
+                "/packs/foo/**/** @Foo"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_merge_subtree_refuses_unknown_sections() {
+        let existing = indoc! {"
+            # Hand written
+            /packs/foo/app/old.rb @Foo
+        "};
+        assert_eq!(merge_subtree(existing, "", "packs/foo"), None);
+    }
+
+    fn entry_lines(codeowners_file: &str) -> Vec<String> {
+        codeowners_entries_by_priority(codeowners_file.as_bytes())
+            .unwrap()
+            .into_iter()
+            .map(|(glob, team_name)| format!("{} {}", glob, team_name))
+            .collect()
+    }
+
+    #[test]
+    fn test_entries_by_priority() {
         let codeownership_file = indoc! {"
             # First Section
             /path/to/owned @Foo
         "};
 
-        let stripped_lines = stripped_lines_by_priority(codeownership_file);
-        assert_eq!(stripped_lines, vec!["/path/to/owned @Foo"]);
-        Ok(())
+        assert_eq!(entry_lines(codeownership_file), vec!["/path/to/owned @Foo"]);
     }
 
     #[test]
-    fn test_stripped_lines_by_priority_with_multiple_sections() -> Result<(), Box<dyn Error>> {
+    fn test_entries_by_priority_with_multiple_sections() {
         let codeownership_file = indoc! {"
             # First Section
             /path/to/owned @Foo
@@ -411,13 +793,14 @@ mod tests {
             /another/path/to/owned @Bar
         "};
 
-        let stripped_lines = stripped_lines_by_priority(codeownership_file);
-        assert_eq!(stripped_lines, vec!["/another/path/to/owned @Bar", "/path/to/owned @Foo"]);
-        Ok(())
+        assert_eq!(
+            entry_lines(codeownership_file),
+            vec!["/another/path/to/owned @Bar", "/path/to/owned @Foo"]
+        );
     }
 
     #[test]
-    fn test_stripped_lines_by_priority_with_ignored_teams() -> Result<(), Box<dyn Error>> {
+    fn test_entries_by_priority_with_ignored_teams() {
         let codeownership_file = indoc! {"
             # STOP! - DO NOT EDIT THIS FILE MANUALLY
             # This file was automatically generated by \"bin/codeownership validate\".
@@ -444,40 +827,9 @@ mod tests {
             /config/brakeman.ignore @Prefix/security
         "};
 
-        // build up each sections lines
-        // resort the lines without the '#'
-        // re-assemble the sections
-        // reverse sort
-        let codeowner_sections = codeowner_sections(codeownership_file)?;
-        assert_eq!(
-            codeowner_sections,
-            vec![
-                Section {
-                    heading: "# Annotations at the top of file".to_string(),
-                    lines: vec![
-                        "/app/assets/config/manifest.js @Prefix/team-foo".to_string(),
-                        "/config/application.rb @Prefix/team-bar".to_string(),
-                        "/config/i18n-tasks.yml.erb @Prefix/language-team".to_string()
-                    ]
-                },
-                Section {
-                    heading: "# Team-specific owned globs".to_string(),
-                    lines: vec![
-                        "/.codeclimate.yml @Prefix/climate-team".to_string(),
-                        "/.github/workflows/pull-translations.yml @Prefix/infra".to_string(),
-                        "/.github/workflows/push-sources.yml @Prefix/infra".to_string(),
-                        "/.vscode/extensions/z/**/* @Prefix/zteam".to_string(),
-                        "/Dockerfile @Prefix/docker-team".to_string(),
-                        "/bin/brakeman @Prefix/psecurity".to_string(),
-                        "/components/create.rb @Prefix/component-team".to_string(),
-                        "/config/brakeman.ignore @Prefix/security".to_string()
-                    ]
-                },
-            ]
-        );
-        let stripped_lines = stripped_lines_by_priority(codeownership_file);
+        // Each section's lines are re-sorted without the '#', then the whole list is reversed
         assert_eq!(
-            stripped_lines,
+            entry_lines(codeownership_file),
             vec![
                 "/config/brakeman.ignore @Prefix/security",
                 "/components/create.rb @Prefix/component-team",
@@ -492,7 +844,70 @@ mod tests {
                 "/app/assets/config/manifest.js @Prefix/team-foo"
             ]
         );
-        Ok(())
+    }
+
+    #[test]
+    fn test_entries_by_priority_edge_cases() {
+        let codeownership_file = indoc! {"
+            /before/any/heading @Early
+
+            # First Section
+            /b @Foo
+            # /a @Bar
+            # a note, not a heading
+              
+            # Second Section
+            /c @Baz
+        "};
+
+        assert_eq!(
+            entry_lines(codeownership_file),
+            vec!["/c @Baz", "/before/any/heading @Early", "/b @Foo", "/a @Bar", "  "]
+        );
+        assert!(entry_lines("/no/heading @Foo\n").is_empty());
+    }
+
+    #[test]
+    fn test_entries_by_priority_match_fixture_codeowners_files() {
+        // Generated files are already sorted, so their entries are the rules below the disclaimer, bottom up
+        let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+        let mut codeowners_paths: Vec<PathBuf> = glob::glob(&format!("{}/*/.github/CODEOWNERS", fixtures.display()))
+            .unwrap()
+            .chain(glob::glob(&format!("{}/custom_codeowners_path/*/CODEOWNERS", fixtures.display())).unwrap())
+            .map(Result::unwrap)
+            .collect();
+        codeowners_paths.sort();
+        assert!(codeowners_paths.len() > 5);
+
+        for codeowners_path in codeowners_paths {
+            let codeowners_file = std::fs::read_to_string(&codeowners_path).unwrap();
+            let mut expected: Vec<String> = codeowners_file
+                .lines()
+                .skip_while(|line| !line.trim().is_empty())
+                .map(|line| line.strip_prefix("# ").filter(|rest| rest.starts_with('/')).unwrap_or(line))
+                .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                .map(str::to_owned)
+                .collect();
+            expected.reverse();
+
+            let streamed = parse_codeowners_entries(codeowners_path.to_string_lossy().into_owned());
+            let streamed: Vec<String> = streamed
+                .into_iter()
+                .map(|(glob, team_name)| format!("{} {}", glob, team_name))
+                .collect();
+            assert_eq!(streamed, expected, "{}", codeowners_path.display());
+        }
+    }
+
+    #[test]
+    fn test_teams_from_files_paths_fails_on_duplicate_teams() {
+        let parser = Parser::new(
+            PathBuf::from("tests/fixtures/duplicate_team"),
+            PathBuf::from("tests/fixtures/duplicate_team/.github/CODEOWNERS"),
+            vec!["config/teams/**/*.yml".to_owned()],
+        );
+        let error = parser.teams_from_files_paths(&[PathBuf::from("app/paycheck.rb")]).unwrap_err();
+        assert!(error.to_string().starts_with("Failed to load teams"), "{}", error);
     }
 
     #[test]
@@ -0,0 +1,192 @@
+use std::collections::{BTreeMap, HashMap};
+
+use fast_glob::glob_match;
+use rayon::prelude::*;
+
+use super::file_generator::compare_lines;
+
+/// A CODEOWNERS line's pattern and its owners, joined by a space as written (empty for a line that unowns).
+struct Rule<'a> {
+    glob: &'a str,
+    owners: String,
+}
+
+fn rules(codeowners_file: &str) -> Vec<Rule<'_>> {
+    codeowners_file
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let mut tokens = line.split_whitespace();
+            let glob = tokens.next()?;
+            Some(Rule {
+                glob,
+                owners: tokens.collect::<Vec<_>>().join(" "),
+            })
+        })
+        .collect()
+}
+
+/// The owners GitHub assigns each of `file_paths` (relative to the project root, `/`-separated) under
+/// `codeowners_file`: those of the last matching line, or `None` when no line with owners matches.
+pub fn owners_by_file<'a>(codeowners_file: &str, file_paths: &'a [String]) -> HashMap<&'a str, Option<String>> {
+    let rules = rules(codeowners_file);
+    file_paths
+        .par_iter()
+        .map(|file_path| {
+            let path = format!("/{}", file_path);
+            let owners = rules
+                .iter()
+                .rev()
+                .find(|rule| glob_match(rule.glob, &path))
+                .map(|rule| rule.owners.clone())
+                .filter(|owners| !owners.is_empty());
+            (file_path.as_str(), owners)
+        })
+        .collect()
+}
+
+#[derive(Default)]
+struct Dir<'a> {
+    dirs: BTreeMap<&'a str, Dir<'a>>,
+    files: BTreeMap<&'a str, Option<&'a str>>,
+    /// How many files anywhere below this directory each owner has
+    owner_counts: HashMap<&'a str, usize>,
+    has_unowned_files: bool,
+}
+
+impl<'a> Dir<'a> {
+    fn insert(&mut self, components: &[&'a str], owners: Option<&'a str>) {
+        match owners {
+            Some(owners) => *self.owner_counts.entry(owners).or_default() += 1,
+            None => self.has_unowned_files = true,
+        }
+        match components {
+            [] => {}
+            [file_name] => {
+                self.files.insert(file_name, owners);
+            }
+            [dir_name, rest @ ..] => self.dirs.entry(dir_name).or_default().insert(rest, owners),
+        }
+    }
+
+    /// The owner with the most files below this directory, ties going to the first by name.
+    fn dominant_owner(&self) -> Option<&'a str> {
+        self.owner_counts
+            .iter()
+            .max_by(|(a, a_count), (b, b_count)| a_count.cmp(b_count).then_with(|| b.cmp(a)))
+            .map(|(owners, _)| *owners)
+    }
+
+    /// Pushes the lines this directory (`path`, without leading or trailing `/`) needs on top of `inherited`, the
+    /// owner an ancestor's `/**` line already gives it. A directory whose files are all owned gets a `/**` line for
+    /// its dominant owner, and lines only for what differs below it. A directory with unowned files can't, as
+    /// nothing would unown them again, so its subdirectories and files are handled one by one.
+    fn push_lines(&self, path: &str, inherited: Option<&'a str>, lines: &mut Vec<String>) {
+        let join = |name: &str| {
+            if path.is_empty() {
+                name.to_owned()
+            } else {
+                format!("{}/{}", path, name)
+            }
+        };
+
+        let mut current = inherited;
+        if !self.has_unowned_files {
+            let dominant = self.dominant_owner();
+            if dominant != inherited
+                && let Some(owners) = dominant
+            {
+                lines.push(format!("/{} {}", join("**"), owners));
+                current = dominant;
+            }
+            if self.owner_counts.len() == 1 {
+                return;
+            }
+        }
+
+        for (name, dir) in &self.dirs {
+            dir.push_lines(&join(name), current, lines);
+        }
+        for (name, owners) in &self.files {
+            if *owners != current
+                && let Some(owners) = owners
+            {
+                lines.push(format!("/{} {}", join(name), owners));
+            }
+        }
+    }
+}
+
+/// The fewest `/dir/**` and `/file` lines, found greedily, giving each file in `owners` the same owners. Lines are
+/// sorted like generated ones, which puts every `/dir/**` line ahead of the lines below that directory, so the last
+/// matching line is still the most specific one.
+pub fn minimized_lines(owners: &HashMap<&str, Option<String>>) -> Vec<String> {
+    let mut root = Dir::default();
+    for (file_path, file_owners) in owners {
+        let components: Vec<&str> = file_path.split('/').collect();
+        root.insert(&components, file_owners.as_deref());
+    }
+
+    let mut lines = Vec::new();
+    root.push_lines("", None, &mut lines);
+    lines.sort_by(compare_lines);
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn owners<'a>(files: &[(&'a str, Option<&str>)]) -> HashMap<&'a str, Option<String>> {
+        files.iter().map(|(file, owners)| (*file, owners.map(str::to_owned))).collect()
+    }
+
+    #[test]
+    fn test_minimized_lines_merge_uniformly_owned_directories() {
+        let owners = owners(&[
+            ("app/models/a.rb", Some("@A")),
+            ("app/models/b.rb", Some("@A")),
+            ("app/models/c.rb", Some("@B")),
+            ("app/services/d.rb", Some("@A")),
+            ("lib/e.rb", Some("@B")),
+            ("README.md", None),
+        ]);
+
+        assert_eq!(minimized_lines(&owners), vec!["/app/** @A", "/app/models/c.rb @B", "/lib/** @B"]);
+    }
+
+    #[test]
+    fn test_minimized_lines_keep_files_with_unowned_siblings_separate() {
+        let owners = owners(&[("app/a.rb", Some("@A")), ("app/b.rb", None)]);
+
+        assert_eq!(minimized_lines(&owners), vec!["/app/a.rb @A"]);
+    }
+
+    #[test]
+    fn test_owners_by_file_uses_last_matching_line() {
+        let file_paths = vec!["app/a.rb".to_owned(), "app/b.rb".to_owned(), "lib/c.rb".to_owned()];
+        let codeowners = "# Heading\n/app/** @A\n/app/b.rb @B @C\n# /lib/** @D\n";
+
+        let owners = owners_by_file(codeowners, &file_paths);
+
+        assert_eq!(owners["app/a.rb"].as_deref(), Some("@A"));
+        assert_eq!(owners["app/b.rb"].as_deref(), Some("@B @C"));
+        assert_eq!(owners["lib/c.rb"], None);
+    }
+
+    #[test]
+    fn test_minimized_lines_reproduce_ownership() {
+        let file_paths: Vec<String> = ["a/x/1.rb", "a/x/2.rb", "a/y/3.rb", "a/4.rb", "b/5.rb", "b/c/6.rb", "7.rb"]
+            .iter()
+            .map(|path| path.to_string())
+            .collect();
+        let expanded = "/a/x/1.rb @A\n/a/x/2.rb @A\n/a/y/3.rb @B\n/a/4.rb @A\n/b/5.rb @B\n/b/c/6.rb @B\n/7.rb @B\n";
+
+        let expected = owners_by_file(expanded, &file_paths);
+        let minimized = minimized_lines(&expected).join("\n");
+
+        assert_eq!(minimized, "/** @B\n/a/** @A\n/a/y/** @B");
+        assert_eq!(owners_by_file(&minimized, &file_paths), expected);
+    }
+}
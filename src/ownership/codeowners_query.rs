@@ -1,32 +1,104 @@
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
-use crate::ownership::codeowners_file_parser::Parser;
+use path_clean::PathClean;
+
+use crate::git::run_git;
+use crate::ownership::codeowners_file_parser::{self, Parser, PatternMatch};
+use crate::path_utils::to_slash_string_lossy;
 use crate::project::Team;
 
+/// Matches `file_paths` against the CODEOWNERS globs only. The paths are normalized lexically and never
+/// looked up on disk, so files that have since been deleted still resolve to their last owner.
 pub(crate) fn teams_for_files_from_codeowners(
     project_root: &Path,
     codeowners_file_path: &Path,
+    teams_root: &Path,
     team_file_globs: &[String],
     file_paths: &[String],
 ) -> Result<HashMap<String, Option<Team>>, String> {
     let relative_file_paths: Vec<PathBuf> = file_paths
         .iter()
-        .map(Path::new)
-        .map(|path| {
-            if path.is_absolute() {
-                crate::path_utils::relative_to_buf(project_root, path)
-            } else {
-                path.to_path_buf()
-            }
-        })
+        .map(|path| normalized_relative_path(project_root, Path::new(path)))
         .collect();
 
-    let parser = Parser {
-        codeowners_file_path: codeowners_file_path.to_path_buf(),
-        project_root: project_root.to_path_buf(),
-        team_file_globs: team_file_globs.to_vec(),
-    };
+    let parser = Parser::new(
+        teams_root.to_path_buf(),
+        codeowners_file_path.to_path_buf(),
+        team_file_globs.to_vec(),
+    );
 
     parser.teams_from_files_paths(&relative_file_paths).map_err(|e| e.to_string())
 }
+
+/// Like `teams_for_files_from_codeowners`, but reads the CODEOWNERS file as it was at `git_ref` through
+/// `git show`, leaving the working tree alone. Teams still come from the current team files.
+pub(crate) fn teams_for_files_from_codeowners_at(
+    project_root: &Path,
+    codeowners_file_path: &Path,
+    git_ref: &str,
+    teams_root: &Path,
+    team_file_globs: &[String],
+    file_paths: &[String],
+    git_timeout: Duration,
+) -> Result<HashMap<String, Option<Team>>, String> {
+    let codeowners_file = codeowners_file_at(project_root, codeowners_file_path, git_ref, git_timeout)?;
+    let relative_file_paths: Vec<PathBuf> = file_paths
+        .iter()
+        .map(|path| normalized_relative_path(project_root, Path::new(path)))
+        .collect();
+
+    let parser = Parser::new(
+        teams_root.to_path_buf(),
+        codeowners_file_path.to_path_buf(),
+        team_file_globs.to_vec(),
+    );
+
+    parser
+        .teams_from_codeowners_content(&codeowners_file, &relative_file_paths)
+        .map_err(|e| e.to_string())
+}
+
+fn codeowners_file_at(project_root: &Path, codeowners_file_path: &Path, git_ref: &str, git_timeout: Duration) -> Result<String, String> {
+    // Otherwise git would take the ref as an option, e.g. `--output=<file>`
+    if git_ref.starts_with('-') {
+        return Err(format!("Invalid git ref `{}`: refs can't start with `-`", git_ref));
+    }
+    let relative_path = normalized_relative_path(project_root, codeowners_file_path);
+    // `./` makes git resolve the path against `project_root` rather than the repository root
+    let object = format!("{}:./{}", git_ref, to_slash_string_lossy(&relative_path));
+    let output = run_git(&["show", &object], project_root, git_timeout).map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        return Err(format!(
+            "Can't read {} at {}: {}",
+            relative_path.display(),
+            git_ref,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    String::from_utf8(output.stdout).map_err(|e| format!("{} at {} is not valid UTF-8: {}", relative_path.display(), git_ref, e))
+}
+
+/// Every CODEOWNERS pattern matching `file_path`, in file order. Like `teams_for_files_from_codeowners`, the
+/// path is never looked up on disk.
+pub(crate) fn matching_codeowners_patterns(
+    project_root: &Path,
+    codeowners_file_path: &Path,
+    file_path: &str,
+) -> Result<Vec<PatternMatch>, String> {
+    let codeowners_file =
+        std::fs::read_to_string(codeowners_file_path).map_err(|e| format!("Can't read {}: {}", codeowners_file_path.display(), e))?;
+    let relative_file_path = normalized_relative_path(project_root, Path::new(file_path));
+    let path = format!("/{}", to_slash_string_lossy(&relative_file_path).trim_start_matches('/'));
+    Ok(codeowners_file_parser::matching_patterns(&codeowners_file, &path))
+}
+
+fn normalized_relative_path(project_root: &Path, path: &Path) -> PathBuf {
+    let path = if path.is_absolute() {
+        crate::path_utils::relative_to_buf(project_root, path)
+    } else {
+        path.to_path_buf()
+    };
+    path.clean()
+}
@@ -1,9 +1,37 @@
-use std::cmp::Ordering;
+use std::{cmp::Ordering, collections::HashMap};
 
-use super::{Entry, Mapper};
+use serde::Serialize;
+
+use crate::project::Team;
+
+use super::{Entry, Mapper, codeowners_file_parser::parse_rule};
+
+/// `SidecarEntry::source_kind` of the rules copied from `extra_codeowners_file`.
+const APPENDIX_SOURCE_KIND: &str = "extra";
+
+/// One generated CODEOWNERS line, as written to the `generate --sidecar` JSON file.
+#[derive(Debug, Serialize)]
+pub struct SidecarEntry {
+    pub glob: String,
+    pub team_name: String,
+    pub github_team: String,
+    pub source_kind: &'static str,
+    pub disabled: bool,
+}
+
+/// A path pattern and the GitHub team slugs whose review it requires, as written by `export --format review-rules`.
+#[derive(Debug, Serialize)]
+pub struct ReviewRule {
+    pub pattern: String,
+    pub teams: Vec<String>,
+}
 
 pub struct FileGenerator {
     pub mappers: Vec<Box<dyn Mapper>>,
+    /// Hand-maintained rules written verbatim after the generated sections (see `extra_codeowners_file`).
+    pub appendix: Option<String>,
+    /// When set, only these sections (by `Mapper::section_name`) are written, without the appendix (see `generate --only`).
+    pub only_sections: Option<Vec<String>>,
 }
 
 impl FileGenerator {
@@ -11,19 +39,97 @@ impl FileGenerator {
         let mut lines: Vec<String> = Vec::new();
         lines.append(&mut Self::disclaimer());
 
-        for mapper in &self.mappers {
-            if mapper.entries().is_empty() {
+        for mapper in self.mappers.iter().filter(|mapper| self.includes_section(mapper.section_name())) {
+            let entries = mapper.entries();
+            if entries.is_empty() {
                 continue;
             }
 
             lines.push(format!("# {}", mapper.name()));
-            lines.append(&mut Self::to_sorted_lines(&mapper.entries()));
+            lines.append(&mut Self::to_sorted_lines(&entries));
             lines.push("".to_owned());
         }
 
+        if let Some(appendix) = &self.appendix
+            && self.only_sections.is_none()
+        {
+            lines.push(appendix.clone());
+        }
+
         lines.join("\n")
     }
 
+    fn includes_section(&self, section_name: &str) -> bool {
+        self.only_sections
+            .as_ref()
+            .is_none_or(|only_sections| only_sections.iter().any(|only| only == section_name))
+    }
+
+    /// Every line `generate_file` writes, in the same order, with the team and mapper behind it. An appendix rule
+    /// gets one entry per owner (or a single ownerless one), with the `team_name` of the team in `teams_by_name`
+    /// whose GitHub team it is, if any.
+    pub fn sidecar_entries(&self, teams_by_name: &HashMap<String, Team>) -> Vec<SidecarEntry> {
+        let mut sidecar_entries = self.generated_entries();
+        for (glob, owners) in self.appendix_rules() {
+            let owners = if owners.is_empty() { vec![""] } else { owners };
+            sidecar_entries.extend(owners.into_iter().map(|owner| SidecarEntry {
+                glob: glob.to_owned(),
+                team_name: teams_by_name.get(owner).map(|team| team.name.clone()).unwrap_or_default(),
+                github_team: owner.to_owned(),
+                source_kind: APPENDIX_SOURCE_KIND,
+                disabled: false,
+            }));
+        }
+        sidecar_entries
+    }
+
+    /// The rules of the appendix, in the order `generate_file` writes them.
+    fn appendix_rules(&self) -> Vec<(&str, Vec<&str>)> {
+        match &self.appendix {
+            Some(appendix) if self.only_sections.is_none() => appendix.lines().filter_map(parse_rule).collect(),
+            _ => vec![],
+        }
+    }
+
+    /// The entries of the generated sections, without the appendix.
+    fn generated_entries(&self) -> Vec<SidecarEntry> {
+        let mut sidecar_entries = Vec::new();
+        for mapper in &self.mappers {
+            let entries = mapper.entries();
+            sidecar_entries.extend(Self::sorted_entries(&entries).into_iter().map(|entry| SidecarEntry {
+                glob: format!("/{}", entry.path),
+                team_name: entry.team_name.clone(),
+                github_team: entry.github_team.clone(),
+                source_kind: mapper.source_kind(),
+                disabled: entry.disabled,
+            }));
+        }
+        sidecar_entries
+    }
+
+    /// One rule per line `generate_file` writes uncommented, in the same order, so a later rule overrides an earlier
+    /// one just as in CODEOWNERS. An appendix rule requires the owners that are teams: those in `teams_by_name` or
+    /// written as `@org/team`. Its users and emails can't be required reviewers, so they're left out.
+    pub fn review_rules(&self, teams_by_name: &HashMap<String, Team>) -> Vec<ReviewRule> {
+        let generated_rules = self
+            .generated_entries()
+            .into_iter()
+            .filter(|entry| !entry.disabled)
+            .map(|entry| ReviewRule {
+                pattern: entry.glob,
+                teams: vec![team_slug(&entry.github_team).to_owned()],
+            });
+        let appendix_rules = self.appendix_rules().into_iter().map(|(glob, owners)| ReviewRule {
+            pattern: glob.to_owned(),
+            teams: owners
+                .into_iter()
+                .filter(|owner| teams_by_name.contains_key(*owner) || (owner.starts_with('@') && owner.contains('/')))
+                .map(|owner| team_slug(owner).to_owned())
+                .collect(),
+        });
+        generated_rules.chain(appendix_rules).collect()
+    }
+
     pub fn disclaimer() -> Vec<String> {
         [
             "# STOP! - DO NOT EDIT THIS FILE MANUALLY",
@@ -41,10 +147,16 @@ impl FileGenerator {
         .collect()
     }
 
+    /// Mappers emit entries in project traversal order, which varies between runs and platforms.
+    /// `compare_lines` is a total order over rows, so sorting makes the section independent of it.
     fn to_sorted_lines(entries: &[Entry]) -> Vec<String> {
-        let mut lines: Vec<String> = entries.iter().map(|entry| entry.to_row()).collect();
-        lines.sort_by(compare_lines);
-        lines
+        Self::sorted_entries(entries).into_iter().map(|entry| entry.to_row()).collect()
+    }
+
+    fn sorted_entries(entries: &[Entry]) -> Vec<&Entry> {
+        let mut rows: Vec<(String, &Entry)> = entries.iter().map(|entry| (entry.to_row(), entry)).collect();
+        rows.sort_by(|(a, _), (b, _)| compare_lines(a, b));
+        rows.into_iter().map(|(_, entry)| entry).collect()
     }
 }
 
@@ -73,12 +185,24 @@ fn extract_path(line: &str) -> &str {
     stripped.split_once(' ').map(|(p, _)| p).unwrap_or(stripped)
 }
 
+/// A GitHub team handle without its `@` and organization, e.g. `payroll` for `@acme/payroll`.
+fn team_slug(github_team: &str) -> &str {
+    let handle = github_team.trim_start_matches('@');
+    handle.rsplit_once('/').map_or(handle, |(_, slug)| slug)
+}
+
+/// Wildcards sort before any literal character, so of two overlapping globs the more specific one (see
+/// `glob_specificity`) is written later and wins under CODEOWNERS' last-match rule.
 fn compare_component(a: &str, b: &str) -> Ordering {
+    let is_wildcard = |c: char| matches!(c, '*' | '?' | '[' | '{');
     match (a == "**", b == "**") {
         (true, true) => Ordering::Equal,
         (true, false) => Ordering::Less,
         (false, true) => Ordering::Greater,
-        (false, false) => a.cmp(b),
+        (false, false) => match a.chars().zip(b.chars()).find(|(ca, cb)| ca != cb) {
+            Some((ca, cb)) if is_wildcard(ca) != is_wildcard(cb) => is_wildcard(cb).cmp(&is_wildcard(ca)),
+            _ => a.cmp(b),
+        },
     }
 }
 
@@ -291,6 +415,17 @@ mod tests {
         assert_eq!(compare_lines(&other, &enabled), compare_lines(&other, &disabled));
     }
 
+    #[test]
+    fn test_compare_lines_orders_wildcards_before_literals() {
+        let broader = "/packs/?x/** @a".to_string();
+        let specific = "/packs/1x/** @b".to_string();
+        assert_eq!(compare_lines(&broader, &specific), Ordering::Less);
+
+        let broader = "/packs/*/** @a".to_string();
+        let specific = "/packs/(legacy)/** @b".to_string();
+        assert_eq!(compare_lines(&broader, &specific), Ordering::Less);
+    }
+
     #[test]
     fn test_sorting_with_special_characters() {
         let entries = vec![
@@ -310,4 +445,10 @@ mod tests {
         let sorted = FileGenerator::to_sorted_lines(&entries);
         assert_eq!(sorted, vec!["/directory/owner-1/** @foo", "/directory/owner_2/** @bar"]);
     }
+
+    #[test]
+    fn test_team_slug_drops_at_and_organization() {
+        assert_eq!(team_slug("@acme/payroll-team"), "payroll-team");
+        assert_eq!(team_slug("@PayrollTeam"), "PayrollTeam");
+    }
 }
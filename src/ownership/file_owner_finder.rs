@@ -1,8 +1,8 @@
-use std::{collections::HashMap, path::Path};
+use std::{cmp::Reverse, collections::HashMap, path::Path};
 
-use super::mapper::{OwnerMatcher, Source, TeamName};
+use super::mapper::{OwnerMatcher, Source, TeamName, glob_specificity};
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct Owner {
     pub sources: Vec<Source>,
     pub team_name: TeamName,
@@ -10,21 +10,39 @@ pub struct Owner {
 
 pub struct FileOwnerFinder<'a> {
     pub owner_matchers: &'a [OwnerMatcher],
+    /// Match globs ignoring case (see `case_insensitive_paths` in the config).
+    pub case_insensitive_paths: bool,
 }
 
 impl FileOwnerFinder<'_> {
     pub fn find(&self, relative_path: &Path) -> Vec<Owner> {
         let mut team_sources_map: HashMap<&TeamName, Vec<Source>> = HashMap::new();
         let mut directory_overrider = DirectoryOverrider::default();
+        let mut extension_owner: Option<(&TeamName, &Source)> = None;
+        let mut default_owner: Option<(&TeamName, &Source)> = None;
+        let mut override_owners: Vec<(&TeamName, &Source)> = Vec::new();
+        let mut team_glob_owners: Vec<(&TeamName, &Source)> = Vec::new();
 
         for owner_matcher in self.owner_matchers {
-            let (owner, source) = owner_matcher.owner_for(relative_path);
+            let (owner, source) = owner_matcher.owner_for(relative_path, self.case_insensitive_paths);
 
             if let Some(team_name) = owner {
                 match source {
                     Source::Directory(_) => {
                         directory_overrider.process(team_name, source);
                     }
+                    Source::Extension(_) => {
+                        extension_owner.get_or_insert((team_name, source));
+                    }
+                    Source::DefaultOwner => {
+                        default_owner.get_or_insert((team_name, source));
+                    }
+                    Source::Override(_) => {
+                        override_owners.push((team_name, source));
+                    }
+                    Source::TeamGlob(_) => {
+                        team_glob_owners.push((team_name, source));
+                    }
                     _ => {
                         team_sources_map.entry(team_name).or_default().push(source.clone());
                     }
@@ -32,21 +50,64 @@ impl FileOwnerFinder<'_> {
             }
         }
 
+        // The most specific matching override replaces every other claim; equally specific ones for different teams conflict
+        if !override_owners.is_empty() {
+            team_sources_map.clear();
+            for (team_name, source) in most_specific_globs(override_owners) {
+                team_sources_map.entry(team_name).or_default().push(source.clone());
+            }
+            return Self::owners(team_sources_map);
+        }
+
+        // Like directories, only the most specific matching team glob counts
+        for (team_name, source) in most_specific_globs(team_glob_owners) {
+            team_sources_map.entry(team_name).or_default().push(source.clone());
+        }
+
         // Add most specific directory owner if it exists
         if let Some((team_name, source)) = directory_overrider.specific_directory_owner() {
             team_sources_map.entry(team_name).or_default().push(source.clone());
         }
 
+        // Extension owners only apply to files nothing else claims, and the default owner to files not even they claim
+        if team_sources_map.is_empty()
+            && let Some((team_name, source)) = extension_owner.or(default_owner)
+        {
+            team_sources_map.entry(team_name).or_default().push(source.clone());
+        }
+
+        Self::owners(team_sources_map)
+    }
+
+    fn owners(team_sources_map: HashMap<&TeamName, Vec<Source>>) -> Vec<Owner> {
         team_sources_map
             .into_iter()
             .map(|(team_name, sources)| Owner {
-                sources,
+                sources: Source::normalize(sources),
                 team_name: team_name.clone(),
             })
             .collect()
     }
 }
 
+fn glob_source_specificity(source: &Source) -> (usize, Reverse<usize>) {
+    match source {
+        Source::TeamGlob(glob) | Source::Override(glob) => glob_specificity(glob),
+        _ => (0, Reverse(0)),
+    }
+}
+
+/// The claims whose glob is the most specific, keeping ties so that equally specific globs for different teams conflict.
+fn most_specific_globs<'a>(claims: Vec<(&'a TeamName, &'a Source)>) -> Vec<(&'a TeamName, &'a Source)> {
+    let Some(most_specific) = claims.iter().map(|(_, source)| glob_source_specificity(source)).max() else {
+        return claims;
+    };
+    claims
+        .into_iter()
+        .filter(|(_, source)| glob_source_specificity(source) == most_specific)
+        .collect()
+}
+
 /// DirectoryOverrider is used to override the owner of a directory if a more specific directory owner is found.
 #[derive(Debug, Default)]
 pub struct DirectoryOverrider<'a> {
@@ -1,101 +1,625 @@
 use std::{
-    collections::{HashMap, HashSet},
+    cmp::Reverse,
+    collections::{BTreeMap, HashMap, HashSet},
+    fmt::{self, Display},
     fs,
-    path::Path,
+    path::{Path, PathBuf},
 };
 
-use fast_glob::glob_match;
-use glob::glob;
+use itertools::Itertools;
+use path_clean::PathClean;
 
-use crate::{config::Config, project::Team, project_file_builder::build_project_file_without_cache};
+use crate::{
+    config::{Config, PackageDiscovery},
+    path_utils::{glob_matches, to_slash_str, to_slash_string_lossy},
+    project::{Team, team_file_paths},
+    project_file_builder::read_file_header,
+};
 
-use super::{FileOwner, mapper::Source};
+use super::{
+    FileOwner,
+    annotations::AnnotationParser,
+    mapper::{Source, glob_specificity},
+};
 
 pub fn find_file_owners(project_root: &Path, config: &Config, file_path: &Path) -> Result<Vec<FileOwner>, String> {
-    let absolute_file_path = if file_path.is_absolute() {
-        file_path.to_path_buf()
-    } else {
-        project_root.join(file_path)
-    };
-    let relative_file_path = crate::path_utils::relative_to_buf(project_root, &absolute_file_path);
+    Ok(trace_file_owners(project_root, config, file_path)?.file_owners(project_root))
+}
 
-    let teams = load_teams(project_root, &config.team_file_glob)?;
-    let teams_by_name = build_teams_by_name_map(&teams);
+/// A single mapper's verdict on a file: every team it claimed the file for, and why.
+#[derive(Debug, Clone)]
+pub struct MapperVerdict {
+    pub mapper: &'static str,
+    pub claims: Vec<(Team, Source)>,
+    pub note: Option<String>,
+}
 
-    let mut sources_by_team: HashMap<String, Vec<Source>> = HashMap::new();
+/// Every mapper's verdict on a file, in evaluation order, before the claims are collapsed into owners.
+#[derive(Debug, Clone)]
+pub struct OwnershipTrace {
+    pub relative_file_path: PathBuf,
+    pub verdicts: Vec<MapperVerdict>,
+}
 
-    if let Some(team_name) = read_top_of_file_team(&absolute_file_path) {
-        // Only consider top-of-file annotations for files included by config.owned_globs and not excluded by config.unowned_globs
-        if let Some(rel_str) = relative_file_path.to_str() {
-            let is_config_owned = glob_list_matches(rel_str, &config.owned_globs);
-            let is_config_unowned = glob_list_matches(rel_str, &config.unowned_globs);
-            if is_config_owned
-                && !is_config_unowned
-                && let Some(team) = teams_by_name.get(&team_name)
-            {
-                sources_by_team.entry(team.name.clone()).or_default().push(Source::AnnotatedFile);
-            }
+pub fn trace_file_owners(project_root: &Path, config: &Config, file_path: &Path) -> Result<OwnershipTrace, String> {
+    Ok(BatchResolver::new(project_root, config)?.trace(file_path))
+}
+
+/// Owners for each of `file_paths`, in order. Teams are loaded once and the `.codeowner` and package lookups are
+/// shared by every file in the same directory, so this is much cheaper than calling `find_file_owners` per file.
+pub fn find_files_owners<P: AsRef<Path>>(project_root: &Path, config: &Config, file_paths: &[P]) -> Result<Vec<Vec<FileOwner>>, String> {
+    let mut resolver = BatchResolver::new(project_root, config)?;
+    Ok(file_paths
+        .iter()
+        .map(|file_path| resolver.file_owners(file_path.as_ref()))
+        .collect())
+}
+
+/// Owners of a directory itself: its most specific `.codeowner` file and its nearest package, the claims a new
+/// file directly inside it would get before any annotation or glob.
+pub fn find_directory_owners(project_root: &Path, config: &Config, directory_path: &Path) -> Result<Vec<FileOwner>, String> {
+    Ok(BatchResolver::new(project_root, config)?.directory_owners(directory_path))
+}
+
+/// How many owned files under a directory each owner has, keyed by team name. Unowned files count towards
+/// `Unowned` and files with several owners towards the team names joined with `, `.
+pub fn directory_owner_breakdown(project_root: &Path, config: &Config, directory_path: &Path) -> Result<BTreeMap<String, usize>, String> {
+    let absolute_directory = project_root.join(directory_path);
+    let files: Vec<PathBuf> =
+        match crate::tracked_files::find_tracked_files(&absolute_directory, config.git_timeout(), config.include_submodules)
+            .map_err(|e| e.to_string())?
+        {
+            Some(tracked_files) => tracked_files.into_keys().collect(),
+            None => ignore::WalkBuilder::new(&absolute_directory)
+                .hidden(false)
+                .build()
+                .flatten()
+                .filter(|entry| entry.file_type().is_some_and(|file_type| file_type.is_file()))
+                .map(|entry| entry.into_path())
+                .collect(),
+        };
+
+    let mut resolver = BatchResolver::new(project_root, config)?;
+    let mut breakdown = BTreeMap::new();
+    for file in files {
+        let Some(rel_str) = to_slash_str(crate::path_utils::relative_to(project_root, &file)) else {
+            continue;
+        };
+        let is_config_owned = glob_list_matches(&rel_str, &config.owned_globs, config.case_insensitive_paths)
+            && !glob_list_matches(&rel_str, &config.unowned_globs, config.case_insensitive_paths);
+        if !is_config_owned || resolver.submodule_roots.iter().any(|root| file.starts_with(root)) {
+            continue;
         }
+        let owners = resolver.file_owners(&file);
+        let key = if owners.is_empty() {
+            FileOwner::default().team.name
+        } else {
+            owners.iter().map(|owner| owner.team.name.as_str()).join(", ")
+        };
+        *breakdown.entry(key).or_insert(0) += 1;
     }
+    Ok(breakdown)
+}
 
-    if let Some((owner_team_name, dir_source)) = most_specific_directory_owner(project_root, &relative_file_path, &teams_by_name) {
-        sources_by_team.entry(owner_team_name).or_default().push(dir_source);
+/// Calls `on_file` with the path, relative to the project root, of every tracked file `team_name` owns, in path
+/// order, as soon as it resolves. Files outside `owned_globs` or inside `unowned_globs`, other than team files, are
+/// skipped before any lookup, so the first files arrive without the whole project being resolved. Returns how many
+/// files were owned.
+pub fn stream_team_files(project_root: &Path, config: &Config, team_name: &str, mut on_file: impl FnMut(&Path)) -> Result<usize, String> {
+    let mut resolver = BatchResolver::new(project_root, config)?;
+    if !resolver.teams_by_name.contains_key(team_name) {
+        return Err(format!("Team not found: {}", team_name));
     }
 
-    if let Some((owner_team_name, package_source)) = nearest_package_owner(project_root, &relative_file_path, config, &teams_by_name) {
-        sources_by_team.entry(owner_team_name).or_default().push(package_source);
+    let team_files: HashSet<PathBuf> = resolver
+        .teams
+        .iter()
+        .map(|team| crate::path_utils::relative_to_buf(project_root, &team.path))
+        .collect();
+    let mut files = crate::team_lint::tracked_file_paths(project_root, config)?;
+    files.sort();
+    let mut owned = 0;
+    for file in files {
+        let relative_file_path = crate::path_utils::relative_to(project_root, &file);
+        let Some(rel_str) = to_slash_str(relative_file_path) else {
+            continue;
+        };
+        let is_config_owned = glob_list_matches(&rel_str, &config.owned_globs, config.case_insensitive_paths)
+            && !glob_list_matches(&rel_str, &config.unowned_globs, config.case_insensitive_paths);
+        if !is_config_owned && !team_files.contains(relative_file_path) {
+            continue;
+        }
+        if resolver.file_owners(&file).iter().any(|owner| owner.team.name == team_name) {
+            on_file(relative_file_path);
+            owned += 1;
+        }
+    }
+    Ok(owned)
+}
+
+/// The `.codeowner` and package claims for every file directly inside one directory: the nearest ones at or above it.
+#[derive(Debug, Clone)]
+struct DirectoryClaims {
+    directory: Option<(Team, Source)>,
+    package: Option<(Team, Source)>,
+}
+
+/// Resolves many files against one set of loaded teams, memoizing the directory-level lookups for each directory and
+/// its ancestors, so files in the same or neighbouring directories don't re-read the same `.codeowner` and manifests.
+pub struct BatchResolver<'a> {
+    project_root: &'a Path,
+    config: &'a Config,
+    teams: Vec<Team>,
+    teams_by_name: HashMap<String, Team>,
+    claims_by_directory: HashMap<PathBuf, DirectoryClaims>,
+    submodule_roots: Vec<PathBuf>,
+    annotation_parser: AnnotationParser,
+}
+
+impl<'a> BatchResolver<'a> {
+    pub fn new(project_root: &'a Path, config: &'a Config) -> Result<Self, String> {
+        Self::with_team_files(project_root, config, &[])
+    }
+
+    /// Like `new`, with `team_files` loaded on top of `team_file_glob` (see `load_teams_with_team_files`).
+    pub fn with_team_files(project_root: &'a Path, config: &'a Config, team_files: &[PathBuf]) -> Result<Self, String> {
+        let teams = load_teams_with_team_files(project_root, config, team_files)?;
+        let teams_by_name = build_teams_by_name_map(&teams);
+        Ok(Self {
+            project_root,
+            config,
+            teams,
+            teams_by_name,
+            claims_by_directory: HashMap::new(),
+            submodule_roots: excluded_submodule_roots(project_root, config),
+            annotation_parser: AnnotationParser::from_config(config),
+        })
+    }
+
+    pub fn file_owners(&mut self, file_path: &Path) -> Vec<FileOwner> {
+        self.trace(file_path).file_owners(self.project_root)
+    }
+
+    /// The team to suggest annotating an unowned file with: the nearest `.codeowner` owner, else the owner of the
+    /// nearest `package.yml`/`package.json`, even one outside the configured package paths.
+    pub fn suggest_owner(&self, file_path: &Path) -> Option<String> {
+        let (project_root, config, teams_by_name) = (self.project_root, self.config, &self.teams_by_name);
+        let relative_file_path = crate::path_utils::relative_to_buf(project_root, &project_root.join(file_path));
+        let any_package_config = Config {
+            package_discovery: crate::config::PackageDiscovery::Anywhere,
+            ..config.clone()
+        };
+        let owner = most_specific_directory_owner(project_root, &relative_file_path, config, teams_by_name)
+            .or_else(|| nearest_package_owner(project_root, &relative_file_path, &any_package_config, teams_by_name));
+        owner.map(|(team_name, _)| team_name)
+    }
+
+    /// See `explain_unowned`. No reasons when some mapper claimed the traced file.
+    pub fn unowned_reasons(&self, trace: &OwnershipTrace) -> Vec<String> {
+        if trace.verdicts.iter().any(|verdict| !verdict.claims.is_empty()) {
+            return vec![];
+        }
+        unowned_reasons(self, &trace.relative_file_path)
+    }
+
+    /// How many directories were read for `.codeowner` and package files so far. Each is read at most once.
+    pub fn directory_lookups(&self) -> usize {
+        self.claims_by_directory.len()
+    }
+
+    fn directory_claims(&mut self, relative_file_path: &Path) -> DirectoryClaims {
+        self.claims_for_directory(relative_file_path.parent().unwrap_or(Path::new("")))
+    }
+
+    /// The claims of the `.codeowner` and package manifest directly inside `relative_directory`, falling back to
+    /// those of its parent, which are looked up (and memoized) only when one of the two is missing.
+    fn claims_for_directory(&mut self, relative_directory: &Path) -> DirectoryClaims {
+        if let Some(claims) = self.claims_by_directory.get(relative_directory) {
+            return claims.clone();
+        }
+
+        let (project_root, config, teams_by_name) = (self.project_root, self.config, &self.teams_by_name);
+        let with_team = |(team_name, source): (String, Source)| teams_by_name.get(&team_name).map(|team| (team.clone(), source));
+        let mut claims = DirectoryClaims {
+            directory: directory_owner_in(project_root, relative_directory, config, teams_by_name).and_then(with_team),
+            package: package_owner_in(project_root, relative_directory, config, teams_by_name).and_then(with_team),
+        };
+        if (claims.directory.is_none() || claims.package.is_none())
+            && let Some(parent) = relative_directory.parent()
+        {
+            let inherited = self.claims_for_directory(parent);
+            claims.directory = claims.directory.or(inherited.directory);
+            claims.package = claims.package.or(inherited.package);
+        }
+        self.claims_by_directory.insert(relative_directory.to_path_buf(), claims.clone());
+        claims
+    }
+
+    pub fn directory_owners(&mut self, directory_path: &Path) -> Vec<FileOwner> {
+        let relative_directory = crate::path_utils::relative_to_buf(self.project_root, &self.project_root.join(directory_path));
+        // The claims are looked up by parent directory, so ask on behalf of a file inside it
+        let DirectoryClaims { directory, package } = self.directory_claims(&relative_directory.join("_"));
+        let trace = OwnershipTrace {
+            relative_file_path: relative_directory,
+            verdicts: vec![
+                MapperVerdict {
+                    mapper: "Owner in .codeowner",
+                    claims: directory.into_iter().collect(),
+                    note: None,
+                },
+                MapperVerdict {
+                    mapper: "Owner metadata key in package.yml, package.json or Cargo.toml",
+                    claims: package.into_iter().collect(),
+                    note: None,
+                },
+            ],
+        };
+        trace.file_owners(self.project_root)
+    }
+
+    pub fn trace(&mut self, file_path: &Path) -> OwnershipTrace {
+        let (project_root, config) = (self.project_root, self.config);
+        // Lexically, so `./a.rb`, `x/../a.rb`, and `a.rb/` all resolve like `a.rb`
+        let absolute_file_path = if file_path.is_absolute() {
+            file_path.clean()
+        } else {
+            project_root.join(file_path).clean()
+        };
+        let relative_file_path = crate::path_utils::relative_to_buf(project_root, &absolute_file_path);
+        let DirectoryClaims { directory, package } = self.directory_claims(&relative_file_path);
+        let (teams, teams_by_name) = (&self.teams, &self.teams_by_name);
+
+        let mut annotation_claims: Vec<(Team, Source)> = Vec::new();
+        let mut annotation_note: Option<String> = None;
+        if let Some(team_name) = read_top_of_file_team(&absolute_file_path, &self.annotation_parser, config.annotation_header_lines) {
+            // Only consider top-of-file annotations for files included by config.owned_globs and not excluded by config.unowned_globs
+            if let Some(rel_str) = to_slash_str(&relative_file_path) {
+                let is_config_owned = glob_list_matches(&rel_str, &config.owned_globs, config.case_insensitive_paths);
+                let is_config_unowned = glob_list_matches(&rel_str, &config.unowned_globs, config.case_insensitive_paths);
+                if is_config_owned && !is_config_unowned {
+                    if config.ignored_owners.contains(&team_name) {
+                        annotation_note = Some(format!(
+                            "The annotation owner `{}` is listed in `ignored_owners` and does not count towards ownership",
+                            team_name
+                        ));
+                    } else if let Some(team) = teams_by_name.get(&team_name) {
+                        annotation_claims.push((team.clone(), Source::AnnotatedFile));
+                    }
+                }
+            }
+        }
+
+        let directory_claims = directory.into_iter().collect();
+        let package_claims = package.into_iter().collect();
+
+        let mut team_glob_claims: Vec<(Team, Source)> = Vec::new();
+        if let Some(rel_str) = to_slash_str(&relative_file_path) {
+            for team in teams {
+                let subtracts: HashSet<&str> = team.subtracted_globs.iter().map(|s| s.as_str()).collect();
+                for owned_glob in &team.owned_globs {
+                    if glob_matches(owned_glob, &rel_str, config.case_insensitive_paths)
+                        && !subtracts
+                            .iter()
+                            .any(|sub| glob_matches(sub, &rel_str, config.case_insensitive_paths))
+                    {
+                        team_glob_claims.push((team.clone(), Source::TeamGlob(owned_glob.clone())));
+                    }
+                }
+            }
+        }
+        let team_glob_note = retain_most_specific_globs(&mut team_glob_claims);
+
+        let gem_claims = vendored_gem_owner(project_root, &relative_file_path, config, teams)
+            .and_then(|(team_name, source)| teams_by_name.get(&team_name).map(|team| (team.clone(), source)))
+            .into_iter()
+            .collect();
+
+        let mut team_yml_claims: Vec<(Team, Source)> = Vec::new();
+        for team in teams {
+            let team_rel = crate::path_utils::relative_to_buf(project_root, &team.path);
+            if team_rel != relative_file_path {
+                continue;
+            }
+            // With `team_files_owner` set, that team owns every team file (or none, if it's unknown)
+            let owning_team = match &config.team_files_owner {
+                Some(owner) => teams_by_name.get(owner),
+                None => Some(team),
+            };
+            if let Some(owning_team) = owning_team {
+                team_yml_claims.push((owning_team.clone(), Source::TeamYml));
+            }
+        }
+
+        let claimed_elsewhere = [
+            &annotation_claims,
+            &directory_claims,
+            &package_claims,
+            &team_glob_claims,
+            &gem_claims,
+            &team_yml_claims,
+        ]
+        .iter()
+        .any(|claims| !claims.is_empty());
+        let extension_claims = if claimed_elsewhere {
+            Vec::new()
+        } else {
+            extension_owner(&relative_file_path, config, teams_by_name).into_iter().collect()
+        };
+
+        let mut trace = OwnershipTrace {
+            relative_file_path,
+            verdicts: vec![
+                MapperVerdict {
+                    mapper: "Annotations at the top of file",
+                    claims: annotation_claims,
+                    note: annotation_note,
+                },
+                MapperVerdict {
+                    mapper: "Owner in .codeowner",
+                    claims: directory_claims,
+                    note: None,
+                },
+                MapperVerdict {
+                    mapper: "Owner metadata key in package.yml, package.json or Cargo.toml",
+                    claims: package_claims,
+                    note: None,
+                },
+                MapperVerdict {
+                    mapper: "Team-specific owned globs",
+                    claims: team_glob_claims,
+                    note: team_glob_note,
+                },
+                MapperVerdict {
+                    mapper: "Team owned gems",
+                    claims: gem_claims,
+                    note: None,
+                },
+                MapperVerdict {
+                    mapper: "Team YML ownership",
+                    claims: team_yml_claims,
+                    note: None,
+                },
+                MapperVerdict {
+                    mapper: "Owner by file extension",
+                    claims: extension_claims,
+                    note: None,
+                },
+            ],
+        };
+        // Only listed when configured, so traces of projects without a default owner read as before
+        if config.default_owner.is_some() {
+            let unclaimed = trace.verdicts.iter().all(|verdict| verdict.claims.is_empty());
+            trace.verdicts.push(MapperVerdict {
+                mapper: "Default owner",
+                claims: if unclaimed {
+                    default_owner(&trace.relative_file_path, config, teams_by_name)
+                        .into_iter()
+                        .collect()
+                } else {
+                    Vec::new()
+                },
+                note: None,
+            });
+        }
+        // Likewise only listed when configured. A matching override replaces every other claim
+        if !config.overrides.is_empty() {
+            let mut override_claims = override_owners(&trace.relative_file_path, config, teams_by_name);
+            let override_note = retain_most_specific_globs(&mut override_claims);
+            if !override_claims.is_empty() {
+                for verdict in trace.verdicts.iter_mut().filter(|verdict| !verdict.claims.is_empty()) {
+                    verdict.claims.clear();
+                    verdict.note = Some("overridden by `overrides` in the config".to_owned());
+                }
+            }
+            trace.verdicts.insert(
+                0,
+                MapperVerdict {
+                    mapper: "Ownership overrides",
+                    claims: override_claims,
+                    note: override_note,
+                },
+            );
+        }
+        // Files in a submodule aren't part of the project, whatever would otherwise claim them
+        if self.submodule_roots.iter().any(|root| absolute_file_path.starts_with(root)) {
+            for verdict in &mut trace.verdicts {
+                verdict.claims.clear();
+                verdict.note = None;
+            }
+        }
+        trace
     }
+}
 
-    if let Some((owner_team_name, gem_source)) = vendored_gem_owner(&relative_file_path, config, &teams) {
-        sources_by_team.entry(owner_team_name).or_default().push(gem_source);
+impl OwnershipTrace {
+    /// Remarks from mappers that looked at the file but deliberately declined to claim it.
+    pub fn notes(&self) -> Vec<String> {
+        self.verdicts.iter().filter_map(|verdict| verdict.note.clone()).collect()
     }
 
-    if let Some(rel_str) = relative_file_path.to_str() {
-        for team in &teams {
-            let subtracts: HashSet<&str> = team.subtracted_globs.iter().map(|s| s.as_str()).collect();
-            for owned_glob in &team.owned_globs {
-                if glob_match(owned_glob, rel_str) && !subtracts.iter().any(|sub| glob_match(sub, rel_str)) {
-                    sources_by_team
-                        .entry(team.name.clone())
-                        .or_default()
-                        .push(Source::TeamGlob(owned_glob.clone()));
+    /// Collapses the claims into one `FileOwner` per team, highest priority first.
+    pub fn file_owners(&self, project_root: &Path) -> Vec<FileOwner> {
+        let mut sources_by_team: HashMap<String, (Team, Vec<Source>)> = HashMap::new();
+        for (team, source) in self.verdicts.iter().flat_map(|verdict| verdict.claims.iter()) {
+            sources_by_team
+                .entry(team.name.clone())
+                .or_insert_with(|| (team.clone(), Vec::new()))
+                .1
+                .push(source.clone());
+        }
+
+        let mut file_owners: Vec<FileOwner> = sources_by_team
+            .into_values()
+            .map(|(team, sources)| {
+                let relative_team_yml_path = crate::path_utils::relative_to(project_root, &team.path)
+                    .to_string_lossy()
+                    .to_string();
+                FileOwner {
+                    team,
+                    team_config_file_path: relative_team_yml_path,
+                    sources: Source::normalize(sources),
                 }
+            })
+            .collect();
+
+        file_owners.sort_by(FileOwner::cmp_precedence);
+        file_owners
+    }
+}
+
+impl Display for OwnershipTrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Mapper evaluation order for {}:", self.relative_file_path.display())?;
+        for (index, verdict) in self.verdicts.iter().enumerate() {
+            match (&verdict.note, verdict.claims.is_empty()) {
+                (Some(note), _) => writeln!(f, "{}. {}: {}", index + 1, verdict.mapper, note)?,
+                (None, true) => writeln!(f, "{}. {}: no claim", index + 1, verdict.mapper)?,
+                (None, false) => {}
+            }
+            for (team, source) in &verdict.claims {
+                writeln!(
+                    f,
+                    "{}. {}: claimed by {} (priority {}) - {}",
+                    index + 1,
+                    verdict.mapper,
+                    team.name,
+                    source.priority(),
+                    source
+                )?;
             }
         }
+
+        let claims: Vec<&(Team, Source)> = self
+            .verdicts
+            .iter()
+            .flat_map(|verdict| verdict.claims.iter())
+            .sorted_by_key(|(team, source)| (source.priority(), team.name.clone(), source.to_string()))
+            .collect();
+        let claiming_teams: Vec<&str> = claims.iter().map(|(team, _)| team.name.as_str()).unique().collect();
+
+        match (claims.first(), claiming_teams.len()) {
+            (None, _) => write!(f, "\nOwner: Unowned\nReason: no mapper claimed the file"),
+            (Some((team, source)), 1) => write!(
+                f,
+                "\nOwner: {}\nReason: highest priority claim (priority {}) - {}",
+                team.name,
+                source.priority(),
+                source
+            ),
+            (Some((team, source)), _) => write!(
+                f,
+                "\nOwner: {}\nReason: highest priority claim (priority {}) - {}\nWarning: file is owned by multiple teams! ({})",
+                team.name,
+                source.priority(),
+                source,
+                claiming_teams.join(", ")
+            ),
+        }
     }
+}
 
-    for team in &teams {
-        let team_rel = crate::path_utils::relative_to_buf(project_root, &team.path);
-        if team_rel == relative_file_path {
-            sources_by_team.entry(team.name.clone()).or_default().push(Source::TeamYml);
+/// Human-readable reasons why no mapper claimed `file_path`, one per mapper that could have owned it.
+/// Returns no reasons when the file is owned.
+pub fn explain_unowned(project_root: &Path, config: &Config, file_path: &Path) -> Result<Vec<String>, String> {
+    let mut resolver = BatchResolver::new(project_root, config)?;
+    let trace = resolver.trace(file_path);
+    Ok(resolver.unowned_reasons(&trace))
+}
+
+fn excluded_submodule_roots(project_root: &Path, config: &Config) -> Vec<PathBuf> {
+    if config.include_submodules {
+        vec![]
+    } else {
+        crate::tracked_files::find_submodule_roots(project_root)
+    }
+}
+
+fn unowned_reasons(resolver: &BatchResolver, relative_file_path: &Path) -> Vec<String> {
+    let (project_root, config, teams_by_name) = (resolver.project_root, resolver.config, &resolver.teams_by_name);
+    let rel_str = to_slash_string_lossy(relative_file_path);
+    let case_insensitive = config.case_insensitive_paths;
+    let mut reasons = Vec::new();
+
+    let absolute_file_path = project_root.join(relative_file_path);
+    if let Some(root) = resolver.submodule_roots.iter().find(|root| absolute_file_path.starts_with(root)) {
+        reasons.push(format!(
+            "inside git submodule `{}`; set `include_submodules` to own its files",
+            crate::path_utils::relative_to(project_root, root).display()
+        ));
+    }
+    if !glob_list_matches(&rel_str, &config.owned_globs, case_insensitive) {
+        reasons.push("not matched by any owned_glob".to_owned());
+    }
+    for glob in config
+        .unowned_globs
+        .iter()
+        .filter(|glob| glob_matches(glob, &rel_str, case_insensitive))
+    {
+        reasons.push(format!("matched unowned_glob `{}`", glob));
+    }
+
+    let annotation_parser = AnnotationParser::from_config(config);
+    match read_top_of_file_team(
+        &project_root.join(relative_file_path),
+        &annotation_parser,
+        config.annotation_header_lines,
+    ) {
+        None => reasons.push("no annotation found".to_owned()),
+        Some(team_name) if config.ignored_owners.contains(&team_name) => {
+            reasons.push(format!("annotation owner `{}` is listed in `ignored_owners`", team_name))
         }
+        Some(team_name) if !teams_by_name.contains_key(&team_name) => {
+            reasons.push(format!("annotation references unknown team `{}`", team_name))
+        }
+        Some(team_name) => reasons.push(format!(
+            "annotation for `{}` only applies to files matched by owned_globs and not by unowned_globs",
+            team_name
+        )),
     }
 
-    let mut file_owners: Vec<FileOwner> = Vec::new();
-    for (team_name, sources) in sources_by_team.into_iter() {
-        if let Some(team) = teams_by_name.get(&team_name) {
-            let relative_team_yml_path = crate::path_utils::relative_to(project_root, &team.path)
-                .to_string_lossy()
-                .to_string();
-            file_owners.push(FileOwner {
-                team: team.clone(),
-                team_config_file_path: relative_team_yml_path,
-                sources,
-            });
+    match nearest_codeowner_file(project_root, relative_file_path) {
+        None => reasons.push("directory has no .codeowner up to the project root".to_owned()),
+        Some((directory, owner)) => reasons.push(format!(
+            "`{}/.codeowner` references unknown team `{}`",
+            to_slash_string_lossy(&directory),
+            owner
+        )),
+    }
+
+    match nearest_package_manifest(project_root, relative_file_path, config) {
+        None => reasons.push(missing_package_reason(config)),
+        Some(manifest) => reasons.push(format!("`{}` has no owner matching a team", to_slash_string_lossy(&manifest))),
+    }
+
+    let mut subtracted_by = Vec::new();
+    for team in &resolver.teams {
+        for owned_glob in team
+            .owned_globs
+            .iter()
+            .filter(|glob| glob_matches(glob, &rel_str, case_insensitive))
+        {
+            if let Some(subtracted) = team
+                .subtracted_globs
+                .iter()
+                .find(|glob| glob_matches(glob, &rel_str, case_insensitive))
+            {
+                subtracted_by.push(format!(
+                    "team `{}` owned_glob `{}` is excluded by its subtracted_glob `{}`",
+                    team.name, owned_glob, subtracted
+                ));
+            }
         }
     }
+    if subtracted_by.is_empty() {
+        reasons.push("not matched by any team's owned_globs".to_owned());
+    }
+    reasons.extend(subtracted_by);
 
-    // TODO: remove this once we've verified the fast path is working
-    // This is simply matching the order of behavior of the original codeowners CLI
-    if file_owners.len() > 1 {
-        file_owners.sort_by(|a, b| {
-            let priority_a = a.sources.iter().map(source_priority).min().unwrap_or(u8::MAX);
-            let priority_b = b.sources.iter().map(source_priority).min().unwrap_or(u8::MAX);
-            priority_a.cmp(&priority_b).then_with(|| a.team.name.cmp(&b.team.name))
-        });
+    if let Some(gem_name) = vendored_gem_name(relative_file_path, config) {
+        reasons.push(format!("vendored gem `{}` is not listed in any team's owned_gems", gem_name));
     }
 
-    Ok(file_owners)
+    reasons
 }
 
 fn build_teams_by_name_map(teams: &[Team]) -> HashMap<String, Team> {
@@ -107,122 +631,173 @@ fn build_teams_by_name_map(teams: &[Team]) -> HashMap<String, Team> {
     map
 }
 
-fn load_teams(project_root: &Path, team_file_globs: &[String]) -> std::result::Result<Vec<Team>, String> {
+pub(crate) fn load_teams(project_root: &Path, config: &Config) -> std::result::Result<Vec<Team>, String> {
+    load_teams_with_team_files(project_root, config, &[])
+}
+
+/// Loads the teams matched by `team_file_globs`, then the explicit `team_files` (see `for-file --team-file`). An
+/// explicit team file replaces any globbed team with the same name.
+pub(crate) fn load_teams_with_team_files(
+    project_root: &Path,
+    config: &Config,
+    team_files: &[PathBuf],
+) -> std::result::Result<Vec<Team>, String> {
+    let teams_root = config.resolve_teams_root(project_root);
     let mut teams: Vec<Team> = Vec::new();
-    for glob_str in team_file_globs {
-        let absolute_glob = project_root.join(glob_str).to_string_lossy().into_owned();
-        let paths = glob(&absolute_glob).map_err(|e| e.to_string())?;
-        for path in paths.flatten() {
-            match Team::from_team_file_path(path.clone()) {
-                Ok(team) => teams.push(team),
-                Err(e) => {
-                    eprintln!("Error parsing team file: {e:?}, path: {}", path.display());
-                    continue;
-                }
+    for path in team_file_paths(&teams_root, &config.team_file_glob).map_err(|e| e.to_string())? {
+        match Team::from_team_file_path(path.clone()) {
+            Ok(team) => teams.push(team),
+            Err(e) => {
+                eprintln!("Error parsing team file: {e:?}, path: {}", path.display());
+                continue;
             }
         }
     }
-    Ok(teams)
+    for path in team_files {
+        let team =
+            Team::from_team_file_path(project_root.join(path)).map_err(|e| format!("Can't load team file {}: {e:?}", path.display()))?;
+        teams.retain(|existing| existing.name != team.name);
+        teams.push(team);
+    }
+    Team::resolve_parents(teams).map_err(|e| format!("{e:?}"))
+}
+
+fn read_top_of_file_team(path: &Path, annotation_parser: &AnnotationParser, header_lines: usize) -> Option<String> {
+    read_file_header(path)
+        .and_then(|content| annotation_parser.parse_owner(&content, header_lines))
+        .map(|annotation| annotation.team)
+}
+
+/// The closest `.codeowner` file above `relative_file_path`, as its directory (relative to the project root) and trimmed contents.
+fn nearest_codeowner_file(project_root: &Path, relative_file_path: &Path) -> Option<(PathBuf, String)> {
+    let mut current = project_root.join(relative_file_path);
+    while current.pop() && current.starts_with(project_root) {
+        if let Ok(owner_str) = fs::read_to_string(current.join(".codeowner")) {
+            return Some((
+                crate::path_utils::relative_to_buf(project_root, &current),
+                owner_str.trim().to_owned(),
+            ));
+        }
+    }
+    None
 }
 
-fn read_top_of_file_team(path: &Path) -> Option<String> {
-    let project_file = build_project_file_without_cache(&path.to_path_buf());
-    if let Some(owner) = project_file.owner {
-        return Some(owner);
+/// The closest package manifest above `relative_file_path` that `nearest_package_owner` would consider, relative to the project root.
+/// Each package manifest's file name with the `*_package_paths` its packages are found under.
+fn package_manifests(config: &Config) -> [(&'static str, &[String]); 3] {
+    [
+        ("package.yml", &config.ruby_package_paths),
+        ("package.json", &config.javascript_package_paths),
+        ("Cargo.toml", &config.cargo_package_paths),
+    ]
+}
+
+/// Names the manifests a package could have been found through: those with package paths configured, or all of
+/// them when packages are discovered anywhere.
+fn missing_package_reason(config: &Config) -> String {
+    let manifests: Vec<&str> = package_manifests(config)
+        .into_iter()
+        .filter(|(_, package_paths)| config.package_discovery == PackageDiscovery::Anywhere || !package_paths.is_empty())
+        .map(|(manifest, _)| manifest)
+        .collect();
+    match manifests.split_last() {
+        None => "no package paths are configured".to_owned(),
+        Some((last, [])) => format!("no {} ancestor", last),
+        Some((last, rest)) => format!("no {} or {} ancestor", rest.join(", "), last),
     }
+}
 
+fn nearest_package_manifest(project_root: &Path, relative_file_path: &Path, config: &Config) -> Option<PathBuf> {
+    let mut current = project_root.join(relative_file_path);
+    while current.pop() && current.starts_with(project_root) {
+        let parent_rel = crate::path_utils::relative_to(project_root, current.as_path());
+        for (manifest, package_paths) in package_manifests(config) {
+            if config.package_discovery.is_package_dir(parent_rel, package_paths) && current.join(manifest).exists() {
+                return Some(parent_rel.join(manifest));
+            }
+        }
+    }
     None
 }
 
 fn most_specific_directory_owner(
     project_root: &Path,
     relative_file_path: &Path,
+    config: &Config,
     teams_by_name: &HashMap<String, Team>,
 ) -> Option<(String, Source)> {
-    let mut current = project_root.join(relative_file_path);
-    let mut best: Option<(String, Source)> = None;
-    loop {
-        if !current.pop() {
-            break;
-        }
-        let codeowner_path = current.join(".codeowner");
-        if let Ok(owner_str) = fs::read_to_string(&codeowner_path) {
-            let owner = owner_str.trim();
-            if let Some(team) = teams_by_name.get(owner) {
-                let relative_dir = crate::path_utils::relative_to(project_root, current.as_path())
-                    .to_string_lossy()
-                    .to_string();
-                let candidate = (team.name.clone(), Source::Directory(relative_dir));
-                match &best {
-                    None => best = Some(candidate),
-                    Some((_, existing_source)) => {
-                        if candidate.1.len() > existing_source.len() {
-                            best = Some(candidate);
-                        }
-                    }
-                }
-            }
-        }
-        if current == project_root {
-            break;
-        }
+    // The nearest `.codeowner` is the most specific one
+    relative_file_path
+        .ancestors()
+        .skip(1)
+        .find_map(|relative_dir| directory_owner_in(project_root, relative_dir, config, teams_by_name))
+}
+
+/// The known team named by the `.codeowner` file directly inside `relative_dir`, if any.
+fn directory_owner_in(
+    project_root: &Path,
+    relative_dir: &Path,
+    config: &Config,
+    teams_by_name: &HashMap<String, Team>,
+) -> Option<(String, Source)> {
+    // A gem's own `.codeowner` is handled by `vendored_gem_owner`
+    if config.is_vendored_gem_root(relative_dir) {
+        return None;
     }
-    best
+    let owner_str = fs::read_to_string(project_root.join(relative_dir).join(".codeowner")).ok()?;
+    let team = teams_by_name.get(owner_str.trim())?;
+    Some((team.name.clone(), Source::Directory(to_slash_string_lossy(relative_dir))))
 }
 
+/// The closest enclosing package whose manifest names a known team. Packages without an owner (or with an
+/// unknown one) are walked past, so their files inherit an outer package's owner.
 fn nearest_package_owner(
     project_root: &Path,
     relative_file_path: &Path,
     config: &Config,
     teams_by_name: &HashMap<String, Team>,
 ) -> Option<(String, Source)> {
-    let mut current = project_root.join(relative_file_path);
-    loop {
-        if !current.pop() {
-            break;
-        }
-        let parent_rel = crate::path_utils::relative_to(project_root, current.as_path());
-        if let Some(rel_str) = parent_rel.to_str() {
-            if glob_list_matches(rel_str, &config.ruby_package_paths) {
-                let pkg_yml = current.join("package.yml");
-                if pkg_yml.exists()
-                    && let Ok(owner) = read_ruby_package_owner(&pkg_yml)
-                    && let Some(team) = teams_by_name.get(&owner)
-                {
-                    let package_path = parent_rel.join("package.yml");
-                    let package_glob = format!("{rel_str}/**/**");
-                    return Some((
-                        team.name.clone(),
-                        Source::Package(package_path.to_string_lossy().to_string(), package_glob),
-                    ));
-                }
-            }
-            if glob_list_matches(rel_str, &config.javascript_package_paths) {
-                let pkg_json = current.join("package.json");
-                if pkg_json.exists()
-                    && let Ok(owner) = read_js_package_owner(&pkg_json)
-                    && let Some(team) = teams_by_name.get(&owner)
-                {
-                    let package_path = parent_rel.join("package.json");
-                    let package_glob = format!("{rel_str}/**/**");
-                    return Some((
-                        team.name.clone(),
-                        Source::Package(package_path.to_string_lossy().to_string(), package_glob),
-                    ));
-                }
-            }
+    relative_file_path
+        .ancestors()
+        .skip(1)
+        .find_map(|relative_dir| package_owner_in(project_root, relative_dir, config, teams_by_name))
+}
+
+/// The known team owning the package rooted at `relative_dir`, if it's a package whose manifest names one. A
+/// `package.yml` is read before a `package.json`, and that before a `Cargo.toml`.
+fn package_owner_in(
+    project_root: &Path,
+    relative_dir: &Path,
+    config: &Config,
+    teams_by_name: &HashMap<String, Team>,
+) -> Option<(String, Source)> {
+    let rel_str = to_slash_str(relative_dir)?;
+    let read_owner = |manifest: &str, path: &Path| match manifest {
+        "package.yml" => read_ruby_package_owner(path),
+        "package.json" => read_js_package_owner(path, config.owner_field.as_deref()),
+        _ => read_cargo_package_owner(path),
+    };
+    package_manifests(config).into_iter().find_map(|(manifest, package_paths)| {
+        if !config.package_discovery.is_package_dir(relative_dir, package_paths) {
+            return None;
         }
-        if current == project_root {
-            break;
+        let manifest_path = project_root.join(relative_dir).join(manifest);
+        if !manifest_path.exists() {
+            return None;
         }
-    }
-    None
+        let team = teams_by_name.get(&read_owner(manifest, &manifest_path).ok()?)?;
+        let package_path = crate::path_utils::symlink_target(project_root, &manifest_path).unwrap_or_else(|| relative_dir.join(manifest));
+        Some((
+            team.name.clone(),
+            Source::Package(to_slash_string_lossy(&package_path), format!("{rel_str}/**/**")),
+        ))
+    })
 }
 
 // removed: use `Source::len()` instead
 
-fn glob_list_matches(path: &str, globs: &[String]) -> bool {
-    globs.iter().any(|g| glob_match(g, path))
+fn glob_list_matches(path: &str, globs: &[String], case_insensitive: bool) -> bool {
+    globs.iter().any(|g| glob_matches(g, path, case_insensitive))
 }
 
 fn read_ruby_package_owner(path: &Path) -> std::result::Result<String, String> {
@@ -231,16 +806,37 @@ fn read_ruby_package_owner(path: &Path) -> std::result::Result<String, String> {
     deserializer.owner.ok_or_else(|| "Missing owner".to_string())
 }
 
-fn read_js_package_owner(path: &Path) -> std::result::Result<String, String> {
+fn read_js_package_owner(path: &Path, owner_field: Option<&str>) -> std::result::Result<String, String> {
     let file = std::fs::File::open(path).map_err(|e| e.to_string())?;
     let deserializer: crate::project::deserializers::JavascriptPackage = serde_json::from_reader(file).map_err(|e| e.to_string())?;
-    deserializer
-        .metadata
-        .and_then(|m| m.owner)
-        .ok_or_else(|| "Missing owner".to_string())
+    deserializer.owner(owner_field).ok_or_else(|| "Missing owner".to_string())
 }
 
-fn vendored_gem_owner(relative_file_path: &Path, config: &Config, teams: &[Team]) -> Option<(String, Source)> {
+fn read_cargo_package_owner(path: &Path) -> std::result::Result<String, String> {
+    let cargo_toml = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let deserializer: crate::project::deserializers::CargoPackage = toml::from_str(&cargo_toml).map_err(|e| e.to_string())?;
+    deserializer.owner().ok_or_else(|| "Missing owner".to_string())
+}
+
+fn vendored_gem_owner(project_root: &Path, relative_file_path: &Path, config: &Config, teams: &[Team]) -> Option<(String, Source)> {
+    let gem_name = vendored_gem_name(relative_file_path, config)?;
+    for team in teams {
+        if team.owns_gem(&gem_name) {
+            return Some((team.name.clone(), Source::TeamGem));
+        }
+    }
+    // Fall back to an owner the gem declares for itself
+    let gem_rel = Path::new(&config.vendored_gems_path).join(&gem_name);
+    let gem_dir = project_root.join(&gem_rel);
+    let owner = match fs::read_to_string(gem_dir.join(".codeowner")) {
+        Ok(owner_str) => owner_str.trim().to_owned(),
+        Err(_) => read_ruby_package_owner(&gem_dir.join("package.yml")).ok()?,
+    };
+    Some((owner, Source::VendoredGem(to_slash_string_lossy(&gem_rel))))
+}
+
+/// The name of the vendored gem `relative_file_path` lives in, if it is under `vendored_gems_path`.
+fn vendored_gem_name(relative_file_path: &Path, config: &Config) -> Option<String> {
     use std::path::Component;
     let mut comps = relative_file_path.components();
     let first = comps.next()?;
@@ -252,28 +848,74 @@ fn vendored_gem_owner(relative_file_path: &Path, config: &Config, teams: &[Team]
     if first_str != config.vendored_gems_path {
         return None;
     }
-    let gem_name = match second {
-        Component::Normal(s) => s.to_string_lossy().to_string(),
-        _ => return None,
+    match second {
+        Component::Normal(s) => Some(s.to_string_lossy().to_string()),
+        _ => None,
+    }
+}
+
+/// Like the deepest `.codeowner` winning over its ancestors, keeps only the claims of the most specific matching
+/// team glob (or override glob): the longest literal prefix, then the fewest wildcards. Returns a note naming the
+/// globs that lost.
+fn retain_most_specific_globs(claims: &mut Vec<(Team, Source)>) -> Option<String> {
+    let specificity = |source: &Source| match source {
+        Source::TeamGlob(glob) | Source::Override(glob) => glob_specificity(glob),
+        _ => (0, Reverse(0)),
     };
-    for team in teams {
-        if team.owned_gems.iter().any(|g| g == &gem_name) {
-            return Some((team.name.clone(), Source::TeamGem));
-        }
+    let most_specific = claims.iter().map(|(_, source)| specificity(source)).max()?;
+    let (kept, overridden): (Vec<_>, Vec<_>) = claims.drain(..).partition(|(_, source)| specificity(source) == most_specific);
+    *claims = kept;
+    if overridden.is_empty() {
+        return None;
     }
-    None
+    let overridden = overridden
+        .iter()
+        .map(|(team, source)| match source {
+            Source::TeamGlob(glob) | Source::Override(glob) => format!("`{}` ({})", glob, team.name),
+            _ => team.name.clone(),
+        })
+        .join(", ");
+    Some(format!("overridden by a more specific glob: {}", overridden))
+}
+
+/// The `extension_owners` team for an owned file, used only when no other mapper claims it.
+fn extension_owner(relative_file_path: &Path, config: &Config, teams_by_name: &HashMap<String, Team>) -> Option<(Team, Source)> {
+    let extension = relative_file_path.extension()?.to_str()?;
+    let team = teams_by_name.get(config.extension_owners.get(extension)?)?;
+    let rel_str = to_slash_str(relative_file_path)?;
+    let is_config_owned = glob_list_matches(&rel_str, &config.owned_globs, config.case_insensitive_paths);
+    let is_config_unowned = glob_list_matches(&rel_str, &config.unowned_globs, config.case_insensitive_paths);
+    (is_config_owned && !is_config_unowned).then(|| (team.clone(), Source::Extension(extension.to_owned())))
 }
 
-fn source_priority(source: &Source) -> u8 {
-    match source {
-        // Highest confidence first
-        Source::AnnotatedFile => 0,
-        Source::Directory(_) => 1,
-        Source::Package(_, _) => 2,
-        Source::TeamGlob(_) => 3,
-        Source::TeamGem => 4,
-        Source::TeamYml => 5,
+/// The `overrides` entries matching an owned file, with their teams.
+fn override_owners(relative_file_path: &Path, config: &Config, teams_by_name: &HashMap<String, Team>) -> Vec<(Team, Source)> {
+    let Some(rel_str) = to_slash_str(relative_file_path) else {
+        return vec![];
+    };
+    let case_insensitive = config.case_insensitive_paths;
+    if !glob_list_matches(&rel_str, &config.owned_globs, case_insensitive)
+        || glob_list_matches(&rel_str, &config.unowned_globs, case_insensitive)
+    {
+        return vec![];
     }
+    config
+        .overrides
+        .iter()
+        .filter(|ownership_override| glob_matches(&ownership_override.glob, &rel_str, case_insensitive))
+        .filter_map(|ownership_override| {
+            let team = teams_by_name.get(&ownership_override.team)?;
+            Some((team.clone(), Source::Override(ownership_override.glob.clone())))
+        })
+        .collect()
+}
+
+fn default_owner(relative_file_path: &Path, config: &Config, teams_by_name: &HashMap<String, Team>) -> Option<(Team, Source)> {
+    let team = teams_by_name.get(config.default_owner.as_ref()?)?;
+    let rel_str = to_slash_str(relative_file_path)?;
+    let is_config_owned = glob_list_matches(&rel_str, &config.owned_globs, config.case_insensitive_paths);
+    let is_config_unowned = glob_list_matches(&rel_str, &config.unowned_globs, config.case_insensitive_paths);
+    (is_config_owned && !is_config_unowned).then(|| (team.clone(), Source::DefaultOwner))
 }
 
 #[cfg(test)]
@@ -288,6 +930,7 @@ mod tests {
             owned_globs: vec!["**/*".to_string()],
             ruby_package_paths: vec![ruby_glob.to_string()],
             javascript_package_paths: vec![frontend_glob.to_string()],
+            cargo_package_paths: vec![],
             team_file_glob: vec!["config/teams/**/*.yml".to_string()],
             unowned_globs: vec![],
             vendored_gems_path: vendored_path.to_string(),
@@ -295,6 +938,25 @@ mod tests {
             ignore_dirs: vec![],
             executable_name: "codeowners".to_string(),
             codeowners_path: ".github".to_string(),
+            package_discovery: crate::config::PackageDiscovery::Glob,
+            for_file_default_mode: crate::config::ForFileMode::Derived,
+            ignored_owners: vec![],
+            max_depth: None,
+            case_insensitive_paths: false,
+            strict_annotations: false,
+            annotation_header_lines: 5,
+            annotation_keywords: vec!["team".to_string()],
+            annotation_forbidden_globs: vec![],
+            extension_owners: std::collections::BTreeMap::new(),
+            include_submodules: false,
+            owner_field: None,
+            team_files_owner: None,
+            default_owner: None,
+            overrides: vec![],
+            extra_codeowners_file: None,
+            fail_on_empty_generation: false,
+            git_timeout_secs: 30,
+            teams_root: None,
         }
     }
 
@@ -307,6 +969,8 @@ mod tests {
             subtracted_globs: vec![],
             owned_gems: vec![],
             avoid_ownership: false,
+            exclude_from_sections: vec![],
+            parent: None,
         }
     }
 
@@ -317,7 +981,10 @@ mod tests {
         // @team form
         let file_at = td.path().join("at_form.rb");
         std::fs::write(&file_at, "# @team Payroll\nputs 'x'\n").unwrap();
-        assert_eq!(read_top_of_file_team(&file_at), Some("Payroll".to_string()));
+        assert_eq!(
+            read_top_of_file_team(&file_at, &AnnotationParser::default(), 5),
+            Some("Payroll".to_string())
+        );
     }
 
     #[test]
@@ -344,7 +1011,8 @@ mod tests {
         }
 
         let rel_file = Path::new("a/b/c/file.rb");
-        let result = most_specific_directory_owner(project_root, rel_file, &tbn).unwrap();
+        let config = build_config_for_temp("frontend/**/*", "packs/**/*", "vendored");
+        let result = most_specific_directory_owner(project_root, rel_file, &config, &tbn).unwrap();
         match result.1 {
             Source::Directory(path) => {
                 assert!(path.ends_with("a/b/c"), "expected deepest directory, got {}", path);
@@ -402,6 +1070,181 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_nearest_package_owner_anywhere_ignores_package_paths() {
+        let td = tempdir().unwrap();
+        let project_root = td.path();
+        let mut config = build_config_for_temp("frontend/**/*", "packs/**/*", "vendored");
+
+        let engine_pkg = project_root.join("lib/engines/billing");
+        std::fs::create_dir_all(&engine_pkg).unwrap();
+        std::fs::write(engine_pkg.join("package.yml"), "---\nowner: Payroll\n").unwrap();
+
+        let mut tbn: HashMap<String, Team> = HashMap::new();
+        let t = team_named("Payroll");
+        tbn.insert(t.name.clone(), t);
+
+        let rel_file = Path::new("lib/engines/billing/app/models/invoice.rb");
+        assert!(nearest_package_owner(project_root, rel_file, &config, &tbn).is_none());
+
+        config.package_discovery = crate::config::PackageDiscovery::Anywhere;
+        let owner = nearest_package_owner(project_root, rel_file, &config, &tbn).unwrap();
+        assert_eq!(owner.0, "Payroll");
+        match owner.1 {
+            Source::Package(pkg_path, glob) => {
+                assert_eq!(pkg_path, "lib/engines/billing/package.yml");
+                assert_eq!(glob, "lib/engines/billing/**/**");
+            }
+            _ => panic!("expected Package source"),
+        }
+    }
+
+    #[test]
+    #[cfg(windows)]
+    fn test_trace_file_owners_with_backslash_separators() {
+        let td = tempdir().unwrap();
+        let project_root = td.path();
+        let config = build_config_for_temp("frontend/**/*", "packs/**/*", "vendored");
+
+        std::fs::create_dir_all(project_root.join("config/teams")).unwrap();
+        std::fs::write(
+            project_root.join("config/teams/payroll.yml"),
+            "name: Payroll\ngithub:\n  team: \"@PayrollTeam\"\nowned_globs:\n  - \"app/payroll/**/*\"\n",
+        )
+        .unwrap();
+
+        let trace = trace_file_owners(project_root, &config, Path::new("app\\payroll\\models\\check.rb")).unwrap();
+        let file_owners = trace.file_owners(project_root);
+        assert_eq!(file_owners.len(), 1);
+        assert_eq!(file_owners[0].team.name, "Payroll");
+        assert_eq!(file_owners[0].sources, vec![Source::TeamGlob("app/payroll/**/*".to_string())]);
+    }
+
+    fn write(project_root: &Path, relative_path: &str, content: &str) {
+        let path = project_root.join(relative_path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn test_missing_package_reason_names_the_configured_manifests() {
+        let mut config = build_config_for_temp("frontend/**/*", "packs/**/*", "vendored");
+        assert_eq!(missing_package_reason(&config), "no package.yml or package.json ancestor");
+
+        config.cargo_package_paths = vec!["crates/*".to_string()];
+        assert_eq!(
+            missing_package_reason(&config),
+            "no package.yml, package.json or Cargo.toml ancestor"
+        );
+
+        config.ruby_package_paths = vec![];
+        config.javascript_package_paths = vec![];
+        assert_eq!(missing_package_reason(&config), "no Cargo.toml ancestor");
+
+        config.cargo_package_paths = vec![];
+        assert_eq!(missing_package_reason(&config), "no package paths are configured");
+
+        config.package_discovery = PackageDiscovery::Anywhere;
+        assert_eq!(
+            missing_package_reason(&config),
+            "no package.yml, package.json or Cargo.toml ancestor"
+        );
+    }
+
+    #[test]
+    fn test_explain_unowned_reasons() {
+        let td = tempdir().unwrap();
+        let project_root = td.path();
+        let mut config = build_config_for_temp("frontend/**/*", "packs/**/*", "vendored");
+        config.owned_globs = vec!["{app,packs,vendored}/**/*.rb".to_string()];
+        config.unowned_globs = vec!["app/legacy/**/*".to_string()];
+        config.ignored_owners = vec!["Deprecated".to_string()];
+        write(
+            project_root,
+            "config/teams/payroll.yml",
+            "name: Payroll\ngithub:\n  team: \"@PayrollTeam\"\nowned_globs:\n  - \"app/payroll/**/*\"\nunowned_globs:\n  - \"app/payroll/tmp/**/*\"\n",
+        );
+        write(project_root, "app/plain.rb", "class Plain; end\n");
+        write(project_root, "app/legacy/old.rb", "# @team Payroll\nclass Old; end\n");
+        write(project_root, "app/deprecated.rb", "# @team Deprecated\nclass Deprecated; end\n");
+        write(project_root, "app/ghost.rb", "# @team Ghost\nclass Ghost; end\n");
+        write(project_root, "lib/outside.rb", "class Outside; end\n");
+        write(project_root, "app/orphaned/.codeowner", "Ghost\n");
+        write(project_root, "app/orphaned/thing.rb", "class Thing; end\n");
+        write(project_root, "packs/unclaimed/package.yml", "---\nowner: Ghost\n");
+        write(project_root, "packs/unclaimed/app/thing.rb", "class Thing; end\n");
+        write(project_root, "app/payroll/tmp/scratch.rb", "class Scratch; end\n");
+        write(project_root, "vendored/unowned_gem/lib/a.rb", "class A; end\n");
+        write(project_root, "app/payroll/check.rb", "class Check; end\n");
+
+        let reasons = |path: &str| explain_unowned(project_root, &config, Path::new(path)).unwrap();
+        let common_reasons = |annotation: &str| {
+            vec![
+                annotation.to_string(),
+                "directory has no .codeowner up to the project root".to_string(),
+                "no package.yml or package.json ancestor".to_string(),
+                "not matched by any team's owned_globs".to_string(),
+            ]
+        };
+
+        assert_eq!(reasons("app/plain.rb"), common_reasons("no annotation found"));
+        assert_eq!(
+            reasons("app/deprecated.rb"),
+            common_reasons("annotation owner `Deprecated` is listed in `ignored_owners`")
+        );
+        assert_eq!(
+            reasons("app/ghost.rb"),
+            common_reasons("annotation references unknown team `Ghost`")
+        );
+
+        let mut expected = vec!["not matched by any owned_glob".to_string()];
+        expected.extend(common_reasons("no annotation found"));
+        assert_eq!(reasons("lib/outside.rb"), expected);
+
+        let mut expected = vec!["matched unowned_glob `app/legacy/**/*`".to_string()];
+        expected.extend(common_reasons(
+            "annotation for `Payroll` only applies to files matched by owned_globs and not by unowned_globs",
+        ));
+        assert_eq!(reasons("app/legacy/old.rb"), expected);
+
+        assert!(reasons("app/orphaned/thing.rb").contains(&"`app/orphaned/.codeowner` references unknown team `Ghost`".to_string()));
+        assert!(
+            reasons("packs/unclaimed/app/thing.rb").contains(&"`packs/unclaimed/package.yml` has no owner matching a team".to_string())
+        );
+
+        let scratch = reasons("app/payroll/tmp/scratch.rb");
+        assert!(scratch.contains(
+            &"team `Payroll` owned_glob `app/payroll/**/*` is excluded by its subtracted_glob `app/payroll/tmp/**/*`".to_string()
+        ));
+        assert!(!scratch.contains(&"not matched by any team's owned_globs".to_string()));
+
+        assert!(
+            reasons("vendored/unowned_gem/lib/a.rb")
+                .contains(&"vendored gem `unowned_gem` is not listed in any team's owned_gems".to_string())
+        );
+
+        assert!(reasons("app/payroll/check.rb").is_empty());
+    }
+
+    #[test]
+    fn test_load_teams_with_overlapping_globs_loads_each_team_once() {
+        let td = tempdir().unwrap();
+        let project_root = td.path();
+        std::fs::create_dir_all(project_root.join("config/teams")).unwrap();
+        std::fs::write(
+            project_root.join("config/teams/payroll.yml"),
+            "name: Payroll\ngithub:\n  team: \"@Payroll\"\n",
+        )
+        .unwrap();
+
+        let mut config = build_config_for_temp("frontend/**/*", "packs/**/*", "vendored");
+        config.team_file_glob = vec!["config/teams/**/*.yml".to_string(), "config/teams/payroll.yml".to_string()];
+
+        let teams = load_teams(project_root, &config).unwrap();
+        assert_eq!(teams.len(), 1);
+        assert_eq!(teams[0].name, "Payroll");
+    }
+
     #[test]
     fn test_vendored_gem_owner() {
         let config = build_config_for_temp("frontend/**/*", "packs/**/*", "vendored");
@@ -409,8 +1252,141 @@ mod tests {
         teams[0].owned_gems = vec!["awesome_gem".to_string()];
 
         let path = Path::new("vendored/awesome_gem/lib/a.rb");
-        let result = vendored_gem_owner(path, &config, &teams).unwrap();
+        let result = vendored_gem_owner(Path::new("."), path, &config, &teams).unwrap();
         assert_eq!(result.0, "Payroll");
         matches!(result.1, Source::TeamGem);
     }
+
+    #[test]
+    fn test_vendored_gem_owner_declared_by_gem() {
+        let td = tempdir().unwrap();
+        let project_root = td.path();
+        std::fs::create_dir_all(project_root.join("vendored/awesome_gem/lib")).unwrap();
+        std::fs::write(project_root.join("vendored/awesome_gem/.codeowner"), "Gems\n").unwrap();
+        std::fs::create_dir_all(project_root.join("vendored/packaged_gem")).unwrap();
+        std::fs::write(project_root.join("vendored/packaged_gem/package.yml"), "owner: Gems\n").unwrap();
+
+        let config = build_config_for_temp("frontend/**/*", "packs/**/*", "vendored");
+        let mut teams: Vec<Team> = vec![team_named("Payroll"), team_named("Gems")];
+
+        let result = vendored_gem_owner(project_root, Path::new("vendored/awesome_gem/lib/a.rb"), &config, &teams).unwrap();
+        assert_eq!(result.0, "Gems");
+        assert_eq!(result.1, Source::VendoredGem("vendored/awesome_gem".to_owned()));
+        let result = vendored_gem_owner(project_root, Path::new("vendored/packaged_gem/b.rb"), &config, &teams).unwrap();
+        assert_eq!(result.0, "Gems");
+
+        // A team listing the gem in owned_gems wins over the gem's own declaration
+        teams[0].owned_gems = vec!["awesome_gem".to_string()];
+        let result = vendored_gem_owner(project_root, Path::new("vendored/awesome_gem/lib/a.rb"), &config, &teams).unwrap();
+        assert_eq!(result, ("Payroll".to_owned(), Source::TeamGem));
+    }
+
+    #[test]
+    fn test_batch_resolver_shares_directory_lookups_between_siblings() {
+        let td = tempdir().unwrap();
+        let project_root = td.path();
+        std::fs::create_dir_all(project_root.join("config/teams")).unwrap();
+        std::fs::write(
+            project_root.join("config/teams/payroll.yml"),
+            "name: Payroll\ngithub:\n  team: \"@Payroll\"\n",
+        )
+        .unwrap();
+        std::fs::create_dir_all(project_root.join("packs/payroll/app/models")).unwrap();
+        std::fs::write(project_root.join("packs/payroll/package.yml"), "owner: Payroll\n").unwrap();
+        let file_paths: Vec<PathBuf> = (0..50)
+            .map(|i| {
+                let path = PathBuf::from(format!("packs/payroll/app/models/model_{i}.rb"));
+                std::fs::write(project_root.join(&path), "class Model; end\n").unwrap();
+                path
+            })
+            .collect();
+        let config = build_config_for_temp("frontend/**/*", "packs/*", "vendored");
+
+        let mut resolver = BatchResolver::new(project_root, &config).unwrap();
+        let batch_owners: Vec<Vec<FileOwner>> = file_paths.iter().map(|path| resolver.file_owners(path)).collect();
+        // `packs/payroll/app/models` and its four ancestors, once for all 50 files
+        assert_eq!(resolver.directory_lookups(), 5);
+
+        for (path, owners) in file_paths.iter().zip(&batch_owners) {
+            let owners_alone = find_file_owners(project_root, &config, path).unwrap();
+            assert_eq!(
+                owners.iter().map(|o| o.to_string()).collect::<Vec<_>>(),
+                owners_alone.iter().map(|o| o.to_string()).collect::<Vec<_>>()
+            );
+            assert_eq!(owners[0].team.name, "Payroll");
+        }
+    }
+
+    #[test]
+    fn test_batch_resolver_shares_ancestor_lookups_between_directories() {
+        let td = tempdir().unwrap();
+        let project_root = td.path();
+        std::fs::create_dir_all(project_root.join("config/teams")).unwrap();
+        for team in ["Payroll", "Tax"] {
+            std::fs::write(
+                project_root.join(format!("config/teams/{}.yml", team.to_lowercase())),
+                format!("name: {team}\ngithub:\n  team: \"@{team}\"\n"),
+            )
+            .unwrap();
+        }
+        std::fs::create_dir_all(project_root.join("packs/payroll/app")).unwrap();
+        std::fs::write(project_root.join("packs/payroll/package.yml"), "owner: Payroll\n").unwrap();
+        std::fs::write(project_root.join("packs/payroll/app/.codeowner"), "Tax\n").unwrap();
+        let mut file_paths = Vec::new();
+        for dir in 0..20 {
+            let dir_path = PathBuf::from(format!("packs/payroll/app/models_{dir}"));
+            std::fs::create_dir_all(project_root.join(&dir_path)).unwrap();
+            for file in 0..5 {
+                let path = dir_path.join(format!("model_{file}.rb"));
+                std::fs::write(project_root.join(&path), "class Model; end\n").unwrap();
+                file_paths.push(path);
+            }
+        }
+        let config = build_config_for_temp("frontend/**/*", "packs/*", "vendored");
+
+        let mut resolver = BatchResolver::new(project_root, &config).unwrap();
+        let batch_owners: Vec<Vec<FileOwner>> = file_paths.iter().map(|path| resolver.file_owners(path)).collect();
+        // Walking up from each file would read 100 * 5 directories; the 20 leaves share `app` and everything above it
+        assert_eq!(resolver.directory_lookups(), 20 + 4);
+
+        for (path, owners) in file_paths.iter().zip(&batch_owners) {
+            let owners_alone = find_file_owners(project_root, &config, path).unwrap();
+            assert_eq!(
+                owners.iter().map(|o| o.to_string()).collect::<Vec<_>>(),
+                owners_alone.iter().map(|o| o.to_string()).collect::<Vec<_>>()
+            );
+            assert_eq!(owners[0].team.name, "Tax");
+        }
+    }
+
+    #[test]
+    fn test_glob_specificity_prefers_longer_literal_prefix_then_fewer_wildcards() {
+        assert!(glob_specificity("packs/payroll/**") > glob_specificity("packs/**"));
+        assert!(glob_specificity("packs/payroll/**") > glob_specificity("packs/pay*/**"));
+        assert!(glob_specificity("packs/*.rb") > glob_specificity("packs/**/*.rb"));
+        assert_eq!(glob_specificity("app/**"), glob_specificity("lib/**"));
+    }
+
+    #[test]
+    fn test_glob_specificity_treats_escaped_characters_as_literal() {
+        assert!(glob_specificity("app/\\[id\\]/**") > glob_specificity("app/**"));
+        assert_eq!(glob_specificity("app/\\[id\\]/**"), (9, Reverse(2)));
+        assert!(glob_specificity("app/\\{a,b\\}/**") > glob_specificity("app/{a,b}/**"));
+    }
+
+    #[test]
+    fn test_retain_most_specific_globs() {
+        let glob_claim = |team: &str, glob: &str| (team_named(team), Source::TeamGlob(glob.to_owned()));
+
+        let mut claims = vec![glob_claim("Foo", "packs/**"), glob_claim("Bar", "packs/payroll/**")];
+        let note = retain_most_specific_globs(&mut claims);
+        assert_eq!(claims.len(), 1);
+        assert_eq!(claims[0].0.name, "Bar");
+        assert_eq!(note.as_deref(), Some("overridden by a more specific glob: `packs/**` (Foo)"));
+
+        // Equally specific globs still both claim the file
+        let mut claims = vec![glob_claim("Foo", "packs/*/app/**"), glob_claim("Bar", "packs/*/lib/**")];
+        assert_eq!(retain_most_specific_globs(&mut claims), None);
+        assert_eq!(claims.len(), 2);
+    }
 }
@@ -1,20 +1,30 @@
 use fast_glob::glob_match;
 use std::{
+    cmp::Reverse,
     collections::HashMap,
     fmt::{self, Display},
     path::{Path, PathBuf},
 };
 
+use crate::path_utils::{glob_matches, to_slash_str};
+
 mod annotated_file_mapper;
+mod default_owner_mapper;
 pub(crate) mod directory_mapper;
 mod escaper;
+mod extension_mapper;
+mod override_mapper;
 mod package_mapper;
 mod team_gem_mapper;
 mod team_glob_mapper;
 mod team_yml_mapper;
 
 pub use annotated_file_mapper::TeamFileMapper;
+pub use default_owner_mapper::DefaultOwnerMapper;
 pub use directory_mapper::DirectoryMapper;
+pub use extension_mapper::ExtensionMapper;
+pub use override_mapper::OverrideMapper;
+pub use package_mapper::CargoPackageMapper;
 pub use package_mapper::JavascriptPackageMapper;
 pub use package_mapper::RubyPackageMapper;
 pub use team_gem_mapper::TeamGemMapper;
@@ -25,19 +35,27 @@ use super::Entry;
 
 pub trait Mapper {
     fn name(&self) -> String;
+    /// The `Source::kind` of the entries this mapper writes.
+    fn source_kind(&self) -> &'static str;
+    /// How `generate --only` names this mapper's section, e.g. `package-yml`.
+    fn section_name(&self) -> &'static str;
     fn entries(&self) -> Vec<Entry>;
     fn owner_matchers(&self) -> Vec<OwnerMatcher>;
 }
 pub type TeamName = String;
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Hash, serde::Serialize, serde::Deserialize)]
 pub enum Source {
     Directory(String),
     AnnotatedFile,
     TeamGem,
+    VendoredGem(String),
     TeamGlob(String),
     Package(String, String),
     TeamYml,
+    Extension(String),
+    DefaultOwner,
+    Override(String),
 }
 
 impl Display for Source {
@@ -46,22 +64,69 @@ impl Display for Source {
             Source::Directory(path) => write!(f, "Owner specified in `{}/.codeowner`", path),
             Source::AnnotatedFile => write!(f, "Owner annotation at the top of the file"),
             Source::TeamGem => write!(f, "Owner specified in Team YML's `owned_gems`"),
+            Source::VendoredGem(gem_path) => write!(f, "Owner declared inside the vendored gem `{}`", gem_path),
             Source::TeamGlob(glob) => write!(f, "Owner specified in Team YML as an owned_glob `{}`", glob),
             Source::Package(package_path, glob) => {
                 write!(f, "Owner defined in `{}` with implicitly owned glob: `{}`", package_path, glob)
             }
             Source::TeamYml => write!(f, "Teams own their configuration files"),
+            Source::Extension(extension) => write!(f, "Owner of `.{}` files in `extension_owners`", extension),
+            Source::DefaultOwner => write!(f, "Default owner from `default_owner` in the config"),
+            Source::Override(glob) => write!(f, "Owner overridden by `{}` in the config's `overrides`", glob),
         }
     }
 }
 
 impl Source {
+    /// Short name for the kind of mapper this source comes from, used when tallying sources.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Source::AnnotatedFile => "annotation",
+            Source::Directory(_) => "directory",
+            Source::Package(_, _) => "package",
+            Source::TeamGlob(_) => "glob",
+            Source::TeamGem | Source::VendoredGem(_) => "gem",
+            Source::TeamYml => "yml",
+            Source::Extension(_) => "extension",
+            Source::DefaultOwner => "default",
+            Source::Override(_) => "override",
+        }
+    }
+
     pub fn len(&self) -> usize {
         match self {
             Source::Directory(path) => path.matches('/').count(),
             _ => 0,
         }
     }
+
+    /// How confident the claim is; lower wins when teams compete for a file.
+    pub fn priority(&self) -> u8 {
+        match self {
+            // Highest confidence first
+            Source::Override(_) => 0,
+            Source::AnnotatedFile => 1,
+            Source::Directory(_) => 2,
+            Source::Package(_, _) => 3,
+            Source::TeamGlob(_) => 4,
+            Source::TeamGem | Source::VendoredGem(_) => 5,
+            Source::TeamYml => 6,
+            Source::Extension(_) => 7,
+            Source::DefaultOwner => 8,
+        }
+    }
+
+    /// Drops repeated sources and orders the rest by priority, then by description, so an owner's sources
+    /// read the same in text and JSON output regardless of the order mappers reported them in.
+    pub fn normalize(sources: Vec<Source>) -> Vec<Source> {
+        let mut sources: Vec<(u8, String, Source)> = sources
+            .into_iter()
+            .map(|source| (source.priority(), source.to_string(), source))
+            .collect();
+        sources.sort_by(|a, b| (a.0, &a.1).cmp(&(b.0, &b.1)));
+        sources.dedup_by(|a, b| a.2 == b.2);
+        sources.into_iter().map(|(_, _, source)| source).collect()
+    }
 }
 
 #[derive(Debug, PartialEq)]
@@ -75,6 +140,35 @@ pub enum OwnerMatcher {
     },
 }
 
+/// How specific a glob is, for picking among globs that match the same file: the longest literal prefix wins,
+/// then the fewest wildcards. A character escaped with `\` (e.g. the brackets in `app/\[id\]/**`) is literal.
+pub(crate) fn glob_specificity(glob: &str) -> (usize, Reverse<usize>) {
+    let mut literal_prefix = 0;
+    let mut wildcards = 0;
+    let mut chars = glob.chars();
+    while let Some(c) = chars.next() {
+        let is_wildcard = match c {
+            '\\' => {
+                chars.next();
+                false
+            }
+            '*' | '?' | '[' | '{' => true,
+            _ => false,
+        };
+        if is_wildcard {
+            wildcards += 1;
+        } else if wildcards == 0 {
+            literal_prefix += 1;
+        }
+    }
+    (literal_prefix, Reverse(wildcards))
+}
+
+/// Whether either glob, read as a path, matches the other. Only overlapping subtracted globs are applied to an owned glob.
+pub(crate) fn globs_overlap(a: &str, b: &str) -> bool {
+    glob_match(a, b) || glob_match(b, a)
+}
+
 impl OwnerMatcher {
     pub fn new_glob_with_candidate_subtracted_globs(
         glob: String,
@@ -84,9 +178,7 @@ impl OwnerMatcher {
     ) -> Self {
         let subtracted_globs = candidate_subtracted_globs
             .iter()
-            .filter(|candidate_subtracted_glob| {
-                glob_match(candidate_subtracted_glob, &glob) || glob_match(&glob, candidate_subtracted_glob)
-            })
+            .filter(|candidate_subtracted_glob| globs_overlap(candidate_subtracted_glob, &glob))
             .cloned()
             .collect();
         OwnerMatcher::Glob {
@@ -106,16 +198,22 @@ impl OwnerMatcher {
         }
     }
 
-    pub fn owner_for(&self, relative_path: &Path) -> (Option<&TeamName>, &Source) {
+    /// The team this matcher assigns `relative_path` to, if any. `case_insensitive` mirrors the `case_insensitive_paths`
+    /// config, so a glob like `app/**` also claims `App/models/user.rb`.
+    pub fn owner_for(&self, relative_path: &Path, case_insensitive: bool) -> (Option<&TeamName>, &Source) {
         match self {
             OwnerMatcher::Glob {
                 glob,
                 subtracted_globs,
                 team_name,
                 source,
-            } => relative_path
-                .to_str()
-                .filter(|path| glob_match(glob, path) && !subtracted_globs.iter().any(|subtracted| glob_match(subtracted, path)))
+            } => to_slash_str(relative_path)
+                .filter(|path| {
+                    glob_matches(glob, path, case_insensitive)
+                        && !subtracted_globs
+                            .iter()
+                            .any(|subtracted| glob_matches(subtracted, path, case_insensitive))
+                })
                 .map_or((None, source), |_| (Some(team_name), source)),
             OwnerMatcher::ExactMatches(path_to_team, source) => (path_to_team.get(relative_path), source),
         }
@@ -126,6 +224,27 @@ impl OwnerMatcher {
 mod tests {
     use super::*;
 
+    #[test]
+    fn normalize_dedups_and_orders_by_priority() {
+        let sources = vec![
+            Source::TeamGlob("app/**".to_string()),
+            Source::Package("packs/foo/package.yml".to_string(), "packs/foo/**/**".to_string()),
+            Source::AnnotatedFile,
+            Source::TeamGlob("app/**".to_string()),
+            Source::TeamGlob("app/models/**".to_string()),
+            Source::AnnotatedFile,
+        ];
+        assert_eq!(
+            Source::normalize(sources),
+            vec![
+                Source::AnnotatedFile,
+                Source::Package("packs/foo/package.yml".to_string(), "packs/foo/**/**".to_string()),
+                Source::TeamGlob("app/**".to_string()),
+                Source::TeamGlob("app/models/**".to_string()),
+            ]
+        );
+    }
+
     fn assert_owner_for(glob: &str, subtracted_globs: &[&str], relative_path: &str, expect_match: bool) {
         let source = Source::Directory("packs/bam".to_string());
         let team_name = "team1".to_string();
@@ -135,7 +254,7 @@ mod tests {
             team_name.clone(),
             source.clone(),
         );
-        let response = owner_matcher.owner_for(Path::new(relative_path));
+        let response = owner_matcher.owner_for(Path::new(relative_path), false);
         if expect_match {
             assert_eq!(response, (Some(&team_name), &source));
         } else {
@@ -194,6 +313,18 @@ mod tests {
         );
     }
 
+    #[test]
+    #[cfg(windows)]
+    fn owner_for_with_backslash_separators() {
+        assert_owner_for("packs/bam/**/**", &[], "packs\\bam\\app\\components\\sidebar.jsx", true);
+        assert_owner_for(
+            "packs/bam/**/**",
+            &["packs/bam/app/excluded/**"],
+            "packs\\bam\\app\\excluded\\some_file.rb",
+            false,
+        );
+    }
+
     #[test]
     fn display_source() {
         assert_eq!(
@@ -202,6 +333,10 @@ mod tests {
         );
         assert_eq!(Source::AnnotatedFile.to_string(), "Owner annotation at the top of the file");
         assert_eq!(Source::TeamGem.to_string(), "Owner specified in Team YML's `owned_gems`");
+        assert_eq!(
+            Source::VendoredGem("gems/pets".to_owned()).to_string(),
+            "Owner declared inside the vendored gem `gems/pets`"
+        );
         assert_eq!(
             Source::TeamGlob("a/glob/**".to_string()).to_string(),
             "Owner specified in Team YML as an owned_glob `a/glob/**`"
@@ -6,6 +6,7 @@ use super::Entry;
 use super::escaper::escape_brackets;
 use super::{Mapper, OwnerMatcher};
 use crate::ownership::mapper::Source;
+use crate::path_utils::to_slash_string_lossy;
 use crate::project::Project;
 
 pub struct TeamFileMapper {
@@ -24,17 +25,19 @@ impl Mapper for TeamFileMapper {
         let team_by_name = self.project.teams_by_name.clone();
 
         for owned_file in &self.project.files {
-            if let Some(ref owner) = owned_file.owner {
+            if let Some(ref owner) = owned_file.owner
+                && !self.project.is_ignored_owner(owner)
+            {
                 let team = team_by_name.get(owner);
 
                 if let Some(team) = team {
                     let relative_path = self.project.relative_path(&owned_file.path);
 
                     entries.push(Entry {
-                        path: escape_brackets(&relative_path.to_string_lossy()),
+                        path: escape_brackets(&to_slash_string_lossy(relative_path)),
                         github_team: team.github_team.to_owned(),
                         team_name: team.name.to_owned(),
-                        disabled: team.avoid_ownership,
+                        disabled: team.is_disabled_in(self.section_name()),
                     });
                 }
             }
@@ -49,7 +52,9 @@ impl Mapper for TeamFileMapper {
         let mut path_to_team: HashMap<PathBuf, String> = HashMap::new();
 
         for owned_file in &self.project.files {
-            if let Some(ref owner) = owned_file.owner {
+            if let Some(ref owner) = owned_file.owner
+                && !self.project.is_ignored_owner(owner)
+            {
                 let relative_path = self.project.relative_path(&owned_file.path);
                 let team_name = team_by_name
                     .get(owner)
@@ -65,6 +70,14 @@ impl Mapper for TeamFileMapper {
     fn name(&self) -> String {
         "Annotations at the top of file".to_owned()
     }
+
+    fn source_kind(&self) -> &'static str {
+        "annotation"
+    }
+
+    fn section_name(&self) -> &'static str {
+        "annotation"
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,82 @@
+use std::sync::Arc;
+
+use rayon::prelude::*;
+
+use super::escaper::escape_brackets;
+use super::{Entry, Mapper, OwnerMatcher, Source};
+use crate::ownership::{file_owner_finder::FileOwnerFinder, mappers_for};
+use crate::path_utils::to_slash_string_lossy;
+use crate::project::{Project, Team};
+
+/// Catch-all owner (see `default_owner` in the config) for owned files no other mapper claims, extensions
+/// included. CODEOWNERS gets one line per such file rather than a `/**` line, which would also hand GitHub the
+/// files outside `owned_globs` or in `unowned_globs`. Its lines come first: GitHub applies the last matching line,
+/// so they have the lowest precedence there too.
+pub struct DefaultOwnerMapper {
+    project: Arc<Project>,
+}
+
+const CATCH_ALL_GLOB: &str = "**";
+
+impl DefaultOwnerMapper {
+    pub fn build(project: Arc<Project>) -> Self {
+        Self { project }
+    }
+
+    fn default_team(&self) -> Option<&Team> {
+        self.project
+            .default_owner
+            .as_ref()
+            .and_then(|owner| self.project.teams_by_name.get(owner))
+    }
+}
+
+impl Mapper for DefaultOwnerMapper {
+    fn entries(&self) -> Vec<Entry> {
+        let Some(team) = self.default_team() else {
+            return vec![];
+        };
+        let other_owner_matchers: Vec<OwnerMatcher> = mappers_for(&self.project)
+            .iter()
+            .filter(|mapper| mapper.section_name() != self.section_name())
+            .flat_map(|mapper| mapper.owner_matchers())
+            .collect();
+        let file_owner_finder = FileOwnerFinder {
+            owner_matchers: &other_owner_matchers,
+            case_insensitive_paths: self.project.case_insensitive_paths,
+        };
+        let disabled = team.is_disabled_in(self.section_name());
+
+        self.project
+            .files
+            .par_iter()
+            .map(|file| self.project.relative_path(&file.path))
+            .filter(|relative_path| file_owner_finder.find(relative_path).is_empty())
+            .map(|relative_path| Entry {
+                path: escape_brackets(&to_slash_string_lossy(relative_path)),
+                github_team: team.github_team.to_owned(),
+                team_name: team.name.to_owned(),
+                disabled,
+            })
+            .collect()
+    }
+
+    fn owner_matchers(&self) -> Vec<OwnerMatcher> {
+        self.default_team()
+            .map(|team| OwnerMatcher::new_glob(CATCH_ALL_GLOB.to_owned(), team.github_team.to_owned(), Source::DefaultOwner))
+            .into_iter()
+            .collect()
+    }
+
+    fn name(&self) -> String {
+        "Default owner".to_owned()
+    }
+
+    fn source_kind(&self) -> &'static str {
+        "default"
+    }
+
+    fn section_name(&self) -> &'static str {
+        "default"
+    }
+}
@@ -3,6 +3,7 @@ use std::sync::Arc;
 use super::escaper::escape_brackets;
 use super::{Entry, Source};
 use super::{Mapper, OwnerMatcher};
+use crate::path_utils::to_slash_string_lossy;
 use crate::project::Project;
 
 pub struct DirectoryMapper {
@@ -23,7 +24,7 @@ impl Mapper for DirectoryMapper {
         for directory_codeowner_file in &self.project.directory_codeowner_files {
             let dir_root = directory_codeowner_file
                 .directory_root()
-                .map(|p| p.to_string_lossy())
+                .map(to_slash_string_lossy)
                 .unwrap_or_default();
             let team = team_by_name.get(&directory_codeowner_file.owner);
             if let Some(team) = team {
@@ -31,7 +32,7 @@ impl Mapper for DirectoryMapper {
                     path: format!("{}/**/**", escape_brackets(&dir_root)),
                     github_team: team.github_team.to_owned(),
                     team_name: team.name.to_owned(),
-                    disabled: team.avoid_ownership,
+                    disabled: team.is_disabled_in(self.section_name()),
                 });
             }
         }
@@ -46,10 +47,10 @@ impl Mapper for DirectoryMapper {
             owner_matchers.push(OwnerMatcher::new_glob(
                 format!(
                     "{}/**/**",
-                    escape_brackets(&file.directory_root().map(|p| p.to_string_lossy()).unwrap_or_default())
+                    escape_brackets(&file.directory_root().map(to_slash_string_lossy).unwrap_or_default())
                 ),
                 file.owner.to_owned(),
-                Source::Directory(file.directory_root().map(|p| p.to_string_lossy().to_string()).unwrap_or_default()),
+                Source::Directory(file.directory_root().map(to_slash_string_lossy).unwrap_or_default()),
             ));
         }
 
@@ -59,6 +60,14 @@ impl Mapper for DirectoryMapper {
     fn name(&self) -> String {
         "Owner in .codeowner".to_owned()
     }
+
+    fn source_kind(&self) -> &'static str {
+        "directory"
+    }
+
+    fn section_name(&self) -> &'static str {
+        "directory"
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,113 @@
+use std::sync::Arc;
+
+use super::{Entry, Mapper, OwnerMatcher, Source};
+use crate::project::{Project, Team};
+
+/// Owns files by extension (see `extension_owners` in the config). It only claims files no other mapper owns,
+/// and its lines come first in CODEOWNERS so that GitHub, which applies the last matching line, lets every other
+/// line take precedence.
+pub struct ExtensionMapper {
+    project: Arc<Project>,
+}
+
+impl ExtensionMapper {
+    pub fn build(project: Arc<Project>) -> Self {
+        Self { project }
+    }
+
+    fn extension_teams(&self) -> impl Iterator<Item = (&String, &Team)> {
+        self.project
+            .extension_owners
+            .iter()
+            .filter_map(|(extension, owner)| self.project.teams_by_name.get(owner).map(|team| (extension, team)))
+    }
+}
+
+fn extension_glob(extension: &str) -> String {
+    format!("**/*.{}", extension)
+}
+
+impl Mapper for ExtensionMapper {
+    fn entries(&self) -> Vec<Entry> {
+        self.extension_teams()
+            .map(|(extension, team)| Entry {
+                path: extension_glob(extension),
+                github_team: team.github_team.to_owned(),
+                team_name: team.name.to_owned(),
+                disabled: team.is_disabled_in(self.section_name()),
+            })
+            .collect()
+    }
+
+    fn owner_matchers(&self) -> Vec<OwnerMatcher> {
+        self.extension_teams()
+            .map(|(extension, team)| {
+                OwnerMatcher::new_glob(
+                    extension_glob(extension),
+                    team.github_team.to_owned(),
+                    Source::Extension(extension.to_owned()),
+                )
+            })
+            .collect()
+    }
+
+    fn name(&self) -> String {
+        "Owner by file extension".to_owned()
+    }
+
+    fn source_kind(&self) -> &'static str {
+        "extension"
+    }
+
+    fn section_name(&self) -> &'static str {
+        "extension"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+
+    use tempfile::tempdir;
+
+    use crate::common_test::tests::{DEFAULT_CODE_OWNERSHIP_YML, TestConfig, TestProjectFile, build_ownership, vecs_match};
+
+    use super::*;
+
+    #[test]
+    fn test_entries_skip_unknown_teams() -> Result<(), Box<dyn Error>> {
+        let temp_dir = tempdir()?;
+        let ownership = build_ownership(TestConfig {
+            code_ownership_config_yml: format!(
+                "{}extension_owners:\n  proto: Foo\n  .sql: Bar\n  csv: Nobody\n",
+                DEFAULT_CODE_OWNERSHIP_YML
+            ),
+            ..TestConfig::new(
+                temp_dir.path().to_path_buf(),
+                vec![TestProjectFile {
+                    relative_path: "app/api/user.proto".to_owned(),
+                    content: "message User {}\n".to_owned(),
+                }],
+            )
+        })?;
+        let mapper = ExtensionMapper::build(ownership.project.clone());
+        vecs_match(
+            &mapper.entries(),
+            &vec![
+                Entry {
+                    path: "**/*.proto".to_owned(),
+                    github_team: "@Foo".to_owned(),
+                    team_name: "Foo".to_owned(),
+                    disabled: false,
+                },
+                Entry {
+                    path: "**/*.sql".to_owned(),
+                    github_team: "@Bar".to_owned(),
+                    team_name: "Bar".to_owned(),
+                    disabled: false,
+                },
+            ],
+        );
+        Ok(())
+    }
+}
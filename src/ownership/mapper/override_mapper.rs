@@ -0,0 +1,54 @@
+use std::sync::Arc;
+
+use super::{Entry, Mapper, OwnerMatcher, Source};
+use crate::project::{Project, Team};
+
+/// Manual owners from `overrides` in the config, which win over every other mapper for the files they match.
+/// Their section is the last in CODEOWNERS so GitHub, which applies the last matching line, agrees.
+pub struct OverrideMapper {
+    project: Arc<Project>,
+}
+
+impl OverrideMapper {
+    pub fn build(project: Arc<Project>) -> Self {
+        Self { project }
+    }
+
+    fn overrides(&self) -> impl Iterator<Item = (&String, &Team)> {
+        self.project
+            .overrides
+            .iter()
+            .filter_map(|ownership_override| Some((&ownership_override.glob, self.project.teams_by_name.get(&ownership_override.team)?)))
+    }
+}
+
+impl Mapper for OverrideMapper {
+    fn entries(&self) -> Vec<Entry> {
+        self.overrides()
+            .map(|(glob, team)| Entry {
+                path: glob.to_owned(),
+                github_team: team.github_team.to_owned(),
+                team_name: team.name.to_owned(),
+                disabled: team.is_disabled_in(self.section_name()),
+            })
+            .collect()
+    }
+
+    fn owner_matchers(&self) -> Vec<OwnerMatcher> {
+        self.overrides()
+            .map(|(glob, team)| OwnerMatcher::new_glob(glob.to_owned(), team.github_team.to_owned(), Source::Override(glob.to_owned())))
+            .collect()
+    }
+
+    fn name(&self) -> String {
+        "Ownership overrides".to_owned()
+    }
+
+    fn source_kind(&self) -> &'static str {
+        "override"
+    }
+
+    fn section_name(&self) -> &'static str {
+        "override"
+    }
+}
@@ -2,6 +2,7 @@ use std::sync::Arc;
 
 use super::{Entry, Source};
 use super::{Mapper, OwnerMatcher};
+use crate::path_utils::to_slash_string_lossy;
 use crate::project::{Package, PackageType, Project};
 use itertools::Itertools;
 
@@ -13,6 +14,10 @@ pub struct JavascriptPackageMapper {
     project: Arc<Project>,
 }
 
+pub struct CargoPackageMapper {
+    project: Arc<Project>,
+}
+
 struct PackageMapper {
     project: Arc<Project>,
 }
@@ -25,7 +30,7 @@ impl RubyPackageMapper {
 
 impl Mapper for RubyPackageMapper {
     fn entries(&self) -> Vec<Entry> {
-        PackageMapper::build(self.project.clone()).entries(&PackageType::Ruby)
+        PackageMapper::build(self.project.clone()).entries(&PackageType::Ruby, self.section_name())
     }
 
     fn owner_matchers(&self) -> Vec<OwnerMatcher> {
@@ -35,6 +40,14 @@ impl Mapper for RubyPackageMapper {
     fn name(&self) -> String {
         "Owner metadata key in package.yml".to_owned()
     }
+
+    fn source_kind(&self) -> &'static str {
+        "package"
+    }
+
+    fn section_name(&self) -> &'static str {
+        "package-yml"
+    }
 }
 
 impl JavascriptPackageMapper {
@@ -45,7 +58,7 @@ impl JavascriptPackageMapper {
 
 impl Mapper for JavascriptPackageMapper {
     fn entries(&self) -> Vec<Entry> {
-        PackageMapper::build(self.project.clone()).entries(&PackageType::Javascript)
+        PackageMapper::build(self.project.clone()).entries(&PackageType::Javascript, self.section_name())
     }
 
     fn owner_matchers(&self) -> Vec<OwnerMatcher> {
@@ -55,6 +68,42 @@ impl Mapper for JavascriptPackageMapper {
     fn name(&self) -> String {
         "Owner metadata key in package.json".to_owned()
     }
+
+    fn source_kind(&self) -> &'static str {
+        "package"
+    }
+
+    fn section_name(&self) -> &'static str {
+        "package-json"
+    }
+}
+
+impl CargoPackageMapper {
+    pub fn build(project: Arc<Project>) -> Self {
+        Self { project }
+    }
+}
+
+impl Mapper for CargoPackageMapper {
+    fn entries(&self) -> Vec<Entry> {
+        PackageMapper::build(self.project.clone()).entries(&PackageType::Rust, self.section_name())
+    }
+
+    fn owner_matchers(&self) -> Vec<OwnerMatcher> {
+        PackageMapper::build(self.project.clone()).owner_matchers(&PackageType::Rust)
+    }
+
+    fn name(&self) -> String {
+        "Owner metadata key in Cargo.toml".to_owned()
+    }
+
+    fn source_kind(&self) -> &'static str {
+        "package"
+    }
+
+    fn section_name(&self) -> &'static str {
+        "cargo-toml"
+    }
 }
 
 impl PackageMapper {
@@ -64,13 +113,13 @@ impl PackageMapper {
 }
 
 impl PackageMapper {
-    fn entries(&self, package_type: &PackageType) -> Vec<Entry> {
+    fn entries(&self, package_type: &PackageType, section_name: &str) -> Vec<Entry> {
         let mut entries: Vec<Entry> = Vec::new();
         let team_by_name = self.project.teams_by_name.clone();
 
         for package in self.project.packages.iter().filter(|package| &package.package_type == package_type) {
             if let Some(package_root) = package.package_root() {
-                let package_root = package_root.to_string_lossy();
+                let package_root = to_slash_string_lossy(package_root);
                 let team = team_by_name.get(&package.owner);
 
                 if let Some(team) = team {
@@ -78,7 +127,7 @@ impl PackageMapper {
                         path: format!("{}/**/**", package_root),
                         github_team: team.github_team.to_owned(),
                         team_name: team.name.to_owned(),
-                        disabled: team.avoid_ownership,
+                        disabled: team.is_disabled_in(section_name),
                     });
                 }
             }
@@ -100,14 +149,14 @@ impl PackageMapper {
 
         for package in packages {
             if let Some(package_root) = package.package_root() {
-                let package_root = package_root.to_string_lossy();
+                let package_root = to_slash_string_lossy(package_root);
                 let team = team_by_name.get(&package.owner);
 
                 if let Some(team) = team {
                     owner_matchers.push(OwnerMatcher::new_glob(
                         format!("{}/**/**", package_root),
                         team.name.to_owned(),
-                        Source::Package(package.path.to_string_lossy().to_string(), format!("{}/**/**", package_root)),
+                        Source::Package(to_slash_string_lossy(package.manifest_path()), format!("{}/**/**", package_root)),
                     ));
                 }
             }
@@ -152,21 +201,25 @@ mod tests {
                 path: Path::new("packs/a/package.yml").to_owned(),
                 package_type: PackageType::Ruby,
                 owner: "owner_a".to_owned(),
+                symlink_target: None,
             },
             Package {
                 path: Path::new("packs/a/b/e/package.yml").to_owned(),
                 package_type: PackageType::Ruby,
                 owner: "owner_b".to_owned(),
+                symlink_target: None,
             },
             Package {
                 path: Path::new("packs/a/b/c/e/d/f/package.yml").to_owned(),
                 package_type: PackageType::Ruby,
                 owner: "owner_b".to_owned(),
+                symlink_target: None,
             },
             Package {
                 path: Path::new("packs/c/package.yml").to_owned(),
                 package_type: PackageType::Ruby,
                 owner: "owner_a".to_owned(),
+                symlink_target: None,
             },
         ];
 
@@ -2,7 +2,8 @@ use std::sync::Arc;
 
 use super::{Entry, Source};
 use super::{Mapper, OwnerMatcher};
-use crate::project::Project;
+use crate::path_utils::to_slash_string_lossy;
+use crate::project::{Project, Team, VendoredGem};
 
 pub struct TeamGemMapper {
     project: Arc<Project>,
@@ -12,55 +13,92 @@ impl TeamGemMapper {
     pub fn build(project: Arc<Project>) -> Self {
         Self { project }
     }
+
+    /// The vendored gems `team` lists in `owned_gems`, with each glob entry expanded to every gem it matches.
+    fn team_gems<'a>(&'a self, team: &'a Team) -> impl Iterator<Item = &'a VendoredGem> {
+        self.project
+            .vendored_gems
+            .iter()
+            .filter(|vendored_gem| team.owns_gem(&vendored_gem.name))
+    }
+
+    /// Gems that declare their own owner and aren't claimed by any team's `owned_gems`, which take precedence.
+    fn self_owned_gems(&self) -> Vec<(&VendoredGem, &Team)> {
+        self.project
+            .vendored_gems
+            .iter()
+            .filter(|vendored_gem| !self.project.teams.iter().any(|team| team.owns_gem(&vendored_gem.name)))
+            .filter_map(|vendored_gem| {
+                let owner = vendored_gem.owner.as_ref()?;
+                self.project.teams_by_name.get(owner).map(|team| (vendored_gem, team))
+            })
+            .collect()
+    }
 }
 
 impl Mapper for TeamGemMapper {
     fn entries(&self) -> Vec<Entry> {
-        let vendored_gem_by_name = self.project.vendored_gem_by_name();
         let mut entries: Vec<Entry> = Vec::new();
 
         for team in &self.project.teams {
-            for owned_gem in &team.owned_gems {
-                let vendored_gem = vendored_gem_by_name.get(owned_gem);
-
-                if let Some(vendored_gem) = vendored_gem {
-                    entries.push(Entry {
-                        path: format!("{}/**/**", self.project.relative_path(&vendored_gem.path).to_string_lossy()),
-                        github_team: team.github_team.to_owned(),
-                        team_name: team.name.to_owned(),
-                        disabled: team.avoid_ownership,
-                    });
-                }
+            for vendored_gem in self.team_gems(team) {
+                entries.push(Entry {
+                    path: format!("{}/**/**", to_slash_string_lossy(self.project.relative_path(&vendored_gem.path))),
+                    github_team: team.github_team.to_owned(),
+                    team_name: team.name.to_owned(),
+                    disabled: team.is_disabled_in(self.section_name()),
+                });
             }
         }
 
+        for (vendored_gem, team) in self.self_owned_gems() {
+            entries.push(Entry {
+                path: format!("{}/**/**", to_slash_string_lossy(self.project.relative_path(&vendored_gem.path))),
+                github_team: team.github_team.to_owned(),
+                team_name: team.name.to_owned(),
+                disabled: team.is_disabled_in(self.section_name()),
+            });
+        }
+
         entries
     }
 
     fn owner_matchers(&self) -> Vec<OwnerMatcher> {
         let mut owner_matchers: Vec<OwnerMatcher> = Vec::new();
-        let vendored_gem_by_name = self.project.vendored_gem_by_name();
 
         for team in &self.project.teams {
-            for owned_gem in &team.owned_gems {
-                let vendored_gem = vendored_gem_by_name.get(owned_gem);
-
-                if let Some(vendored_gem) = vendored_gem {
-                    owner_matchers.push(OwnerMatcher::new_glob(
-                        format!("{}/**/*", self.project.relative_path(&vendored_gem.path).to_string_lossy()),
-                        team.name.clone(),
-                        Source::TeamGem,
-                    ));
-                }
+            for vendored_gem in self.team_gems(team) {
+                owner_matchers.push(OwnerMatcher::new_glob(
+                    format!("{}/**/*", to_slash_string_lossy(self.project.relative_path(&vendored_gem.path))),
+                    team.name.clone(),
+                    Source::TeamGem,
+                ));
             }
         }
 
+        for (vendored_gem, team) in self.self_owned_gems() {
+            let gem_path = to_slash_string_lossy(self.project.relative_path(&vendored_gem.path));
+            owner_matchers.push(OwnerMatcher::new_glob(
+                format!("{}/**/*", gem_path),
+                team.name.clone(),
+                Source::VendoredGem(gem_path),
+            ));
+        }
+
         owner_matchers
     }
 
     fn name(&self) -> String {
         "Team owned gems".to_owned()
     }
+
+    fn source_kind(&self) -> &'static str {
+        "gem"
+    }
+
+    fn section_name(&self) -> &'static str {
+        "team-gem"
+    }
 }
 
 #[cfg(test)]
@@ -24,7 +24,7 @@ impl Mapper for TeamGlobMapper {
                     path: owned_glob.to_owned(),
                     github_team: team.github_team.to_owned(),
                     team_name: team.name.to_owned(),
-                    disabled: team.avoid_ownership,
+                    disabled: team.is_disabled_in(self.section_name()),
                 });
             }
         }
@@ -53,6 +53,14 @@ impl Mapper for TeamGlobMapper {
     fn name(&self) -> String {
         "Team-specific owned globs".to_owned()
     }
+
+    fn source_kind(&self) -> &'static str {
+        "glob"
+    }
+
+    fn section_name(&self) -> &'static str {
+        "team-glob"
+    }
 }
 
 #[cfg(test)]
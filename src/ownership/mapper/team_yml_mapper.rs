@@ -5,7 +5,8 @@ use std::sync::Arc;
 use super::Entry;
 use super::{Mapper, OwnerMatcher};
 use crate::ownership::mapper::Source;
-use crate::project::Project;
+use crate::path_utils::to_slash_string_lossy;
+use crate::project::{Project, Team};
 
 pub struct TeamYmlMapper {
     project: Arc<Project>,
@@ -15,18 +16,35 @@ impl TeamYmlMapper {
     pub fn build(project: Arc<Project>) -> Self {
         Self { project }
     }
+
+    // Team files shared from outside the project (see `teams_root`) have no path in its CODEOWNERS
+    fn teams_in_project(&self) -> impl Iterator<Item = &Team> {
+        self.project
+            .teams
+            .iter()
+            .filter(|team| team.path.starts_with(&self.project.base_path))
+    }
+
+    /// Each team file in the project paired with its owner: the `team_files_owner` team if configured, else the team it defines.
+    /// An unknown `team_files_owner` leaves the files unowned; validate reports it.
+    fn team_files_with_owners(&self) -> impl Iterator<Item = (&Team, &Team)> {
+        self.teams_in_project().filter_map(|team| match &self.project.team_files_owner {
+            Some(owner) => self.project.teams_by_name.get(owner).map(|owner| (team, owner)),
+            None => Some((team, team)),
+        })
+    }
 }
 
 impl Mapper for TeamYmlMapper {
     fn entries(&self) -> Vec<Entry> {
         let mut entries: Vec<Entry> = Vec::new();
 
-        for team in &self.project.teams {
+        for (team, owner) in self.team_files_with_owners() {
             entries.push(Entry {
-                path: self.project.relative_path(&team.path).to_string_lossy().to_string(),
-                github_team: team.github_team.to_owned(),
-                team_name: team.name.to_owned(),
-                disabled: team.avoid_ownership,
+                path: to_slash_string_lossy(self.project.relative_path(&team.path)),
+                github_team: owner.github_team.to_owned(),
+                team_name: owner.name.to_owned(),
+                disabled: owner.is_disabled_in(self.section_name()),
             });
         }
 
@@ -36,8 +54,8 @@ impl Mapper for TeamYmlMapper {
     fn owner_matchers(&self) -> Vec<OwnerMatcher> {
         let mut path_to_team: HashMap<PathBuf, String> = HashMap::new();
 
-        for team in &self.project.teams {
-            path_to_team.insert(self.project.relative_path(&team.path).to_owned(), team.name.to_owned());
+        for (team, owner) in self.team_files_with_owners() {
+            path_to_team.insert(self.project.relative_path(&team.path).to_owned(), owner.name.to_owned());
         }
 
         vec![OwnerMatcher::ExactMatches(path_to_team, Source::TeamYml)]
@@ -46,6 +64,14 @@ impl Mapper for TeamYmlMapper {
     fn name(&self) -> String {
         "Team YML ownership".to_owned()
     }
+
+    fn source_kind(&self) -> &'static str {
+        "yml"
+    }
+
+    fn section_name(&self) -> &'static str {
+        "team-yml"
+    }
 }
 
 #[cfg(test)]
@@ -1,9 +1,12 @@
-use crate::project::{Project, ProjectFile};
+use crate::cache::{Cache, Caching, ResolvedOwners};
+use crate::project::{Project, ProjectFile, Team};
 use core::fmt;
 use std::collections::HashSet;
 use std::fmt::Display;
-use std::path::PathBuf;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 use error_stack::Context;
 use itertools::Itertools;
@@ -13,39 +16,184 @@ use similar::{ChangeTag, TextDiff};
 use tracing::debug;
 use tracing::instrument;
 
+use crate::path_utils::{glob_matches, to_slash_str, to_slash_string_lossy};
+use crate::project_file_builder::read_file_header;
+
+use super::audit_log::AuditLog;
 use super::file_generator::FileGenerator;
 use super::file_owner_finder::FileOwnerFinder;
 use super::file_owner_finder::Owner;
-use super::mapper::{Mapper, OwnerMatcher, TeamName};
+use super::mapper::{Mapper, OwnerMatcher, Source, TeamName};
 
 pub struct Validator {
     pub project: Arc<Project>,
     pub mappers: Vec<Box<dyn Mapper>>,
     pub file_generator: FileGenerator,
     pub executable_name: String,
+    /// Also require a file's `@team` annotation to agree with its package or `.codeowner` owner.
+    pub consistent_annotations: bool,
+    /// Where each file's resolved owners are recorded, if anywhere
+    pub audit_log: Option<Arc<AuditLog>>,
+    /// Stop at the first error found, skipping the files and checks after it. `validate_since_cache` ignores it,
+    /// as it refreshes the cache for every file anyway
+    pub fail_fast: bool,
+    /// Annotated files outside the project whose annotations must still name a known team (`validate --check-all-annotations`)
+    pub annotated_files_outside_project: Vec<ProjectFile>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, serde::Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
 enum Error {
-    InvalidTeam { name: String, path: PathBuf },
-    FileWithoutOwner { path: PathBuf },
-    FileWithMultipleOwners { path: PathBuf, owners: Vec<Owner> },
-    CodeownershipFileIsStale { executable_name: String, diff: String },
+    InvalidTeam {
+        name: String,
+        path: PathBuf,
+    },
+    FileWithoutOwner {
+        path: PathBuf,
+    },
+    FileWithMultipleOwners {
+        path: PathBuf,
+        #[serde(serialize_with = "serialize_owners")]
+        owners: Vec<Owner>,
+    },
+    CodeownershipFileIsStale {
+        executable_name: String,
+        diff: String,
+    },
+    TeamNotFound {
+        name: String,
+    },
+    ConflictingAnnotations {
+        path: PathBuf,
+        teams: Vec<String>,
+        /// 1-based line of the first annotation.
+        #[serde(skip)]
+        line: Option<usize>,
+    },
+    ForbiddenAnnotation {
+        path: PathBuf,
+        team: String,
+        #[serde(skip)]
+        line: Option<usize>,
+    },
+    InconsistentAnnotation {
+        path: PathBuf,
+        annotated_team: String,
+        owner_team: String,
+        #[serde(serialize_with = "serialize_display")]
+        source: Source,
+        #[serde(skip)]
+        line: Option<usize>,
+    },
+}
+
+/// Owners as `{team, sources}` with each source described in words, as the text output does.
+fn serialize_owners<S: serde::Serializer>(owners: &[Owner], serializer: S) -> Result<S::Ok, S::Error> {
+    use serde::ser::SerializeSeq;
+    let mut seq = serializer.serialize_seq(Some(owners.len()))?;
+    for owner in owners.iter().sorted_by_key(|owner| owner.team_name.to_lowercase()) {
+        seq.serialize_element(&serde_json::json!({
+            "team": owner.team_name,
+            "sources": owner.sources.iter().map(|source| source.to_string()).collect::<Vec<_>>(),
+        }))?;
+    }
+    seq.end()
+}
+
+fn serialize_display<S: serde::Serializer>(value: &impl Display, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(value)
 }
 
 #[derive(Debug)]
 pub struct Errors(Vec<Error>);
 
+/// One validation error for `validate --json`: the category it's listed under in the text output, plus the error's
+/// own fields, tagged by `kind` (e.g. `file_without_owner`).
+#[derive(Debug, serde::Serialize)]
+pub struct ErrorRecord<'a> {
+    pub category: String,
+    #[serde(flatten)]
+    error: &'a Error,
+}
+
+/// One validation error flattened to the file it's about (if any), its category and a one-line message, for
+/// output formats that report errors individually.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct ValidationEntry {
+    pub path: Option<PathBuf>,
+    /// Stable kebab-case identifier of the kind of error, e.g. `missing-ownership`
+    pub rule_id: &'static str,
+    pub category: String,
+    pub message: String,
+}
+
 impl Validator {
     #[instrument(level = "debug", skip_all)]
     pub fn validate(&self) -> Result<(), Errors> {
+        if self.fail_fast {
+            return self.validate_until_first_error();
+        }
+        self.validate_file_to_owners(self.file_to_owners())
+    }
+
+    /// `validate` with `fail_fast`: runs the checks in the same order, but each stops at its first error and
+    /// the later checks are skipped, so files after the first failing one are never resolved.
+    fn validate_until_first_error(&self) -> Result<(), Errors> {
+        let first_error = self
+            .validate_invalid_team(None)
+            .into_iter()
+            .next()
+            .or_else(|| {
+                debug!("validate_file_ownership");
+                self.first_file_ownership_error()
+            })
+            .or_else(|| self.validate_conflicting_annotations(None).into_iter().next())
+            .or_else(|| self.validate_forbidden_annotations(None).into_iter().next())
+            .or_else(|| self.validate_codeowners_file().into_iter().next());
+
+        match first_error {
+            Some(error) => Err(Errors(vec![error])),
+            None => Ok(()),
+        }
+    }
+
+    /// Like `validate`, but reuses the owners `cache` holds for files unchanged since they were resolved,
+    /// as long as no package, directory, glob, or team has changed either. Resolved owners are written back to `cache`.
+    #[instrument(level = "debug", skip_all)]
+    pub fn validate_since_cache(&self, cache: &Cache) -> (Result<(), Errors>, ResolutionCounts) {
+        let (file_to_owners, counts) = self.file_to_owners_since_cache(cache);
+        (self.validate_file_to_owners(file_to_owners), counts)
+    }
+
+    fn validate_file_to_owners(&self, file_to_owners: Vec<(&ProjectFile, Vec<Owner>)>) -> Result<(), Errors> {
         let mut validation_errors = Vec::new();
 
         debug!("validate_invalid_team");
-        validation_errors.append(&mut self.validate_invalid_team());
+        validation_errors.append(&mut self.validate_invalid_team(None));
 
         debug!("validate_file_ownership");
-        validation_errors.append(&mut self.validate_file_ownership());
+        let mut inconsistent_annotations = if self.consistent_annotations {
+            debug!("validate_consistent_annotations");
+            inconsistent_annotation_errors(&self.project, &file_to_owners)
+        } else {
+            vec![]
+        };
+        let mut file_ownership_errors = self.file_ownership_errors(file_to_owners);
+        // A disagreeing annotation also gives the file two owners; report it once, as the more specific error
+        file_ownership_errors.retain(|error| match error {
+            Error::FileWithMultipleOwners { path, .. } => !inconsistent_annotations
+                .iter()
+                .any(|inconsistent| inconsistent.path() == Some(path)),
+            _ => true,
+        });
+        validation_errors.append(&mut file_ownership_errors);
+        validation_errors.append(&mut inconsistent_annotations);
+
+        debug!("validate_conflicting_annotations");
+        validation_errors.append(&mut self.validate_conflicting_annotations(None));
+
+        debug!("validate_forbidden_annotations");
+        validation_errors.append(&mut self.validate_forbidden_annotations(None));
 
         debug!("validate_codeowners_file");
         validation_errors.append(&mut self.validate_codeowners_file());
@@ -57,27 +205,91 @@ impl Validator {
         }
     }
 
-    fn validate_invalid_team(&self) -> Vec<Error> {
+    /// Like `validate`, but only reports errors for files the team is responsible for: files it owns
+    /// through any mapper, anything under its packages and `.codeowner` directories, and its team file.
+    /// The CODEOWNERS staleness check is likewise limited to the team's own lines.
+    #[instrument(level = "debug", skip_all)]
+    pub fn validate_team(&self, team_name: &str) -> Result<(), Errors> {
+        let Some(team) = self.project.get_team(team_name) else {
+            return Err(Errors(vec![Error::TeamNotFound {
+                name: team_name.to_owned(),
+            }]));
+        };
+
+        // The scope needs every file's owners, but with `fail_fast` the checks after the first failing one are skipped
+        let file_to_owners = self.file_to_owners();
+        let scope = TeamScope::build(&self.project, &team, &file_to_owners);
+        let mut validation_errors = Vec::new();
+
+        debug!("validate_invalid_team");
+        validation_errors.append(&mut self.validate_invalid_team(Some(&scope)));
+
+        if !self.stops_at(&validation_errors) {
+            debug!("validate_file_ownership");
+            let mut file_ownership_errors = self.file_ownership_errors(file_to_owners);
+            file_ownership_errors.retain(|error| scope.covers(error));
+            validation_errors.append(&mut file_ownership_errors);
+        }
+
+        if !self.stops_at(&validation_errors) {
+            debug!("validate_conflicting_annotations");
+            validation_errors.append(&mut self.validate_conflicting_annotations(Some(&scope)));
+        }
+
+        if !self.stops_at(&validation_errors) {
+            debug!("validate_forbidden_annotations");
+            validation_errors.append(&mut self.validate_forbidden_annotations(Some(&scope)));
+        }
+
+        if !self.stops_at(&validation_errors) {
+            debug!("validate_codeowners_file");
+            validation_errors.append(&mut self.validate_team_codeowners_lines(&team));
+        }
+
+        if self.fail_fast {
+            validation_errors.truncate(1);
+        }
+        if validation_errors.is_empty() {
+            Ok(())
+        } else {
+            Err(Errors(validation_errors))
+        }
+    }
+
+    /// With `fail_fast`, whether `validation_errors` already holds the error to report, so later checks can be skipped.
+    fn stops_at(&self, validation_errors: &[Error]) -> bool {
+        self.fail_fast && !validation_errors.is_empty()
+    }
+
+    /// Errors for owners naming unknown teams. With `scope`, only those the team is responsible for.
+    fn validate_invalid_team(&self, scope: Option<&TeamScope>) -> Vec<Error> {
         debug!("validating project");
         let mut errors: Vec<Error> = Vec::new();
 
-        let team_names: HashSet<&TeamName> = self.project.teams.iter().map(|team| &team.name).collect();
+        // Owners may be written as a team's name or its full GitHub handle (e.g. `@org/parent/child`), as the mappers accept both
+        let team_names: HashSet<&TeamName> = self.project.teams_by_name.keys().collect();
 
-        errors.append(&mut self.invalid_team_annotation(&team_names));
+        errors.append(&mut self.invalid_team_annotation(&team_names, scope));
         errors.append(&mut self.invalid_package_ownership(&team_names));
+        errors.append(&mut self.invalid_extension_ownership(&team_names));
+        errors.append(&mut self.invalid_team_files_owner(&team_names));
+        errors.append(&mut self.invalid_default_owner(&team_names));
+        errors.append(&mut self.invalid_override_ownership(&team_names));
+        errors.retain(|error| scope.is_none_or(|scope| scope.covers(error)));
 
         errors
     }
 
-    fn invalid_team_annotation(&self, team_names: &HashSet<&String>) -> Vec<Error> {
+    fn invalid_team_annotation(&self, team_names: &HashSet<&String>, scope: Option<&TeamScope>) -> Vec<Error> {
         let project = self.project.clone();
 
-        self.project
-            .files
-            .par_iter()
-            .flat_map(|file| {
+        self.check_files(
+            self.project.files.par_iter().chain(self.annotated_files_outside_project.par_iter()),
+            scope,
+            |file| {
                 if let Some(owner) = &file.owner
                     && !team_names.contains(owner)
+                    && !project.is_ignored_owner(owner)
                 {
                     return Some(Error::InvalidTeam {
                         name: owner.clone(),
@@ -86,8 +298,8 @@ impl Validator {
                 }
 
                 None
-            })
-            .collect()
+            },
+        )
     }
 
     fn invalid_package_ownership(&self, team_names: &HashSet<&String>) -> Vec<Error> {
@@ -107,23 +319,183 @@ impl Validator {
             .collect()
     }
 
-    fn validate_file_ownership(&self) -> Vec<Error> {
-        let mut validation_errors = Vec::new();
+    fn invalid_extension_ownership(&self, team_names: &HashSet<&String>) -> Vec<Error> {
+        self.project
+            .extension_owners
+            .iter()
+            .filter(|(_, owner)| !team_names.contains(owner))
+            .map(|(extension, owner)| Error::InvalidTeam {
+                name: owner.clone(),
+                path: PathBuf::from(format!("extension_owners.{}", extension)),
+            })
+            .collect()
+    }
 
-        for (file, owners) in self.file_to_owners() {
-            let relative_path = self.project.relative_path(&file.path).to_owned();
+    fn invalid_default_owner(&self, team_names: &HashSet<&String>) -> Vec<Error> {
+        self.project
+            .default_owner
+            .iter()
+            .filter(|owner| !team_names.contains(owner))
+            .map(|owner| Error::InvalidTeam {
+                name: owner.clone(),
+                path: PathBuf::from("default_owner"),
+            })
+            .collect()
+    }
+
+    fn invalid_override_ownership(&self, team_names: &HashSet<&String>) -> Vec<Error> {
+        self.project
+            .overrides
+            .iter()
+            .filter(|ownership_override| !team_names.contains(&ownership_override.team))
+            .map(|ownership_override| Error::InvalidTeam {
+                name: ownership_override.team.clone(),
+                path: PathBuf::from(format!("overrides.{}", ownership_override.glob)),
+            })
+            .collect()
+    }
+
+    fn invalid_team_files_owner(&self, team_names: &HashSet<&String>) -> Vec<Error> {
+        self.project
+            .team_files_owner
+            .iter()
+            .filter(|owner| !team_names.contains(owner))
+            .map(|owner| Error::InvalidTeam {
+                name: owner.clone(),
+                path: PathBuf::from("team_files_owner"),
+            })
+            .collect()
+    }
+
+    /// In strict mode, re-reads each file's header to catch annotations for more than one team,
+    /// which would otherwise resolve silently to the first.
+    fn validate_conflicting_annotations(&self, scope: Option<&TeamScope>) -> Vec<Error> {
+        let Some(header_lines) = self.project.strict_annotation_lines else {
+            return vec![];
+        };
+        let project = self.project.clone();
+
+        self.check_files(self.project.files.par_iter(), scope, |file| {
+            let content = read_file_header(&file.path)?;
+            let teams = project.annotation_parser.annotated_teams(&content, header_lines);
+            // A team annotated once by name and once by GitHub handle isn't a conflict
+            let distinct_teams = teams.iter().map(|team| canonical_team_name(&project, team)).unique().count();
+            (distinct_teams > 1).then(|| Error::ConflictingAnnotations {
+                path: project.relative_path(&file.path).to_owned(),
+                teams,
+                line: project
+                    .annotation_parser
+                    .parse_owner(&content, header_lines)
+                    .map(|annotation| annotation.line),
+            })
+        })
+    }
+
+    fn validate_forbidden_annotations(&self, scope: Option<&TeamScope>) -> Vec<Error> {
+        if self.project.annotation_forbidden_globs.is_empty() {
+            return vec![];
+        }
+        let project = self.project.clone();
 
-            if owners.is_empty() {
-                validation_errors.push(Error::FileWithoutOwner { path: relative_path })
-            } else if owners.len() > 1 {
-                validation_errors.push(Error::FileWithMultipleOwners {
-                    path: relative_path,
-                    owners,
+        self.check_files(self.project.files.par_iter(), scope, |file| {
+            let owner = file.owner.as_ref().filter(|owner| !project.is_ignored_owner(owner))?;
+            let relative_path = project.relative_path(&file.path);
+            let slash_path = to_slash_str(relative_path)?;
+            project
+                .annotation_forbidden_globs
+                .iter()
+                .any(|glob| glob_matches(glob, &slash_path, project.case_insensitive_paths))
+                .then(|| Error::ForbiddenAnnotation {
+                    path: relative_path.to_owned(),
+                    team: owner.clone(),
+                    line: annotation_line(&project, file),
                 })
+        })
+    }
+
+    pub fn unowned_files(&self) -> Vec<PathBuf> {
+        let mut unowned_files: Vec<PathBuf> = self
+            .file_to_owners()
+            .into_iter()
+            .filter(|(_, owners)| owners.is_empty())
+            .map(|(file, _)| self.project.relative_path(&file.path).to_owned())
+            .collect();
+        unowned_files.sort();
+        unowned_files
+    }
+
+    /// Project files with at least one owner, relative to the project root and sorted by path.
+    pub fn owned_files(&self) -> Vec<(PathBuf, Vec<Owner>)> {
+        let mut owned_files: Vec<(PathBuf, Vec<Owner>)> = self
+            .file_to_owners()
+            .into_iter()
+            .filter(|(_, owners)| !owners.is_empty())
+            .map(|(file, owners)| (self.project.relative_path(&file.path).to_owned(), owners))
+            .collect();
+        owned_files.sort_by(|(a, _), (b, _)| a.cmp(b));
+        owned_files
+    }
+
+    fn file_ownership_errors(&self, file_to_owners: Vec<(&ProjectFile, Vec<Owner>)>) -> Vec<Error> {
+        file_to_owners
+            .into_iter()
+            .filter_map(|(file, owners)| file_ownership_error(&self.project, file, owners))
+            .collect()
+    }
+
+    /// The first file, in project order, that is unowned, has several owners, or (with `consistent_annotations`)
+    /// has an annotation disagreeing with its package or directory. Files are resolved in parallel, but resolution
+    /// stops once an earlier file is known to fail.
+    fn first_file_ownership_error(&self) -> Option<Error> {
+        let owner_matchers: Vec<OwnerMatcher> = self.mappers.iter().flat_map(|mapper| mapper.owner_matchers()).collect();
+        let file_owner_finder = FileOwnerFinder {
+            owner_matchers: &owner_matchers,
+            case_insensitive_paths: self.project.case_insensitive_paths,
+        };
+        let project = self.project.clone();
+        let consistent_annotations = self.consistent_annotations;
+        let audit_log = self.audit_log.as_deref();
+
+        self.project.files.par_iter().find_map_first(|project_file| {
+            let relative_path = project.relative_path(&project_file.path);
+            let owners = file_owner_finder.find(relative_path);
+            if let Some(audit_log) = audit_log {
+                audit_log.record(relative_path, &owners);
             }
+            let inconsistent_annotation = if consistent_annotations {
+                inconsistent_annotation_error(&project, project_file, &owners)
+            } else {
+                None
+            };
+            inconsistent_annotation.or_else(|| file_ownership_error(&project, project_file, owners))
+        })
+    }
+
+    /// Runs `check` over `files`, keeping the errors `scope` covers (all of them without one): every error, or
+    /// with `fail_fast` only the first, after which the remaining files are skipped.
+    fn check_files<'a>(
+        &self,
+        files: impl ParallelIterator<Item = &'a ProjectFile>,
+        scope: Option<&TeamScope>,
+        check: impl Fn(&'a ProjectFile) -> Option<Error> + Sync + Send,
+    ) -> Vec<Error> {
+        let check = |file| check(file).filter(|error| scope.is_none_or(|scope| scope.covers(error)));
+        if self.fail_fast {
+            files.find_map_first(check).into_iter().collect()
+        } else {
+            files.filter_map(check).collect()
         }
+    }
 
-        validation_errors
+    /// Only checks that the CODEOWNERS file matches what `generate` would write, for when the files being
+    /// validated are given explicitly.
+    pub fn validate_codeowners_file_is_fresh(&self) -> Result<(), Errors> {
+        let validation_errors = self.validate_codeowners_file();
+        if validation_errors.is_empty() {
+            Ok(())
+        } else {
+            Err(Errors(validation_errors))
+        }
     }
 
     fn validate_codeowners_file(&self) -> Vec<Error> {
@@ -140,12 +512,36 @@ impl Validator {
         }
     }
 
+    fn validate_team_codeowners_lines(&self, team: &Team) -> Vec<Error> {
+        let team_suffix = format!(" {}", team.github_team);
+        let team_lines = |file: &str| file.lines().filter(|line| line.ends_with(&team_suffix)).join("\n");
+
+        let generated_lines = team_lines(&self.file_generator.generate_file());
+        let current_lines = team_lines(&self.project.get_codeowners_file().unwrap_or_default());
+
+        if generated_lines == current_lines {
+            vec![]
+        } else {
+            vec![Error::CodeownershipFileIsStale {
+                executable_name: self.executable_name.to_string(),
+                diff: codeowners_diff(&current_lines, &generated_lines),
+            }]
+        }
+    }
+
+    /// Resolves every file, for the audit log's sake, without validating anything.
+    pub fn record_resolutions(&self) {
+        self.file_to_owners();
+    }
+
     fn file_to_owners(&self) -> Vec<(&ProjectFile, Vec<Owner>)> {
         let owner_matchers: Vec<OwnerMatcher> = self.mappers.iter().flat_map(|mapper| mapper.owner_matchers()).collect();
         let file_owner_finder = FileOwnerFinder {
             owner_matchers: &owner_matchers,
+            case_insensitive_paths: self.project.case_insensitive_paths,
         };
         let project = self.project.clone();
+        let audit_log = self.audit_log.as_deref();
 
         self.project
             .files
@@ -153,10 +549,226 @@ impl Validator {
             .filter_map(|project_file| {
                 let relative_path = project.relative_path(&project_file.path);
                 let owners = file_owner_finder.find(relative_path);
+                if let Some(audit_log) = audit_log {
+                    audit_log.record(relative_path, &owners);
+                }
                 Some((project_file, owners))
             })
             .collect()
     }
+
+    fn file_to_owners_since_cache(&self, cache: &Cache) -> (Vec<(&ProjectFile, Vec<Owner>)>, ResolutionCounts) {
+        let owner_matchers: Vec<OwnerMatcher> = self.mappers.iter().flat_map(|mapper| mapper.owner_matchers()).collect();
+        let fingerprint = resolution_fingerprint(&self.project, &owner_matchers);
+        let file_owner_finder = FileOwnerFinder {
+            owner_matchers: &owner_matchers,
+            case_insensitive_paths: self.project.case_insensitive_paths,
+        };
+        let project = self.project.clone();
+        let audit_log = self.audit_log.as_deref();
+        let resolved = AtomicUsize::new(0);
+
+        let file_to_owners: Vec<(&ProjectFile, Vec<Owner>)> = self
+            .project
+            .files
+            .par_iter()
+            .map(|project_file| {
+                let cached = cache
+                    .get_file_owner(&project_file.path)
+                    .ok()
+                    .flatten()
+                    .and_then(|entry| entry.resolved_owners)
+                    .filter(|resolved_owners| resolved_owners.fingerprint == fingerprint);
+                let relative_path = project.relative_path(&project_file.path);
+                let owners = match cached {
+                    Some(resolved_owners) => resolved_owners.owners,
+                    None => {
+                        resolved.fetch_add(1, Ordering::Relaxed);
+                        let owners = file_owner_finder.find(relative_path);
+                        cache.write_resolved_owners(
+                            &project_file.path,
+                            ResolvedOwners {
+                                fingerprint,
+                                owners: owners.clone(),
+                            },
+                        );
+                        owners
+                    }
+                };
+                // Owners reused from the cache are recorded too, so the log covers every file the run relied on
+                if let Some(audit_log) = audit_log {
+                    audit_log.record(relative_path, &owners);
+                }
+                (project_file, owners)
+            })
+            .collect();
+
+        let counts = ResolutionCounts {
+            resolved: resolved.into_inner(),
+            total: file_to_owners.len(),
+        };
+        (file_to_owners, counts)
+    }
+}
+
+/// How many files `validate_since_cache` ran the mappers for, out of all project files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResolutionCounts {
+    pub resolved: usize,
+    pub total: usize,
+}
+
+/// Identifies everything besides a file's own annotation that decides its owners. Annotation matchers
+/// are left out: each only covers its own file, whose mtime already invalidates the cached owners.
+fn resolution_fingerprint(project: &Project, owner_matchers: &[OwnerMatcher]) -> u64 {
+    fn hash_of(value: impl Hash) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    // Mappers list packages and directories in walk order, which varies between runs
+    let matcher_hashes: Vec<u64> = owner_matchers
+        .iter()
+        .filter_map(|owner_matcher| match owner_matcher {
+            OwnerMatcher::ExactMatches(_, Source::AnnotatedFile) => None,
+            OwnerMatcher::ExactMatches(matches, source) => Some(hash_of((matches.iter().sorted().collect_vec(), source))),
+            OwnerMatcher::Glob {
+                glob,
+                subtracted_globs,
+                team_name,
+                source,
+            } => Some(hash_of((glob, subtracted_globs, team_name, source))),
+        })
+        .sorted()
+        .collect();
+    let team_names: Vec<&String> = project.teams.iter().map(|team| &team.name).sorted().collect();
+
+    hash_of((env!("CARGO_PKG_VERSION"), team_names, &project.ignored_owners, matcher_hashes))
+}
+
+/// The name of the team `owner` refers to by name or GitHub handle, or `owner` itself for an unknown team.
+fn canonical_team_name<'a>(project: &'a Project, owner: &'a str) -> &'a str {
+    project.teams_by_name.get(owner).map_or(owner, |team| team.name.as_str())
+}
+
+/// 1-based line of the annotation `file.owner` was read from. That is the first annotation in the file, so the whole
+/// header is searched, whatever `annotation_header_lines` was.
+fn annotation_line(project: &Project, file: &ProjectFile) -> Option<usize> {
+    let content = read_file_header(&file.path)?;
+    project
+        .annotation_parser
+        .parse_owner(&content, usize::MAX)
+        .map(|annotation| annotation.line)
+}
+
+fn file_ownership_error(project: &Project, file: &ProjectFile, owners: Vec<Owner>) -> Option<Error> {
+    let relative_path = project.relative_path(&file.path).to_owned();
+
+    if owners.is_empty() {
+        Some(Error::FileWithoutOwner { path: relative_path })
+    } else if owners.len() > 1 {
+        Some(Error::FileWithMultipleOwners {
+            path: relative_path,
+            owners,
+        })
+    } else {
+        None
+    }
+}
+
+/// Annotated files whose package or most specific `.codeowner` names a different team than the annotation.
+fn inconsistent_annotation_errors(project: &Project, file_to_owners: &[(&ProjectFile, Vec<Owner>)]) -> Vec<Error> {
+    let mut errors: Vec<Error> = file_to_owners
+        .iter()
+        .filter_map(|(file, owners)| inconsistent_annotation_error(project, file, owners))
+        .collect();
+    errors.sort_by(|a, b| a.path().cmp(&b.path()));
+    errors
+}
+
+fn inconsistent_annotation_error(project: &Project, file: &ProjectFile, owners: &[Owner]) -> Option<Error> {
+    let annotated_team = file.owner.as_ref().filter(|owner| !project.is_ignored_owner(owner))?;
+    let annotated_team_name = canonical_team_name(project, annotated_team);
+    owners
+        .iter()
+        .filter(|owner| owner.team_name != annotated_team_name)
+        .find_map(|owner| {
+            let source = owner
+                .sources
+                .iter()
+                .find(|source| matches!(source, Source::Package(_, _) | Source::Directory(_)))?;
+            Some(Error::InconsistentAnnotation {
+                path: project.relative_path(&file.path).to_owned(),
+                annotated_team: annotated_team.clone(),
+                owner_team: owner.team_name.clone(),
+                source: source.clone(),
+                line: annotation_line(project, file),
+            })
+        })
+}
+
+/// The slice of the project a single team is responsible for, used by `Validator::validate_team`.
+struct TeamScope {
+    team_names: [String; 2],
+    owned_paths: HashSet<PathBuf>,
+    roots: Vec<PathBuf>,
+}
+
+impl TeamScope {
+    fn build(project: &Project, team: &Team, file_to_owners: &[(&ProjectFile, Vec<Owner>)]) -> Self {
+        let team_names = [team.name.clone(), team.github_team.clone()];
+        let is_team = |name: &String| team_names.contains(name);
+
+        let owned_paths = file_to_owners
+            .iter()
+            .filter(|(_, owners)| owners.iter().any(|owner| is_team(&owner.team_name)))
+            .map(|(file, _)| project.relative_path(&file.path).to_owned())
+            .chain(std::iter::once(project.relative_path(&team.path).to_owned()))
+            .collect();
+
+        let package_roots = project
+            .packages
+            .iter()
+            .filter(|package| is_team(&package.owner))
+            .filter_map(|package| package.package_root());
+        let directory_roots = project
+            .directory_codeowner_files
+            .iter()
+            .filter(|file| is_team(&file.owner))
+            .filter_map(|file| file.directory_root());
+        let roots = package_roots.chain(directory_roots).map(Path::to_path_buf).collect();
+
+        Self {
+            team_names,
+            owned_paths,
+            roots,
+        }
+    }
+
+    fn contains(&self, relative_path: &Path) -> bool {
+        self.owned_paths.contains(relative_path) || self.roots.iter().any(|root| relative_path.starts_with(root))
+    }
+
+    fn covers(&self, error: &Error) -> bool {
+        match error {
+            Error::InvalidTeam { path, .. } | Error::FileWithoutOwner { path } => self.contains(path),
+            Error::ConflictingAnnotations { path, teams, .. } => {
+                self.contains(path) || teams.iter().any(|team| self.team_names.contains(team))
+            }
+            Error::ForbiddenAnnotation { path, team, .. } => self.contains(path) || self.team_names.contains(team),
+            Error::InconsistentAnnotation {
+                path,
+                annotated_team,
+                owner_team,
+                ..
+            } => self.contains(path) || self.team_names.contains(annotated_team) || self.team_names.contains(owner_team),
+            Error::FileWithMultipleOwners { path, owners } => {
+                self.contains(path) || owners.iter().any(|owner| self.team_names.contains(&owner.team_name))
+            }
+            Error::CodeownershipFileIsStale { .. } | Error::TeamNotFound { .. } => true,
+        }
+    }
 }
 
 /// Builds a line-oriented diff between the current (on-disk) CODEOWNERS file and the
@@ -188,6 +800,14 @@ impl Error {
                     format!("CODEOWNERS out of date. Run `{}` to update the CODEOWNERS file", executable_name)
                 }
                 Error::InvalidTeam { name: _, path: _ } => "Found invalid team annotations".to_owned(),
+                Error::TeamNotFound { name } => format!("Team `{}` not found", name),
+                Error::ConflictingAnnotations { path: _, teams: _, line: _ } => "Found conflicting annotations in file".to_owned(),
+                Error::ForbiddenAnnotation { path: _, team: _, line: _ } => {
+                    "Found annotations under `annotation_forbidden_globs`. Declare ownership with a package or `.codeowner` file instead".to_owned()
+                }
+                Error::InconsistentAnnotation { .. } => {
+                    "Found annotations that disagree with the file's package or directory owner".to_owned()
+                }
             }
     }
 
@@ -213,10 +833,51 @@ impl Error {
             // so that a long diff doesn't bury the actionable headline.
             Error::CodeownershipFileIsStale { .. } => vec![],
             Error::InvalidTeam { name, path } => vec![format!("- {} is referencing an invalid team - '{}'", path.to_string_lossy(), name)],
+            Error::TeamNotFound { .. } => vec![],
+            Error::ConflictingAnnotations { path, teams, .. } => vec![format!(
+                "- {} is annotated with multiple teams - {}",
+                path.to_string_lossy(),
+                teams.iter().map(|team| format!("'{}'", team)).join(", ")
+            )],
+            Error::ForbiddenAnnotation { path, team, .. } => vec![format!("- {} is annotated with '{}'", path.to_string_lossy(), team)],
+            Error::InconsistentAnnotation {
+                path,
+                annotated_team,
+                owner_team,
+                source,
+                ..
+            } => vec![format!(
+                "- {} is annotated with '{}', but {} names '{}'",
+                path.to_string_lossy(),
+                annotated_team,
+                owner_declaration(source),
+                owner_team
+            )],
+        }
+    }
+
+    fn path(&self) -> Option<&PathBuf> {
+        match self {
+            Error::InvalidTeam { path, .. }
+            | Error::FileWithoutOwner { path }
+            | Error::FileWithMultipleOwners { path, .. }
+            | Error::ConflictingAnnotations { path, .. }
+            | Error::ForbiddenAnnotation { path, .. }
+            | Error::InconsistentAnnotation { path, .. } => Some(path),
+            Error::CodeownershipFileIsStale { .. } | Error::TeamNotFound { .. } => None,
         }
     }
 }
 
+/// The file declaring a package or directory owner, e.g. `packs/foo/package.yml`.
+fn owner_declaration(source: &Source) -> String {
+    match source {
+        Source::Package(package_path, _) => format!("`{}`", package_path),
+        Source::Directory(directory) => format!("`{}/.codeowner`", directory),
+        source => source.to_string(),
+    }
+}
+
 impl Errors {
     /// Supplementary detail that explains *what* is wrong without itself being an error.
     /// The stale-CODEOWNERS diff is surfaced here, as informational output, rather than
@@ -234,6 +895,176 @@ impl Errors {
             })
             .collect()
     }
+
+    /// These errors without the stale-CODEOWNERS one, for validating against the generated file instead of the
+    /// one on disk, which by definition matches it.
+    pub fn without_stale_codeowners(self) -> Result<(), Errors> {
+        let errors: Vec<Error> = self
+            .0
+            .into_iter()
+            .filter(|error| !matches!(error, Error::CodeownershipFileIsStale { .. }))
+            .collect();
+        if errors.is_empty() { Ok(()) } else { Err(Errors(errors)) }
+    }
+}
+
+impl Errors {
+    /// Every error, whatever its category, ordered by category and then path as in the text output.
+    pub fn records(&self) -> Vec<ErrorRecord<'_>> {
+        self.0
+            .iter()
+            .map(|error| ErrorRecord {
+                category: error.category(),
+                error,
+            })
+            .sorted_by_cached_key(|record| (record.category.clone(), record.error.messages()))
+            .collect()
+    }
+
+    /// Every error as a `ValidationEntry`, ordered by category, then path, then message.
+    pub fn to_entries(&self) -> Vec<ValidationEntry> {
+        self.0
+            .iter()
+            .map(Error::entry)
+            .sorted_by(|a, b| (&a.category, &a.path, &a.message).cmp(&(&b.category, &b.path, &b.message)))
+            .collect()
+    }
+
+    /// Each error as a GitHub Actions `::error` workflow command, so CI shows it inline on the diff.
+    /// Files are relative to `project_root`; annotation errors also point at the offending line.
+    pub fn github_annotations(&self, project_root: &Path) -> Vec<String> {
+        self.0
+            .iter()
+            .map(|error| {
+                let entry = error.entry();
+                let line = error.line(project_root);
+                github_annotation(entry.path.as_deref(), line, &entry.message)
+            })
+            .sorted()
+            .collect()
+    }
+
+    /// Each error as a SARIF 2.1.0 `result`, in `to_entries` order. Locations are relative to `project_root`;
+    /// a stale CODEOWNERS file points at `codeowners_file`, and a missing team at nothing.
+    pub fn sarif_results(&self, project_root: &Path, codeowners_file: &Path) -> Vec<serde_json::Value> {
+        self.0
+            .iter()
+            .map(|error| (error.entry(), error.line(project_root), error))
+            .sorted_by(|(a, ..), (b, ..)| (&a.category, &a.path, &a.message).cmp(&(&b.category, &b.path, &b.message)))
+            .map(|(entry, line, error)| {
+                let path = match error {
+                    Error::CodeownershipFileIsStale { .. } => Some(codeowners_file),
+                    _ => entry.path.as_deref(),
+                };
+                let locations: Vec<serde_json::Value> = path
+                    .map(|path| {
+                        let mut physical_location = serde_json::json!({
+                            "artifactLocation": { "uri": to_slash_string_lossy(path) },
+                        });
+                        if let Some(line) = line {
+                            physical_location["region"] = serde_json::json!({ "startLine": line });
+                        }
+                        serde_json::json!({ "physicalLocation": physical_location })
+                    })
+                    .into_iter()
+                    .collect();
+                serde_json::json!({
+                    "ruleId": entry.rule_id,
+                    "level": "error",
+                    "message": { "text": entry.message },
+                    "locations": locations,
+                })
+            })
+            .collect()
+    }
+}
+
+impl Error {
+    fn entry(&self) -> ValidationEntry {
+        let message = match self {
+            Error::FileWithoutOwner { .. } => "File is missing ownership".to_owned(),
+            Error::FileWithMultipleOwners { owners, .. } => format!(
+                "File has ownership declared in multiple ways: {}",
+                owners.iter().map(|owner| owner.team_name.as_str()).sorted().join(", ")
+            ),
+            Error::CodeownershipFileIsStale { .. } | Error::TeamNotFound { .. } => self.category(),
+            Error::InvalidTeam { name, .. } => format!("References an invalid team - '{}'", name),
+            Error::ConflictingAnnotations { teams, .. } => format!(
+                "Annotated with multiple teams - {}",
+                teams.iter().map(|team| format!("'{}'", team)).join(", ")
+            ),
+            Error::ForbiddenAnnotation { team, .. } => format!("Annotated with '{}' under `annotation_forbidden_globs`", team),
+            Error::InconsistentAnnotation {
+                annotated_team,
+                owner_team,
+                source,
+                ..
+            } => format!(
+                "Annotated with '{}', but {} names '{}'",
+                annotated_team,
+                owner_declaration(source),
+                owner_team
+            ),
+        };
+        ValidationEntry {
+            path: self.path().cloned(),
+            rule_id: self.rule_id(),
+            category: self.category(),
+            message,
+        }
+    }
+
+    fn rule_id(&self) -> &'static str {
+        match self {
+            Error::InvalidTeam { .. } => "invalid-team",
+            Error::FileWithoutOwner { .. } => "missing-ownership",
+            Error::FileWithMultipleOwners { .. } => "multiple-owners",
+            Error::CodeownershipFileIsStale { .. } => "stale-codeowners",
+            Error::TeamNotFound { .. } => "team-not-found",
+            Error::ConflictingAnnotations { .. } => "conflicting-annotations",
+            Error::ForbiddenAnnotation { .. } => "forbidden-annotation",
+            Error::InconsistentAnnotation { .. } => "inconsistent-annotation",
+        }
+    }
+
+    /// 1-based line of the file the error is about that it concerns, e.g. the annotation's.
+    fn line(&self, project_root: &Path) -> Option<usize> {
+        match self {
+            Error::InvalidTeam { name, path } => line_containing(project_root, path, name),
+            Error::ConflictingAnnotations { line, .. }
+            | Error::ForbiddenAnnotation { line, .. }
+            | Error::InconsistentAnnotation { line, .. } => *line,
+            Error::FileWithoutOwner { .. }
+            | Error::FileWithMultipleOwners { .. }
+            | Error::CodeownershipFileIsStale { .. }
+            | Error::TeamNotFound { .. } => None,
+        }
+    }
+}
+
+fn github_annotation(path: Option<&Path>, line: Option<usize>, message: &str) -> String {
+    // See https://docs.github.com/en/actions/reference/workflow-commands-for-github-actions#setting-an-error-message
+    let escape_data = |value: &str| value.replace('%', "%25").replace('\r', "%0D").replace('\n', "%0A");
+    let escape_property = |value: &str| escape_data(value).replace(':', "%3A").replace(',', "%2C");
+
+    let mut properties = Vec::new();
+    if let Some(path) = path {
+        properties.push(format!("file={}", escape_property(&path.to_string_lossy())));
+    }
+    if let Some(line) = line {
+        properties.push(format!("line={}", line));
+    }
+    if properties.is_empty() {
+        format!("::error::{}", escape_data(message))
+    } else {
+        format!("::error {}::{}", properties.join(","), escape_data(message))
+    }
+}
+
+/// 1-based number of the first line of `relative_path` containing `needle`, if the file can be read.
+fn line_containing(project_root: &Path, relative_path: &Path, needle: &str) -> Option<usize> {
+    let content = std::fs::read_to_string(project_root.join(relative_path)).ok()?;
+    content.lines().position(|line| line.contains(needle)).map(|index| index + 1)
 }
 
 impl Display for Errors {
@@ -298,4 +1129,46 @@ mod tests {
 
         assert_eq!(codeowners_diff(file, file), "");
     }
+
+    #[test]
+    fn test_github_annotation_escapes_properties_and_data() {
+        assert_eq!(
+            github_annotation(Some(Path::new("app/a,b:c.rb")), Some(3), "50% done\nnext"),
+            "::error file=app/a%2Cb%3Ac.rb,line=3::50%25 done%0Anext"
+        );
+        assert_eq!(github_annotation(None, None, "Team `A` not found"), "::error::Team `A` not found");
+    }
+
+    #[test]
+    fn test_to_entries_orders_by_category_then_path() {
+        let errors = Errors(vec![
+            Error::FileWithoutOwner {
+                path: PathBuf::from("app/b.rb"),
+            },
+            Error::TeamNotFound { name: "Ghost".to_owned() },
+            Error::FileWithoutOwner {
+                path: PathBuf::from("app/a.rb"),
+            },
+        ]);
+
+        let missing_ownership = |path: &str| ValidationEntry {
+            path: Some(PathBuf::from(path)),
+            rule_id: "missing-ownership",
+            category: "Some files are missing ownership".to_owned(),
+            message: "File is missing ownership".to_owned(),
+        };
+        assert_eq!(
+            errors.to_entries(),
+            vec![
+                missing_ownership("app/a.rb"),
+                missing_ownership("app/b.rb"),
+                ValidationEntry {
+                    path: None,
+                    rule_id: "team-not-found",
+                    category: "Team `Ghost` not found".to_owned(),
+                    message: "Team `Ghost` not found".to_owned(),
+                },
+            ]
+        );
+    }
 }
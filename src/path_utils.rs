@@ -1,4 +1,9 @@
-use std::path::{Path, PathBuf};
+use std::{
+    borrow::Cow,
+    path::{Path, PathBuf},
+};
+
+use fast_glob::glob_match;
 
 /// Return `path` relative to `root` if possible; otherwise return `path` unchanged.
 pub fn relative_to<'a>(root: &'a Path, path: &'a Path) -> &'a Path {
@@ -10,6 +15,112 @@ pub fn relative_to_buf(root: &Path, path: &Path) -> PathBuf {
     relative_to(root, path).to_path_buf()
 }
 
+/// Where `path` points if it's a symlink: relative to `root` when the target is inside it, otherwise absolute.
+/// `None` for anything that isn't a symlink, or a symlink that doesn't resolve.
+pub fn symlink_target(root: &Path, path: &Path) -> Option<PathBuf> {
+    if !std::fs::symlink_metadata(path).ok()?.file_type().is_symlink() {
+        return None;
+    }
+    let target = path.canonicalize().ok()?;
+    let root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+    Some(relative_to_buf(&root, &target))
+}
+
+/// Return `path` as a `/`-separated string, or `None` if it isn't valid UTF-8. Globs and CODEOWNERS
+/// entries always use `/`, while paths collected on Windows use `\\`. Elsewhere `\\` is a valid file name
+/// character and is kept.
+pub fn to_slash_str(path: &Path) -> Option<Cow<'_, str>> {
+    path.to_str().map(to_slashes)
+}
+
+/// Like `to_slash_str`, but replaces invalid UTF-8 instead of failing.
+pub fn to_slash_string_lossy(path: &Path) -> String {
+    to_slashes(&path.to_string_lossy()).into_owned()
+}
+
+#[cfg(windows)]
+fn to_slashes(path: &str) -> Cow<'_, str> {
+    if path.contains('\\') {
+        Cow::Owned(path.replace('\\', "/"))
+    } else {
+        Cow::Borrowed(path)
+    }
+}
+
+#[cfg(not(windows))]
+fn to_slashes(path: &str) -> Cow<'_, str> {
+    Cow::Borrowed(path)
+}
+
+/// Like `fast_glob::glob_match`, but compares lowercased glob and path when `case_insensitive` is set.
+pub fn glob_matches(glob: &str, path: &str, case_insensitive: bool) -> bool {
+    if case_insensitive {
+        glob_match(glob.to_lowercase(), path.to_lowercase())
+    } else {
+        glob_match(glob, path)
+    }
+}
+
+/// Expands each `{a,b}` group into one glob per alternative, e.g. `packs/{foo,bar}/**` into `packs/foo/**` and
+/// `packs/bar/**`. CODEOWNERS has no brace syntax, so team globs are expanded before they're written or matched.
+/// Nested groups expand too; a group without a comma, an escaped brace, or an unbalanced one is left as written.
+pub fn expand_braces(glob: &str) -> Vec<String> {
+    let Some((open, close, alternatives)) = first_brace_group(glob) else {
+        return vec![glob.to_owned()];
+    };
+    let mut expanded: Vec<String> = Vec::new();
+    for alternative in alternatives {
+        for glob in expand_braces(&format!("{}{}{}", &glob[..open], alternative, &glob[close + 1..])) {
+            if !expanded.contains(&glob) {
+                expanded.push(glob);
+            }
+        }
+    }
+    expanded
+}
+
+/// The byte offsets of the first `{`...`}` group with a top-level comma, and the alternatives between its commas.
+fn first_brace_group(glob: &str) -> Option<(usize, usize, Vec<&str>)> {
+    let bytes = glob.as_bytes();
+    let mut open = 0;
+    while open < bytes.len() {
+        match bytes[open] {
+            b'\\' => {
+                open += 2;
+                continue;
+            }
+            b'{' => {}
+            _ => {
+                open += 1;
+                continue;
+            }
+        }
+
+        let mut depth = 0;
+        let mut commas = Vec::new();
+        let mut index = open;
+        let close = loop {
+            match bytes.get(index)? {
+                b'\\' => index += 1,
+                b'{' => depth += 1,
+                b'}' if depth == 1 => break index,
+                b'}' => depth -= 1,
+                b',' if depth == 1 => commas.push(index),
+                _ => {}
+            }
+            index += 1;
+        };
+
+        if !commas.is_empty() {
+            let starts = std::iter::once(open).chain(commas.iter().copied());
+            let ends = commas.iter().copied().chain(std::iter::once(close));
+            return Some((open, close, starts.zip(ends).map(|(start, end)| &glob[start + 1..end]).collect()));
+        }
+        open += 1;
+    }
+    None
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -46,4 +157,46 @@ mod tests {
         let rel_buf = relative_to_buf(root, path);
         assert_eq!(rel_ref, rel_buf.as_path());
     }
+
+    #[test]
+    #[cfg(windows)]
+    fn to_slash_str_normalizes_backslashes() {
+        assert_eq!(to_slash_str(Path::new("app\\models\\x.rb")).as_deref(), Some("app/models/x.rb"));
+        assert_eq!(to_slash_str(Path::new("app/models/x.rb")).as_deref(), Some("app/models/x.rb"));
+        assert_eq!(to_slash_string_lossy(Path::new("packs\\foo")), "packs/foo");
+    }
+
+    #[test]
+    #[cfg(not(windows))]
+    fn to_slash_str_keeps_backslashes_in_file_names() {
+        assert_eq!(to_slash_str(Path::new("app/models/a\\b.rb")).as_deref(), Some("app/models/a\\b.rb"));
+        assert_eq!(to_slash_string_lossy(Path::new("packs\\foo")), "packs\\foo");
+    }
+
+    #[test]
+    fn glob_matches_respects_case_insensitivity() {
+        assert!(!glob_matches("app/**/*.rb", "App/Models/x.rb", false));
+        assert!(glob_matches("app/**/*.rb", "App/Models/x.rb", true));
+        assert!(glob_matches("App/**/*.RB", "app/models/x.rb", true));
+    }
+
+    #[test]
+    fn expand_braces_splits_alternatives() {
+        assert_eq!(expand_braces("packs/{foo,bar}/**"), vec!["packs/foo/**", "packs/bar/**"]);
+        assert_eq!(
+            expand_braces("{app,lib}/**/*.{rb,rake}"),
+            vec!["app/**/*.rb", "app/**/*.rake", "lib/**/*.rb", "lib/**/*.rake"]
+        );
+        assert_eq!(expand_braces("packs/{a,{b,c}}/**"), vec!["packs/a/**", "packs/b/**", "packs/c/**"]);
+        assert_eq!(expand_braces("packs/{x{1,2}}/**"), vec!["packs/{x1}/**", "packs/{x2}/**"]);
+        assert_eq!(expand_braces("{a,a,b}"), vec!["a", "b"]);
+        assert_eq!(expand_braces("{,_}test.rb"), vec!["test.rb", "_test.rb"]);
+    }
+
+    #[test]
+    fn expand_braces_leaves_other_globs_alone() {
+        for glob in ["packs/**", "packs/{foo}/**", "packs/\\{a,b}/**", "packs/{a,b/**"] {
+            assert_eq!(expand_braces(glob), vec![glob], "{}", glob);
+        }
+    }
 }
@@ -1,13 +1,19 @@
 use core::fmt;
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap, HashSet},
     fmt::Display,
     fs::File,
     path::{Path, PathBuf},
 };
 
 use error_stack::{Context, Result, ResultExt};
+use fast_glob::glob_match;
 
+use crate::config::OwnershipOverride;
+use crate::ownership::annotations::AnnotationParser;
+use crate::path_utils::expand_braces;
+
+#[derive(Default)]
 pub struct Project {
     pub base_path: PathBuf,
     pub files: Vec<ProjectFile>,
@@ -18,12 +24,36 @@ pub struct Project {
     pub directory_codeowner_files: Vec<DirectoryCodeownersFile>,
     pub teams_by_name: HashMap<String, Team>,
     pub executable_name: String,
+    pub ignored_owners: Vec<String>,
+    /// How many header lines to scan for conflicting annotations, when `strict_annotations` is enabled.
+    pub strict_annotation_lines: Option<usize>,
+    /// Recognises annotations using the configured `annotation_keywords`.
+    pub annotation_parser: AnnotationParser,
+    /// Globs under which files may not carry an `@team` annotation.
+    pub annotation_forbidden_globs: Vec<String>,
+    /// Team owning otherwise unowned files by extension, keyed by extension without the dot.
+    pub extension_owners: BTreeMap<String, String>,
+    /// Team owning every team file in place of the team it defines (see `team_files_owner` in the config).
+    pub team_files_owner: Option<String>,
+    /// Team owning every otherwise unowned file (see `default_owner` in the config).
+    pub default_owner: Option<String>,
+    /// Owners that win over every mapper for matching files (see `overrides` in the config).
+    pub overrides: Vec<OwnershipOverride>,
+    /// Contents of `extra_codeowners_file`, written below the generated CODEOWNERS sections.
+    pub codeowners_appendix: Option<String>,
+    /// Files matching both `owned_globs` and `unowned_globs`, whose annotations are ignored. They are only read when
+    /// validate checks them for annotations (see `Ownership::suppressed_annotations`).
+    pub unowned_glob_files: Vec<PathBuf>,
+    /// Whether globs match paths ignoring case (see `case_insensitive_paths` in the config).
+    pub case_insensitive_paths: bool,
 }
 
 #[derive(Clone, Debug)]
 pub struct VendoredGem {
     pub path: PathBuf,
     pub name: String,
+    /// Owner the gem declares for itself in a `.codeowner` or `package.yml` at its root.
+    pub owner: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -41,22 +71,162 @@ pub struct Team {
     pub subtracted_globs: Vec<String>,
     pub owned_gems: Vec<String>,
     pub avoid_ownership: bool,
+    /// CODEOWNERS sections (by source kind, e.g. `package`) whose lines for this team are commented out.
+    pub exclude_from_sections: Vec<String>,
+    /// The team this one inherits `owned_globs`, `subtracted_globs`, and `owned_gems` from.
+    pub parent: Option<String>,
 }
 
 impl Team {
     pub fn from_team_file_path(absolute_path: PathBuf) -> Result<Self, Error> {
         let file = File::open(&absolute_path).change_context(Error::Io)?;
-        let deserializer: deserializers::Team = serde_yaml::from_reader(file).change_context(Error::SerdeYaml)?;
-        Ok(Self {
+        let mut value: serde_yaml::Value = serde_yaml::from_reader(file).change_context(Error::SerdeYaml)?;
+        // Aliases (`*shared`) resolve while parsing, but `<<: *shared` merge keys are only applied on request
+        value.apply_merge().change_context(Error::SerdeYaml)?;
+        let deserializer: deserializers::Team = serde_yaml::from_value(value).change_context(Error::SerdeYaml)?;
+        let team = Self {
             path: absolute_path.to_owned(),
             name: deserializer.name,
             github_team: deserializer.github.team,
-            owned_globs: deserializer.owned_globs,
-            subtracted_globs: deserializer.subtracted_globs,
+            owned_globs: deserializer
+                .owned_globs
+                .iter()
+                .flat_map(|glob| expand_braces(glob))
+                .chain(deserializer.owned_extensions_in.iter().flat_map(|owned| owned.globs()))
+                .collect(),
+            subtracted_globs: deserializer.subtracted_globs.iter().flat_map(|glob| expand_braces(glob)).collect(),
             owned_gems: deserializer.ruby.map(|ruby| ruby.owned_gems).unwrap_or_default(),
             avoid_ownership: deserializer.github.do_not_add_to_codeowners_file,
+            exclude_from_sections: deserializer.exclude_from_sections,
+            parent: deserializer.parent,
+        };
+        // `exclude_from_sections` names sections as `generate --only` does
+        let excludable_sections = crate::ownership::section_names();
+        if let Some(section) = team
+            .exclude_from_sections
+            .iter()
+            .find(|section| !excludable_sections.contains(&section.as_str()))
+        {
+            return Err(Error::SerdeYaml).attach_printable(format!(
+                "Unknown section `{}` in `exclude_from_sections` of {}; expected one of {}",
+                section,
+                absolute_path.display(),
+                excludable_sections.join(", ")
+            ));
+        }
+        Ok(team)
+    }
+
+    /// Whether this team's CODEOWNERS lines in the section named `section_name` (see `Mapper::section_name`) are commented out.
+    pub fn is_disabled_in(&self, section_name: &str) -> bool {
+        self.avoid_ownership || self.exclude_from_sections.iter().any(|section| section == section_name)
+    }
+
+    /// Whether `gem_name` is listed in `owned_gems`, either by name or by an entry with glob metacharacters such as
+    /// `payroll_*`.
+    pub fn owns_gem(&self, gem_name: &str) -> bool {
+        self.owned_gems.iter().any(|owned_gem| {
+            if owned_gem.contains(['*', '?', '[', '{']) {
+                glob_match(owned_gem, gem_name)
+            } else {
+                owned_gem == gem_name
+            }
         })
     }
+
+    /// Fails when two team files declare the same `name` or GitHub team, naming both files, rather than letting
+    /// one silently replace the other. Team files may live under several `team_file_glob` entries.
+    pub fn ensure_unique(teams: &[Team]) -> Result<(), Error> {
+        let mut by_name: HashMap<&str, &Team> = HashMap::new();
+        let mut by_github_team: HashMap<&str, &Team> = HashMap::new();
+        for team in teams {
+            for (field, value, seen) in [
+                ("name", team.name.as_str(), &mut by_name),
+                ("GitHub team", team.github_team.as_str(), &mut by_github_team),
+            ] {
+                if let Some(other) = seen.insert(value, team) {
+                    let mut paths = [other.path.display().to_string(), team.path.display().to_string()];
+                    paths.sort();
+                    return Err(Error::DuplicateTeam).attach_printable(format!(
+                        "Team {} `{}` is declared by both {} and {}",
+                        field, value, paths[0], paths[1]
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Merges each team's ancestors' `owned_globs`, `subtracted_globs`, and `owned_gems` into it, ancestors' entries
+    /// first. Every sub-team then holds its parent's globs and gems, so the child wins the overlap: a parent keeps
+    /// only its `subtracted_globs`, and owns files through annotations, packages, and directories instead. Run once
+    /// every team file is parsed, since a parent may be defined in any of them.
+    pub fn resolve_parents(teams: Vec<Team>) -> Result<Vec<Team>, Error> {
+        let teams_by_name: HashMap<&str, &Team> = teams.iter().map(|team| (team.name.as_str(), team)).collect();
+        let parent_names: HashSet<&str> = teams.iter().filter_map(|team| team.parent.as_deref()).collect();
+        let mut resolved = Vec::with_capacity(teams.len());
+        for team in &teams {
+            let mut merged = team.clone();
+            let mut lineage = vec![team.name.as_str()];
+            let mut current = team;
+            while let Some(parent_name) = &current.parent {
+                if lineage.contains(&parent_name.as_str()) {
+                    lineage.push(parent_name);
+                    return Err(Error::InvalidTeamParent).attach_printable(format!("Team inheritance cycle: {}", lineage.join(" -> ")));
+                }
+                let parent = teams_by_name
+                    .get(parent_name.as_str())
+                    .ok_or(Error::InvalidTeamParent)
+                    .attach_printable_lazy(|| {
+                        format!(
+                            "Team `{}` has unknown parent `{}` in {}",
+                            current.name,
+                            parent_name,
+                            current.path.display()
+                        )
+                    })?;
+                lineage.push(&parent.name);
+                merged.owned_globs = inherit(&parent.owned_globs, merged.owned_globs);
+                merged.subtracted_globs = inherit(&parent.subtracted_globs, merged.subtracted_globs);
+                merged.owned_gems = inherit(&parent.owned_gems, merged.owned_gems);
+                current = parent;
+            }
+            if parent_names.contains(team.name.as_str()) {
+                merged.owned_globs.clear();
+                merged.owned_gems.clear();
+            }
+            resolved.push(merged);
+        }
+        Ok(resolved)
+    }
+}
+
+/// `inherited` followed by the entries of `own` it doesn't already have.
+fn inherit(inherited: &[String], own: Vec<String>) -> Vec<String> {
+    let mut merged = inherited.to_vec();
+    for entry in own {
+        if !merged.contains(&entry) {
+            merged.push(entry);
+        }
+    }
+    merged
+}
+
+/// Expands each of `team_file_globs` under `teams_root`. A file matched by more than one glob is returned
+/// once, so overlapping globs don't load the same team twice.
+pub(crate) fn team_file_paths(teams_root: &Path, team_file_globs: &[String]) -> std::result::Result<Vec<PathBuf>, glob::PatternError> {
+    let mut seen: HashSet<PathBuf> = HashSet::new();
+    let mut paths = Vec::new();
+    for team_file_glob in team_file_globs {
+        let absolute_glob = teams_root.join(team_file_glob).to_string_lossy().into_owned();
+        for path in glob::glob(&absolute_glob)?.flatten() {
+            let canonical_path = path.canonicalize().unwrap_or_else(|_| path.clone());
+            if seen.insert(canonical_path) {
+                paths.push(path);
+            }
+        }
+    }
+    Ok(paths)
 }
 
 #[derive(Clone, Debug)]
@@ -64,12 +234,20 @@ pub struct Package {
     pub path: PathBuf,
     pub package_type: PackageType,
     pub owner: String,
+    /// Where `path` resolves to when the manifest is a symlink to a shared file (see `path_utils::symlink_target`)
+    pub symlink_target: Option<PathBuf>,
 }
 
 impl Package {
     pub fn package_root(&self) -> Option<&Path> {
         self.path.parent()
     }
+
+    /// The file the owner was read from: the symlink's target, if any, since that's the file to edit to change it.
+    /// The package itself still covers the directory holding `path`.
+    pub fn manifest_path(&self) -> &Path {
+        self.symlink_target.as_deref().unwrap_or(&self.path)
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -88,6 +266,7 @@ impl DirectoryCodeownersFile {
 pub enum PackageType {
     Ruby,
     Javascript,
+    Rust,
 }
 
 impl Display for PackageType {
@@ -107,6 +286,46 @@ pub mod deserializers {
     #[derive(Deserialize)]
     pub struct JavascriptPackage {
         pub metadata: Option<Metadata>,
+
+        #[serde(flatten)]
+        pub other_fields: serde_json::Map<String, serde_json::Value>,
+    }
+
+    impl JavascriptPackage {
+        /// `metadata.owner`, falling back to the first person listed under `owner_field` (e.g. `maintainers`).
+        /// People are either npm's `"Name <email> (url)"` strings or `{ "name": ... }` objects.
+        pub fn owner(self, owner_field: Option<&str>) -> Option<String> {
+            if let Some(owner) = self.metadata.and_then(|metadata| metadata.owner) {
+                return Some(owner);
+            }
+            let person = match self.other_fields.get(owner_field?)? {
+                serde_json::Value::Array(people) => people.first()?,
+                person => person,
+            };
+            let name = match person {
+                serde_json::Value::String(person) => person.split(['<', '(']).next().unwrap_or_default(),
+                serde_json::Value::Object(person) => person.get("name")?.as_str()?,
+                _ => return None,
+            };
+            Some(name.trim().to_owned()).filter(|name| !name.is_empty())
+        }
+    }
+
+    /// A `Cargo.toml`, whose owner is set under `[package.metadata]`.
+    #[derive(Deserialize)]
+    pub struct CargoPackage {
+        pub package: Option<CargoPackageSection>,
+    }
+
+    #[derive(Deserialize)]
+    pub struct CargoPackageSection {
+        pub metadata: Option<Metadata>,
+    }
+
+    impl CargoPackage {
+        pub fn owner(self) -> Option<String> {
+            self.package?.metadata?.owner
+        }
     }
 
     #[derive(Deserialize)]
@@ -128,17 +347,40 @@ pub mod deserializers {
         pub owned_gems: Vec<String>,
     }
 
+    /// Files with any of `extensions` anywhere under `dir`, e.g. `.yml` files under `config/locales`.
+    #[derive(Deserialize)]
+    pub struct OwnedExtensionsIn {
+        pub dir: String,
+        pub extensions: Vec<String>,
+    }
+
+    impl OwnedExtensionsIn {
+        pub fn globs(&self) -> impl Iterator<Item = String> + '_ {
+            let dir = self.dir.trim_end_matches('/');
+            self.extensions
+                .iter()
+                .map(move |extension| format!("{}/**/*.{}", dir, extension.trim_start_matches('.')))
+        }
+    }
+
     #[derive(Deserialize)]
     pub struct Team {
         pub name: String,
         pub github: Github,
         pub ruby: Option<Ruby>,
+        pub parent: Option<String>,
+
+        #[serde(default = "empty_string_vec")]
+        pub exclude_from_sections: Vec<String>,
 
         #[serde(default = "empty_string_vec")]
         pub owned_globs: Vec<String>,
 
         #[serde(alias = "unowned_globs", default = "empty_string_vec")]
         pub subtracted_globs: Vec<String>,
+
+        #[serde(default)]
+        pub owned_extensions_in: Vec<OwnedExtensionsIn>,
     }
 
     fn empty_string_vec() -> Vec<String> {
@@ -155,6 +397,9 @@ pub enum Error {
     Io,
     SerdeYaml,
     SerdeJson,
+    SerdeToml,
+    InvalidTeamParent,
+    DuplicateTeam,
 }
 
 impl fmt::Display for Error {
@@ -163,6 +408,9 @@ impl fmt::Display for Error {
             Error::Io => fmt.write_str("IO operation failed"),
             Error::SerdeYaml => fmt.write_str("YAML serialization/deserialization failed"),
             Error::SerdeJson => fmt.write_str("JSON serialization/deserialization failed"),
+            Error::SerdeToml => fmt.write_str("TOML deserialization failed"),
+            Error::InvalidTeamParent => fmt.write_str("Invalid team parent"),
+            Error::DuplicateTeam => fmt.write_str("Duplicate team"),
         }
     }
 }
@@ -183,6 +431,11 @@ impl Project {
         absolute_path.strip_prefix(&self.base_path).unwrap_or(absolute_path)
     }
 
+    /// Whether a top-of-file annotation naming `owner` is disregarded (see `ignored_owners` in the config).
+    pub fn is_ignored_owner(&self, owner: &str) -> bool {
+        self.ignored_owners.iter().any(|ignored| ignored == owner)
+    }
+
     pub fn get_team(&self, name: &str) -> Option<Team> {
         self.teams_by_name.get(name).cloned()
     }
@@ -201,16 +454,19 @@ impl Project {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use indoc::indoc;
 
     #[test]
     fn test_vendored_gem_by_name_maps_all_gems() {
         let vg1 = VendoredGem {
             path: PathBuf::from("vendored/a"),
             name: "a".to_string(),
+            owner: None,
         };
         let vg2 = VendoredGem {
             path: PathBuf::from("vendored/b"),
             name: "b".to_string(),
+            owner: None,
         };
         let project = Project {
             base_path: PathBuf::from("."),
@@ -222,6 +478,17 @@ mod tests {
             directory_codeowner_files: vec![],
             teams_by_name: HashMap::new(),
             executable_name: "codeowners generate".to_string(),
+            ignored_owners: vec![],
+            strict_annotation_lines: None,
+            annotation_parser: AnnotationParser::default(),
+            annotation_forbidden_globs: vec![],
+            extension_owners: BTreeMap::new(),
+            team_files_owner: None,
+            default_owner: None,
+            overrides: vec![],
+            codeowners_appendix: None,
+            unowned_glob_files: vec![],
+            case_insensitive_paths: false,
         };
 
         let map = project.vendored_gem_by_name();
@@ -229,4 +496,149 @@ mod tests {
         assert_eq!(map.get("a").unwrap().name, vg1.name);
         assert_eq!(map.get("b").unwrap().name, vg2.name);
     }
+
+    #[test]
+    fn test_team_file_paths_dedups_overlapping_globs() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(temp_dir.path().join("config/teams/platform")).unwrap();
+        std::fs::write(temp_dir.path().join("config/teams/payroll.yml"), "").unwrap();
+        std::fs::write(temp_dir.path().join("config/teams/platform/infra.yml"), "").unwrap();
+
+        let globs = vec![
+            "config/teams/**/*.yml".to_owned(),
+            "config/teams/*.yml".to_owned(),
+            "config/./teams/payroll.yml".to_owned(),
+        ];
+        let paths = team_file_paths(temp_dir.path(), &globs).unwrap();
+        assert_eq!(
+            paths,
+            vec![
+                temp_dir.path().join("config/teams/payroll.yml"),
+                temp_dir.path().join("config/teams/platform/infra.yml"),
+            ]
+        );
+    }
+
+    fn javascript_package_owner(package_json: &str, owner_field: Option<&str>) -> Option<String> {
+        serde_json::from_str::<deserializers::JavascriptPackage>(package_json)
+            .unwrap()
+            .owner(owner_field)
+    }
+
+    #[test]
+    fn test_javascript_package_owner_from_owner_field() {
+        let people = r#"{"maintainers": ["Payroll <payroll@example.com> (https://example.com)", "Infra"]}"#;
+        assert_eq!(javascript_package_owner(people, Some("maintainers")), Some("Payroll".to_owned()));
+        assert_eq!(javascript_package_owner(people, None), None);
+        assert_eq!(javascript_package_owner(people, Some("contributors")), None);
+
+        let objects = r#"{"contributors": [{"name": "Infra", "email": "infra@example.com"}]}"#;
+        assert_eq!(javascript_package_owner(objects, Some("contributors")), Some("Infra".to_owned()));
+
+        let single = r#"{"author": {"name": "Infra"}}"#;
+        assert_eq!(javascript_package_owner(single, Some("author")), Some("Infra".to_owned()));
+
+        let both = r#"{"metadata": {"owner": "Payroll"}, "maintainers": ["Infra"]}"#;
+        assert_eq!(javascript_package_owner(both, Some("maintainers")), Some("Payroll".to_owned()));
+
+        assert_eq!(javascript_package_owner(r#"{"maintainers": []}"#, Some("maintainers")), None);
+    }
+
+    #[test]
+    fn test_cargo_package_owner_from_package_metadata() {
+        let cargo_package_owner = |cargo_toml: &str| toml::from_str::<deserializers::CargoPackage>(cargo_toml).unwrap().owner();
+
+        let owned =
+            "[package]\nname = \"payroll\"\n\n[package.metadata]\nowner = \"Payroll\"\n\n[package.metadata.docs.rs]\nall-features = true\n";
+        assert_eq!(cargo_package_owner(owned), Some("Payroll".to_owned()));
+        assert_eq!(cargo_package_owner("[package]\nname = \"payroll\"\n"), None);
+        assert_eq!(cargo_package_owner("[workspace]\nmembers = [\"crates/*\"]\n"), None);
+    }
+
+    #[test]
+    fn test_owned_extensions_in_expands_into_owned_globs() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let team_file = temp_dir.path().join("i18n.yml");
+        std::fs::write(
+            &team_file,
+            indoc! {"
+                name: I18n
+                github:
+                  team: '@I18n'
+                owned_globs:
+                  - config/i18n.rb
+                owned_extensions_in:
+                  - dir: config/locales/
+                    extensions: [yml, .json]
+            "},
+        )
+        .unwrap();
+
+        let team = Team::from_team_file_path(team_file).unwrap();
+        assert_eq!(
+            team.owned_globs,
+            vec!["config/i18n.rb", "config/locales/**/*.yml", "config/locales/**/*.json"]
+        );
+    }
+
+    fn team_with_parent(name: &str, parent: Option<&str>, owned_globs: &[&str]) -> Team {
+        Team {
+            name: name.to_owned(),
+            github_team: format!("@{}", name),
+            owned_globs: owned_globs.iter().map(|glob| glob.to_string()).collect(),
+            parent: parent.map(str::to_owned),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_resolve_parents_merges_ancestors_entries() {
+        let mut platform = team_with_parent("Platform", None, &["lib/**"]);
+        platform.owned_gems = vec!["rails".to_owned()];
+        platform.subtracted_globs = vec!["lib/legacy/**".to_owned()];
+        let teams = vec![
+            team_with_parent("Infra", Some("Platform"), &["infra/**", "lib/**"]),
+            platform,
+            team_with_parent("Database", Some("Infra"), &["db/**"]),
+        ];
+
+        let teams = Team::resolve_parents(teams).unwrap();
+        assert_eq!(teams[2].owned_globs, vec!["lib/**", "infra/**", "db/**"]);
+        assert_eq!(teams[2].subtracted_globs, vec!["lib/legacy/**"]);
+        assert_eq!(teams[2].owned_gems, vec!["rails"]);
+
+        // Parents hand their globs and gems down, so the most derived team owns the files they match
+        for parent in &teams[..2] {
+            assert!(parent.owned_globs.is_empty(), "{:?}", parent.owned_globs);
+            assert!(parent.owned_gems.is_empty());
+        }
+        assert_eq!(teams[0].subtracted_globs, vec!["lib/legacy/**"]);
+        assert_eq!(teams[1].subtracted_globs, vec!["lib/legacy/**"]);
+    }
+
+    #[test]
+    fn test_resolve_parents_rejects_cycles_and_unknown_parents() {
+        let teams = vec![
+            team_with_parent("A", Some("B"), &[]),
+            team_with_parent("B", Some("C"), &[]),
+            team_with_parent("C", Some("A"), &[]),
+        ];
+        let error = Team::resolve_parents(teams).unwrap_err();
+        assert!(
+            format!("{:?}", error).contains("Team inheritance cycle: A -> B -> C -> A"),
+            "{:?}",
+            error
+        );
+
+        let teams = vec![team_with_parent("A", Some("A"), &[])];
+        assert!(Team::resolve_parents(teams).is_err());
+
+        let teams = vec![team_with_parent("A", Some("Missing"), &[])];
+        let error = Team::resolve_parents(teams).unwrap_err();
+        assert!(
+            format!("{:?}", error).contains("Team `A` has unknown parent `Missing`"),
+            "{:?}",
+            error
+        );
+    }
 }
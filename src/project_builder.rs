@@ -5,7 +5,6 @@ use std::{
 };
 
 use error_stack::{Report, Result, ResultExt};
-use fast_glob::glob_match;
 use ignore::{DirEntry, WalkBuilder, WalkParallel, WalkState};
 use rayon::iter::{IntoParallelIterator, ParallelIterator};
 use tracing::instrument;
@@ -13,7 +12,11 @@ use tracing::instrument;
 use crate::{
     cache::Cache,
     config::Config,
-    project::{DirectoryCodeownersFile, Error, Package, PackageType, Project, ProjectFile, Team, VendoredGem, deserializers},
+    ownership::annotations::AnnotationParser,
+    path_utils::{glob_matches, symlink_target, to_slash_str},
+    project::{
+        DirectoryCodeownersFile, Error, Package, PackageType, Project, ProjectFile, Team, VendoredGem, deserializers, team_file_paths,
+    },
     project_file_builder::ProjectFileBuilder,
     tracked_files,
 };
@@ -26,9 +29,11 @@ enum EntryType {
     Directory(AbsolutePath, RelativePath),
     RubyPackage(AbsolutePath, RelativePath),
     JavascriptPackage(AbsolutePath, RelativePath),
+    CargoPackage(AbsolutePath, RelativePath),
     CodeownerFile(AbsolutePath, RelativePath),
     TeamFile(AbsolutePath, RelativePath),
     OwnedFile(ProjectFile),
+    UnownedGlobFile(AbsolutePath),
     NullEntry(),
 }
 
@@ -37,32 +42,62 @@ pub struct ProjectBuilder<'a> {
     base_path: PathBuf,
     codeowners_file_path: PathBuf,
     project_file_builder: ProjectFileBuilder<'a>,
+    subtree: Option<RelativePath>,
+    threads: Option<usize>,
 }
 
 const INITIAL_VECTOR_CAPACITY: usize = 1000;
 
 impl<'a> ProjectBuilder<'a> {
     pub fn new(config: &'a Config, base_path: PathBuf, codeowners_file_path: PathBuf, cache: &'a Cache) -> Self {
-        let project_file_builder = ProjectFileBuilder::new(cache);
+        let project_file_builder = ProjectFileBuilder::new(cache, config);
         Self {
             project_file_builder,
             config,
             base_path,
             codeowners_file_path,
+            subtree: None,
+            threads: None,
         }
     }
 
+    /// Caps the threads used by the walk (see `--threads`). `None` falls back to `RAYON_NUM_THREADS`, which rayon
+    /// itself honors, and then to the walker's own per-core default.
+    pub fn with_threads(mut self, threads: Option<usize>) -> Self {
+        self.threads = threads;
+        self
+    }
+
+    /// Only walks `subtree` (relative to the project root) and the files directly inside its ancestors,
+    /// where `.codeowner` and package files that apply to it live. Teams are loaded from `team_file_glob`.
+    pub fn with_subtree(mut self, subtree: RelativePath) -> Self {
+        self.subtree = Some(subtree);
+        self
+    }
+
     #[instrument(level = "debug", skip_all, fields(base_path = %self.base_path.display()))]
     pub fn build(&mut self) -> Result<Project, Error> {
         tracing::info!("Starting project build");
         let mut builder = WalkBuilder::new(&self.base_path);
         builder.hidden(false);
         builder.follow_links(false);
+        // Files nested deeper than `max_depth` below the project root are never visited
+        builder.max_depth(self.config.max_depth);
+        if let Some(threads) = self.walk_threads() {
+            builder.threads(threads);
+        }
 
         // Prune traversal early: skip heavy and irrelevant directories
         let ignore_dirs = self.config.ignore_dirs.clone();
         let base_path = self.base_path.clone();
-        let tracked_files = tracked_files::find_tracked_files(&self.base_path);
+        let subtree = self.subtree.clone();
+        let tracked_files = tracked_files::find_tracked_files(&self.base_path, self.config.git_timeout(), self.config.include_submodules)
+            .map_err(|e| error_stack::report!(Error::Io).attach_printable(e.to_string()))?;
+        let submodule_roots = if self.config.include_submodules {
+            vec![]
+        } else {
+            tracked_files::find_submodule_roots(&self.base_path)
+        };
 
         builder.filter_entry(move |entry: &DirEntry| {
             let path = entry.path();
@@ -74,6 +109,9 @@ impl<'a> ProjectBuilder<'a> {
             {
                 return false;
             }
+            if submodule_roots.iter().any(|root| path.starts_with(root)) {
+                return false;
+            }
             if let Some(ft) = entry.file_type()
                 && ft.is_dir()
                 && let Ok(rel) = path.strip_prefix(&base_path)
@@ -82,6 +120,14 @@ impl<'a> ProjectBuilder<'a> {
             {
                 return false;
             }
+            if let Some(subtree) = &subtree
+                && let Ok(rel) = path.strip_prefix(&base_path)
+                && !rel.starts_with(subtree)
+            {
+                let is_dir = entry.file_type().is_some_and(|ft| ft.is_dir());
+                let dir = if is_dir { Some(rel) } else { rel.parent() };
+                return dir.is_some_and(|dir| subtree.starts_with(dir));
+            }
 
             true
         });
@@ -152,27 +198,50 @@ impl<'a> ProjectBuilder<'a> {
 
         match file_name.as_str() {
             name if name == "package.yml"
-                && relative_path
-                    .parent()
-                    .is_some_and(|parent| matches_globs(parent, &self.config.ruby_package_paths)) =>
+                && relative_path.parent().is_some_and(|parent| {
+                    self.config
+                        .package_discovery
+                        .is_package_dir(parent, &self.config.ruby_package_paths)
+                }) =>
             {
                 Ok(EntryType::RubyPackage(absolute_path.to_owned(), relative_path.to_owned()))
             }
             name if name == "package.json"
-                && relative_path
-                    .parent()
-                    .is_some_and(|parent| matches_globs(parent, &self.config.javascript_package_paths)) =>
+                && relative_path.parent().is_some_and(|parent| {
+                    self.config
+                        .package_discovery
+                        .is_package_dir(parent, &self.config.javascript_package_paths)
+                }) =>
             {
                 Ok(EntryType::JavascriptPackage(absolute_path.to_owned(), relative_path.to_owned()))
             }
+            name if name == "cargo.toml"
+                && relative_path.parent().is_some_and(|parent| {
+                    self.config
+                        .package_discovery
+                        .is_package_dir(parent, &self.config.cargo_package_paths)
+                }) =>
+            {
+                Ok(EntryType::CargoPackage(absolute_path.to_owned(), relative_path.to_owned()))
+            }
             ".codeowner" => Ok(EntryType::CodeownerFile(absolute_path.to_owned(), relative_path.to_owned())),
-            _ if matches_globs(&relative_path, &self.config.team_file_glob) => {
+            // With a `teams_root` or a subtree, team files are globbed instead, since the walk may not reach them (see `load_shared_teams`)
+            _ if self.config.teams_root.is_none()
+                && self.subtree.is_none()
+                && matches_globs(&relative_path, &self.config.team_file_glob, self.config.case_insensitive_paths) =>
+            {
                 Ok(EntryType::TeamFile(absolute_path.to_owned(), relative_path.to_owned()))
             }
-            _ if matches_globs(&relative_path, &self.config.owned_globs) && !matches_globs(&relative_path, &self.config.unowned_globs) => {
+            _ if matches_globs(&relative_path, &self.config.owned_globs, self.config.case_insensitive_paths)
+                && !matches_globs(&relative_path, &self.config.unowned_globs, self.config.case_insensitive_paths) =>
+            {
                 let project_file = self.project_file_builder.build(absolute_path.to_path_buf());
                 Ok(EntryType::OwnedFile(project_file))
             }
+            // Listed without being read; only validate reads them, to warn about ignored annotations
+            _ if matches_globs(&relative_path, &self.config.owned_globs, self.config.case_insensitive_paths) => {
+                Ok(EntryType::UnownedGlobFile(absolute_path.to_owned()))
+            }
             _ => Ok(EntryType::NullEntry()),
         }
     }
@@ -186,7 +255,14 @@ impl<'a> ProjectBuilder<'a> {
             Vec<Team>,
         );
 
-        let (project_files, packages, vendored_gems, directory_codeowners, teams): Accumulator = entry_types
+        let unowned_glob_files: Vec<PathBuf> = entry_types
+            .iter()
+            .filter_map(|entry_type| match entry_type {
+                EntryType::UnownedGlobFile(absolute_path) => Some(absolute_path.clone()),
+                _ => None,
+            })
+            .collect();
+        let (project_files, packages, vendored_gems, directory_codeowners, mut teams): Accumulator = entry_types
             .into_par_iter()
             .try_fold(
                 || {
@@ -209,9 +285,12 @@ impl<'a> ProjectBuilder<'a> {
                                     error_stack::report!(Error::Io)
                                         .attach_printable(format!("Vendored gem path has no file name: {}", relative_path.display()))
                                 })?;
+                                let owner = vendored_gem_declared_owner(&absolute_path)
+                                    .attach_printable_lazy(|| format!("Failed to read vendored gem owner: {}", absolute_path.display()))?;
                                 gems.push(VendoredGem {
-                                    path: absolute_path,
                                     name: file_name.to_string_lossy().to_string(),
+                                    path: absolute_path,
+                                    owner,
                                 });
                             }
                         }
@@ -224,6 +303,7 @@ impl<'a> ProjectBuilder<'a> {
                                         path: relative_path.clone(),
                                         owner,
                                         package_type: PackageType::Ruby,
+                                        symlink_target: symlink_target(&self.base_path, &absolute_path),
                                     });
                                 }
                                 Ok(None) => { /* No owner, do nothing */ }
@@ -231,7 +311,7 @@ impl<'a> ProjectBuilder<'a> {
                             }
                         }
                         EntryType::JavascriptPackage(absolute_path, relative_path) => {
-                            match javascript_package_owner(&absolute_path)
+                            match javascript_package_owner(&absolute_path, self.config.owner_field.as_deref())
                                 .attach_printable_lazy(|| format!("Failed to read javascript package: {}", absolute_path.display()))
                             {
                                 Ok(Some(owner)) => {
@@ -239,12 +319,32 @@ impl<'a> ProjectBuilder<'a> {
                                         path: relative_path.clone(),
                                         owner,
                                         package_type: PackageType::Javascript,
+                                        symlink_target: symlink_target(&self.base_path, &absolute_path),
                                     });
                                 }
                                 Ok(None) => { /* No owner, do nothing */ }
                                 Err(e) => return Err(e),
                             }
                         }
+                        EntryType::CargoPackage(absolute_path, relative_path) => {
+                            match rust_package_owner(&absolute_path)
+                                .attach_printable_lazy(|| format!("Failed to read cargo package: {}", absolute_path.display()))
+                            {
+                                Ok(Some(owner)) => {
+                                    pkgs.push(Package {
+                                        path: relative_path.clone(),
+                                        owner,
+                                        package_type: PackageType::Rust,
+                                        symlink_target: symlink_target(&self.base_path, &absolute_path),
+                                    });
+                                }
+                                Ok(None) => { /* No owner, do nothing */ }
+                                Err(e) => return Err(e),
+                            }
+                        }
+                        // A gem's own `.codeowner` is read with the gem so that `owned_gems` can take precedence over it
+                        EntryType::CodeownerFile(_, relative_path)
+                            if relative_path.parent().is_some_and(|dir| self.config.is_vendored_gem_root(dir)) => {}
                         EntryType::CodeownerFile(absolute_path, relative_path) => {
                             let owner = std::fs::read_to_string(&absolute_path)
                                 .change_context(Error::Io)
@@ -261,7 +361,7 @@ impl<'a> ProjectBuilder<'a> {
                                 .attach_printable_lazy(|| format!("Failed to read team file: {}", absolute_path.display()))?;
                             team_files.push(team);
                         }
-                        EntryType::NullEntry() => {}
+                        EntryType::UnownedGlobFile(_) | EntryType::NullEntry() => {}
                     }
                     Ok((project_files, pkgs, gems, codeowners, team_files))
                 },
@@ -277,6 +377,21 @@ impl<'a> ProjectBuilder<'a> {
                     Ok(acc)
                 },
             )?;
+        if self.config.teams_root.is_some() || self.subtree.is_some() {
+            teams.extend(self.load_shared_teams()?);
+        }
+        Team::ensure_unique(&teams)?;
+        let teams = Team::resolve_parents(teams)?;
+        let codeowners_appendix = self
+            .config
+            .extra_codeowners_file
+            .as_ref()
+            .map(|path| {
+                std::fs::read_to_string(self.base_path.join(path))
+                    .change_context(Error::Io)
+                    .attach_printable_lazy(|| format!("Failed to read extra_codeowners_file: {}", path.display()))
+            })
+            .transpose()?;
         let teams_by_name = teams
             .iter()
             .flat_map(|team| vec![(team.name.clone(), team.clone()), (team.github_team.clone(), team.clone())])
@@ -299,17 +414,65 @@ impl<'a> ProjectBuilder<'a> {
             directory_codeowner_files: directory_codeowners,
             teams_by_name,
             executable_name: self.config.executable_name.clone(),
+            ignored_owners: self.config.ignored_owners.clone(),
+            strict_annotation_lines: self.config.strict_annotations.then_some(self.config.annotation_header_lines),
+            annotation_parser: AnnotationParser::from_config(self.config),
+            annotation_forbidden_globs: self.config.annotation_forbidden_globs.clone(),
+            extension_owners: self.config.extension_owners.clone(),
+            team_files_owner: self.config.team_files_owner.clone(),
+            default_owner: self.config.default_owner.clone(),
+            overrides: self.config.overrides.clone(),
+            codeowners_appendix,
+            unowned_glob_files,
+            case_insensitive_paths: self.config.case_insensitive_paths,
+        })
+    }
+
+    fn load_shared_teams(&self) -> Result<Vec<Team>, Error> {
+        let teams_root = self.config.resolve_teams_root(&self.base_path);
+        let paths = team_file_paths(&teams_root, &self.config.team_file_glob)
+            .change_context(Error::Io)
+            .attach_printable_lazy(|| format!("Invalid team file glob under {}", teams_root.display()))?;
+        paths
+            .into_iter()
+            .map(|path| {
+                Team::from_team_file_path(path.clone())
+                    .change_context(Error::Io)
+                    .attach_printable_lazy(|| format!("Failed to read team file: {}", path.display()))
+            })
+            .collect()
+    }
+
+    fn walk_threads(&self) -> Option<usize> {
+        self.threads.or_else(|| {
+            std::env::var("RAYON_NUM_THREADS")
+                .ok()
+                .and_then(|threads| threads.trim().parse().ok())
+                .filter(|threads| *threads > 0)
         })
     }
 }
 
-fn matches_globs(path: &Path, globs: &[String]) -> bool {
-    match path.to_str() {
-        Some(s) => globs.iter().any(|glob| glob_match(glob, s)),
+fn matches_globs(path: &Path, globs: &[String], case_insensitive: bool) -> bool {
+    match to_slash_str(path) {
+        Some(s) => globs.iter().any(|glob| glob_matches(glob, &s, case_insensitive)),
         None => false,
     }
 }
 
+fn vendored_gem_declared_owner(gem_path: &Path) -> Result<Option<String>, Error> {
+    let codeowner_path = gem_path.join(".codeowner");
+    if codeowner_path.exists() {
+        let owner = std::fs::read_to_string(&codeowner_path).change_context(Error::Io)?;
+        return Ok(Some(owner.trim().to_owned()));
+    }
+    let package_path = gem_path.join("package.yml");
+    if package_path.exists() {
+        return ruby_package_owner(&package_path);
+    }
+    Ok(None)
+}
+
 fn ruby_package_owner(path: &Path) -> Result<Option<String>, Error> {
     let file = File::open(path).change_context(Error::Io)?;
     let deserializer: deserializers::RubyPackage = serde_yaml::from_reader(file).change_context(Error::SerdeYaml)?;
@@ -329,22 +492,43 @@ fn ruby_package_owner(path: &Path) -> Result<Option<String>, Error> {
     }
 }
 
-fn javascript_package_owner(path: &Path) -> Result<Option<String>, Error> {
+fn javascript_package_owner(path: &Path, owner_field: Option<&str>) -> Result<Option<String>, Error> {
     let file = File::open(path).change_context(Error::Io)?;
     let deserializer: deserializers::JavascriptPackage = serde_json::from_reader(file).change_context(Error::SerdeJson)?;
 
-    Ok(deserializer.metadata.and_then(|metadata| metadata.owner))
+    Ok(deserializer.owner(owner_field))
+}
+
+fn rust_package_owner(path: &Path) -> Result<Option<String>, Error> {
+    let cargo_toml = std::fs::read_to_string(path).change_context(Error::Io)?;
+    let deserializer: deserializers::CargoPackage = toml::from_str(&cargo_toml).change_context(Error::SerdeToml)?;
+
+    Ok(deserializer.owner())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use fast_glob::glob_match;
 
     const OWNED_GLOB: &str = "{app,components,config,frontend,lib,packs,spec,danger,script}/**/*.{rb,arb,erb,rake,js,jsx,ts,tsx}";
 
     #[test]
     fn test_matches_globs() {
-        assert!(matches_globs(Path::new("script/.eslintrc.js"), &[OWNED_GLOB.to_string()]));
+        assert!(matches_globs(Path::new("script/.eslintrc.js"), &[OWNED_GLOB.to_string()], false));
+    }
+
+    #[test]
+    fn test_matches_globs_case_insensitive() {
+        assert!(!matches_globs(Path::new("Script/.ESLintrc.js"), &[OWNED_GLOB.to_string()], false));
+        assert!(matches_globs(Path::new("Script/.ESLintrc.js"), &[OWNED_GLOB.to_string()], true));
+    }
+
+    #[test]
+    #[cfg(windows)]
+    fn test_matches_globs_with_backslash_separators() {
+        assert!(matches_globs(Path::new("script\\.eslintrc.js"), &[OWNED_GLOB.to_string()], false));
+        assert!(matches_globs(Path::new("packs\\foo\\app\\x.rb"), &[OWNED_GLOB.to_string()], false));
     }
 
     #[test]
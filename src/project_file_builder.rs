@@ -1,25 +1,28 @@
 use error_stack::Result;
-use lazy_static::lazy_static;
-use regex::Regex;
+use std::io::Read;
 use std::path::{Path, PathBuf};
 
 use crate::{
     cache::{Cache, Caching},
+    config::Config,
+    ownership::annotations::AnnotationParser,
     project::{Error, ProjectFile},
 };
 
 pub struct ProjectFileBuilder<'a> {
     global_cache: &'a Cache,
-}
-
-lazy_static! {
-    static ref TEAM_REGEX: Regex =
-        Regex::new(r#"^(?:#|//|<!--|<%#)\s*(?:@?team:?\s*)(.*?)\s*(?:-->|%>)?$"#).expect("error compiling regular expression");
+    annotation_parser: AnnotationParser,
+    /// How many lines at the top of a file may hold its annotation (`annotation_header_lines`)
+    header_lines: usize,
 }
 
 impl<'a> ProjectFileBuilder<'a> {
-    pub fn new(global_cache: &'a Cache) -> Self {
-        Self { global_cache }
+    pub fn new(global_cache: &'a Cache, config: &Config) -> Self {
+        Self {
+            global_cache,
+            annotation_parser: AnnotationParser::from_config(config),
+            header_lines: config.annotation_header_lines,
+        }
     }
 
     pub(crate) fn build(&self, path: PathBuf) -> ProjectFile {
@@ -27,7 +30,7 @@ impl<'a> ProjectFileBuilder<'a> {
             return cached_project_file;
         }
 
-        let project_file = build_project_file_without_cache(&path);
+        let project_file = build_project_file_without_cache(&path, &self.annotation_parser, self.header_lines);
 
         self.save_project_file_to_cache(&path, &project_file);
 
@@ -48,66 +51,65 @@ impl<'a> ProjectFileBuilder<'a> {
     }
 }
 
-pub(crate) fn build_project_file_without_cache(path: &PathBuf) -> ProjectFile {
-    let content = match std::fs::read_to_string(path) {
-        Ok(content) => content,
-        Err(_) => {
-            return ProjectFile {
-                path: path.clone(),
-                owner: None,
-            };
-        }
-    };
-
-    let first_line = content.lines().next();
-    let Some(first_line) = first_line else {
-        return ProjectFile {
-            path: path.clone(),
-            owner: None,
-        };
-    };
-
-    let owner = TEAM_REGEX
-        .captures(first_line)
-        .and_then(|cap| cap.get(1))
-        .map(|m| m.as_str().to_string());
-
-    ProjectFile { path: path.clone(), owner }
+/// Annotations only ever live at the top of a file, so reading further is wasted work on large files.
+const HEADER_BYTES: u64 = 64 * 1024;
+
+/// The start of the file at `path`, for annotation scanning. `None` when it can't be read or looks binary
+/// (contains a NUL byte), so binary files never claim an owner. Invalid UTF-8 is replaced rather than
+/// rejected, keeping an annotation readable even if bytes further down aren't valid UTF-8.
+pub(crate) fn read_file_header(path: &Path) -> Option<String> {
+    let mut header = Vec::new();
+    std::fs::File::open(path).ok()?.take(HEADER_BYTES).read_to_end(&mut header).ok()?;
+    if header.contains(&0) {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&header).into_owned())
+}
+
+pub(crate) fn build_project_file_without_cache(path: &Path, annotation_parser: &AnnotationParser, header_lines: usize) -> ProjectFile {
+    ProjectFile {
+        path: path.to_path_buf(),
+        owner: read_file_header(path)
+            .and_then(|content| annotation_parser.parse_owner(&content, header_lines))
+            .map(|annotation| annotation.team),
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::collections::HashMap;
-
     use super::*;
-    type FirstLine = &'static str;
-    type Owner = &'static str;
 
     #[test]
-    fn test_team_regex() {
-        let mut map: HashMap<FirstLine, Owner> = HashMap::new();
-        map.insert("// @team Foo", "Foo");
-        map.insert("// @team Foo Bar", "Foo Bar");
-        map.insert("// @team Zoo", "Zoo");
-        map.insert("// @team: Zoo Foo", "Zoo Foo");
-        map.insert("# @team: Bap", "Bap");
-        map.insert("# @team: Bap Hap", "Bap Hap");
-        map.insert("<!-- @team: Zoink -->", "Zoink");
-        map.insert("<!-- @team: Zoink Err -->", "Zoink Err");
-        map.insert("<%# @team: Zap %>", "Zap");
-        map.insert("<%# @team: Zap Zip%>", "Zap Zip");
-        map.insert("<!-- @team Blast -->", "Blast");
-        map.insert("<!-- @team Blast Off -->", "Blast Off");
-
-        // New team: format (without @ symbol)
-        map.insert("# team: MyTeam", "MyTeam");
-        map.insert("// team: MyTeam", "MyTeam");
-        map.insert("<!-- team: MyTeam -->", "MyTeam");
-        map.insert("<%# team: MyTeam %>", "MyTeam");
-
-        for (key, value) in map {
-            let owner = TEAM_REGEX.captures(key).and_then(|cap| cap.get(1)).map(|m| m.as_str());
-            assert_eq!(owner, Some(value));
-        }
+    fn test_annotation_below_shebang() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("script");
+        std::fs::write(&path, "#!/usr/bin/env ruby\n# @team Foo\nputs 1\n").unwrap();
+        assert_eq!(
+            build_project_file_without_cache(&path, &AnnotationParser::default(), 5).owner,
+            Some("Foo".to_owned())
+        );
+
+        std::fs::write(&path, "#!/usr/bin/env ruby\n\n# @team Foo\n").unwrap();
+        assert_eq!(
+            build_project_file_without_cache(&path, &AnnotationParser::default(), 5).owner,
+            Some("Foo".to_owned())
+        );
+        assert_eq!(build_project_file_without_cache(&path, &AnnotationParser::default(), 2).owner, None);
+    }
+
+    #[test]
+    fn test_annotation_in_non_utf8_and_binary_files() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("latin1.rb");
+        std::fs::write(&path, b"# @team Foo\n# caf\xe9\n").unwrap();
+        assert_eq!(
+            build_project_file_without_cache(&path, &AnnotationParser::default(), 5).owner,
+            Some("Foo".to_owned())
+        );
+
+        // Bytes that happen to spell an annotation don't make a binary file owned
+        std::fs::write(&path, b"# @team Foo\n\x00\xff\xfe").unwrap();
+        assert_eq!(build_project_file_without_cache(&path, &AnnotationParser::default(), 5).owner, None);
+        assert_eq!(read_file_header(&path), None);
     }
 }
@@ -1,21 +1,26 @@
 use std::path::{Path, PathBuf};
-use std::process::Command;
+use std::sync::Arc;
 
 use error_stack::{Result, ResultExt};
-use fast_glob::glob_match;
 use serde::Serialize;
 
 use crate::{
     cache::{Cache, Caching, file::GlobalCache, noop::NoopCache},
     config::Config,
-    ownership::{FileOwner, Ownership},
+    git::run_git,
+    ownership::{FileOwner, Ownership, annotation_fixer, annotations::AnnotationParser, audit_log::AuditLog},
+    path_utils::{glob_matches, to_slash_str, to_slash_string_lossy},
     project_builder::ProjectBuilder,
+    project_file_builder::build_project_file_without_cache,
 };
+use rayon::prelude::{IntoParallelRefIterator, ParallelIterator};
 
 mod types;
-pub use self::types::{Error, RunConfig, RunResult};
+pub use self::types::{Error, RunConfig, RunResult, TeamFormat, TeamNameSource};
 mod api;
 pub use self::api::*;
+pub use crate::ownership::annotation_fixer::SuggestedAnnotation;
+pub use crate::team_lint::LintChecks;
 
 pub struct Runner {
     run_config: RunConfig,
@@ -23,6 +28,8 @@ pub struct Runner {
     cache: Cache,
     config: Config,
     codeowners_file_path: PathBuf,
+    config_warnings: Vec<String>,
+    audit_log: Option<Arc<AuditLog>>,
 }
 
 pub fn version() -> String {
@@ -32,10 +39,36 @@ pub fn version() -> String {
 pub type Runnable = fn(Runner) -> RunResult;
 
 pub fn run<F>(run_config: &RunConfig, runnable: F) -> RunResult
+where
+    F: FnOnce(Runner) -> RunResult + Send,
+{
+    run_with(run_config, None, runnable)
+}
+
+/// Like `run`, but the runner's project only covers `subtree` (see `ProjectBuilder::with_subtree`).
+pub(crate) fn run_with<F>(run_config: &RunConfig, subtree: Option<&Path>, runnable: F) -> RunResult
+where
+    F: FnOnce(Runner) -> RunResult + Send,
+{
+    let Some(threads) = run_config.threads else {
+        return run_with_runner(run_config, subtree, runnable);
+    };
+
+    // A scoped pool rather than the global one, so each run can pick its own thread count
+    match rayon::ThreadPoolBuilder::new().num_threads(threads).build() {
+        Ok(pool) => pool.install(|| run_with_runner(run_config, subtree, runnable)),
+        Err(err) => RunResult {
+            io_errors: vec![format!("Can't start a pool of {} threads: {}", threads, err)],
+            ..Default::default()
+        },
+    }
+}
+
+fn run_with_runner<F>(run_config: &RunConfig, subtree: Option<&Path>, runnable: F) -> RunResult
 where
     F: FnOnce(Runner) -> RunResult,
 {
-    let runner = match Runner::new(run_config) {
+    let runner = match Runner::build(run_config, subtree) {
         Ok(runner) => runner,
         Err(err) => {
             return RunResult {
@@ -44,15 +77,30 @@ where
             };
         }
     };
-    runnable(runner)
+    let config_warnings = runner.config_warnings.clone();
+    let audit_log = runner.audit_log.clone();
+    let mut result = runnable(runner);
+    result.warnings.splice(0..0, config_warnings);
+    if let Some(audit_log) = audit_log
+        && let Err(err) = audit_log.finish()
+    {
+        result.io_errors.push(format!("Can't write the audit log: {}", err));
+    }
+    result
 }
 
 pub(crate) fn config_from_run_config(run_config: &RunConfig) -> Result<Config, Error> {
     match crate::config::Config::load_from_path(&run_config.config_path) {
         Ok(mut c) => {
+            c.apply_overrides(&run_config.config_overrides)
+                .map_err(|msg| error_stack::Report::new(Error::Io(msg)))?;
+            c.unowned_globs.extend(run_config.exclude_globs.iter().cloned());
             if let Some(executable_name) = &run_config.executable_name {
                 c.executable_name = executable_name.clone();
             }
+            if let Some(max_depth) = run_config.max_depth {
+                c.max_depth = Some(max_depth);
+            }
             Ok(c)
         }
         Err(msg) => Err(error_stack::Report::new(Error::Io(msg))),
@@ -80,7 +128,12 @@ pub(crate) fn resolve_codeowners_file_path(run_config: &RunConfig, config: &Conf
 
 impl Runner {
     pub fn new(run_config: &RunConfig) -> Result<Self, Error> {
+        Self::build(run_config, None)
+    }
+
+    fn build(run_config: &RunConfig, subtree: Option<&Path>) -> Result<Self, Error> {
         let config = config_from_run_config(run_config)?;
+        let config_warnings = config.validate(&run_config.project_root);
         let codeowners_file_path = resolve_codeowners_file_path(run_config, &config);
 
         let cache: Cache = if run_config.no_cache {
@@ -95,12 +148,27 @@ impl Runner {
                 .into()
         };
 
-        let mut project_builder = ProjectBuilder::new(&config, run_config.project_root.clone(), codeowners_file_path.clone(), &cache);
+        let mut project_builder = ProjectBuilder::new(&config, run_config.project_root.clone(), codeowners_file_path.clone(), &cache)
+            .with_threads(run_config.threads);
+        if let Some(subtree) = subtree {
+            project_builder = project_builder.with_subtree(subtree.to_path_buf());
+        }
         let project = project_builder.build().change_context(Error::Io(format!(
             "Can't build project: {}",
             &run_config.config_path.to_string_lossy()
         )))?;
-        let ownership = Ownership::build(project);
+        let audit_log = match &run_config.audit_log {
+            Some(path) => {
+                let path = run_config.project_root.join(path);
+                let audit_log = AuditLog::open(&path).change_context(Error::Io(format!("Can't open audit log: {}", path.display())))?;
+                Some(Arc::new(audit_log))
+            }
+            None => None,
+        };
+        let mut ownership = Ownership::build(project);
+        if let Some(audit_log) = &audit_log {
+            ownership = ownership.with_audit_log(audit_log.clone());
+        }
 
         cache.persist_cache().change_context(Error::Io(format!(
             "Can't persist cache: {}",
@@ -113,6 +181,8 @@ impl Runner {
             cache,
             config,
             codeowners_file_path,
+            config_warnings,
+            audit_log,
         })
     }
 
@@ -120,84 +190,450 @@ impl Runner {
         if file_paths.is_empty() {
             self.validate_all()
         } else {
-            self.validate_files(file_paths)
+            self.validate_files(file_paths, false)
+        }
+    }
+
+    /// Like `validate`, but reports only the first error found, skipping the checks (or files) after it.
+    pub fn validate_fail_fast(&self, file_paths: Vec<String>) -> RunResult {
+        if file_paths.is_empty() {
+            self.validation_result(self.ownership.validate_fail_fast())
+        } else {
+            self.validate_files(file_paths, true)
         }
     }
 
     fn validate_all(&self) -> RunResult {
+        self.validation_result(self.ownership.validate())
+    }
+
+    /// Validates the whole project, also flagging annotations that disagree with the file's package or directory owner.
+    pub fn validate_consistent_annotations(&self) -> RunResult {
+        self.validation_result(self.ownership.validate_consistent_annotations())
+    }
+
+    /// Validates the whole project, also checking the annotations of tracked files outside it (e.g. outside `owned_globs`)
+    /// name known teams.
+    pub fn validate_all_annotations(&self) -> RunResult {
+        let tracked_files = match crate::team_lint::tracked_file_paths(&self.run_config.project_root, &self.config) {
+            Ok(tracked_files) => tracked_files,
+            Err(err) => {
+                return RunResult {
+                    io_errors: vec![err],
+                    ..Default::default()
+                };
+            }
+        };
+        let annotation_parser = AnnotationParser::from_config(&self.config);
+        let annotated_files = tracked_files
+            .par_iter()
+            .map(|path| build_project_file_without_cache(path, &annotation_parser, self.config.annotation_header_lines))
+            .filter(|file| file.owner.is_some())
+            .collect();
+        self.validation_result(self.ownership.validate_all_annotations(annotated_files))
+    }
+
+    /// Validates the whole project, only re-resolving owners for files changed since the cache was written.
+    pub fn validate_since_cache(&self) -> RunResult {
+        let (validation, counts) = self.ownership.validate_since_cache(&self.cache);
+        let mut result = self.validation_result(validation);
+        result.info_messages.push(format!(
+            "Resolved owners for {} of {} files; the rest were unchanged since the cache was written",
+            counts.resolved, counts.total
+        ));
+        if let Err(err) = self.cache.persist_cache() {
+            result.io_errors.push(format!("Can't persist cache: {:?}", err));
+        }
+        result
+    }
+
+    fn validation_result(&self, validation: std::result::Result<(), crate::ownership::ValidatorErrors>) -> RunResult {
+        let mut info_messages = self.suppressed_annotation_warning().into_iter().collect::<Vec<_>>();
+        match validation {
+            Ok(_) => RunResult {
+                info_messages,
+                ..Default::default()
+            },
+            Err(err) => {
+                // The stale-CODEOWNERS diff (if any) rides along as informational output,
+                // printed ahead of the errors, so the actionable headline isn't buried.
+                info_messages.extend(err.info_messages());
+                RunResult {
+                    info_messages,
+                    validation_errors: vec![err.to_string()],
+                    ..Default::default()
+                }
+            }
+        }
+    }
+
+    fn suppressed_annotation_warning(&self) -> Option<String> {
+        let suppressed = self.ownership.suppressed_annotations(self.config.annotation_header_lines);
+        if suppressed.is_empty() {
+            return None;
+        }
+        let files: String = suppressed
+            .iter()
+            .map(|annotation| format!("\n- {} (@team {})", annotation.path.display(), annotation.team))
+            .collect();
+        Some(format!(
+            "Warning: these annotations are ignored because their files match `unowned_globs`:{}",
+            files
+        ))
+    }
+
+    /// Teams that own nothing but their own team file, see `Ownership::unused_teams`.
+    pub(crate) fn unused_teams(&self) -> Vec<crate::project::Team> {
+        self.ownership.unused_teams()
+    }
+
+    /// Validates the whole project, printing each error as a GitHub Actions `::error` workflow command.
+    pub fn validate_github_annotations(&self) -> RunResult {
         match self.ownership.validate() {
             Ok(_) => RunResult::default(),
             Err(err) => RunResult {
-                // The stale-CODEOWNERS diff (if any) rides along as informational output,
-                // printed ahead of the errors, so the actionable headline isn't buried.
+                validation_errors: err.github_annotations(&self.run_config.project_root),
+                ..Default::default()
+            },
+        }
+    }
+
+    /// Validates the whole project, printing a SARIF 2.1.0 log with one result per error for code scanning tools.
+    /// The log is printed even when there are no errors.
+    pub fn validate_sarif(&self) -> RunResult {
+        let validation = self.ownership.validate();
+        let results = match &validation {
+            Ok(_) => vec![],
+            Err(err) => err.sarif_results(
+                &self.run_config.project_root,
+                crate::path_utils::relative_to(&self.run_config.project_root, &self.codeowners_file_path),
+            ),
+        };
+        let rule_ids: std::collections::BTreeSet<&str> = results.iter().filter_map(|result| result["ruleId"].as_str()).collect();
+        let sarif = serde_json::json!({
+            "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+            "version": "2.1.0",
+            "runs": [{
+                "tool": {
+                    "driver": {
+                        "name": "codeowners",
+                        "version": env!("CARGO_PKG_VERSION"),
+                        "informationUri": "https://github.com/rubyatscale/codeowners-rs",
+                        "rules": rule_ids.iter().map(|id| serde_json::json!({ "id": id })).collect::<Vec<_>>(),
+                    }
+                },
+                "results": results,
+            }],
+        });
+        let json = match serde_json::to_string_pretty(&sarif) {
+            Ok(json) => json,
+            Err(e) => return RunResult::from_io_error(Error::Io(e.to_string()), false),
+        };
+        if validation.is_ok() {
+            RunResult {
+                info_messages: vec![json],
+                ..Default::default()
+            }
+        } else {
+            RunResult {
+                validation_errors: vec![json],
+                ..Default::default()
+            }
+        }
+    }
+
+    /// Validates the whole project, printing errors and suppressed-annotation warnings as one JSON object.
+    pub fn validate_json(&self) -> RunResult {
+        let validation = self.ownership.validate();
+        let (validation_errors, errors) = match &validation {
+            Ok(_) => (vec![], vec![]),
+            Err(err) => (vec![format!("{}", err)], err.records()),
+        };
+        let result = serde_json::json!({
+            "validation_errors": validation_errors,
+            "errors": errors,
+            "warnings": self.ownership.suppressed_annotations(self.config.annotation_header_lines),
+        });
+        let json = match serde_json::to_string_pretty(&result) {
+            Ok(json) => json,
+            Err(e) => return RunResult::json_io_error(Error::Io(e.to_string())),
+        };
+        if validation_errors.is_empty() {
+            RunResult {
+                info_messages: vec![json],
+                ..Default::default()
+            }
+        } else {
+            RunResult {
+                validation_errors: vec![json],
+                ..Default::default()
+            }
+        }
+    }
+
+    pub fn validate_team(&self, team_name: &str, fail_fast: bool) -> RunResult {
+        let result = if fail_fast {
+            self.ownership.validate_team_fail_fast(team_name)
+        } else {
+            self.ownership.validate_team(team_name)
+        };
+        match result {
+            Ok(_) => RunResult::default(),
+            Err(err) => RunResult {
                 info_messages: err.info_messages(),
-                validation_errors: vec![format!("{}", err)],
+                validation_errors: vec![err.to_string()],
                 ..Default::default()
             },
         }
     }
 
-    fn validate_files(&self, file_paths: Vec<String>) -> RunResult {
-        let mut unowned_files = Vec::new();
+    /// Annotates unowned files with the team that owns their directory or nearest package,
+    /// asking `confirm` before each write. Files left unowned are reported as validation errors.
+    pub fn validate_fix<F>(&self, mut confirm: F) -> RunResult
+    where
+        F: FnMut(&SuggestedAnnotation) -> bool,
+    {
+        let unowned_files = self.ownership.unowned_files();
+        let (suggestions, mut still_unowned) =
+            match annotation_fixer::suggest_annotations(&self.run_config.project_root, &self.config, &unowned_files) {
+                Ok(result) => result,
+                Err(err) => return RunResult::from_io_error(Error::Io(err), false),
+            };
+
+        let mut info_messages = Vec::new();
         let mut io_errors = Vec::new();
+        for suggestion in suggestions {
+            if !confirm(&suggestion) {
+                still_unowned.push(suggestion.path);
+                continue;
+            }
+            match suggestion.apply(&self.run_config.project_root) {
+                Ok(()) => info_messages.push(format!(
+                    "Annotated {} with `{}`",
+                    suggestion.path.display(),
+                    suggestion.annotation()
+                )),
+                Err(err) => io_errors.push(format!("Can't annotate {}: {}", suggestion.path.display(), err)),
+            }
+        }
+        if !info_messages.is_empty() {
+            info_messages.push("Run `generate` to update the CODEOWNERS file.".to_owned());
+        }
+
+        let mut validation_errors = Vec::new();
+        if !still_unowned.is_empty() {
+            still_unowned.sort();
+            validation_errors.push("Unowned files detected:".to_owned());
+            validation_errors.extend(still_unowned.iter().map(|file| format!("  {}", file.display())));
+        }
+
+        RunResult {
+            info_messages,
+            validation_errors,
+            io_errors,
+            ..Default::default()
+        }
+    }
 
-        // Filter files based on owned_globs and unowned_globs configuration
-        // Only validate files that match owned_globs and don't match unowned_globs
-        let filtered_paths: Vec<String> = file_paths
+    /// Those of `file_paths` matching `owned_globs` and not `unowned_globs`, mirroring the filtering applied by
+    /// ProjectBuilder when walking the project.
+    fn owned_file_paths(&self, file_paths: Vec<String>) -> Vec<String> {
+        file_paths
             .into_iter()
             .filter(|file_path| {
-                // Convert to relative path for glob matching
-                let path = Path::new(file_path);
-                let relative_path = if path.is_absolute() {
-                    path.strip_prefix(&self.run_config.project_root).unwrap_or(path)
-                } else {
-                    path
-                };
-
-                // Mirror the filtering applied by ProjectBuilder when walking the project
-                matches_globs(relative_path, &self.config.owned_globs) && !matches_globs(relative_path, &self.config.unowned_globs)
+                let relative_path = crate::path_utils::relative_to(&self.run_config.project_root, Path::new(file_path));
+                let case_insensitive = self.config.case_insensitive_paths;
+                matches_globs(relative_path, &self.config.owned_globs, case_insensitive)
+                    && !matches_globs(relative_path, &self.config.unowned_globs, case_insensitive)
             })
-            .collect();
+            .collect()
+    }
+
+    fn validate_files(&self, file_paths: Vec<String>, fail_fast: bool) -> RunResult {
+        let mut unowned_files = Vec::new();
+        let mut io_errors = Vec::new();
 
-        for file_path in filtered_paths {
+        for file_path in self.owned_file_paths(file_paths) {
             match team_for_file_from_codeowners(&self.run_config, &file_path) {
                 Ok(Some(_)) => {}
                 Ok(None) => unowned_files.push(file_path),
                 Err(err) => io_errors.push(format!("{}: {}", file_path, err)),
             }
+            if fail_fast && !(unowned_files.is_empty() && io_errors.is_empty()) {
+                break;
+            }
         }
 
+        let mut validation_errors = Vec::new();
         if !unowned_files.is_empty() {
-            let validation_errors = std::iter::once("Unowned files detected:".to_string())
-                .chain(unowned_files.into_iter().map(|file| format!("  {}", file)))
-                .collect();
+            validation_errors.push("Unowned files detected:".to_string());
+            validation_errors.extend(unowned_files.into_iter().map(|file| format!("  {}", file)));
+        }
 
-            return RunResult {
-                validation_errors,
-                io_errors,
+        // The owners above come from CODEOWNERS, so it must also be up to date, as when validating the whole project
+        let mut info_messages = Vec::new();
+        let stopped_early = fail_fast && !(validation_errors.is_empty() && io_errors.is_empty());
+        if !stopped_early && let Err(err) = self.ownership.validate_codeowners_file() {
+            info_messages.extend(err.info_messages());
+            validation_errors.push(err.to_string());
+        }
+
+        RunResult {
+            info_messages,
+            validation_errors,
+            io_errors,
+            ..Default::default()
+        }
+    }
+
+    pub fn generate(&self, git_stage: bool) -> RunResult {
+        let run_result = self.write_generated_file(git_stage, false);
+        if !run_result.has_errors() {
+            self.ownership.record_resolutions();
+        }
+        run_result
+    }
+
+    /// Like `generate`, but writes the fewest lines that give every tracked file the same owners.
+    pub fn generate_minimized(&self, git_stage: bool) -> RunResult {
+        let run_result = self.write_generated_file(git_stage, true);
+        if !run_result.has_errors() {
+            self.ownership.record_resolutions();
+        }
+        run_result
+    }
+
+    /// Prints a CODEOWNERS file holding only the sections named in `sections`, for debugging. The CODEOWNERS file
+    /// itself is left alone, as the partial file would drop every other rule.
+    pub fn generate_sections(&self, sections: &[String]) -> RunResult {
+        match self.ownership.generate_sections_file(sections) {
+            Ok(content) => RunResult {
+                info_messages: vec![content.trim_end().to_owned()],
                 ..Default::default()
-            };
+            },
+            Err(err) => RunResult::from_io_error(Error::Io(err), false),
         }
+    }
 
-        if !io_errors.is_empty() {
-            return RunResult {
-                io_errors,
+    fn minimized_codeowners_file(&self) -> std::result::Result<String, String> {
+        let project_root = &self.run_config.project_root;
+        let file_paths: Vec<String> = crate::team_lint::tracked_file_paths(project_root, &self.config)?
+            .iter()
+            .map(|path| to_slash_string_lossy(crate::path_utils::relative_to(project_root, path)))
+            .collect();
+        self.ownership.generate_minimized_file(&file_paths)
+    }
+
+    /// The error `fail_on_empty_generation` calls for, when set and the generated file would own nothing.
+    fn empty_generation_error(&self) -> Option<RunResult> {
+        (self.config.fail_on_empty_generation && !self.ownership.has_ownership_entries()).then(|| RunResult {
+            io_errors: vec![format!(
+                "Refusing to write {}: it would own no files besides the team files. Check `owned_globs` and `team_file_glob`, or unset `fail_on_empty_generation`",
+                self.codeowners_file_path.display()
+            )],
+            ..Default::default()
+        })
+    }
+
+    fn write_generated_file(&self, git_stage: bool, minimize: bool) -> RunResult {
+        if let Some(run_result) = self.empty_generation_error() {
+            return run_result;
+        }
+        let content = if minimize {
+            match self.minimized_codeowners_file() {
+                Ok(content) => content,
+                Err(err) => return RunResult::from_io_error(Error::Io(err), false),
+            }
+        } else {
+            self.ownership.generate_file()
+        };
+        self.write_codeowners_file(content, git_stage)
+    }
+
+    /// Like `generate`, and also writes a JSON file describing the team and source kind behind each generated line.
+    pub fn generate_with_sidecar(&self, sidecar_path: &Path, git_stage: bool) -> RunResult {
+        let run_result = self.generate(git_stage);
+        if run_result.has_errors() {
+            return run_result;
+        }
+        let sidecar = serde_json::json!({ "entries": self.ownership.generate_sidecar() });
+        let written = serde_json::to_string_pretty(&sidecar)
+            .map_err(|err| err.to_string())
+            .and_then(|json| {
+                if let Some(parent) = sidecar_path.parent() {
+                    let _ = std::fs::create_dir_all(parent);
+                }
+                std::fs::write(sidecar_path, json + "\n").map_err(|err| format!("Can't write {}: {}", sidecar_path.display(), err))
+            });
+        match written {
+            Ok(()) => run_result,
+            Err(err) => RunResult {
+                io_errors: vec![err],
                 ..Default::default()
-            };
+            },
         }
+    }
 
-        RunResult::default()
+    /// Writes the generated ownership as YAML required-review rules for branch protection, mapping each CODEOWNERS
+    /// pattern to the slug of the team that owns it.
+    pub fn export_review_rules(&self, path: &Path) -> RunResult {
+        let rules = serde_yaml::to_string(&serde_json::json!({ "rules": self.ownership.review_rules() })).map_err(|err| err.to_string());
+        write_export(path, rules)
     }
 
-    pub fn generate(&self, git_stage: bool) -> RunResult {
-        let content = self.ownership.generate_file();
+    /// Writes every owned file with its team, GitHub team, and source kind as tab-separated lines under a header,
+    /// in path order.
+    pub fn export_tsv(&self, path: &Path) -> RunResult {
+        let mut tsv = String::from("path\tteam_name\tgithub_team\tsource_kind\n");
+        for entry in self.ownership.ownership_index() {
+            tsv.push_str(&format!(
+                "{}\t{}\t{}\t{}\n",
+                entry.path, entry.team_name, entry.github_team, entry.source_kind
+            ));
+        }
+        write_export(path, Ok(tsv))
+    }
+
+    /// Regenerates only the CODEOWNERS lines under `subtree`, for a runner built with that subtree. Falls
+    /// back to a full `generate` when the lines can't be merged into the existing file unambiguously.
+    pub fn generate_subtree(&self, subtree: &Path, git_stage: bool) -> RunResult {
+        let merged = match std::fs::read_to_string(&self.codeowners_file_path) {
+            Err(_) => Err("there is no CODEOWNERS file to merge into".to_owned()),
+            // Team files feed the team YML section and every team's globs, so changes to them ripple past the subtree
+            Ok(_) if self.ownership.has_team_files_under(subtree) => Err(format!("{} contains team files", subtree.display())),
+            Ok(existing) => {
+                let generated = self.ownership.generate_file();
+                crate::ownership::codeowners_file_parser::merge_subtree(&existing, &generated, &to_slash_string_lossy(subtree))
+                    .ok_or_else(|| "the CODEOWNERS file has sections `generate` doesn't write".to_owned())
+            }
+        };
+
+        match merged {
+            Ok(merged) => self.write_codeowners_file(merged, git_stage),
+            Err(reason) => {
+                let mut run_result = match Runner::new(&self.run_config) {
+                    Ok(runner) => runner.generate(git_stage),
+                    Err(err) => RunResult::from_io_error(Error::Io(format!("{:?}", err)), false),
+                };
+                run_result
+                    .info_messages
+                    .insert(0, format!("Regenerating the whole CODEOWNERS file because {}", reason));
+                run_result
+            }
+        }
+    }
+
+    fn write_codeowners_file(&self, content: String, git_stage: bool) -> RunResult {
         if let Some(parent) = &self.codeowners_file_path.parent() {
             let _ = std::fs::create_dir_all(parent);
         }
         match std::fs::write(&self.codeowners_file_path, content) {
             Ok(_) => {
-                if git_stage {
-                    self.git_stage();
+                if git_stage && let Err(err) = self.git_stage() {
+                    return RunResult {
+                        io_errors: vec![err],
+                        ..Default::default()
+                    };
                 }
                 RunResult::default()
             }
@@ -209,32 +645,99 @@ impl Runner {
     }
 
     pub fn generate_and_validate(&self, file_paths: Vec<String>, git_stage: bool) -> RunResult {
-        let run_result = self.generate(git_stage);
+        // Validating the whole project records every file to the audit log, so generating needn't as well
+        let run_result = if file_paths.is_empty() {
+            self.write_generated_file(git_stage, false)
+        } else {
+            self.generate(git_stage)
+        };
         if run_result.has_errors() {
             return run_result;
         }
         self.validate(file_paths)
     }
 
-    fn git_stage(&self) {
-        let _ = Command::new("git")
-            .arg("add")
-            .arg(&self.codeowners_file_path)
-            .current_dir(&self.run_config.project_root)
-            .output();
+    /// Like `generate_and_validate`, but leaves the working tree alone: reports whether the CODEOWNERS file would
+    /// change, then validates as if the generated file had been written.
+    pub fn generate_and_validate_dry_run(&self, file_paths: Vec<String>) -> RunResult {
+        if let Some(run_result) = self.empty_generation_error() {
+            return run_result;
+        }
+        let codeowners_file = crate::path_utils::relative_to(&self.run_config.project_root, &self.codeowners_file_path);
+        let mut info_messages = match self.ownership.validate_codeowners_file() {
+            Ok(()) => vec![format!("Dry run: {} is up to date", codeowners_file.display())],
+            Err(err) => std::iter::once(format!("Dry run: {} would change; nothing was written", codeowners_file.display()))
+                .chain(err.info_messages())
+                .collect(),
+        };
+
+        let mut run_result = if file_paths.is_empty() {
+            self.validation_result(self.ownership.validate_generated())
+        } else {
+            let relative_paths: Vec<String> = self
+                .owned_file_paths(file_paths)
+                .iter()
+                .map(|file_path| to_slash_string_lossy(crate::path_utils::relative_to(&self.run_config.project_root, Path::new(file_path))))
+                .collect();
+            let unowned_files = self.ownership.unowned_in_generated_file(&relative_paths);
+            let mut validation_errors = Vec::new();
+            if !unowned_files.is_empty() {
+                validation_errors.push("Unowned files detected:".to_string());
+                validation_errors.extend(unowned_files.into_iter().map(|file| format!("  {}", file)));
+            }
+            RunResult {
+                validation_errors,
+                ..Default::default()
+            }
+        };
+        info_messages.append(&mut run_result.info_messages);
+        run_result.info_messages = info_messages;
+        run_result
+    }
+
+    // A failing `git add` (e.g. outside a repository) is ignored; a missing or hung git is reported
+    fn git_stage(&self) -> std::result::Result<(), String> {
+        let codeowners_file_path = self.codeowners_file_path.to_string_lossy();
+        match run_git(
+            &["add", codeowners_file_path.as_ref()],
+            &self.run_config.project_root,
+            self.config.git_timeout(),
+        ) {
+            Ok(_) => Ok(()),
+            Err(e) => Err(e.to_string()),
+        }
     }
 
-    pub fn for_team(&self, team_name: &str) -> RunResult {
+    pub fn for_team(&self, team_name: &str, with_source: bool) -> RunResult {
         let mut info_messages = vec![];
         let mut io_errors = vec![];
-        match self.ownership.for_team(team_name) {
+        let team_ownerships = if with_source {
+            self.ownership.for_team_with_sources(team_name)
+        } else {
+            self.ownership
+                .for_team(team_name)
+                .map(|team_ownerships| team_ownerships.into_iter().map(|team_ownership| (team_ownership, vec![])).collect())
+        };
+        match team_ownerships {
             Ok(team_ownerships) => {
                 info_messages.push(format!("# Code Ownership Report for `{}` Team", team_name));
-                for team_ownership in team_ownerships {
+                for (team_ownership, sources) in team_ownerships {
                     info_messages.push(format!("\n#{}", team_ownership.heading));
-                    match team_ownership.globs.len() {
-                        0 => info_messages.push("This team owns nothing in this category.".to_string()),
-                        _ => info_messages.push(team_ownership.globs.join("\n")),
+                    if team_ownership.globs.is_empty() {
+                        info_messages.push("This team owns nothing in this category.".to_string());
+                    } else if with_source {
+                        let lines: Vec<String> = team_ownership
+                            .globs
+                            .iter()
+                            .zip(sources)
+                            .map(|(glob, source)| match source {
+                                Some(source) => format!("{} ({})", glob, source),
+                                None => format!("{} (source not found, regenerate the CODEOWNERS file)", glob),
+                            })
+                            .collect();
+                        info_messages.push(lines.join("\n"));
+                    } else {
+                        info_messages.push(team_ownership.globs.join("\n"));
                     }
                 }
             }
@@ -260,8 +763,23 @@ impl Runner {
         }
     }
 
-    pub fn crosscheck_owners(&self) -> RunResult {
-        crate::crosscheck::crosscheck_owners(&self.run_config, &self.cache)
+    /// Building the runner already read every tracked owned file through the cache and persisted it,
+    /// so all that's left is to report the result.
+    pub fn warm_cache(&self) -> RunResult {
+        if self.run_config.no_cache {
+            return RunResult {
+                io_errors: vec!["Can't warm the cache with --no-cache".to_owned()],
+                ..Default::default()
+            };
+        }
+        RunResult {
+            info_messages: vec![format!("Wrote {} entries to the cache", self.cache.entry_count())],
+            ..Default::default()
+        }
+    }
+
+    pub fn crosscheck_owners(&self, json: bool) -> RunResult {
+        crate::crosscheck::crosscheck_owners(&self.run_config, &self.cache, json)
     }
 
     pub fn owners_for_file(&self, file_path: &str) -> Result<Vec<FileOwner>, Error> {
@@ -272,7 +790,7 @@ impl Runner {
 
     pub fn for_file_derived(&self, file_path: &str, json: bool) -> RunResult {
         let file_owners = match self.owners_for_file(file_path) {
-            Ok(v) => v,
+            Ok(v) => format_file_owners(v, &self.run_config),
             Err(err) => {
                 return RunResult::from_io_error(Error::Io(err.to_string()), json);
             }
@@ -281,27 +799,22 @@ impl Runner {
         match file_owners.as_slice() {
             [] => RunResult::from_file_owner(&FileOwner::default(), json),
             [owner] => RunResult::from_file_owner(owner, json),
-            many => {
-                let mut error_messages = vec!["Error: file is owned by multiple teams!".to_string()];
-                for owner in many {
-                    error_messages.push(format!("\n{}", owner));
-                }
-                RunResult::from_validation_errors(error_messages, json)
-            }
+            many => RunResult::from_multiple_owners(many, json),
         }
     }
 
     pub fn for_file_codeowners_only(&self, file_path: &str, json: bool) -> RunResult {
         match team_for_file_from_codeowners(&self.run_config, file_path) {
             Ok(Some(team)) => {
-                let team_yml = crate::path_utils::relative_to(&self.run_config.project_root, team.path.as_path())
-                    .to_string_lossy()
-                    .to_string();
+                let team_yml = self
+                    .run_config
+                    .display_path(&crate::path_utils::relative_to(&self.run_config.project_root, team.path.as_path()).to_string_lossy());
                 let result = ForFileResult {
-                    team_name: team.name.clone(),
-                    github_team: team.github_team.clone(),
+                    team_name: self.run_config.display_team_name(&team),
+                    github_team: self.run_config.team_format.render(&team.github_team),
                     team_yml,
                     description: vec!["Owner inferred from codeowners file".to_string()],
+                    schema_version: FOR_FILE_SCHEMA_VERSION,
                 };
                 if json {
                     RunResult::json_info(result)
@@ -333,12 +846,137 @@ impl Runner {
     }
 }
 
+fn write_export(path: &Path, contents: std::result::Result<String, String>) -> RunResult {
+    let written = contents.and_then(|contents| {
+        if let Some(parent) = path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        std::fs::write(path, contents).map_err(|err| format!("Can't write {}: {}", path.display(), err))
+    });
+    match written {
+        Ok(()) => RunResult::default(),
+        Err(err) => RunResult {
+            io_errors: vec![err],
+            ..Default::default()
+        },
+    }
+}
+
+/// `file_owners` with their team names, GitHub team handles and team file paths rendered as `run_config` asks, ready for output.
+pub(crate) fn format_file_owners(file_owners: Vec<FileOwner>, run_config: &RunConfig) -> Vec<FileOwner> {
+    file_owners
+        .into_iter()
+        .map(|mut file_owner| {
+            file_owner.team.name = run_config.display_team_name(&file_owner.team);
+            file_owner.team.github_team = run_config.team_format.render(&file_owner.team.github_team);
+            file_owner.team_config_file_path = run_config.display_path(&file_owner.team_config_file_path);
+            file_owner
+        })
+        .collect()
+}
+
+/// Bumped whenever the shape of `for-file --json` output changes; see `for_file_json_schema`.
+pub const FOR_FILE_SCHEMA_VERSION: u32 = 1;
+
 #[derive(Debug, Clone, Serialize)]
 pub struct ForFileResult {
     pub team_name: String,
     pub github_team: String,
     pub team_yml: String,
     pub description: Vec<String>,
+    pub schema_version: u32,
+}
+
+/// JSON Schema for everything `for-file --json` prints: an owner, a multiple-owners error, an error message, or, with
+/// `--explain`, an owner and the claims it beat.
+pub fn for_file_json_schema() -> serde_json::Value {
+    serde_json::json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "$id": format!("https://github.com/rubyatscale/codeowners-rs/schemas/for-file/v{}.json", FOR_FILE_SCHEMA_VERSION),
+        "title": "codeowners for-file --json",
+        "oneOf": [
+            { "$ref": "#/$defs/owner" },
+            {
+                "type": "object",
+                "properties": {
+                    "error": { "const": "multiple_owners" },
+                    "owners": { "type": "array", "items": { "$ref": "#/$defs/owner" } }
+                },
+                "required": ["error", "owners"]
+            },
+            {
+                "type": "object",
+                "properties": { "error": { "type": "string" } },
+                "required": ["error"],
+                "additionalProperties": false
+            },
+            {
+                "type": "object",
+                "properties": {
+                    "winner": { "$ref": "#/$defs/owner" },
+                    "also_matched": { "type": "array", "items": { "$ref": "#/$defs/claim" } }
+                },
+                "required": ["winner", "also_matched"]
+            }
+        ],
+        "$defs": {
+            "owner": {
+                "type": "object",
+                "properties": {
+                    "team_name": { "type": "string", "description": "Team name (its GitHub team handle with `--team-name-from github`), or `Unowned`" },
+                    "github_team": { "type": "string" },
+                    "team_yml": { "type": "string", "description": "Team file relative to the project root (absolute with `--absolute-paths`); empty when unowned" },
+                    "description": { "type": "array", "items": { "type": "string" }, "description": "Why the team owns the file" },
+                    "schema_version": { "const": FOR_FILE_SCHEMA_VERSION }
+                },
+                "required": ["team_name", "github_team", "team_yml", "description", "schema_version"]
+            },
+            "claim": {
+                "type": "object",
+                "properties": {
+                    "team_name": { "type": "string" },
+                    "github_team": { "type": "string" },
+                    "source": { "type": "string", "description": "Why the team claimed the file" },
+                    "priority": { "type": "integer", "description": "Lower outranks higher" }
+                },
+                "required": ["team_name", "github_team", "source", "priority"]
+            }
+        }
+    })
+}
+
+/// A claim that lost to the winning owner, as listed by `for-file --explain`.
+#[derive(Debug, Clone, Serialize)]
+pub struct AlsoMatched {
+    pub team_name: String,
+    pub github_team: String,
+    pub source: String,
+    pub priority: u8,
+}
+
+/// `for-file --explain --json` output: the owner, then every other team's claims, strongest first.
+#[derive(Debug, Clone, Serialize)]
+pub struct ExplainedOwner {
+    pub winner: ForFileResult,
+    pub also_matched: Vec<AlsoMatched>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MultipleOwnersError {
+    pub error: &'static str,
+    pub owners: Vec<ForFileResult>,
+}
+
+impl From<&FileOwner> for ForFileResult {
+    fn from(file_owner: &FileOwner) -> Self {
+        Self {
+            team_name: file_owner.team.name.clone(),
+            github_team: file_owner.team.github_team.clone(),
+            team_yml: file_owner.team_config_file_path.clone(),
+            description: file_owner.sources.iter().map(|source| source.to_string()).collect(),
+            schema_version: FOR_FILE_SCHEMA_VERSION,
+        }
+    }
 }
 
 impl RunResult {
@@ -359,17 +997,7 @@ impl RunResult {
 
     fn from_file_owner(file_owner: &FileOwner, json: bool) -> Self {
         if json {
-            let description: Vec<String> = if file_owner.sources.is_empty() {
-                vec![]
-            } else {
-                file_owner.sources.iter().map(|source| source.to_string()).collect()
-            };
-            Self::json_info(ForFileResult {
-                team_name: file_owner.team.name.clone(),
-                github_team: file_owner.team.github_team.clone(),
-                team_yml: file_owner.team_config_file_path.clone(),
-                description,
-            })
+            Self::json_info(ForFileResult::from(file_owner))
         } else {
             Self {
                 info_messages: vec![format!("{}", file_owner)],
@@ -378,10 +1006,71 @@ impl RunResult {
         }
     }
 
-    fn from_validation_errors(validation_errors: Vec<String>, json: bool) -> Self {
+    fn from_unowned(reasons: &[String]) -> Self {
+        let description: String = reasons.iter().map(|reason| format!("\n- {}", reason)).collect();
+        Self {
+            info_messages: vec![format!("{}{}", FileOwner::default(), description)],
+            ..Default::default()
+        }
+    }
+
+    /// Reports the first of `file_owners` (sorted by `FileOwner::cmp_precedence`) as the owner when its strongest claim
+    /// outranks every other team's, followed by the other teams' claims. Teams tied for the strongest claim are still
+    /// a multiple-owners error.
+    fn from_explained_owners(file_owners: &[FileOwner], json: bool) -> Self {
+        let default_owner = FileOwner::default();
+        let (winner, others) = match file_owners {
+            [] => (&default_owner, &[][..]),
+            [first, second, ..] if first.priority() == second.priority() => return Self::from_multiple_owners(file_owners, json),
+            [first, others @ ..] => (first, others),
+        };
+        let mut also_matched: Vec<AlsoMatched> = others
+            .iter()
+            .flat_map(|owner| {
+                owner.sources.iter().map(|source| AlsoMatched {
+                    team_name: owner.team.name.clone(),
+                    github_team: owner.team.github_team.clone(),
+                    source: source.to_string(),
+                    priority: source.priority(),
+                })
+            })
+            .collect();
+        also_matched.sort_by(|a, b| (a.priority, &a.team_name, &a.source).cmp(&(b.priority, &b.team_name, &b.source)));
+
+        if json {
+            let explained = ExplainedOwner {
+                winner: ForFileResult::from(winner),
+                also_matched,
+            };
+            return match serde_json::to_string_pretty(&explained) {
+                Ok(json) => Self {
+                    info_messages: vec![json],
+                    ..Default::default()
+                },
+                Err(e) => Self::fallback_io_error(&e.to_string()),
+            };
+        }
+        let mut message = winner.to_string();
+        if !also_matched.is_empty() {
+            message.push_str("\nAlso matched:");
+            for claim in &also_matched {
+                message.push_str(&format!("\n- {} (priority {}): {}", claim.team_name, claim.priority, claim.source));
+            }
+        }
+        Self {
+            info_messages: vec![message],
+            ..Default::default()
+        }
+    }
+
+    fn from_multiple_owners(file_owners: &[FileOwner], json: bool) -> Self {
         if json {
-            Self::json_validation_error(validation_errors)
+            Self::json_multiple_owners_error(file_owners.iter().map(ForFileResult::from).collect())
         } else {
+            let mut validation_errors = vec!["Error: file is owned by multiple teams!".to_string()];
+            for owner in file_owners {
+                validation_errors.push(format!("\n{}", owner));
+            }
             Self {
                 validation_errors,
                 ..Default::default()
@@ -427,6 +1116,21 @@ impl RunResult {
         }
     }
 
+    pub fn json_multiple_owners_error(owners: Vec<ForFileResult>) -> Self {
+        let json_obj = MultipleOwnersError {
+            error: "multiple_owners",
+            owners,
+        };
+        let json = match serde_json::to_string_pretty(&json_obj) {
+            Ok(json) => json,
+            Err(e) => return Self::fallback_io_error(&format!("JSON serialization failed: {}", e)),
+        };
+        Self {
+            validation_errors: vec![json],
+            ..Default::default()
+        }
+    }
+
     fn fallback_io_error(message: &str) -> Self {
         Self {
             io_errors: vec![format!("{{\"error\": \"{}\"}}", message.replace('"', "\\\""))],
@@ -436,9 +1140,9 @@ impl RunResult {
 }
 
 /// Returns true if `path` matches any of the provided glob patterns.
-fn matches_globs(path: &Path, globs: &[String]) -> bool {
-    match path.to_str() {
-        Some(s) => globs.iter().any(|glob| glob_match(glob, s)),
+fn matches_globs(path: &Path, globs: &[String], case_insensitive: bool) -> bool {
+    match to_slash_str(path) {
+        Some(s) => globs.iter().any(|glob| glob_matches(glob, &s, case_insensitive)),
         None => false,
     }
 }
@@ -458,12 +1162,13 @@ mod tests {
             github_team: "team1".to_string(),
             team_yml: "config/teams/team1.yml".to_string(),
             description: vec!["file annotation".to_string()],
+            schema_version: FOR_FILE_SCHEMA_VERSION,
         };
         let result = RunResult::json_info(result);
         assert_eq!(result.info_messages.len(), 1);
         assert_eq!(
             result.info_messages[0],
-            "{\n  \"team_name\": \"team1\",\n  \"github_team\": \"team1\",\n  \"team_yml\": \"config/teams/team1.yml\",\n  \"description\": [\n    \"file annotation\"\n  ]\n}"
+            "{\n  \"team_name\": \"team1\",\n  \"github_team\": \"team1\",\n  \"team_yml\": \"config/teams/team1.yml\",\n  \"description\": [\n    \"file annotation\"\n  ],\n  \"schema_version\": 1\n}"
         );
     }
 
@@ -474,6 +1179,26 @@ mod tests {
         assert_eq!(result.io_errors[0], "{\"error\":\"unable to find file\"}");
     }
 
+    #[test]
+    fn test_json_multiple_owners_error() {
+        let owners = ["team1", "team2"]
+            .iter()
+            .map(|name| ForFileResult {
+                team_name: name.to_string(),
+                github_team: format!("@{}", name),
+                team_yml: format!("config/teams/{}.yml", name),
+                description: vec![],
+                schema_version: FOR_FILE_SCHEMA_VERSION,
+            })
+            .collect();
+        let result = RunResult::json_multiple_owners_error(owners);
+        assert_eq!(result.validation_errors.len(), 1);
+        let parsed: serde_json::Value = serde_json::from_str(&result.validation_errors[0]).unwrap();
+        assert_eq!(parsed["error"], "multiple_owners");
+        assert_eq!(parsed["owners"][0]["team_name"], "team1");
+        assert_eq!(parsed["owners"][1]["github_team"], "@team2");
+    }
+
     #[test]
     fn test_json_validation_error() {
         let result = RunResult::json_validation_error(vec!["file has multiple owners".to_string()]);
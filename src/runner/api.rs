@@ -1,39 +1,425 @@
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 
+use itertools::Itertools;
+use path_clean::PathClean;
+
+use crate::config::ForFileMode;
 use crate::project::Team;
 use crate::{ownership::FileOwner, runner::config_from_run_config};
 
-use super::{Error, ForFileResult, RunConfig, RunResult, run};
+use super::{Error, ForFileResult, LintChecks, RunConfig, RunResult, run};
 
 pub fn for_file(run_config: &RunConfig, file_path: &str, from_codeowners: bool, json: bool) -> RunResult {
     if from_codeowners {
         return for_file_codeowners_only_fast(run_config, file_path, json);
     }
-    for_file_optimized(run_config, file_path, json)
+    for_file_optimized(run_config, file_path, &[], json)
+}
+
+// Like `for_file`, but takes the mode from `for_file_default_mode` in the config
+pub fn for_file_in_default_mode(run_config: &RunConfig, file_path: &str, json: bool) -> RunResult {
+    let config = match config_from_run_config(run_config) {
+        Ok(c) => c,
+        Err(err) => {
+            return RunResult::from_io_error(Error::Io(err.to_string()), json);
+        }
+    };
+    for_file(run_config, file_path, config.for_file_default_mode == ForFileMode::Codeowners, json)
+}
+
+// Reports who owns a directory through its `.codeowner` file or package and, with `uniform`, whether every owned
+// file inside shares that owner
+pub fn for_directory(run_config: &RunConfig, directory_path: &str, uniform: bool, json: bool) -> RunResult {
+    let config = match config_from_run_config(run_config) {
+        Ok(c) => c,
+        Err(err) => return RunResult::from_io_error(Error::Io(err.to_string()), json),
+    };
+
+    use crate::ownership::file_owner_resolver::{directory_owner_breakdown, find_directory_owners};
+    let mut result = match find_directory_owners(&run_config.project_root, &config, Path::new(directory_path)) {
+        Ok(owners) => match super::format_file_owners(owners, run_config).as_slice() {
+            [] => RunResult::from_file_owner(&FileOwner::default(), json),
+            [owner] => RunResult::from_file_owner(owner, json),
+            many => RunResult::from_multiple_owners(many, json),
+        },
+        Err(err) => return RunResult::from_io_error(Error::Io(err), json),
+    };
+    if uniform {
+        match directory_owner_breakdown(&run_config.project_root, &config, Path::new(directory_path)) {
+            Ok(breakdown) => result.info_messages.push(match breakdown.len() {
+                0 => "Files: no owned files".to_owned(),
+                1 => breakdown
+                    .iter()
+                    .map(|(team, count)| format!("Files: uniform, all {} owned by {}", count, team))
+                    .collect(),
+                _ => std::iter::once("Files: mixed".to_owned())
+                    .chain(breakdown.iter().map(|(team, count)| format!("- {}: {}", team, count)))
+                    .join("\n"),
+            }),
+            Err(err) => result.io_errors.push(err),
+        }
+    }
+    result
+}
+
+// Prints `path\tteam\tgithub_team\tsource` on one line, for logs and grep
+pub fn for_file_line(run_config: &RunConfig, file_path: &str, from_codeowners: bool) -> RunResult {
+    let config = match config_from_run_config(run_config) {
+        Ok(c) => c,
+        Err(err) => return RunResult::from_io_error(Error::Io(err.to_string()), false),
+    };
+    let displayed_path = run_config.display_path(file_path);
+    if from_codeowners {
+        return match team_for_file_from_codeowners(run_config, file_path) {
+            Ok(Some(team)) => RunResult {
+                info_messages: vec![format!(
+                    "{}\t{}\t{}\tcodeowners",
+                    displayed_path,
+                    run_config.display_team_name(&team),
+                    run_config.team_format.render(&team.github_team)
+                )],
+                ..Default::default()
+            },
+            Ok(None) => for_file_line_result(&displayed_path, &[]),
+            Err(err) => RunResult::from_io_error(Error::Io(err.to_string()), false),
+        };
+    }
+
+    use crate::ownership::file_owner_resolver::find_file_owners;
+    match find_file_owners(&run_config.project_root, &config, Path::new(file_path)) {
+        Ok(file_owners) => for_file_line_result(&displayed_path, &super::format_file_owners(file_owners, run_config)),
+        Err(err) => RunResult::from_io_error(Error::Io(err), false),
+    }
+}
+
+// Like `for_file_line`, but takes the mode from `for_file_default_mode` in the config
+pub fn for_file_line_in_default_mode(run_config: &RunConfig, file_path: &str) -> RunResult {
+    match config_from_run_config(run_config) {
+        Ok(config) => for_file_line(run_config, file_path, config.for_file_default_mode == ForFileMode::Codeowners),
+        Err(err) => RunResult::from_io_error(Error::Io(err.to_string()), false),
+    }
+}
+
+// An owner's first source is its highest-priority one; a conflict joins the teams and fails like the text output does
+fn for_file_line_result(file_path: &str, file_owners: &[FileOwner]) -> RunResult {
+    match file_owners {
+        [] => RunResult {
+            info_messages: vec![format!("{}\tUnowned\tUnowned\tunowned", file_path)],
+            ..Default::default()
+        },
+        [owner] => RunResult {
+            info_messages: vec![format!(
+                "{}\t{}\t{}\t{}",
+                file_path,
+                owner.team.name,
+                owner.team.github_team,
+                owner.sources.first().map_or("unowned", |source| source.kind())
+            )],
+            ..Default::default()
+        },
+        many => RunResult {
+            validation_errors: vec![format!(
+                "{}\t{}\t{}\tmultiple",
+                file_path,
+                many.iter().map(|owner| owner.team.name.as_str()).collect::<Vec<_>>().join(","),
+                many.iter()
+                    .map(|owner| owner.team.github_team.as_str())
+                    .collect::<Vec<_>>()
+                    .join(",")
+            )],
+            ..Default::default()
+        },
+    }
+}
+
+// Lists every CODEOWNERS pattern matching a file in file order, marking the last one, which GitHub applies
+pub fn for_file_all_codeowners_patterns(run_config: &RunConfig, file_path: &str, json: bool) -> RunResult {
+    let config = match config_from_run_config(run_config) {
+        Ok(c) => c,
+        Err(err) => {
+            return RunResult::from_io_error(Error::Io(err.to_string()), json);
+        }
+    };
+    let codeowners_file_path = super::resolve_codeowners_file_path(run_config, &config);
+    let matches = match crate::ownership::codeowners_query::matching_codeowners_patterns(
+        &run_config.project_root,
+        &codeowners_file_path,
+        file_path,
+    ) {
+        Ok(matches) => matches,
+        Err(err) => return RunResult::from_io_error(Error::Io(err), json),
+    };
+
+    let info_message = if json {
+        match serde_json::to_string_pretty(&serde_json::json!({ "matches": matches })) {
+            Ok(json) => json,
+            Err(err) => return RunResult::from_io_error(Error::Io(err.to_string()), json),
+        }
+    } else if matches.is_empty() {
+        format!("No CODEOWNERS pattern matches {}", file_path)
+    } else {
+        let mut lines = vec![format!("CODEOWNERS patterns matching {}, in file order:", file_path)];
+        for (index, pattern) in matches.iter().enumerate() {
+            let applied = if index + 1 == matches.len() { " (applied by GitHub)" } else { "" };
+            lines.push(format!(
+                "{}: {} {}{}",
+                pattern.line_number,
+                pattern.glob,
+                pattern.owners.join(" "),
+                applied
+            ));
+        }
+        lines.join("\n")
+    };
+    RunResult {
+        info_messages: vec![info_message],
+        ..Default::default()
+    }
+}
+
+// Resolves the owner of a file as if `team_files` were added to the project, replacing any existing team of the same name
+pub fn for_file_with_team_files(run_config: &RunConfig, file_path: &str, team_files: &[PathBuf], json: bool) -> RunResult {
+    for_file_optimized(run_config, file_path, team_files, json)
+}
+
+// Explains how the owner of a file was resolved by listing every mapper's verdict in evaluation order
+pub fn for_file_trace(run_config: &RunConfig, file_path: &str) -> RunResult {
+    let config = match config_from_run_config(run_config) {
+        Ok(c) => c,
+        Err(err) => {
+            return RunResult::from_io_error(Error::Io(err.to_string()), false);
+        }
+    };
+
+    use crate::ownership::file_owner_resolver::trace_file_owners;
+    match trace_file_owners(&run_config.project_root, &config, std::path::Path::new(file_path)) {
+        Ok(trace) => RunResult {
+            info_messages: vec![trace.to_string()],
+            ..Default::default()
+        },
+        Err(err) => RunResult::from_io_error(Error::Io(err), false),
+    }
+}
+
+// Like `for_file` in derived mode, but a team whose strongest claim outranks the others' wins, and the claims it beat
+// are listed after it
+pub fn for_file_explained(run_config: &RunConfig, file_path: &str, json: bool) -> RunResult {
+    let config = match config_from_run_config(run_config) {
+        Ok(c) => c,
+        Err(err) => return RunResult::from_io_error(Error::Io(err.to_string()), json),
+    };
+
+    use crate::ownership::file_owner_resolver::find_file_owners;
+    match find_file_owners(&run_config.project_root, &config, Path::new(file_path)) {
+        Ok(owners) => RunResult::from_explained_owners(&super::format_file_owners(owners, run_config), json),
+        Err(err) => RunResult::from_io_error(Error::Io(err), json),
+    }
+}
+
+// Prints the JSON Schema of `for-file --json` output so tooling can validate against a known version
+pub fn for_file_schema() -> RunResult {
+    match serde_json::to_string_pretty(&super::for_file_json_schema()) {
+        Ok(schema) => RunResult {
+            info_messages: vec![schema],
+            ..Default::default()
+        },
+        Err(err) => RunResult::from_io_error(Error::Io(err.to_string()), false),
+    }
 }
 
 pub fn for_team(run_config: &RunConfig, team_name: &str) -> RunResult {
-    run(run_config, |runner| runner.for_team(team_name))
+    run(run_config, |runner| runner.for_team(team_name, false))
+}
+
+// Hands `on_file` each file `team_name` owns as soon as it resolves, without building the whole project
+pub fn stream_team_files(run_config: &RunConfig, team_name: &str, mut on_file: impl FnMut(&str)) -> RunResult {
+    let config = match config_from_run_config(run_config) {
+        Ok(c) => c,
+        Err(err) => return RunResult::from_io_error(Error::Io(err.to_string()), false),
+    };
+
+    use crate::ownership::file_owner_resolver;
+    let streamed = file_owner_resolver::stream_team_files(&run_config.project_root, &config, team_name, |file| {
+        on_file(&run_config.display_path(&crate::path_utils::to_slash_string_lossy(file)))
+    });
+    match streamed {
+        Ok(0) => RunResult {
+            info_messages: vec![format!("`{}` owns no files", team_name)],
+            ..Default::default()
+        },
+        Ok(_) => RunResult::default(),
+        Err(err) => RunResult::from_io_error(Error::Io(err), false),
+    }
+}
+
+// Same report as `for_team`, with each glob followed by the mapper source that produced it
+pub fn for_team_with_sources(run_config: &RunConfig, team_name: &str) -> RunResult {
+    run(run_config, |runner| runner.for_team(team_name, true))
 }
 
 pub fn validate(run_config: &RunConfig, file_paths: Vec<String>) -> RunResult {
     run(run_config, |runner| runner.validate(file_paths))
 }
 
+// Stops at the first error rather than collecting every one, for quicker feedback on large changesets
+pub fn validate_fail_fast(run_config: &RunConfig, file_paths: Vec<String>) -> RunResult {
+    run(run_config, |runner| runner.validate_fail_fast(file_paths))
+}
+
+pub fn validate_since_cache(run_config: &RunConfig) -> RunResult {
+    run(run_config, |runner| runner.validate_since_cache())
+}
+
+pub fn validate_json(run_config: &RunConfig) -> RunResult {
+    run(run_config, |runner| runner.validate_json())
+}
+
+pub fn validate_github_annotations(run_config: &RunConfig) -> RunResult {
+    run(run_config, |runner| runner.validate_github_annotations())
+}
+
+pub fn validate_sarif(run_config: &RunConfig) -> RunResult {
+    run(run_config, |runner| runner.validate_sarif())
+}
+
+pub fn validate_team(run_config: &RunConfig, team_name: &str) -> RunResult {
+    run(run_config, |runner| runner.validate_team(team_name, false))
+}
+
+pub fn validate_team_fail_fast(run_config: &RunConfig, team_name: &str) -> RunResult {
+    run(run_config, |runner| runner.validate_team(team_name, true))
+}
+
+pub fn validate_fix<F>(run_config: &RunConfig, confirm: F) -> RunResult
+where
+    F: FnMut(&super::SuggestedAnnotation) -> bool + Send,
+{
+    run(run_config, |runner| runner.validate_fix(confirm))
+}
+
 pub fn generate(run_config: &RunConfig, git_stage: bool) -> RunResult {
     run(run_config, |runner| runner.generate(git_stage))
 }
 
+// Generates a CODEOWNERS file with only the named sections (e.g. `directory`, `team-glob`), for debugging
+pub fn generate_sections(run_config: &RunConfig, sections: &[String]) -> RunResult {
+    run(run_config, |runner| runner.generate_sections(sections))
+}
+
+// Generates CODEOWNERS as the fewest lines giving every tracked file the same owner; `validate` will call it stale
+pub fn generate_minimized(run_config: &RunConfig, git_stage: bool) -> RunResult {
+    run(run_config, |runner| runner.generate_minimized(git_stage))
+}
+
+// Generates CODEOWNERS plus a JSON sidecar at `sidecar_path` (relative to the project root unless absolute)
+pub fn generate_with_sidecar(run_config: &RunConfig, sidecar_path: &Path, git_stage: bool) -> RunResult {
+    let sidecar_path = run_config.project_root.join(sidecar_path);
+    run(run_config, |runner| runner.generate_with_sidecar(&sidecar_path, git_stage))
+}
+
+// Writes required-review rules for branch protection to `path` (relative to the project root unless absolute)
+pub fn export_review_rules(run_config: &RunConfig, path: &Path) -> RunResult {
+    let path = run_config.project_root.join(path);
+    run(run_config, |runner| runner.export_review_rules(&path))
+}
+
+// Writes the ownership index as TSV to `path` (relative to the project root unless absolute)
+pub fn export_tsv(run_config: &RunConfig, path: &Path) -> RunResult {
+    let path = run_config.project_root.join(path);
+    run(run_config, |runner| runner.export_tsv(&path))
+}
+
+// Regenerates only the CODEOWNERS lines under `subtree`, walking just that part of the project
+pub fn generate_subtree(run_config: &RunConfig, subtree: &Path, git_stage: bool) -> RunResult {
+    let subtree = crate::path_utils::relative_to(&run_config.project_root, subtree).clean();
+    if subtree == Path::new(".") {
+        return generate(run_config, git_stage);
+    }
+    if subtree.starts_with("..") || !run_config.project_root.join(&subtree).is_dir() {
+        return RunResult::from_io_error(
+            Error::Io(format!("{} is not a directory inside the project", subtree.display())),
+            false,
+        );
+    }
+    super::run_with(run_config, Some(&subtree), |runner| runner.generate_subtree(&subtree, git_stage))
+}
+
+pub fn validate_consistent_annotations(run_config: &RunConfig) -> RunResult {
+    run(run_config, |runner| runner.validate_consistent_annotations())
+}
+
+pub fn validate_all_annotations(run_config: &RunConfig) -> RunResult {
+    run(run_config, |runner| runner.validate_all_annotations())
+}
+
 pub fn generate_and_validate(run_config: &RunConfig, file_paths: Vec<String>, git_stage: bool) -> RunResult {
     run(run_config, |runner| runner.generate_and_validate(file_paths, git_stage))
 }
 
+// Reports whether CODEOWNERS would change and validates against the generated content, writing nothing
+pub fn generate_and_validate_dry_run(run_config: &RunConfig, file_paths: Vec<String>) -> RunResult {
+    run(run_config, |runner| runner.generate_and_validate_dry_run(file_paths))
+}
+
+// Reports team globs that no tracked file matches and, with `checks.unused`, teams that own nothing. Only the
+// unused check builds the project
+pub fn lint_teams(run_config: &RunConfig, checks: LintChecks, json: bool) -> RunResult {
+    crate::team_lint::lint_teams(run_config, checks, json)
+}
+
+// Lists every team with its GitHub team and team file, sorted by name, without building the project
+pub fn list_teams(run_config: &RunConfig, json: bool) -> RunResult {
+    crate::team_lint::list_teams(run_config, json)
+}
+
+// Reports pass/fail for each piece of setup ownership depends on (config, teams, git, ...)
+pub fn doctor(run_config: &RunConfig) -> RunResult {
+    crate::doctor::doctor(run_config)
+}
+
+// Re-sorts the existing CODEOWNERS file in place (or prints it) without re-deriving its entries from the project
+pub fn normalize(run_config: &RunConfig, to_stdout: bool) -> RunResult {
+    let config = match config_from_run_config(run_config) {
+        Ok(c) => c,
+        Err(err) => {
+            return RunResult::from_io_error(Error::Io(err.to_string()), false);
+        }
+    };
+    let codeowners_file_path = super::resolve_codeowners_file_path(run_config, &config);
+    let codeowners_file = match std::fs::read_to_string(&codeowners_file_path) {
+        Ok(content) => content,
+        Err(err) => {
+            return RunResult::from_io_error(
+                Error::Io(format!("Can't read CODEOWNERS file {}: {}", codeowners_file_path.display(), err)),
+                false,
+            );
+        }
+    };
+
+    let normalized = crate::ownership::codeowners_file_parser::normalize(&codeowners_file);
+    if to_stdout {
+        return RunResult {
+            info_messages: vec![normalized.trim_end().to_owned()],
+            ..Default::default()
+        };
+    }
+    match std::fs::write(&codeowners_file_path, normalized) {
+        Ok(_) => RunResult::default(),
+        Err(err) => RunResult::from_io_error(Error::Io(err.to_string()), false),
+    }
+}
+
+pub fn warm_cache(run_config: &RunConfig) -> RunResult {
+    run(run_config, |runner| runner.warm_cache())
+}
+
 pub fn delete_cache(run_config: &RunConfig) -> RunResult {
     run(run_config, |runner| runner.delete_cache())
 }
 
-pub fn crosscheck_owners(run_config: &RunConfig) -> RunResult {
-    run(run_config, |runner| runner.crosscheck_owners())
+pub fn crosscheck_owners(run_config: &RunConfig, json: bool) -> RunResult {
+    run(run_config, |runner| runner.crosscheck_owners(json))
 }
 
 // Returns all owners for a file without creating a Runner (performance optimized)
@@ -44,6 +430,23 @@ pub fn owners_for_file(run_config: &RunConfig, file_path: &str) -> error_stack::
     Ok(owners)
 }
 
+// Returns all owners for each of `file_paths`, sharing directory-level lookups between files in the same directory
+pub fn owners_for_files(run_config: &RunConfig, file_paths: &[String]) -> error_stack::Result<HashMap<String, Vec<FileOwner>>, Error> {
+    let config = config_from_run_config(run_config)?;
+    use crate::ownership::file_owner_resolver::find_files_owners;
+    let owners = find_files_owners(&run_config.project_root, &config, file_paths).map_err(Error::Io)?;
+    Ok(file_paths.iter().cloned().zip(owners).collect())
+}
+
+// Returns human-readable reasons why a file is unowned, or nothing if some mapper owns it
+pub fn explain_unowned(run_config: &RunConfig, file_path: &str) -> error_stack::Result<Vec<String>, Error> {
+    let config = config_from_run_config(run_config)?;
+    use crate::ownership::file_owner_resolver;
+    let reasons =
+        file_owner_resolver::explain_unowned(&run_config.project_root, &config, std::path::Path::new(file_path)).map_err(Error::Io)?;
+    Ok(reasons)
+}
+
 // Returns the highest priority owner for a file. More to come here.
 pub fn file_owner_for_file(run_config: &RunConfig, file_path: &str) -> error_stack::Result<Option<FileOwner>, Error> {
     let owners = owners_for_file(run_config, file_path)?;
@@ -65,6 +468,7 @@ pub fn teams_for_files_from_codeowners(
     let res = crate::ownership::codeowners_query::teams_for_files_from_codeowners(
         &run_config.project_root,
         &codeowners_file_path,
+        &config.resolve_teams_root(&run_config.project_root),
         &config.team_file_glob,
         file_paths,
     )
@@ -72,6 +476,7 @@ pub fn teams_for_files_from_codeowners(
     Ok(res)
 }
 
+// Never touches `file_path` itself, so it also answers for files that were just deleted
 pub fn team_for_file_from_codeowners(run_config: &RunConfig, file_path: &str) -> error_stack::Result<Option<Team>, Error> {
     let result = teams_for_files_from_codeowners(run_config, &[file_path.to_string()])?;
     // Since we only passed one file, there should be exactly one result
@@ -79,8 +484,39 @@ pub fn team_for_file_from_codeowners(run_config: &RunConfig, file_path: &str) ->
     Ok(result.into_values().next().flatten())
 }
 
+// Like `team_for_file_from_codeowners`, but against the CODEOWNERS file as committed at `git_ref`
+pub fn team_for_file_from_codeowners_at(
+    run_config: &RunConfig,
+    file_path: &str,
+    git_ref: &str,
+) -> error_stack::Result<Option<Team>, Error> {
+    let config = config_from_run_config(run_config)?;
+    let codeowners_file_path = super::resolve_codeowners_file_path(run_config, &config);
+    let result = crate::ownership::codeowners_query::teams_for_files_from_codeowners_at(
+        &run_config.project_root,
+        &codeowners_file_path,
+        git_ref,
+        &config.resolve_teams_root(&run_config.project_root),
+        &config.team_file_glob,
+        &[file_path.to_string()],
+        config.git_timeout(),
+    )
+    .map_err(Error::Io)?;
+    Ok(result.into_values().next().flatten())
+}
+
+// `for_file --from-codeowners` as of `git_ref`, without checking it out
+pub fn for_file_at_ref(run_config: &RunConfig, file_path: &str, git_ref: &str, json: bool) -> RunResult {
+    codeowners_team_result(
+        run_config,
+        team_for_file_from_codeowners_at(run_config, file_path, git_ref),
+        format!("Owner inferred from codeowners file at {}", git_ref),
+        json,
+    )
+}
+
 // Fast path that avoids creating a full Runner for single file queries
-fn for_file_optimized(run_config: &RunConfig, file_path: &str, json: bool) -> RunResult {
+fn for_file_optimized(run_config: &RunConfig, file_path: &str, team_files: &[PathBuf], json: bool) -> RunResult {
     let config = match config_from_run_config(run_config) {
         Ok(c) => c,
         Err(err) => {
@@ -88,38 +524,55 @@ fn for_file_optimized(run_config: &RunConfig, file_path: &str, json: bool) -> Ru
         }
     };
 
-    use crate::ownership::file_owner_resolver::find_file_owners;
-    let file_owners = match find_file_owners(&run_config.project_root, &config, std::path::Path::new(file_path)) {
-        Ok(v) => v,
+    use crate::ownership::file_owner_resolver::BatchResolver;
+    let mut resolver = match BatchResolver::with_team_files(&run_config.project_root, &config, team_files) {
+        Ok(resolver) => resolver,
         Err(err) => {
             return RunResult::from_io_error(Error::Io(err), json);
         }
     };
+    let trace = resolver.trace(std::path::Path::new(file_path));
+    let file_owners = super::format_file_owners(trace.file_owners(&run_config.project_root), run_config);
 
-    match file_owners.as_slice() {
+    let mut result = match file_owners.as_slice() {
+        [] if !json => RunResult::from_unowned(&resolver.unowned_reasons(&trace)),
         [] => RunResult::from_file_owner(&crate::ownership::FileOwner::default(), json),
         [owner] => RunResult::from_file_owner(owner, json),
-        many => {
-            let mut error_messages = vec!["Error: file is owned by multiple teams!".to_string()];
-            for owner in many {
-                error_messages.push(format!("\n{}", owner));
-            }
-            RunResult::from_validation_errors(error_messages, json)
-        }
+        many => RunResult::from_multiple_owners(many, json),
+    };
+    if !json {
+        result
+            .info_messages
+            .extend(trace.notes().into_iter().map(|note| format!("Note: {}", note)));
     }
+    result
 }
 
 fn for_file_codeowners_only_fast(run_config: &RunConfig, file_path: &str, json: bool) -> RunResult {
-    match team_for_file_from_codeowners(run_config, file_path) {
+    codeowners_team_result(
+        run_config,
+        team_for_file_from_codeowners(run_config, file_path),
+        "Owner inferred from codeowners file".to_string(),
+        json,
+    )
+}
+
+fn codeowners_team_result(
+    run_config: &RunConfig,
+    team: error_stack::Result<Option<Team>, Error>,
+    description: String,
+    json: bool,
+) -> RunResult {
+    match team {
         Ok(Some(team)) => {
-            let team_yml = crate::path_utils::relative_to(&run_config.project_root, team.path.as_path())
-                .to_string_lossy()
-                .to_string();
+            let team_yml =
+                run_config.display_path(&crate::path_utils::relative_to(&run_config.project_root, team.path.as_path()).to_string_lossy());
             let result = ForFileResult {
-                team_name: team.name.clone(),
-                github_team: team.github_team.clone(),
+                team_name: run_config.display_team_name(&team),
+                github_team: run_config.team_format.render(&team.github_team),
                 team_yml,
-                description: vec!["Owner inferred from codeowners file".to_string()],
+                description: vec![description],
+                schema_version: super::FOR_FILE_SCHEMA_VERSION,
             };
             if json {
                 RunResult::json_info(result)
@@ -4,20 +4,85 @@ use std::path::PathBuf;
 use error_stack::Context;
 use serde::{Deserialize, Serialize};
 
+use crate::project::Team;
+
 #[derive(Debug, Default, Serialize, Deserialize)]
 pub struct RunResult {
     pub validation_errors: Vec<String>,
     pub io_errors: Vec<String>,
     pub info_messages: Vec<String>,
+    /// Non-fatal problems, such as config misconfigurations, printed to stderr without failing the run
+    #[serde(default)]
+    pub warnings: Vec<String>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct RunConfig {
     pub project_root: PathBuf,
     pub codeowners_file_path: Option<PathBuf>,
     pub config_path: PathBuf,
     pub no_cache: bool,
     pub executable_name: Option<String>,
+    pub max_depth: Option<usize>,
+    /// Caps the threads used by the project walk and rayon; `None` defers to `RAYON_NUM_THREADS` or the core count
+    pub threads: Option<usize>,
+    /// `key=value` pairs applied on top of the config file, e.g. `vendored_gems_path=vendor/gems`
+    pub config_overrides: Vec<String>,
+    /// Globs added to `unowned_globs` for this run only, e.g. from `--exclude`
+    pub exclude_globs: Vec<String>,
+    /// How `for-file` prints GitHub team handles
+    pub team_format: TeamFormat,
+    /// Which team field `for-file` prints as the team's name
+    pub team_name_from: TeamNameSource,
+    /// Whether `for-file` prints paths (team files, and the file itself with `--format line`) as absolute
+    /// rather than relative to `project_root`
+    pub absolute_paths: bool,
+    /// NDJSON file that `generate` and `validate` append each file's resolved owner to, e.g. from `--audit-log`
+    pub audit_log: Option<PathBuf>,
+}
+
+impl RunConfig {
+    /// `path` (relative to `project_root`) as `for-file` should print it. An empty path, as for an unowned file's team file, stays empty.
+    pub(crate) fn display_path(&self, path: &str) -> String {
+        if self.absolute_paths && !path.is_empty() {
+            self.project_root.join(path).to_string_lossy().into_owned()
+        } else {
+            path.to_owned()
+        }
+    }
+
+    /// `team`'s name as `for-file` should print it, with a GitHub handle rendered like `github_team`.
+    pub(crate) fn display_team_name(&self, team: &Team) -> String {
+        match self.team_name_from {
+            TeamNameSource::Name => team.name.clone(),
+            TeamNameSource::Github => self.team_format.render(&team.github_team),
+        }
+    }
+}
+
+/// How GitHub team handles are printed: as written in the team file (`@org/team`) or without the leading `@`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TeamFormat {
+    #[default]
+    At,
+    Plain,
+}
+
+impl TeamFormat {
+    pub fn render(self, github_team: &str) -> String {
+        match self {
+            TeamFormat::At => github_team.to_owned(),
+            TeamFormat::Plain => github_team.strip_prefix('@').unwrap_or(github_team).to_owned(),
+        }
+    }
+}
+
+/// Where a team's displayed name comes from: the team file's `name` or its GitHub team handle.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TeamNameSource {
+    #[default]
+    Name,
+    Github,
 }
 
 #[derive(Debug, Serialize)]
@@ -0,0 +1,325 @@
+use std::path::{Path, PathBuf};
+
+use fast_glob::glob_match;
+use serde::Serialize;
+
+use crate::{
+    config::Config,
+    ownership::{file_owner_resolver::load_teams, mapper::globs_overlap},
+    path_utils::{glob_matches, relative_to, to_slash_string_lossy},
+    runner::{RunConfig, RunResult, Runner, config_from_run_config},
+    tracked_files,
+};
+
+/// A team glob that matches no tracked file.
+#[derive(Debug, Serialize, PartialEq)]
+pub struct OrphanedGlob {
+    pub team: String,
+    pub team_yml: String,
+    /// `owned_globs` or `subtracted_globs`
+    pub key: &'static str,
+    pub glob: String,
+}
+
+/// A team that owns no file and no glob in the generated CODEOWNERS, apart from its own team file.
+#[derive(Debug, Serialize, PartialEq)]
+pub struct UnusedTeam {
+    pub team: String,
+    pub team_yml: String,
+}
+
+/// A `subtracted_globs` entry that either removes a whole owned glob or overlaps none of them.
+#[derive(Debug, Serialize, PartialEq)]
+pub struct SubtractedGlobIssue {
+    pub team: String,
+    pub team_yml: String,
+    pub glob: String,
+    /// The owned glob this entry subtracts entirely, or `None` when it overlaps no owned glob and does nothing
+    pub cancels: Option<String>,
+}
+
+impl std::fmt::Display for SubtractedGlobIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.cancels {
+            Some(owned_glob) => write!(
+                f,
+                "- {} ({}): subtracted_globs `{}` cancels owned_globs `{}` entirely",
+                self.team_yml, self.team, self.glob, owned_glob
+            ),
+            None => write!(
+                f,
+                "- {} ({}): subtracted_globs `{}` overlaps no owned_globs entry",
+                self.team_yml, self.team, self.glob
+            ),
+        }
+    }
+}
+
+/// A team as listed by `list-teams`.
+#[derive(Debug, Serialize, PartialEq)]
+pub struct TeamSummary {
+    pub name: String,
+    pub github_team: String,
+    pub team_yml: String,
+    pub owned_globs: usize,
+    pub owned_gems: usize,
+}
+
+/// Which `lint-teams` checks to run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LintChecks {
+    pub orphaned_globs: bool,
+    pub unused: bool,
+    pub subtracted_globs: bool,
+}
+
+impl LintChecks {
+    pub fn all() -> Self {
+        Self {
+            orphaned_globs: true,
+            unused: true,
+            subtracted_globs: true,
+        }
+    }
+}
+
+/// Checks that weren't run are left out, rather than reported as finding nothing.
+#[derive(Serialize)]
+struct LintReport<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    orphaned_globs: Option<&'a [OrphanedGlob]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    unused_teams: Option<&'a [UnusedTeam]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    subtracted_globs: Option<&'a [SubtractedGlobIssue]>,
+}
+
+pub fn list_teams(run_config: &RunConfig, json: bool) -> RunResult {
+    let teams = match team_summaries(run_config) {
+        Ok(teams) => teams,
+        Err(err) => {
+            return RunResult {
+                io_errors: vec![err],
+                ..Default::default()
+            };
+        }
+    };
+
+    let output = if json {
+        match serde_json::to_string_pretty(&teams) {
+            Ok(json) => json,
+            Err(err) => {
+                return RunResult {
+                    io_errors: vec![err.to_string()],
+                    ..Default::default()
+                };
+            }
+        }
+    } else {
+        teams
+            .iter()
+            .map(|team| format!("{}\t{}\t{}", team.name, team.github_team, team.team_yml))
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+    RunResult {
+        info_messages: vec![output],
+        ..Default::default()
+    }
+}
+
+/// Glob and gem counts include what a team inherits from its parent, and leave out what it hands to its sub-teams.
+fn team_summaries(run_config: &RunConfig) -> Result<Vec<TeamSummary>, String> {
+    let config = config_from_run_config(run_config).map_err(|e| e.to_string())?;
+    let project_root = &run_config.project_root;
+    let mut teams = load_teams(project_root, &config)?;
+    teams.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Ok(teams
+        .into_iter()
+        .map(|team| TeamSummary {
+            team_yml: to_slash_string_lossy(relative_to(project_root, &team.path)),
+            owned_globs: team.owned_globs.len(),
+            owned_gems: team.owned_gems.len(),
+            name: team.name,
+            github_team: team.github_team,
+        })
+        .collect())
+}
+
+pub fn lint_teams(run_config: &RunConfig, checks: LintChecks, json: bool) -> RunResult {
+    let findings = || -> Result<_, String> {
+        Ok((
+            checks.orphaned_globs.then(|| find_orphaned_globs(run_config)).transpose()?,
+            checks.unused.then(|| find_unused_teams(run_config)).transpose()?,
+            checks
+                .subtracted_globs
+                .then(|| find_subtracted_glob_issues(run_config))
+                .transpose()?,
+        ))
+    };
+    let (orphaned_globs, unused_teams, subtracted_globs) = match findings() {
+        Ok(findings) => findings,
+        Err(err) => {
+            return RunResult {
+                io_errors: vec![err],
+                ..Default::default()
+            };
+        }
+    };
+
+    // The JSON report is the whole output, so it exits successfully and leaves judging the findings to the consumer
+    if json {
+        return match serde_json::to_string_pretty(&LintReport {
+            orphaned_globs: orphaned_globs.as_deref(),
+            unused_teams: unused_teams.as_deref(),
+            subtracted_globs: subtracted_globs.as_deref(),
+        }) {
+            Ok(json) => RunResult {
+                info_messages: vec![json],
+                ..Default::default()
+            },
+            Err(err) => RunResult {
+                io_errors: vec![err.to_string()],
+                ..Default::default()
+            },
+        };
+    }
+
+    let mut result = RunResult::default();
+    match orphaned_globs.as_deref() {
+        Some([]) => result
+            .info_messages
+            .push("Every team glob matches at least one tracked file.".to_owned()),
+        Some(orphaned_globs) => {
+            result
+                .validation_errors
+                .push("Found team globs that match no tracked files:".to_owned());
+            result.validation_errors.extend(
+                orphaned_globs
+                    .iter()
+                    .map(|orphan| format!("- {} ({}): {} `{}`", orphan.team_yml, orphan.team, orphan.key, orphan.glob)),
+            );
+        }
+        None => {}
+    }
+    match unused_teams.as_deref() {
+        Some([]) => result.info_messages.push("Every team owns at least one file or glob.".to_owned()),
+        Some(unused_teams) => {
+            result
+                .validation_errors
+                .push("Found teams that own nothing besides their team file:".to_owned());
+            result
+                .validation_errors
+                .extend(unused_teams.iter().map(|unused| format!("- {} ({})", unused.team_yml, unused.team)));
+        }
+        None => {}
+    }
+    match subtracted_globs.as_deref() {
+        Some([]) => result
+            .info_messages
+            .push("Every subtracted glob removes part of an owned glob.".to_owned()),
+        Some(issues) => {
+            result
+                .validation_errors
+                .push("Found subtracted_globs that cancel an owned glob or overlap none:".to_owned());
+            result.validation_errors.extend(issues.iter().map(|issue| issue.to_string()));
+        }
+        None => {}
+    }
+    result
+}
+
+/// Uses the overlap test the team glob mapper applies, so an entry flagged as overlapping nothing is one the
+/// generated CODEOWNERS ignores too.
+fn find_subtracted_glob_issues(run_config: &RunConfig) -> Result<Vec<SubtractedGlobIssue>, String> {
+    let config = config_from_run_config(run_config).map_err(|e| e.to_string())?;
+    let project_root = &run_config.project_root;
+    let mut teams = load_teams(project_root, &config)?;
+    teams.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let mut issues = Vec::new();
+    for team in &teams {
+        let team_yml = to_slash_string_lossy(relative_to(project_root, &team.path));
+        for subtracted_glob in &team.subtracted_globs {
+            // A subtracted glob that matches the owned glob itself, read as a path, covers everything it owns
+            let cancels = team
+                .owned_globs
+                .iter()
+                .find(|owned_glob| glob_match(subtracted_glob, owned_glob))
+                .cloned();
+            if cancels.is_some() || !team.owned_globs.iter().any(|owned_glob| globs_overlap(subtracted_glob, owned_glob)) {
+                issues.push(SubtractedGlobIssue {
+                    team: team.name.clone(),
+                    team_yml: team_yml.clone(),
+                    glob: subtracted_glob.clone(),
+                    cancels,
+                });
+            }
+        }
+    }
+    Ok(issues)
+}
+
+fn find_unused_teams(run_config: &RunConfig) -> Result<Vec<UnusedTeam>, String> {
+    let runner = Runner::new(run_config).map_err(|e| e.to_string())?;
+    Ok(runner
+        .unused_teams()
+        .into_iter()
+        .map(|team| UnusedTeam {
+            team_yml: to_slash_string_lossy(relative_to(&run_config.project_root, &team.path)),
+            team: team.name,
+        })
+        .collect())
+}
+
+fn find_orphaned_globs(run_config: &RunConfig) -> Result<Vec<OrphanedGlob>, String> {
+    let config = config_from_run_config(run_config).map_err(|e| e.to_string())?;
+    let project_root = &run_config.project_root;
+    let mut teams = load_teams(project_root, &config)?;
+    teams.sort_by(|a, b| a.name.cmp(&b.name));
+    let files: Vec<String> = tracked_file_paths(project_root, &config)?
+        .iter()
+        .map(|path| to_slash_string_lossy(relative_to(project_root, path)))
+        .collect();
+
+    let matches_nothing = |glob: &str| !files.iter().any(|file| glob_matches(glob, file, config.case_insensitive_paths));
+    let mut orphaned_globs = Vec::new();
+    for team in &teams {
+        let team_yml = to_slash_string_lossy(relative_to(project_root, &team.path));
+        let globs = team
+            .owned_globs
+            .iter()
+            .map(|glob| ("owned_globs", glob))
+            .chain(team.subtracted_globs.iter().map(|glob| ("subtracted_globs", glob)));
+        for (key, glob) in globs {
+            if matches_nothing(glob) {
+                orphaned_globs.push(OrphanedGlob {
+                    team: team.name.clone(),
+                    team_yml: team_yml.clone(),
+                    key,
+                    glob: glob.clone(),
+                });
+            }
+        }
+    }
+    Ok(orphaned_globs)
+}
+
+/// Files `git ls-files` reports, or every file the ignore rules allow when the project isn't a git repository.
+pub(crate) fn tracked_file_paths(project_root: &Path, config: &Config) -> Result<Vec<PathBuf>, String> {
+    if let Some(tracked_files) =
+        tracked_files::find_tracked_files(project_root, config.git_timeout(), config.include_submodules).map_err(|e| e.to_string())?
+    {
+        return Ok(tracked_files.into_keys().collect());
+    }
+
+    Ok(ignore::WalkBuilder::new(project_root)
+        .hidden(false)
+        .filter_entry(|entry| entry.file_name() != ".git")
+        .build()
+        .flatten()
+        .filter(|entry| entry.file_type().is_some_and(|ft| ft.is_file()))
+        .map(|entry| entry.into_path())
+        .collect())
+}
@@ -1,38 +1,98 @@
 use std::{
     collections::HashMap,
     path::{Path, PathBuf},
-    process::Command,
+    time::Duration,
 };
 
-pub(crate) fn find_tracked_files(base_path: &Path) -> Option<HashMap<PathBuf, bool>> {
-    let output = Command::new("git")
-        .args(["ls-files", "-z", "--", "."])
-        .current_dir(base_path)
-        .output()
-        .ok()?;
+use crate::git::{GitError, run_git};
+
+/// Files tracked by git below `base_path`, or `None` when it isn't inside a git repository or git isn't installed.
+/// With `recurse_submodules`, files tracked by submodules are listed too.
+pub(crate) fn find_tracked_files(
+    base_path: &Path,
+    timeout: Duration,
+    recurse_submodules: bool,
+) -> Result<Option<HashMap<PathBuf, bool>>, GitError> {
+    let args: &[&str] = if recurse_submodules {
+        &["ls-files", "-z", "--recurse-submodules", "--", "."]
+    } else {
+        &["ls-files", "-z", "--", "."]
+    };
+    let output = match run_git(args, base_path, timeout) {
+        Ok(output) => output,
+        Err(GitError::NotFound) => {
+            tracing::warn!("{}; untracked files will not be skipped", GitError::NotFound);
+            return Ok(None);
+        }
+        Err(e) => return Err(e),
+    };
 
     if !output.status.success() {
-        return None;
+        return Ok(None);
     }
 
-    let results: HashMap<PathBuf, bool> = output
+    let results: Option<HashMap<PathBuf, bool>> = output
         .stdout
         .split(|&b| b == b'\0')
         .filter(|chunk| !chunk.is_empty())
         .map(|rel| std::str::from_utf8(rel).ok().map(|s| (base_path.join(s), true)))
-        .collect::<Option<HashMap<PathBuf, bool>>>()?;
+        .collect::<Option<HashMap<PathBuf, bool>>>();
+
+    Ok(results)
+}
 
-    Some(results)
+/// Working trees of the submodules declared in the repository's `.gitmodules`, found by searching from
+/// `base_path` up to the repository root. Paths are absolute; a missing or unreadable `.gitmodules` means none.
+pub(crate) fn find_submodule_roots(base_path: &Path) -> Vec<PathBuf> {
+    for dir in base_path.ancestors() {
+        if let Ok(gitmodules) = std::fs::read_to_string(dir.join(".gitmodules")) {
+            return gitmodules
+                .lines()
+                .filter_map(|line| {
+                    let (key, value) = line.split_once('=')?;
+                    (key.trim() == "path").then(|| dir.join(value.trim()))
+                })
+                .collect();
+        }
+        if dir.join(".git").exists() {
+            break;
+        }
+    }
+    vec![]
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    const TIMEOUT: Duration = Duration::from_secs(30);
+
+    #[test]
+    fn test_find_submodule_roots() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let backend_dir = tmp_dir.path().join("backend");
+        std::fs::create_dir_all(backend_dir.join(".git")).unwrap();
+        assert!(find_submodule_roots(&backend_dir).is_empty());
+
+        // `.gitmodules` above the repository root belongs to another repository
+        std::fs::write(tmp_dir.path().join(".gitmodules"), "[submodule \"x\"]\n\tpath = x\n").unwrap();
+        assert!(find_submodule_roots(&backend_dir).is_empty());
+
+        std::fs::write(
+            backend_dir.join(".gitmodules"),
+            "[submodule \"vendor/lib\"]\n\tpath = vendor/lib\n\turl = https://example.com/lib.git\n[submodule \"docs\"]\n\tpath=docs\n",
+        )
+        .unwrap();
+        assert_eq!(
+            find_submodule_roots(&backend_dir.join("app")),
+            vec![backend_dir.join("vendor/lib"), backend_dir.join("docs")]
+        );
+    }
+
     #[test]
     fn test_untracked_files() {
         let tmp_dir = tempfile::tempdir().unwrap();
-        assert!(find_tracked_files(tmp_dir.path()).is_none());
+        assert!(find_tracked_files(tmp_dir.path(), TIMEOUT, false).unwrap().is_none());
 
         std::process::Command::new("git")
             .arg("init")
@@ -41,7 +101,7 @@ mod tests {
             .expect("failed to run git init");
 
         std::fs::write(tmp_dir.path().join("test.txt"), "test").unwrap();
-        let tracked = find_tracked_files(tmp_dir.path()).unwrap();
+        let tracked = find_tracked_files(tmp_dir.path(), TIMEOUT, false).unwrap().unwrap();
         assert!(tracked.is_empty());
 
         std::process::Command::new("git")
@@ -51,7 +111,7 @@ mod tests {
             .output()
             .expect("failed to add test.txt");
 
-        let tracked = find_tracked_files(tmp_dir.path()).unwrap();
+        let tracked = find_tracked_files(tmp_dir.path(), TIMEOUT, false).unwrap().unwrap();
         assert!(tracked.len() == 1);
         assert!(tracked.get(&tmp_dir.path().join("test.txt")).unwrap());
     }
@@ -78,7 +138,7 @@ mod tests {
             .output()
             .expect("failed to add tracked files");
 
-        let tracked = find_tracked_files(&backend_dir).unwrap();
+        let tracked = find_tracked_files(&backend_dir, TIMEOUT, false).unwrap().unwrap();
         assert_eq!(tracked.len(), 1);
         assert!(tracked.get(&tracked_file).unwrap());
         assert!(!tracked.contains_key(&backend_dir.join("backend/app/models/foo.rb")));
@@ -0,0 +1,55 @@
+use std::path::Path;
+
+use codeowners::runner::{self, RunConfig};
+
+mod common;
+use common::{assert_no_run_errors, build_run_config, write_file, write_team};
+
+fn build_project(temp_dir: &Path, annotated_file: &str, case_insensitive_paths: bool) -> RunConfig {
+    write_file(
+        temp_dir,
+        "config/code_ownership.yml",
+        format!(
+            "---\nowned_globs:\n  - \"{{app,lib}}/**/*.rb\"\nteam_file_glob:\n  - config/teams/**/*.yml\nannotation_forbidden_globs:\n  - \"lib/generated/**/*\"\ncase_insensitive_paths: {}\n",
+            case_insensitive_paths
+        ),
+    );
+    write_team(temp_dir, "Foo", "");
+    write_file(temp_dir, annotated_file, "# @team Foo\nclass Client; end\n");
+
+    build_run_config(temp_dir, ".github/CODEOWNERS")
+}
+
+#[test]
+fn test_annotation_outside_forbidden_globs_is_valid() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let run_config = build_project(temp_dir.path(), "app/client.rb", false);
+
+    assert_no_run_errors(&runner::generate_and_validate(&run_config, vec![], false));
+}
+
+#[test]
+fn test_annotation_under_forbidden_glob_fails_validation() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let run_config = build_project(temp_dir.path(), "lib/generated/client.rb", false);
+
+    let result = runner::generate_and_validate(&run_config, vec![], false);
+    assert_eq!(result.validation_errors.len(), 1, "{:?}", result.validation_errors);
+    let error = &result.validation_errors[0];
+    assert!(error.contains("Found annotations under `annotation_forbidden_globs`"), "{}", error);
+    assert!(error.contains("- lib/generated/client.rb is annotated with 'Foo'"), "{}", error);
+}
+
+#[test]
+fn test_forbidden_globs_honor_case_insensitive_paths() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let run_config = build_project(temp_dir.path(), "Lib/Generated/client.rb", true);
+
+    let result = runner::generate_and_validate(&run_config, vec![], false);
+    assert_eq!(result.validation_errors.len(), 1, "{:?}", result.validation_errors);
+    assert!(
+        result.validation_errors[0].contains("- Lib/Generated/client.rb is annotated with 'Foo'"),
+        "{}",
+        result.validation_errors[0]
+    );
+}
@@ -0,0 +1,80 @@
+use std::path::Path;
+
+use codeowners::runner::{self, RunConfig};
+
+mod common;
+use common::{assert_no_run_errors, build_run_config, write_file};
+
+fn build_project(temp_dir: &Path, extra_config: &str) -> RunConfig {
+    write_file(
+        temp_dir,
+        "config/code_ownership.yml",
+        format!("---\nowned_globs:\n  - \"app/**/*.rb\"\n{}", extra_config),
+    );
+    write_file(
+        temp_dir,
+        "config/teams/payroll.yml",
+        "name: Payroll\ngithub:\n  team: \"@PayrollTeam\"\n  members:\n    - user\n",
+    );
+    write_file(
+        temp_dir,
+        "app/paycheck.rb",
+        "# frozen_string_literal: true\n# @team Payroll\n\nclass Paycheck; end\n",
+    );
+    write_file(
+        temp_dir,
+        "app/deep.rb",
+        "# frozen_string_literal: true\n\nclass Deep\n  def call; end\nend\n# @team Payroll\n",
+    );
+    write_file(
+        temp_dir,
+        "app/payroll_helper.rb",
+        "# frozen_string_literal: true\n# teammates use this helper to compute payroll\n# @team Payroll\n",
+    );
+
+    build_run_config(temp_dir, ".github/CODEOWNERS")
+}
+
+fn owner_of(run_config: &RunConfig, file: &str) -> String {
+    let result = runner::for_file(run_config, file, false, false);
+    assert_no_run_errors(&result);
+    result.info_messages.join("\n")
+}
+
+#[test]
+fn test_annotation_below_magic_comment() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let run_config = build_project(temp_dir.path(), "");
+
+    assert_no_run_errors(&runner::generate(&run_config, false));
+    let codeowners = std::fs::read_to_string(temp_dir.path().join(".github/CODEOWNERS")).unwrap();
+    assert!(codeowners.contains("/app/paycheck.rb @PayrollTeam"), "{}", codeowners);
+    assert!(!codeowners.contains("/app/deep.rb"), "{}", codeowners);
+
+    let output = owner_of(&run_config, "app/paycheck.rb");
+    assert!(output.starts_with("Team: Payroll"), "{}", output);
+    assert!(output.contains("Owner annotation at the top of the file"), "{}", output);
+    assert!(owner_of(&run_config, "app/deep.rb").starts_with("Team: Unowned"));
+}
+
+#[test]
+fn test_prose_comments_in_the_header_are_not_annotations() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let run_config = build_project(temp_dir.path(), "");
+
+    assert_no_run_errors(&runner::generate(&run_config, false));
+    let codeowners = std::fs::read_to_string(temp_dir.path().join(".github/CODEOWNERS")).unwrap();
+    assert!(codeowners.contains("/app/payroll_helper.rb @PayrollTeam"), "{}", codeowners);
+    assert!(owner_of(&run_config, "app/payroll_helper.rb").starts_with("Team: Payroll"));
+}
+
+#[test]
+fn test_annotation_header_lines_limits_the_scan() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let run_config = build_project(temp_dir.path(), "annotation_header_lines: 1\n");
+
+    assert_no_run_errors(&runner::generate(&run_config, false));
+    let codeowners = std::fs::read_to_string(temp_dir.path().join(".github/CODEOWNERS")).unwrap();
+    assert!(!codeowners.contains("/app/paycheck.rb"), "{}", codeowners);
+    assert!(owner_of(&run_config, "app/paycheck.rb").starts_with("Team: Unowned"));
+}
@@ -0,0 +1,80 @@
+use std::path::Path;
+
+use codeowners::runner::{self, RunConfig};
+
+mod common;
+use common::{assert_no_run_errors, build_run_config, write_file};
+
+fn build_project(temp_dir: &Path, extra_config: &str) -> RunConfig {
+    write_file(
+        temp_dir,
+        "config/code_ownership.yml",
+        format!("---\nowned_globs:\n  - \"app/**/*.{{rb,ts}}\"\n{}", extra_config),
+    );
+    write_file(
+        temp_dir,
+        "config/teams/payroll.yml",
+        "name: Payroll\ngithub:\n  team: \"@PayrollTeam\"\n  members:\n    - user\n",
+    );
+    write_file(temp_dir, "app/paycheck.rb", "# @squad Payroll\nclass Paycheck; end\n");
+    write_file(temp_dir, "app/payslip.ts", "// @team Payroll\nexport class Payslip {}\n");
+
+    build_run_config(temp_dir, ".github/CODEOWNERS")
+}
+
+fn owner_of(run_config: &RunConfig, file: &str) -> String {
+    let result = runner::for_file(run_config, file, false, false);
+    assert_no_run_errors(&result);
+    result.info_messages.join("\n")
+}
+
+#[test]
+fn test_annotation_keywords_recognize_custom_words() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let run_config = build_project(temp_dir.path(), "annotation_keywords:\n  - team\n  - squad\n");
+
+    assert_no_run_errors(&runner::generate(&run_config, false));
+    let codeowners = std::fs::read_to_string(temp_dir.path().join(".github/CODEOWNERS")).unwrap();
+    assert!(codeowners.contains("/app/paycheck.rb @PayrollTeam"), "{}", codeowners);
+    assert!(codeowners.contains("/app/payslip.ts @PayrollTeam"), "{}", codeowners);
+
+    assert!(owner_of(&run_config, "app/paycheck.rb").starts_with("Team: Payroll"));
+    assert!(owner_of(&run_config, "app/payslip.ts").starts_with("Team: Payroll"));
+}
+
+#[test]
+fn test_annotation_keywords_default_to_team() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let run_config = build_project(temp_dir.path(), "");
+
+    assert_no_run_errors(&runner::generate(&run_config, false));
+    let codeowners = std::fs::read_to_string(temp_dir.path().join(".github/CODEOWNERS")).unwrap();
+    assert!(!codeowners.contains("/app/paycheck.rb"), "{}", codeowners);
+    assert!(codeowners.contains("/app/payslip.ts @PayrollTeam"), "{}", codeowners);
+
+    assert!(owner_of(&run_config, "app/paycheck.rb").starts_with("Team: Unowned"));
+}
+
+#[test]
+fn test_github_annotations_point_at_custom_keyword_annotations() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let run_config = build_project(
+        temp_dir.path(),
+        "annotation_keywords:\n  - team\n  - squad\nannotation_forbidden_globs:\n  - \"app/**\"\n",
+    );
+    write_file(
+        temp_dir.path(),
+        "app/paycheck.rb",
+        "# frozen_string_literal: true\n# @squad Payroll\nclass Paycheck; end\n",
+    );
+
+    let result = runner::validate_github_annotations(&run_config);
+    assert!(
+        result
+            .validation_errors
+            .iter()
+            .any(|error| error.starts_with("::error file=app/paycheck.rb,line=2::")),
+        "{:?}",
+        result.validation_errors
+    );
+}
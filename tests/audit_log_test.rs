@@ -0,0 +1,79 @@
+use std::path::Path;
+
+use codeowners::runner::{self, RunConfig};
+use tempfile::TempDir;
+
+mod common;
+use common::{assert_no_run_errors, build_run_config, git_add_all_files, setup_fixture_repo};
+
+fn build_project() -> (TempDir, RunConfig) {
+    let temp_dir = setup_fixture_repo(Path::new("tests/fixtures/audit_log_project"));
+    git_add_all_files(temp_dir.path());
+    let run_config = RunConfig {
+        audit_log: Some("tmp/audit.ndjson".into()),
+        ..build_run_config(temp_dir.path(), ".github/CODEOWNERS")
+    };
+    (temp_dir, run_config)
+}
+
+fn audit_records(temp_dir: &Path) -> Vec<serde_json::Value> {
+    std::fs::read_to_string(temp_dir.join("tmp/audit.ndjson"))
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect()
+}
+
+// Records are written as files resolve, so compare them by path
+fn records_by_path(records: &[serde_json::Value]) -> Vec<(String, serde_json::Value, serde_json::Value)> {
+    let mut records: Vec<_> = records
+        .iter()
+        .map(|record| {
+            assert!(record["timestamp"].as_u64().unwrap() > 0, "{}", record);
+            (
+                record["path"].as_str().unwrap().to_owned(),
+                record["team"].clone(),
+                record["source"].clone(),
+            )
+        })
+        .collect();
+    records.sort_by(|a, b| a.0.cmp(&b.0));
+    records
+}
+
+#[test]
+fn test_generate_records_every_file() {
+    let (temp_dir, run_config) = build_project();
+
+    assert_no_run_errors(&runner::generate(&run_config, false));
+    assert_eq!(
+        records_by_path(&audit_records(temp_dir.path())),
+        vec![
+            ("app/models/charge.rb".to_owned(), "Payments".into(), "annotation".into()),
+            ("app/models/orphan.rb".to_owned(), serde_json::Value::Null, serde_json::Value::Null),
+        ]
+    );
+}
+
+#[test]
+fn test_validate_appends_to_the_log() {
+    let (temp_dir, run_config) = build_project();
+
+    assert_no_run_errors(&runner::generate(&run_config, false));
+    let result = runner::validate(&run_config, vec![]);
+    assert!(result.io_errors.is_empty(), "{:?}", result.io_errors);
+    assert_eq!(result.validation_errors.len(), 1, "the orphan is unowned");
+
+    let records = records_by_path(&audit_records(temp_dir.path()));
+    assert_eq!(records.len(), 4, "{:?}", records);
+    assert_eq!(records[0], records[1]);
+    assert_eq!(records[2], records[3]);
+}
+
+#[test]
+fn test_generate_and_validate_records_each_file_once() {
+    let (temp_dir, run_config) = build_project();
+
+    runner::generate_and_validate(&run_config, vec![], false);
+    assert_eq!(audit_records(temp_dir.path()).len(), 2);
+}
@@ -0,0 +1,30 @@
+use std::path::Path;
+
+use codeowners::runner::{self, RunConfig};
+use tempfile::TempDir;
+
+mod common;
+use common::{assert_no_run_errors, build_run_config, git_add_all_files, setup_fixture_repo};
+
+fn build_project() -> (TempDir, RunConfig) {
+    let temp_dir = setup_fixture_repo(Path::new("tests/fixtures/binary_files_project"));
+    git_add_all_files(temp_dir.path());
+    let run_config = build_run_config(temp_dir.path(), ".github/CODEOWNERS");
+    (temp_dir, run_config)
+}
+
+#[test]
+fn test_binary_file_is_owned_by_its_package() {
+    let (temp_dir, run_config) = build_project();
+
+    assert_no_run_errors(&runner::generate_and_validate(&run_config, vec![], false));
+    let codeowners = std::fs::read_to_string(temp_dir.path().join(".github/CODEOWNERS")).unwrap();
+    assert!(!codeowners.contains("/packs/foo/fixture.rb"), "{}", codeowners);
+    assert!(codeowners.contains("/packs/shared/latin1.rb @Bar"), "{}", codeowners);
+
+    // The single-file resolver agrees with the full build
+    let fixture_owner = runner::file_owner_for_file(&run_config, "packs/foo/fixture.rb").unwrap().unwrap();
+    assert_eq!(fixture_owner.team.name, "Foo");
+    let latin1_owner = runner::file_owner_for_file(&run_config, "packs/shared/latin1.rb").unwrap().unwrap();
+    assert_eq!(latin1_owner.team.name, "Bar");
+}
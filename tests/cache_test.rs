@@ -27,3 +27,96 @@ fn test_delete_cache() -> Result<(), Box<dyn Error>> {
     common::teardown();
     Ok(())
 }
+
+#[test]
+fn test_warm_cache() -> Result<(), Box<dyn Error>> {
+    let temp_dir = common::setup_fixture_repo(std::path::Path::new("tests/fixtures/valid_project"));
+    let project_root = temp_dir.path();
+    common::git_add_all_files(project_root);
+    let cache_path = project_root.join("tmp/cache/codeowners/project-file-cache.json");
+    assert!(!cache_path.exists());
+
+    let warm_cache = || -> Result<String, Box<dyn Error>> {
+        let output = Command::cargo_bin("codeowners")?
+            .arg("--project-root")
+            .arg(project_root)
+            .arg("warm-cache")
+            .assert()
+            .success()
+            .get_output()
+            .stdout
+            .clone();
+        Ok(String::from_utf8(output)?)
+    };
+
+    let stdout = warm_cache()?;
+    let cache: serde_json::Map<String, serde_json::Value> = serde_json::from_str(&std::fs::read_to_string(&cache_path)?)?;
+    assert!(!cache.is_empty());
+    assert_eq!(stdout, format!("Wrote {} entries to the cache\n", cache.len()));
+    let payroll = cache
+        .get(&project_root.join("ruby/app/models/payroll.rb").to_string_lossy().to_string())
+        .expect("payroll.rb was not cached");
+    assert_eq!(payroll["owner"], "Payroll");
+
+    // A second run reuses the persisted entries rather than starting over
+    assert_eq!(warm_cache()?, stdout);
+    Command::cargo_bin("codeowners")?
+        .arg("--project-root")
+        .arg(project_root)
+        .arg("validate")
+        .assert()
+        .success();
+    Ok(())
+}
+
+#[test]
+fn test_validate_since_cache() -> Result<(), Box<dyn Error>> {
+    let temp_dir = common::setup_fixture_repo(std::path::Path::new("tests/fixtures/valid_project"));
+    let project_root = temp_dir.path();
+    common::git_add_all_files(project_root);
+
+    let resolved_counts = || -> Result<(usize, usize), Box<dyn Error>> {
+        let output = Command::cargo_bin("codeowners")?
+            .arg("--project-root")
+            .arg(project_root)
+            .arg("validate")
+            .arg("--since-cache")
+            .assert()
+            .success()
+            .get_output()
+            .stdout
+            .clone();
+        let stdout = String::from_utf8(output)?;
+        let counts = stdout
+            .trim()
+            .strip_prefix("Resolved owners for ")
+            .and_then(|rest| rest.split_once(" files;"))
+            .and_then(|(counts, _)| counts.split_once(" of "))
+            .ok_or_else(|| format!("unexpected output: {stdout}"))?;
+        Ok((counts.0.parse()?, counts.1.parse()?))
+    };
+
+    let (resolved, total) = resolved_counts()?;
+    assert!(total > 1);
+    assert_eq!(resolved, total);
+
+    // Nothing changed, so every file's owners come from the cache
+    assert_eq!(resolved_counts()?, (0, total));
+
+    // Only the edited file is resolved again
+    let payroll = project_root.join("ruby/app/models/payroll.rb");
+    std::fs::write(&payroll, "# @team: Payroll\n\nclass Payroll; def pay; end; end\n")?;
+    let modified = std::fs::metadata(&payroll)?.modified()?;
+    std::fs::File::options()
+        .write(true)
+        .open(&payroll)?
+        .set_modified(modified + std::time::Duration::from_secs(5))?;
+    assert_eq!(resolved_counts()?, (1, total));
+
+    // A changed team glob can change any file's owner, so nothing is reused
+    let payments_team = project_root.join("config/teams/payments.yml");
+    let team_yml = std::fs::read_to_string(&payments_team)?;
+    std::fs::write(&payments_team, format!("{}\n  - ruby/app/payments/bar/**/*\n", team_yml.trim_end()))?;
+    assert_eq!(resolved_counts()?, (total, total));
+    Ok(())
+}
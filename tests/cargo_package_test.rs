@@ -0,0 +1,48 @@
+use std::{error::Error, path::Path};
+
+use codeowners::runner;
+use predicates::prelude::*;
+
+mod common;
+use common::{OutputStream, assert_no_run_errors, build_run_config, git_add_all_files, run_codeowners, setup_fixture_repo};
+
+#[test]
+fn test_generate_owns_cargo_packages() {
+    let temp_dir = setup_fixture_repo(Path::new("tests/fixtures/cargo_package_project"));
+    git_add_all_files(temp_dir.path());
+    let run_config = build_run_config(temp_dir.path(), ".github/CODEOWNERS");
+
+    assert_no_run_errors(&runner::generate(&run_config, false));
+    let codeowners = std::fs::read_to_string(temp_dir.path().join(".github/CODEOWNERS")).unwrap();
+    assert!(
+        codeowners.contains("# Owner metadata key in Cargo.toml\n/crates/payroll/**/** @PayrollTeam\n"),
+        "{}",
+        codeowners
+    );
+    // `crates/tooling/Cargo.toml` names no owner
+    assert!(!codeowners.contains("/crates/tooling/**/**"), "{}", codeowners);
+}
+
+#[test]
+fn test_for_file_in_cargo_package() -> Result<(), Box<dyn Error>> {
+    run_codeowners(
+        "cargo_package_project",
+        &["for-file", "crates/payroll/src/lib.rs"],
+        true,
+        OutputStream::Stdout,
+        predicate::str::contains("Team: Payroll").and(predicate::str::contains(
+            "Owner defined in `crates/payroll/Cargo.toml` with implicitly owned glob: `crates/payroll/**/**`",
+        )),
+    )
+}
+
+#[test]
+fn test_validate_cargo_package_project() -> Result<(), Box<dyn Error>> {
+    run_codeowners(
+        "cargo_package_project",
+        &["validate"],
+        true,
+        OutputStream::Stdout,
+        predicate::eq(""),
+    )
+}
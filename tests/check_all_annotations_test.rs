@@ -0,0 +1,49 @@
+use std::{error::Error, path::Path, process::Command};
+
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+
+mod common;
+use common::{git_add_all_files, init_git_repo, write_file};
+
+fn codeowners(project_root: &Path, args: &[&str]) -> Result<Command, Box<dyn Error>> {
+    let mut cmd = Command::cargo_bin("codeowners")?;
+    cmd.arg("--project-root").arg(project_root).arg("--no-cache").args(args);
+    Ok(cmd)
+}
+
+#[test]
+fn test_check_all_annotations_reports_unknown_teams_outside_owned_globs() -> Result<(), Box<dyn Error>> {
+    let temp_dir = tempfile::tempdir()?;
+    let project_root = temp_dir.path();
+    write_file(
+        project_root,
+        "config/code_ownership.yml",
+        "---\nowned_globs:\n  - \"app/**/*.rb\"\n",
+    );
+    write_file(
+        project_root,
+        "config/teams/payroll.yml",
+        "name: Payroll\ngithub:\n  team: \"@PayrollTeam\"\n",
+    );
+    write_file(project_root, "app/paycheck.rb", "# @team Payroll\nclass Paycheck; end\n");
+    write_file(project_root, "scripts/backfill.rb", "# @team Payrol\nputs :backfill\n");
+    write_file(project_root, "scripts/report.rb", "# @team Payroll\nputs :report\n");
+    init_git_repo(project_root);
+    git_add_all_files(project_root);
+    codeowners(project_root, &["generate"])?.assert().success();
+
+    // `scripts/` is outside owned_globs, so its annotations are ignored by default
+    codeowners(project_root, &["validate"])?.assert().success();
+
+    codeowners(project_root, &["validate", "--check-all-annotations"])?
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains(
+            "Found invalid team annotations\n- scripts/backfill.rb is referencing an invalid team - 'Payrol'",
+        ))
+        .stdout(predicate::str::contains("scripts/report.rb").not())
+        .stdout(predicate::str::contains("app/paycheck.rb").not());
+
+    Ok(())
+}
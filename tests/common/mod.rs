@@ -147,6 +147,24 @@ pub fn is_file_staged(repo_root: &Path, rel_path: &str) -> bool {
     stdout.lines().any(|line| line.trim() == rel_path)
 }
 
+/// Writes `content` to `file_path` under `project_root`, creating any missing directories.
+#[allow(dead_code)]
+pub fn write_file(project_root: &Path, file_path: &str, content: impl AsRef<[u8]>) {
+    let file_path = project_root.join(file_path);
+    fs::create_dir_all(file_path.parent().unwrap()).expect("failed to create parent directories");
+    fs::write(file_path, content).expect("failed to write file");
+}
+
+/// Writes `config/teams/<name>.yml` for a team whose GitHub handle is `@<name>`, followed by `extra` YAML.
+#[allow(dead_code)]
+pub fn write_team(project_root: &Path, name: &str, extra: &str) {
+    write_file(
+        project_root,
+        &format!("config/teams/{}.yml", name.to_lowercase()),
+        format!("name: {}\ngithub:\n  team: \"@{}\"\n  members:\n    - user\n{}", name, name, extra),
+    );
+}
+
 #[allow(dead_code)]
 pub fn build_run_config(project_root: &Path, codeowners_rel_path: &str) -> RunConfig {
     let project_root = project_root.canonicalize().expect("failed to canonicalize project root");
@@ -158,6 +176,7 @@ pub fn build_run_config(project_root: &Path, codeowners_rel_path: &str) -> RunCo
         config_path,
         no_cache: true,
         executable_name: None,
+        ..Default::default()
     }
 }
 
@@ -0,0 +1,95 @@
+use codeowners::runner;
+use predicates::prelude::*;
+use std::error::Error;
+use std::path::Path;
+
+mod common;
+use common::OutputStream;
+use common::{build_run_config, run_codeowners};
+
+#[test]
+fn test_override_vendored_gems_path() -> Result<(), Box<dyn Error>> {
+    run_codeowners(
+        "valid_project",
+        &["for-file", "gems/pets/dog.rb"],
+        true,
+        OutputStream::Stdout,
+        predicate::str::contains("Owner specified in Team YML's `owned_gems`"),
+    )?;
+    run_codeowners(
+        "valid_project",
+        &["--config-override", "vendored_gems_path=vendored", "for-file", "gems/pets/dog.rb"],
+        true,
+        OutputStream::Stdout,
+        predicate::str::starts_with("Team: Unowned"),
+    )?;
+    Ok(())
+}
+
+#[test]
+fn test_override_owned_globs() -> Result<(), Box<dyn Error>> {
+    run_codeowners(
+        "valid_project",
+        &[
+            "--config-override",
+            "owned_globs=[javascript/**/*]",
+            "for-file",
+            "ruby/app/models/payroll.rb",
+        ],
+        true,
+        OutputStream::Stdout,
+        predicate::str::starts_with("Team: Unowned").and(predicate::str::contains("- not matched by any owned_glob")),
+    )?;
+    run_codeowners(
+        "valid_project",
+        &[
+            "--config-override",
+            "owned_globs=[javascript/**/*, \"ruby/**/*.{rb,erb}\"]",
+            "for-file",
+            "ruby/app/models/payroll.rb",
+        ],
+        true,
+        OutputStream::Stdout,
+        predicate::str::contains("Team: Payroll"),
+    )?;
+    Ok(())
+}
+
+#[test]
+fn test_override_unknown_key() -> Result<(), Box<dyn Error>> {
+    run_codeowners(
+        "valid_project",
+        &["--config-override", "owned_glob=app/**", "validate"],
+        false,
+        OutputStream::Stderr,
+        predicate::str::contains("Invalid config override `owned_glob=app/**`: unknown config key `owned_glob`"),
+    )?;
+    Ok(())
+}
+
+#[test]
+fn test_config_misconfiguration_is_a_warning() -> Result<(), Box<dyn Error>> {
+    run_codeowners(
+        "valid_project",
+        &["--config-override", "vendored_gems_path=missing_gems", "for-team", "Payroll"],
+        true,
+        OutputStream::Stderr,
+        predicate::str::contains("Warning: `vendored_gems_path` `missing_gems` does not exist under"),
+    )?;
+    Ok(())
+}
+
+#[test]
+fn test_config_misconfiguration_is_in_the_run_result() {
+    let mut run_config = build_run_config(Path::new("tests/fixtures/valid_project"), ".github/CODEOWNERS");
+    run_config.config_overrides = vec!["vendored_gems_path=missing_gems".to_owned()];
+
+    let result = runner::for_team(&run_config, "Payroll");
+    assert!(result.io_errors.is_empty() && result.validation_errors.is_empty(), "{:?}", result);
+    assert_eq!(result.warnings.len(), 1, "{:?}", result.warnings);
+    assert!(
+        result.warnings[0].contains("`vendored_gems_path` `missing_gems` does not exist under"),
+        "{:?}",
+        result.warnings
+    );
+}
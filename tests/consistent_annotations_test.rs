@@ -0,0 +1,66 @@
+use std::path::Path;
+
+use codeowners::runner::{self, RunConfig};
+
+mod common;
+use common::{assert_no_run_errors, build_run_config, write_file, write_team};
+
+fn build_project(temp_dir: &Path) -> RunConfig {
+    write_file(
+        temp_dir,
+        "config/code_ownership.yml",
+        "---\nowned_globs:\n  - \"{app,packs}/**/*.rb\"\nteam_file_glob:\n  - config/teams/**/*.yml\n",
+    );
+    for name in ["Payroll", "Payments"] {
+        write_team(temp_dir, name, "");
+    }
+    write_file(temp_dir, "packs/payroll/package.yml", "owner: Payroll\n");
+    write_file(temp_dir, "packs/payroll/app/payroll.rb", "# @team Payroll\nclass Payroll; end\n");
+    write_file(temp_dir, "app/payments/.codeowner", "Payments\n");
+    write_file(temp_dir, "app/payments/charge.rb", "# @team Payments\nclass Charge; end\n");
+    write_file(temp_dir, "app/models/user.rb", "# @team Payroll\nclass User; end\n");
+
+    build_run_config(temp_dir, ".github/CODEOWNERS")
+}
+
+#[test]
+fn test_annotations_agreeing_with_package_and_directory_owners_pass() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let run_config = build_project(temp_dir.path());
+
+    assert_no_run_errors(&runner::generate(&run_config, false));
+    assert_no_run_errors(&runner::validate_consistent_annotations(&run_config));
+}
+
+#[test]
+fn test_annotations_disagreeing_with_package_or_directory_owner_fail() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let run_config = build_project(temp_dir.path());
+    write_file(
+        temp_dir.path(),
+        "packs/payroll/app/refund.rb",
+        "# @team Payments\nclass Refund; end\n",
+    );
+    write_file(temp_dir.path(), "app/payments/payslip.rb", "# @team Payroll\nclass Payslip; end\n");
+    assert_no_run_errors(&runner::generate(&run_config, false));
+
+    let result = runner::validate_consistent_annotations(&run_config);
+    assert_eq!(
+        result.validation_errors,
+        vec![
+            "\nFound annotations that disagree with the file's package or directory owner\n\
+             - app/payments/payslip.rb is annotated with 'Payroll', but `app/payments/.codeowner` names 'Payments'\n\
+             - packs/payroll/app/refund.rb is annotated with 'Payments', but `packs/payroll/package.yml` names 'Payroll'\n"
+                .to_owned()
+        ]
+    );
+
+    // Without the check, the same files are only reported as having multiple owners
+    let result = runner::validate(&run_config, vec![]);
+    assert!(
+        result.validation_errors[0].contains("The following files have declared ownership in multiple ways"),
+        "{:?}",
+        result.validation_errors
+    );
+    assert!(!result.validation_errors[0].contains("disagree"), "{:?}", result.validation_errors);
+}
@@ -72,3 +72,66 @@ fn test_crosscheck_owners_reports_unowned_mismatch() -> Result<(), Box<dyn Error
 
     Ok(())
 }
+
+#[test]
+fn test_crosscheck_owners_prints_summary() -> Result<(), Box<dyn Error>> {
+    let temp_dir = setup_fixture_repo(Path::new(FIXTURE));
+    let project_root = temp_dir.path();
+    git_add_all_files(project_root);
+
+    Command::cargo_bin("codeowners")?
+        .arg("--project-root")
+        .arg(project_root)
+        .arg("--no-cache")
+        .arg("crosscheck-owners")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Checked 12 files: 12 matches, 0 mismatches. Winning sources: annotation=7, default=0, directory=2, extension=0, gem=2, glob=1, override=0, package=0, unowned=0, yml=0",
+        ));
+
+    Ok(())
+}
+
+#[test]
+fn test_crosscheck_owners_json_summary() -> Result<(), Box<dyn Error>> {
+    let temp_dir = setup_fixture_repo(Path::new(FIXTURE));
+    let project_root = temp_dir.path();
+    let codeowners_path = project_root.join(".github/CODEOWNERS");
+    let original = fs::read_to_string(&codeowners_path)?;
+    fs::write(
+        &codeowners_path,
+        original.replace(
+            "/ruby/app/models/payroll.rb @PayrollTeam",
+            "/ruby/app/models/payroll.rb @PaymentsTeam",
+        ),
+    )?;
+    git_add_all_files(project_root);
+
+    let output = Command::cargo_bin("codeowners")?
+        .arg("--project-root")
+        .arg(project_root)
+        .arg("--no-cache")
+        .arg("crosscheck-owners")
+        .arg("--json")
+        .output()?;
+    assert!(!output.status.success());
+
+    let stdout = String::from_utf8(output.stdout)?;
+    let summary: serde_json::Value = serde_json::from_str(&stdout)?;
+    assert_eq!(summary["total"], 12);
+    assert_eq!(summary["matches"], 11);
+    assert_eq!(summary["mismatches"], 1);
+    assert_eq!(summary["sources"]["annotation"], 7);
+    assert_eq!(summary["sources"]["directory"], 2);
+    assert_eq!(summary["sources"]["gem"], 2);
+    assert_eq!(summary["sources"]["glob"], 1);
+    assert_eq!(
+        summary["mismatched_files"],
+        serde_json::json!([
+            {"path": "ruby/app/models/payroll.rb", "codeowners_owner": "Payments", "fast_owner": "Payroll"}
+        ])
+    );
+
+    Ok(())
+}
@@ -0,0 +1,88 @@
+use std::path::Path;
+
+use codeowners::runner::{self, RunConfig};
+use tempfile::TempDir;
+
+mod common;
+use common::{assert_no_run_errors, build_run_config, git_add_all_files, setup_fixture_repo};
+
+fn build_project() -> (TempDir, RunConfig) {
+    let temp_dir = setup_fixture_repo(Path::new("tests/fixtures/default_owner_project"));
+    git_add_all_files(temp_dir.path());
+    let run_config = build_run_config(temp_dir.path(), ".github/CODEOWNERS");
+    (temp_dir, run_config)
+}
+
+fn owner_and_sources(run_config: &RunConfig, file_path: &str) -> Vec<(String, Vec<String>)> {
+    runner::owners_for_file(run_config, file_path)
+        .unwrap()
+        .into_iter()
+        .map(|owner| (owner.team.name, owner.sources.iter().map(|source| source.to_string()).collect()))
+        .collect()
+}
+
+#[test]
+fn test_default_owner_claims_otherwise_unowned_files() {
+    let (_temp_dir, run_config) = build_project();
+
+    assert_eq!(
+        owner_and_sources(&run_config, "app/models/orphan.rb"),
+        vec![(
+            "Platform".to_owned(),
+            vec!["Default owner from `default_owner` in the config".to_owned()]
+        )]
+    );
+}
+
+#[test]
+fn test_higher_priority_mappers_win_over_default_owner() {
+    let (_temp_dir, run_config) = build_project();
+
+    assert_eq!(
+        owner_and_sources(&run_config, "app/models/charge.rb"),
+        vec![("Payments".to_owned(), vec!["Owner annotation at the top of the file".to_owned()])]
+    );
+    assert_eq!(
+        owner_and_sources(&run_config, "app/api/user.proto"),
+        vec![("Api".to_owned(), vec!["Owner of `.proto` files in `extension_owners`".to_owned()])]
+    );
+}
+
+#[test]
+fn test_default_owner_is_the_first_codeowners_line_and_validates() {
+    let (temp_dir, run_config) = build_project();
+
+    assert_no_run_errors(&runner::generate(&run_config, false));
+    let codeowners = std::fs::read_to_string(temp_dir.path().join(".github/CODEOWNERS")).unwrap();
+    let body = codeowners.split_once("\n\n# ").map(|(_, body)| body).unwrap();
+    assert!(
+        body.starts_with("Default owner\n/app/models/orphan.rb @Platform\n\n# Owner by file extension\n"),
+        "{}",
+        codeowners
+    );
+
+    // The whole-project resolution agrees with the per-file one: nothing is left unowned or doubly owned
+    assert_no_run_errors(&runner::validate(&run_config, vec![]));
+}
+
+#[test]
+fn test_default_owner_leaves_files_outside_the_project_unowned_in_codeowners_too() {
+    let (_temp_dir, run_config) = build_project();
+    assert_no_run_errors(&runner::generate(&run_config, false));
+
+    // Outside owned_globs, and in unowned_globs: both views leave them unowned
+    for file in ["docs/guide.md", "app/legacy/old.rb"] {
+        assert!(
+            runner::team_for_file_from_codeowners(&run_config, file).unwrap().is_none(),
+            "{}",
+            file
+        );
+        assert!(runner::team_for_file(&run_config, file).unwrap().is_none(), "{}", file);
+    }
+    assert_eq!(
+        runner::team_for_file_from_codeowners(&run_config, "app/models/orphan.rb")
+            .unwrap()
+            .map(|team| team.name),
+        Some("Platform".to_owned())
+    );
+}
@@ -0,0 +1,62 @@
+use std::{error::Error, path::Path};
+
+use codeowners::runner::{self, RunConfig};
+use predicates::prelude::*;
+
+mod common;
+use common::{OutputStream, build_run_config, run_codeowners, write_file};
+
+fn doctor_run_config(temp_dir: &Path) -> RunConfig {
+    RunConfig {
+        codeowners_file_path: None,
+        ..build_run_config(temp_dir, ".github/CODEOWNERS")
+    }
+}
+
+#[test]
+fn test_doctor_passes_on_valid_project() -> Result<(), Box<dyn Error>> {
+    run_codeowners(
+        "valid_project",
+        &["doctor"],
+        true,
+        OutputStream::Stdout,
+        predicate::str::contains("[pass] teams: found 3 teams")
+            .and(predicate::str::contains("[pass] owned_globs:"))
+            .and(predicate::str::contains("All 5 checks passed.")),
+    )?;
+
+    Ok(())
+}
+
+#[test]
+fn test_doctor_reports_missing_teams() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    write_file(
+        temp_dir.path(),
+        "config/code_ownership.yml",
+        "---\nowned_globs:\n  - \"app/**/*.rb\"\nteam_file_glob:\n  - config/teams/**/*.yml\n",
+    );
+    write_file(temp_dir.path(), "app/models/user.rb", "class User; end\n");
+
+    let result = runner::doctor(&doctor_run_config(temp_dir.path()));
+    assert!(result.info_messages.is_empty(), "{:?}", result.info_messages);
+    let report = result.validation_errors.join("\n");
+    assert!(report.contains("[pass] config:"), "{}", report);
+    assert!(
+        report.contains("[FAIL] teams: no team files match `team_file_glob` (config/teams/**/*.yml)"),
+        "{}",
+        report
+    );
+    assert!(report.contains("[pass] owned_globs: 1 file to own"), "{}", report);
+    assert!(report.contains("1 of 5 checks failed."), "{}", report);
+}
+
+#[test]
+fn test_doctor_reports_missing_config() {
+    let temp_dir = tempfile::tempdir().unwrap();
+
+    let result = runner::doctor(&doctor_run_config(temp_dir.path()));
+    let report = result.validation_errors.join("\n");
+    assert!(report.contains("[FAIL] config: Can't open config file"), "{}", report);
+    assert!(!report.contains("teams:"), "{}", report);
+}
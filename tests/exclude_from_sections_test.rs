@@ -0,0 +1,84 @@
+use std::path::Path;
+
+use codeowners::runner::{self, RunConfig};
+
+mod common;
+use common::{assert_no_run_errors, build_run_config, write_file};
+
+fn build_project(temp_dir: &Path, exclude_from_sections: &str) -> RunConfig {
+    write_file(
+        temp_dir,
+        "config/code_ownership.yml",
+        "---\nowned_globs:\n  - \"{app,packs}/**/*.rb\"\nruby_package_paths:\n  - packs/*\nteam_file_glob:\n  - config/teams/**/*.yml\n",
+    );
+    write_file(
+        temp_dir,
+        "config/teams/payments.yml",
+        format!(
+            "name: Payments\ngithub:\n  team: \"@Payments\"\n  members:\n    - user\nowned_globs:\n  - \"app/payments/**\"\nexclude_from_sections: {}\n",
+            exclude_from_sections
+        ),
+    );
+    write_file(temp_dir, "app/payments/charge.rb", "class Charge; end\n");
+    write_file(temp_dir, "app/models/refund.rb", "# @team Payments\nclass Refund; end\n");
+    write_file(temp_dir, "app/billing/.codeowner", "Payments\n");
+    write_file(temp_dir, "app/billing/invoice.rb", "class Invoice; end\n");
+    write_file(temp_dir, "packs/ledger/package.yml", "owner: Payments\n");
+    write_file(temp_dir, "packs/ledger/entry.rb", "class Entry; end\n");
+
+    build_run_config(temp_dir, ".github/CODEOWNERS")
+}
+
+fn generate(exclude_from_sections: &str) -> String {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let run_config = build_project(temp_dir.path(), exclude_from_sections);
+    assert_no_run_errors(&runner::generate_and_validate(&run_config, vec![], false));
+
+    // Excluding a section only changes the CODEOWNERS file, not who owns the files
+    for file in [
+        "app/payments/charge.rb",
+        "app/models/refund.rb",
+        "app/billing/invoice.rb",
+        "packs/ledger/entry.rb",
+    ] {
+        let owners = runner::owners_for_file(&run_config, file).unwrap();
+        assert_eq!(owners[0].team.name, "Payments", "{}", file);
+    }
+    std::fs::read_to_string(temp_dir.path().join(".github/CODEOWNERS")).unwrap()
+}
+
+#[test]
+fn test_exclude_package_yml_section() {
+    let codeowners = generate("[package-yml]");
+    assert!(codeowners.contains("\n# /packs/ledger/**/** @Payments\n"), "{}", codeowners);
+    assert!(codeowners.contains("\n/app/models/refund.rb @Payments\n"), "{}", codeowners);
+    assert!(codeowners.contains("\n/app/billing/**/** @Payments\n"), "{}", codeowners);
+}
+
+#[test]
+fn test_exclude_directory_section() {
+    let codeowners = generate("[directory]");
+    assert!(codeowners.contains("\n# /app/billing/**/** @Payments\n"), "{}", codeowners);
+    assert!(codeowners.contains("\n/packs/ledger/**/** @Payments\n"), "{}", codeowners);
+}
+
+#[test]
+fn test_exclude_annotation_and_team_glob_sections() {
+    let codeowners = generate("[annotation, team-glob]");
+    assert!(codeowners.contains("\n# /app/models/refund.rb @Payments\n"), "{}", codeowners);
+    assert!(codeowners.contains("\n# /app/payments/** @Payments\n"), "{}", codeowners);
+    assert!(codeowners.contains("\n/config/teams/payments.yml @Payments\n"), "{}", codeowners);
+}
+
+#[test]
+fn test_exclude_unknown_section_is_an_error() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let run_config = build_project(temp_dir.path(), "[packages]");
+    let result = runner::generate(&run_config, false);
+    let errors = result.io_errors.join("\n");
+    assert!(
+        errors.contains("Unknown section `packages` in `exclude_from_sections`"),
+        "{}",
+        errors
+    );
+}
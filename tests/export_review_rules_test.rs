@@ -0,0 +1,54 @@
+use std::{error::Error, path::Path};
+
+use codeowners::runner;
+use predicates::prelude::*;
+
+mod common;
+use common::{OutputStream, assert_no_run_errors, build_run_config, git_add_all_files, run_codeowners, setup_fixture_repo};
+
+#[test]
+fn test_export_review_rules_has_a_rule_per_generated_line() {
+    let temp_dir = setup_fixture_repo(Path::new("tests/fixtures/valid_project"));
+    git_add_all_files(temp_dir.path());
+    let run_config = build_run_config(temp_dir.path(), ".github/CODEOWNERS");
+
+    assert_no_run_errors(&runner::generate(&run_config, false));
+    assert_no_run_errors(&runner::export_review_rules(&run_config, Path::new("tmp/review_rules.yml")));
+
+    let codeowners = std::fs::read_to_string(temp_dir.path().join(".github/CODEOWNERS")).unwrap();
+    let generated_lines: Vec<String> = codeowners
+        .lines()
+        .filter(|line| line.starts_with('/'))
+        .map(|line| line.replace(" @", " "))
+        .collect();
+    let export: serde_yaml::Value =
+        serde_yaml::from_str(&std::fs::read_to_string(temp_dir.path().join("tmp/review_rules.yml")).unwrap()).unwrap();
+    let rules = export["rules"].as_sequence().unwrap();
+
+    let rule_lines: Vec<String> = rules
+        .iter()
+        .map(|rule| {
+            let teams: Vec<&str> = rule["teams"]
+                .as_sequence()
+                .unwrap()
+                .iter()
+                .map(|team| team.as_str().unwrap())
+                .collect();
+            format!("{} {}", rule["pattern"].as_str().unwrap(), teams.join(" "))
+        })
+        .collect();
+    assert!(!rule_lines.is_empty());
+    assert_eq!(rule_lines, generated_lines);
+    assert!(rule_lines.contains(&"/ruby/app/models/payroll.rb PayrollTeam".to_owned()));
+}
+
+#[test]
+fn test_export_requires_a_format() -> Result<(), Box<dyn Error>> {
+    run_codeowners(
+        "valid_project",
+        &["export", "tmp/review_rules.yml"],
+        false,
+        OutputStream::Stderr,
+        predicate::str::contains("--format <FORMAT>"),
+    )
+}
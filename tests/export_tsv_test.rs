@@ -0,0 +1,30 @@
+use std::path::Path;
+
+use codeowners::runner;
+
+mod common;
+use common::{assert_no_run_errors, build_run_config, git_add_all_files, setup_fixture_repo};
+
+#[test]
+fn test_export_tsv_lists_every_owned_file() {
+    let temp_dir = setup_fixture_repo(Path::new("tests/fixtures/valid_project"));
+    git_add_all_files(temp_dir.path());
+    let run_config = build_run_config(temp_dir.path(), ".github/CODEOWNERS");
+
+    assert_no_run_errors(&runner::export_tsv(&run_config, Path::new("tmp/ownership.tsv")));
+    let tsv = std::fs::read_to_string(temp_dir.path().join("tmp/ownership.tsv")).unwrap();
+    let lines: Vec<&str> = tsv.lines().collect();
+
+    assert_eq!(lines[0], "path\tteam_name\tgithub_team\tsource_kind");
+    for row in [
+        "gems/pets/dog.rb\tUX\t@UX\tgem",
+        "ruby/app/models/bank_account.rb\tPayments\t@PaymentsTeam\tannotation",
+        "ruby/app/payments/nacha.rb\tPayments\t@PaymentsTeam\tglob",
+        "ruby/app/payroll/payroll.rb\tPayroll\t@PayrollTeam\tdirectory",
+    ] {
+        assert!(lines.contains(&row), "missing {:?} in\n{}", row, tsv);
+    }
+    let paths: Vec<&str> = lines[1..].iter().map(|line| line.split('\t').next().unwrap()).collect();
+    assert!(paths.is_sorted(), "{}", tsv);
+    assert!(lines[1..].iter().all(|line| line.split('\t').count() == 4), "{}", tsv);
+}
@@ -0,0 +1,78 @@
+use std::path::Path;
+
+use codeowners::runner::{self, RunConfig};
+
+mod common;
+use common::{assert_no_run_errors, build_run_config, write_file, write_team};
+
+fn build_project(temp_dir: &Path, extension_owners: &str) -> RunConfig {
+    write_file(
+        temp_dir,
+        "config/code_ownership.yml",
+        format!(
+            "---\nowned_globs:\n  - \"**/*.{{rb,proto,sql}}\"\nteam_file_glob:\n  - config/teams/**/*.yml\nextension_owners:\n{}",
+            extension_owners
+        ),
+    );
+    write_team(temp_dir, "Api", "");
+    write_team(temp_dir, "Data", "");
+    write_team(temp_dir, "Payments", "owned_globs:\n  - \"app/payments/**\"\n");
+    write_file(temp_dir, "app/protos/user.proto", "message User {}\n");
+    write_file(temp_dir, "app/payments/charge.proto", "message Charge {}\n");
+    write_file(temp_dir, "packs/billing/package.yml", "owner: Data\n");
+    write_file(temp_dir, "packs/billing/invoice.proto", "message Invoice {}\n");
+    write_file(temp_dir, "app/models/user.rb", "# @team Payments\nclass User; end\n");
+
+    build_run_config(temp_dir, ".github/CODEOWNERS")
+}
+
+fn team_for(run_config: &RunConfig, file_path: &str) -> Vec<String> {
+    runner::owners_for_file(run_config, file_path)
+        .unwrap()
+        .into_iter()
+        .map(|owner| owner.team.name)
+        .collect()
+}
+
+#[test]
+fn test_extension_owner_claims_otherwise_unowned_files() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let run_config = build_project(temp_dir.path(), "  proto: Api\n  .sql: Data\n");
+
+    assert_no_run_errors(&runner::generate_and_validate(&run_config, vec![], false));
+    let codeowners = std::fs::read_to_string(temp_dir.path().join(".github/CODEOWNERS")).unwrap();
+    assert!(codeowners.contains("/**/*.proto @Api"), "{}", codeowners);
+    assert!(codeowners.contains("/**/*.sql @Data"), "{}", codeowners);
+    // Every other line comes later, so GitHub's last-match rule lets it win
+    assert!(
+        codeowners.find("/**/*.proto @Api").unwrap() < codeowners.find("/app/payments/** @Payments").unwrap(),
+        "{}",
+        codeowners
+    );
+
+    assert_eq!(team_for(&run_config, "app/protos/user.proto"), vec!["Api"]);
+}
+
+#[test]
+fn test_extension_owner_yields_to_other_mappers() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let run_config = build_project(temp_dir.path(), "  proto: Api\n");
+
+    assert_eq!(team_for(&run_config, "app/payments/charge.proto"), vec!["Payments"]);
+    assert_eq!(team_for(&run_config, "packs/billing/invoice.proto"), vec!["Data"]);
+    assert_eq!(team_for(&run_config, "app/models/user.rb"), vec!["Payments"]);
+}
+
+#[test]
+fn test_extension_owner_must_be_a_known_team() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let run_config = build_project(temp_dir.path(), "  proto: Nobody\n");
+
+    let result = runner::validate(&run_config, vec![]);
+    let errors = result.validation_errors.join("\n");
+    assert!(
+        errors.contains("extension_owners.proto is referencing an invalid team - 'Nobody'"),
+        "{}",
+        errors
+    );
+}
@@ -0,0 +1,115 @@
+use std::path::Path;
+
+use codeowners::runner::{self, RunConfig};
+
+mod common;
+use common::{assert_no_run_errors, build_run_config, write_file, write_team};
+
+const APPENDIX: &str = "# Managed by hand\n/docs/** @org/docs-team\n/.github/workflows/** @org/platform\n";
+
+fn build_project(temp_dir: &Path) -> RunConfig {
+    write_file(
+        temp_dir,
+        "config/code_ownership.yml",
+        "---\nowned_globs:\n  - \"**/*.rb\"\nteam_file_glob:\n  - config/teams/**/*.yml\nextra_codeowners_file: config/CODEOWNERS.extra\n",
+    );
+    write_team(temp_dir, "Payments", "");
+    write_file(temp_dir, "app/models/charge.rb", "# @team Payments\nclass Charge; end\n");
+    write_file(temp_dir, "config/CODEOWNERS.extra", APPENDIX);
+
+    build_run_config(temp_dir, ".github/CODEOWNERS")
+}
+
+#[test]
+fn test_extra_codeowners_file_is_appended_and_validates() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let run_config = build_project(temp_dir.path());
+
+    assert_no_run_errors(&runner::generate(&run_config, false));
+    let codeowners = std::fs::read_to_string(temp_dir.path().join(".github/CODEOWNERS")).unwrap();
+    assert!(
+        codeowners.ends_with(&format!("/config/teams/payments.yml @Payments\n\n{}", APPENDIX)),
+        "{}",
+        codeowners
+    );
+
+    assert_no_run_errors(&runner::validate(&run_config, vec![]));
+}
+
+#[test]
+fn test_sidecar_includes_the_appendix_rules() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let run_config = build_project(temp_dir.path());
+    write_file(
+        temp_dir.path(),
+        "config/CODEOWNERS.extra",
+        "# Managed by hand\n/docs/** @org/docs-team @Payments # docs\n/vendor/**\n",
+    );
+
+    assert_no_run_errors(&runner::generate_with_sidecar(&run_config, Path::new("tmp/codeowners.json"), false));
+    let sidecar: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(temp_dir.path().join("tmp/codeowners.json")).unwrap()).unwrap();
+    let appendix_entries: Vec<&serde_json::Value> = sidecar["entries"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|entry| entry["source_kind"] == "extra")
+        .collect();
+    assert_eq!(
+        appendix_entries,
+        vec![
+            &serde_json::json!({"glob": "/docs/**", "team_name": "", "github_team": "@org/docs-team", "source_kind": "extra", "disabled": false}),
+            &serde_json::json!({"glob": "/docs/**", "team_name": "Payments", "github_team": "@Payments", "source_kind": "extra", "disabled": false}),
+            &serde_json::json!({"glob": "/vendor/**", "team_name": "", "github_team": "", "source_kind": "extra", "disabled": false}),
+        ]
+    );
+}
+
+#[test]
+fn test_review_rules_include_the_appendix_rules() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let run_config = build_project(temp_dir.path());
+    write_file(
+        temp_dir.path(),
+        "config/CODEOWNERS.extra",
+        "# Managed by hand\n/docs/** @org/docs-team @Payments @alice docs@example.com\n",
+    );
+
+    assert_no_run_errors(&runner::export_review_rules(&run_config, Path::new("tmp/review_rules.yml")));
+    let export: serde_yaml::Value =
+        serde_yaml::from_str(&std::fs::read_to_string(temp_dir.path().join("tmp/review_rules.yml")).unwrap()).unwrap();
+    let rules = export["rules"].as_sequence().unwrap();
+    assert_eq!(
+        rules.last().unwrap(),
+        &serde_yaml::from_str::<serde_yaml::Value>("{pattern: /docs/**, teams: [docs-team, Payments]}").unwrap()
+    );
+}
+
+#[test]
+fn test_validate_reports_a_stale_appendix() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let run_config = build_project(temp_dir.path());
+    assert_no_run_errors(&runner::generate(&run_config, false));
+
+    write_file(temp_dir.path(), "config/CODEOWNERS.extra", "/docs/** @org/writers\n");
+    let result = runner::validate(&run_config, vec![]);
+    assert!(
+        result
+            .validation_errors
+            .iter()
+            .any(|error| error.contains("CODEOWNERS out of date")),
+        "{:?}",
+        result
+    );
+}
+
+#[test]
+fn test_missing_extra_codeowners_file_is_an_error() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let run_config = build_project(temp_dir.path());
+    std::fs::remove_file(temp_dir.path().join("config/CODEOWNERS.extra")).unwrap();
+
+    let result = runner::generate(&run_config, false);
+    assert!(!result.io_errors.is_empty(), "{:?}", result);
+    assert!(!temp_dir.path().join(".github/CODEOWNERS").exists());
+}
@@ -0,0 +1,56 @@
+use std::path::Path;
+
+use codeowners::runner::{self, RunConfig};
+
+mod common;
+use common::{assert_no_run_errors, build_run_config, write_file, write_team};
+
+fn build_project(temp_dir: &Path, owned_glob: &str, fail_on_empty_generation: bool) -> RunConfig {
+    write_file(
+        temp_dir,
+        "config/code_ownership.yml",
+        format!(
+            "---\nowned_globs:\n  - \"{}\"\nteam_file_glob:\n  - config/teams/**/*.yml\nfail_on_empty_generation: {}\n",
+            owned_glob, fail_on_empty_generation
+        ),
+    );
+    write_team(temp_dir, "Payments", "");
+    write_file(temp_dir, "app/models/charge.rb", "# @team Payments\nclass Charge; end\n");
+
+    build_run_config(temp_dir, ".github/CODEOWNERS")
+}
+
+#[test]
+fn test_generate_fails_when_nothing_is_owned() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    // A typo'd glob that matches no file, leaving only the team file lines
+    let run_config = build_project(temp_dir.path(), "ap/**/*.rb", true);
+
+    let result = runner::generate(&run_config, false);
+    assert_eq!(result.io_errors.len(), 1, "{:?}", result.io_errors);
+    assert!(
+        result.io_errors[0].contains("would own no files besides the team files"),
+        "{}",
+        result.io_errors[0]
+    );
+    assert!(!temp_dir.path().join(".github/CODEOWNERS").exists());
+}
+
+#[test]
+fn test_generate_writes_empty_file_without_the_option() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let run_config = build_project(temp_dir.path(), "ap/**/*.rb", false);
+
+    assert_no_run_errors(&runner::generate(&run_config, false));
+    assert!(temp_dir.path().join(".github/CODEOWNERS").exists());
+}
+
+#[test]
+fn test_generate_succeeds_when_something_is_owned() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let run_config = build_project(temp_dir.path(), "app/**/*.rb", true);
+
+    assert_no_run_errors(&runner::generate(&run_config, false));
+    let codeowners = std::fs::read_to_string(temp_dir.path().join(".github/CODEOWNERS")).unwrap();
+    assert!(codeowners.contains("/app/models/charge.rb @Payments"), "{}", codeowners);
+}
@@ -0,0 +1,2 @@
+# @team Payments
+class Charge; end
@@ -0,0 +1,2 @@
+# @team Bar
+# caf
@@ -0,0 +1,2 @@
+// @team Payroll
+pub fn build() {}
@@ -0,0 +1 @@
+class Old; end
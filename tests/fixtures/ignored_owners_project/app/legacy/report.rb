@@ -0,0 +1,2 @@
+# @team DeprecatedTeam
+class Report; end
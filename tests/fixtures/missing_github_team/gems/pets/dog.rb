@@ -1,5 +1,7 @@
 # generated
-# next line should be ignored
-# @team Payments
+# the annotation below is past annotation_header_lines and should be ignored
 
-class Dog; end
+class Dog
+  # bark
+end
+# @team Payments
@@ -0,0 +1 @@
+class W2; end
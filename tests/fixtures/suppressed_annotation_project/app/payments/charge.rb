@@ -0,0 +1 @@
+class Charge; end
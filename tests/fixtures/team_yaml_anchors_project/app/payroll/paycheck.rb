@@ -0,0 +1 @@
+class Paycheck; end
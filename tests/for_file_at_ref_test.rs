@@ -0,0 +1,122 @@
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::{error::Error, fs, path::Path, process::Command};
+
+mod common;
+use common::{git_add_all_files, setup_fixture_repo};
+
+const FIXTURE: &str = "tests/fixtures/valid_project";
+
+fn git(project_root: &Path, args: &[&str]) {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(project_root)
+        .output()
+        .expect("failed to run git");
+    assert!(
+        output.status.success(),
+        "git {:?} failed: {}",
+        args,
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+fn for_file(project_root: &Path, extra_args: &[&str]) -> Command {
+    let mut command = Command::cargo_bin("codeowners").unwrap();
+    command
+        .arg("--project-root")
+        .arg(project_root)
+        .arg("--no-cache")
+        .arg("for-file")
+        .arg("--from-codeowners")
+        .args(extra_args)
+        .arg("ruby/app/models/payroll.rb");
+    command
+}
+
+#[test]
+fn test_for_file_from_codeowners_at_ref() -> Result<(), Box<dyn Error>> {
+    let temp_dir = setup_fixture_repo(Path::new(FIXTURE));
+    let project_root = temp_dir.path();
+    git_add_all_files(project_root);
+    git(project_root, &["commit", "-q", "-m", "initial"]);
+    git(project_root, &["tag", "before-handover"]);
+
+    // Hand the file over to payments in a later commit, and leave the working tree there
+    let codeowners_path = project_root.join(".github/CODEOWNERS");
+    let codeowners = fs::read_to_string(&codeowners_path)?.replace(
+        "/ruby/app/models/payroll.rb @PayrollTeam",
+        "/ruby/app/models/payroll.rb @PaymentsTeam",
+    );
+    fs::write(&codeowners_path, codeowners)?;
+    git_add_all_files(project_root);
+    git(project_root, &["commit", "-q", "-m", "handover"]);
+
+    for_file(project_root, &[])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Team: Payments\n"));
+
+    for_file(project_root, &["--at", "before-handover"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Team: Payroll\n"))
+        .stdout(predicate::str::contains("Owner inferred from codeowners file at before-handover"));
+
+    for_file(project_root, &["--at", "HEAD"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Team: Payments\n"));
+
+    // The working tree is untouched by reading another revision
+    assert!(fs::read_to_string(&codeowners_path)?.contains("/ruby/app/models/payroll.rb @PaymentsTeam"));
+
+    Ok(())
+}
+
+#[test]
+fn test_for_file_from_codeowners_at_unknown_ref() -> Result<(), Box<dyn Error>> {
+    let temp_dir = setup_fixture_repo(Path::new(FIXTURE));
+    let project_root = temp_dir.path();
+    git_add_all_files(project_root);
+    git(project_root, &["commit", "-q", "-m", "initial"]);
+
+    for_file(project_root, &["--at", "no-such-ref"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Can't read .github/CODEOWNERS at no-such-ref"));
+
+    Ok(())
+}
+
+#[test]
+fn test_for_file_at_rejects_option_like_refs() -> Result<(), Box<dyn Error>> {
+    let temp_dir = setup_fixture_repo(Path::new(FIXTURE));
+    let project_root = temp_dir.path();
+    git_add_all_files(project_root);
+    git(project_root, &["commit", "-q", "-m", "initial"]);
+
+    for_file(project_root, &["--at=--output=leaked.txt"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid git ref `--output=leaked.txt`"));
+    assert!(!project_root.join("leaked.txt").exists());
+
+    Ok(())
+}
+
+#[test]
+fn test_for_file_at_requires_from_codeowners() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("codeowners")?
+        .arg("--project-root")
+        .arg(FIXTURE)
+        .arg("for-file")
+        .arg("--at")
+        .arg("HEAD")
+        .arg("ruby/app/models/payroll.rb")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--from-codeowners"));
+
+    Ok(())
+}
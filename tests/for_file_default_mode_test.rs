@@ -0,0 +1,81 @@
+use std::error::Error;
+use std::path::Path;
+use std::process::Command;
+
+use assert_cmd::prelude::*;
+use codeowners::runner::{self, RunConfig};
+use predicates::prelude::*;
+
+mod common;
+use common::{build_run_config, write_file, write_team};
+
+// CODEOWNERS deliberately disagrees with the project's annotation, so the output shows which path resolved the owner
+fn build_project(temp_dir: &Path, for_file_default_mode: &str) -> RunConfig {
+    write_file(
+        temp_dir,
+        "config/code_ownership.yml",
+        format!(
+            "---\nowned_globs:\n  - \"app/**/*.rb\"\nteam_file_glob:\n  - config/teams/**/*.yml\nfor_file_default_mode: {}\n",
+            for_file_default_mode
+        ),
+    );
+    for name in ["Foo", "Bar"] {
+        write_team(temp_dir, name, "");
+    }
+    write_file(temp_dir, "app/report.rb", "# @team Foo\nclass Report; end\n");
+    write_file(
+        temp_dir,
+        ".github/CODEOWNERS",
+        "# Annotations at the top of file\n/app/report.rb @Bar\n",
+    );
+
+    build_run_config(temp_dir, ".github/CODEOWNERS")
+}
+
+#[test]
+fn test_default_mode_derives_owner() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let run_config = build_project(temp_dir.path(), "derived");
+
+    let result = runner::for_file_in_default_mode(&run_config, "app/report.rb", false);
+    assert!(result.info_messages[0].starts_with("Team: Foo\n"), "{:?}", result.info_messages);
+}
+
+#[test]
+fn test_codeowners_mode_reads_codeowners_file() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let run_config = build_project(temp_dir.path(), "codeowners");
+
+    let result = runner::for_file_in_default_mode(&run_config, "app/report.rb", false);
+    assert!(result.info_messages[0].starts_with("Team: Bar\n"), "{:?}", result.info_messages);
+    assert!(result.info_messages[0].contains("Owner inferred from codeowners file"));
+}
+
+#[test]
+fn test_bare_for_file_honors_codeowners_mode() -> Result<(), Box<dyn Error>> {
+    let temp_dir = tempfile::tempdir()?;
+    build_project(temp_dir.path(), "codeowners");
+
+    Command::cargo_bin("codeowners")?
+        .arg("--project-root")
+        .arg(temp_dir.path())
+        .arg("--no-cache")
+        .arg("for-file")
+        .arg("app/report.rb")
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("Team: Bar\n"));
+
+    Command::cargo_bin("codeowners")?
+        .arg("--project-root")
+        .arg(temp_dir.path())
+        .arg("--no-cache")
+        .arg("for-file")
+        .arg("--derived")
+        .arg("app/report.rb")
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("Team: Foo\n"));
+
+    Ok(())
+}
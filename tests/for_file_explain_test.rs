@@ -0,0 +1,109 @@
+use std::error::Error;
+use std::path::Path;
+use std::process::Command;
+
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+
+mod common;
+use common::{git_add_all_files, init_git_repo, write_file};
+
+// `packs/tax/app/w2.rb` is annotated for Payroll inside Tax's package, and `lib/shared.rb` is in both teams' globs
+fn build_project(project_root: &Path) {
+    write_file(
+        project_root,
+        "config/code_ownership.yml",
+        "---\nowned_globs:\n  - \"{packs,lib}/**/*.rb\"\nruby_package_paths:\n  - packs/*\n",
+    );
+    write_file(
+        project_root,
+        "config/teams/payroll.yml",
+        "name: Payroll\ngithub:\n  team: \"@PayrollTeam\"\nowned_globs:\n  - lib/shared.rb\n",
+    );
+    write_file(
+        project_root,
+        "config/teams/tax.yml",
+        "name: Tax\ngithub:\n  team: \"@TaxTeam\"\nowned_globs:\n  - lib/shared.rb\n",
+    );
+    write_file(project_root, "packs/tax/package.yml", "owner: Tax\n");
+    write_file(project_root, "packs/tax/app/w2.rb", "# @team Payroll\nclass W2; end\n");
+    write_file(project_root, "packs/tax/app/form.rb", "class Form; end\n");
+    write_file(project_root, "lib/shared.rb", "module Shared; end\n");
+
+    init_git_repo(project_root);
+    git_add_all_files(project_root);
+}
+
+fn for_file(project_root: &Path, args: &[&str]) -> Result<Command, Box<dyn Error>> {
+    let mut cmd = Command::cargo_bin("codeowners")?;
+    cmd.arg("--project-root")
+        .arg(project_root)
+        .arg("--no-cache")
+        .arg("for-file")
+        .args(args);
+    Ok(cmd)
+}
+
+#[test]
+fn test_explain_lists_the_claims_the_owner_beat() -> Result<(), Box<dyn Error>> {
+    let temp_dir = tempfile::tempdir()?;
+    build_project(temp_dir.path());
+
+    for_file(temp_dir.path(), &["packs/tax/app/w2.rb"])?
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("Error: file is owned by multiple teams!"));
+
+    for_file(temp_dir.path(), &["--explain", "packs/tax/app/w2.rb"])?
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            "Team: Payroll\nGithub Team: @PayrollTeam\nTeam YML: config/teams/payroll.yml\nDescription:\n- Owner annotation at the top of the file\nAlso matched:\n- Tax (priority 3): Owner defined in `packs/tax/package.yml` with implicitly owned glob: `packs/tax/**/**`\n",
+        ));
+
+    for_file(temp_dir.path(), &["--explain", "packs/tax/app/form.rb"])?
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("Team: Tax\n").and(predicate::str::contains("Also matched").not()));
+
+    Ok(())
+}
+
+#[test]
+fn test_explain_json_has_winner_and_also_matched() -> Result<(), Box<dyn Error>> {
+    let temp_dir = tempfile::tempdir()?;
+    build_project(temp_dir.path());
+
+    let output = for_file(temp_dir.path(), &["--explain", "--json", "packs/tax/app/w2.rb"])?.output()?;
+    assert!(output.status.success());
+    let explained: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(explained["winner"]["team_name"], "Payroll");
+    assert_eq!(
+        explained["winner"]["description"],
+        serde_json::json!(["Owner annotation at the top of the file"])
+    );
+    assert_eq!(
+        explained["also_matched"],
+        serde_json::json!([{
+            "team_name": "Tax",
+            "github_team": "@TaxTeam",
+            "source": "Owner defined in `packs/tax/package.yml` with implicitly owned glob: `packs/tax/**/**`",
+            "priority": 3
+        }])
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_explain_still_reports_owners_tied_on_priority() -> Result<(), Box<dyn Error>> {
+    let temp_dir = tempfile::tempdir()?;
+    build_project(temp_dir.path());
+
+    for_file(temp_dir.path(), &["--explain", "lib/shared.rb"])?
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("Error: file is owned by multiple teams!"));
+
+    Ok(())
+}
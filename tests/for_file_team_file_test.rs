@@ -0,0 +1,62 @@
+use assert_cmd::prelude::*;
+use indoc::indoc;
+use predicates::prelude::predicate;
+use std::{error::Error, fs, process::Command};
+
+#[test]
+fn test_for_file_with_team_file_changes_owner() -> Result<(), Box<dyn Error>> {
+    let proposal_dir = tempfile::tempdir()?;
+    let billing_yml = proposal_dir.path().join("billing.yml");
+    fs::write(
+        &billing_yml,
+        "name: Billing\ngithub:\n  team: '@BillingTeam'\nowned_globs:\n  - ruby/app/payments/**/*\n",
+    )?;
+    let payments_yml = proposal_dir.path().join("payments.yml");
+    fs::write(&payments_yml, "name: Payments\ngithub:\n  team: '@PaymentsTeam'\n")?;
+
+    Command::cargo_bin("codeowners")?
+        .arg("--project-root")
+        .arg("tests/fixtures/valid_project")
+        .arg("--no-cache")
+        .arg("for-file")
+        .arg("ruby/app/payments/nacha.rb")
+        .arg("--team-file")
+        .arg(&billing_yml)
+        .arg("--team-file")
+        .arg(&payments_yml)
+        .assert()
+        .success()
+        .stdout(predicate::eq(format!(
+            indoc! {"
+                Team: Billing
+                Github Team: @BillingTeam
+                Team YML: {}
+                Description:
+                - Owner specified in Team YML as an owned_glob `ruby/app/payments/**/*`
+            "},
+            billing_yml.display()
+        )));
+
+    Ok(())
+}
+
+#[test]
+fn test_for_file_with_team_file_replaces_team_of_same_name() -> Result<(), Box<dyn Error>> {
+    let proposal_dir = tempfile::tempdir()?;
+    let payments_yml = proposal_dir.path().join("payments.yml");
+    fs::write(&payments_yml, "name: Payments\ngithub:\n  team: '@PaymentsTeam'\n")?;
+
+    Command::cargo_bin("codeowners")?
+        .arg("--project-root")
+        .arg("tests/fixtures/valid_project")
+        .arg("--no-cache")
+        .arg("for-file")
+        .arg("ruby/app/payments/nacha.rb")
+        .arg("--team-file")
+        .arg(&payments_yml)
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("Team: Unowned"));
+
+    Ok(())
+}
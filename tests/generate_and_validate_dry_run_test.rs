@@ -0,0 +1,71 @@
+use std::{error::Error, path::Path, process::Command};
+
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+
+mod common;
+use common::{git_add_all_files, setup_fixture_repo};
+
+fn dry_run(project_root: &Path, files: &[&str]) -> Result<assert_cmd::assert::Assert, Box<dyn Error>> {
+    Ok(Command::cargo_bin("codeowners")?
+        .arg("--project-root")
+        .arg(project_root)
+        .arg("--no-cache")
+        .arg("generate-and-validate")
+        .arg("--dry-run")
+        .args(files)
+        .assert())
+}
+
+#[test]
+fn test_dry_run_reports_changes_without_writing() -> Result<(), Box<dyn Error>> {
+    let temp_dir = setup_fixture_repo(Path::new("tests/fixtures/valid_project"));
+    let codeowners_path = temp_dir.path().join(".github/CODEOWNERS");
+    std::fs::write(&codeowners_path, "# stale\n")?;
+    git_add_all_files(temp_dir.path());
+
+    dry_run(temp_dir.path(), &[])?
+        .success()
+        .stdout(predicate::str::contains(
+            "Dry run: .github/CODEOWNERS would change; nothing was written",
+        ))
+        .stdout(predicate::str::contains("-# stale"))
+        .stdout(predicate::str::contains("+/ruby/app/models/payroll.rb @PayrollTeam"))
+        .stdout(predicate::str::contains("CODEOWNERS out of date").not());
+    assert_eq!(std::fs::read_to_string(&codeowners_path)?, "# stale\n");
+
+    Ok(())
+}
+
+#[test]
+fn test_dry_run_reports_an_up_to_date_file() -> Result<(), Box<dyn Error>> {
+    let temp_dir = setup_fixture_repo(Path::new("tests/fixtures/valid_project"));
+    git_add_all_files(temp_dir.path());
+
+    dry_run(temp_dir.path(), &[])?
+        .success()
+        .stdout(predicate::str::contains("Dry run: .github/CODEOWNERS is up to date"));
+
+    Ok(())
+}
+
+#[test]
+fn test_dry_run_fails_with_the_validation_status() -> Result<(), Box<dyn Error>> {
+    let temp_dir = setup_fixture_repo(Path::new("tests/fixtures/invalid_project"));
+    git_add_all_files(temp_dir.path());
+    let codeowners_path = temp_dir.path().join(".github/CODEOWNERS");
+    let before = std::fs::read_to_string(&codeowners_path)?;
+
+    dry_run(temp_dir.path(), &[])?
+        .failure()
+        .stdout(predicate::str::contains("would change; nothing was written"))
+        .stdout(predicate::str::contains("- ruby/app/unowned.rb"))
+        .stdout(predicate::str::contains("CODEOWNERS out of date").not());
+    dry_run(temp_dir.path(), &["ruby/app/unowned.rb"])?
+        .failure()
+        .stdout(predicate::str::contains("Unowned files detected:\n  ruby/app/unowned.rb"));
+    dry_run(temp_dir.path(), &["ruby/app/models/payroll.rb"])?.success();
+    assert_eq!(std::fs::read_to_string(&codeowners_path)?, before);
+
+    Ok(())
+}
@@ -0,0 +1,64 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::process::Command;
+
+use codeowners::runner::{self, RunConfig};
+
+mod common;
+use common::{assert_no_run_errors, build_run_config, git_add_all_files, setup_fixture_repo};
+
+fn tracked_files(project_root: &Path) -> Vec<String> {
+    let output = Command::new("git")
+        .arg("ls-files")
+        .current_dir(project_root)
+        .output()
+        .expect("failed to run git ls-files");
+    String::from_utf8(output.stdout).unwrap().lines().map(str::to_owned).collect()
+}
+
+fn github_teams_by_file(run_config: &RunConfig, files: &[String]) -> HashMap<String, Option<String>> {
+    runner::teams_for_files_from_codeowners(run_config, files)
+        .unwrap()
+        .into_iter()
+        .map(|(file, team)| (file, team.map(|team| team.github_team)))
+        .collect()
+}
+
+fn ownership_lines(codeowners: &str) -> usize {
+    codeowners.lines().filter(|line| !line.is_empty() && !line.starts_with('#')).count()
+}
+
+#[test]
+fn test_minimized_codeowners_keeps_every_files_owner() {
+    let temp_dir = setup_fixture_repo(Path::new("tests/fixtures/valid_project"));
+    git_add_all_files(temp_dir.path());
+    let run_config = build_run_config(temp_dir.path(), ".github/CODEOWNERS");
+    let files = tracked_files(&run_config.project_root);
+
+    assert_no_run_errors(&runner::generate(&run_config, false));
+    let expanded = std::fs::read_to_string(run_config.codeowners_file_path.as_ref().unwrap()).unwrap();
+    let expanded_owners = github_teams_by_file(&run_config, &files);
+
+    assert_no_run_errors(&runner::generate_minimized(&run_config, false));
+    let minimized = std::fs::read_to_string(run_config.codeowners_file_path.as_ref().unwrap()).unwrap();
+    let minimized_owners = github_teams_by_file(&run_config, &files);
+
+    assert!(
+        minimized.contains("\n# Minimized ownership\n/config/teams/** @PaymentsTeam\n"),
+        "{}",
+        minimized
+    );
+    assert!(ownership_lines(&minimized) < ownership_lines(&expanded), "{}", minimized);
+    assert!(expanded_owners.values().any(Option::is_some));
+    assert_eq!(minimized_owners, expanded_owners);
+}
+
+#[test]
+fn test_minimize_conflicts_with_sidecar() {
+    assert_cmd::Command::cargo_bin("codeowners")
+        .unwrap()
+        .args(["generate", "--minimize", "--sidecar", "tmp/sidecar.json"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("cannot be used with"));
+}
@@ -0,0 +1,68 @@
+use std::{error::Error, path::Path, process::Command};
+
+use assert_cmd::prelude::*;
+use codeowners::runner;
+
+mod common;
+use common::{build_run_config, git_add_all_files, setup_fixture_repo};
+
+#[test]
+fn test_generate_only_directory_section() -> Result<(), Box<dyn Error>> {
+    let temp_dir = setup_fixture_repo(Path::new("tests/fixtures/valid_project"));
+    git_add_all_files(temp_dir.path());
+    let before = std::fs::read_to_string(temp_dir.path().join(".github/CODEOWNERS"))?;
+
+    let output = Command::cargo_bin("codeowners")?
+        .arg("--project-root")
+        .arg(temp_dir.path())
+        .arg("--no-cache")
+        .arg("generate")
+        .arg("--only")
+        .arg("directory")
+        .output()?;
+    assert!(output.status.success());
+
+    let codeowners = String::from_utf8(output.stdout)?;
+    assert!(codeowners.starts_with("# STOP! - DO NOT EDIT THIS FILE MANUALLY"), "{}", codeowners);
+    assert!(
+        codeowners.contains("# Owner in .codeowner\n/javascript/packages/items/**/** @PayrollTeam\n"),
+        "{}",
+        codeowners
+    );
+    for absent in [
+        "# Annotations at the top of file",
+        "# Team-specific owned globs",
+        "# Owner metadata key in package.yml",
+        "# Team YML ownership",
+        "/ruby/app/models/payroll.rb",
+    ] {
+        assert!(!codeowners.contains(absent), "{} in {}", absent, codeowners);
+    }
+
+    // The partial file is only printed, never written over the CODEOWNERS file
+    assert_eq!(std::fs::read_to_string(temp_dir.path().join(".github/CODEOWNERS"))?, before);
+
+    Ok(())
+}
+
+#[test]
+fn test_generate_only_rejects_unknown_sections() {
+    let temp_dir = setup_fixture_repo(Path::new("tests/fixtures/valid_project"));
+    git_add_all_files(temp_dir.path());
+    let run_config = build_run_config(temp_dir.path(), ".github/CODEOWNERS");
+
+    let result = runner::generate_sections(&run_config, &["packages".to_owned()]);
+    assert_eq!(result.io_errors.len(), 1, "{:?}", result);
+    assert!(
+        result.io_errors[0].starts_with("Unknown section `packages`; expected one of default, extension, annotation"),
+        "{:?}",
+        result
+    );
+
+    let result = runner::generate_sections(&run_config, &["team-glob".to_owned(), "package-yml".to_owned()]);
+    assert!(result.io_errors.is_empty(), "{:?}", result);
+    let codeowners = result.info_messages.join("\n");
+    assert!(codeowners.contains("# Team-specific owned globs"), "{}", codeowners);
+    assert!(codeowners.contains("# Owner metadata key in package.yml"), "{}", codeowners);
+    assert!(!codeowners.contains("# Owner in .codeowner"), "{}", codeowners);
+}
@@ -0,0 +1,58 @@
+use std::{collections::HashSet, error::Error, path::Path};
+
+use codeowners::runner;
+use predicates::prelude::*;
+
+mod common;
+use common::{OutputStream, assert_no_run_errors, build_run_config, git_add_all_files, run_codeowners, setup_fixture_repo};
+
+#[test]
+fn test_generate_sidecar_has_an_entry_per_generated_line() {
+    let temp_dir = setup_fixture_repo(Path::new("tests/fixtures/valid_project"));
+    git_add_all_files(temp_dir.path());
+    let run_config = build_run_config(temp_dir.path(), ".github/CODEOWNERS");
+
+    assert_no_run_errors(&runner::generate_with_sidecar(&run_config, Path::new("tmp/codeowners.json"), false));
+
+    let codeowners = std::fs::read_to_string(temp_dir.path().join(".github/CODEOWNERS")).unwrap();
+    let generated_lines: Vec<&str> = codeowners
+        .lines()
+        .filter(|line| line.starts_with('/') || line.starts_with("# /"))
+        .collect();
+    let sidecar: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(temp_dir.path().join("tmp/codeowners.json")).unwrap()).unwrap();
+    let entries = sidecar["entries"].as_array().unwrap();
+
+    let sidecar_lines: Vec<String> = entries
+        .iter()
+        .map(|entry| {
+            let line = format!("{} {}", entry["glob"].as_str().unwrap(), entry["github_team"].as_str().unwrap());
+            if entry["disabled"].as_bool().unwrap() {
+                format!("# {}", line)
+            } else {
+                line
+            }
+        })
+        .collect();
+    assert!(!sidecar_lines.is_empty());
+    assert_eq!(sidecar_lines, generated_lines);
+
+    let source_kinds: HashSet<&str> = entries.iter().map(|entry| entry["source_kind"].as_str().unwrap()).collect();
+    for kind in ["annotation", "glob", "directory", "package", "yml"] {
+        assert!(source_kinds.contains(kind), "missing {} in {:?}", kind, source_kinds);
+    }
+    let team_yml_entry = entries.iter().find(|entry| entry["source_kind"] == "yml").unwrap();
+    assert!(team_yml_entry["glob"].as_str().unwrap().starts_with("/config/teams/"));
+    assert!(!team_yml_entry["team_name"].as_str().unwrap().is_empty());
+}
+
+#[test]
+fn test_generate_sidecar_cannot_be_combined_with_path() -> Result<(), Box<dyn Error>> {
+    run_codeowners(
+        "valid_project",
+        &["generate", "--path", "ruby", "--sidecar", "codeowners.json"],
+        false,
+        OutputStream::Stderr,
+        predicate::str::contains("cannot be used with"),
+    )
+}
@@ -0,0 +1,80 @@
+use std::path::Path;
+
+use codeowners::runner::{self, RunConfig};
+
+mod common;
+use common::{assert_no_run_errors, build_run_config, write_file, write_team};
+
+fn build_project(temp_dir: &Path) -> RunConfig {
+    write_file(
+        temp_dir,
+        "config/code_ownership.yml",
+        "---\nowned_globs:\n  - \"packs/**/*.rb\"\nteam_file_glob:\n  - config/teams/**/*.yml\n",
+    );
+    for name in ["Foo", "Bar"] {
+        write_team(temp_dir, name, "");
+    }
+    write_file(temp_dir, "packs/foo/package.yml", "owner: Foo\n");
+    write_file(temp_dir, "packs/foo/app/foo.rb", "class Foo; end\n");
+    write_file(temp_dir, "packs/foo/app/shared.rb", "# @team Bar\nclass Shared; end\n");
+    write_file(temp_dir, "packs/bar/.codeowner", "Bar\n");
+    write_file(temp_dir, "packs/bar/app/bar.rb", "class Bar; end\n");
+    write_file(temp_dir, "packs/bar/app/report.rb", "# @team Foo\nclass Report; end\n");
+
+    build_run_config(temp_dir, ".github/CODEOWNERS")
+}
+
+fn read_codeowners(temp_dir: &Path) -> String {
+    std::fs::read_to_string(temp_dir.join(".github/CODEOWNERS")).unwrap()
+}
+
+#[test]
+fn test_generate_subtree_leaves_other_subtrees_intact() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let run_config = build_project(temp_dir.path());
+    assert_no_run_errors(&runner::generate(&run_config, false));
+
+    // Change ownership in both packs, but only regenerate packs/foo
+    write_file(temp_dir.path(), "packs/foo/app/shared.rb", "class Shared; end\n");
+    write_file(temp_dir.path(), "packs/foo/app/new.rb", "# @team Bar\nclass New; end\n");
+    write_file(temp_dir.path(), "packs/bar/app/report.rb", "class Report; end\n");
+    assert_no_run_errors(&runner::generate_subtree(&run_config, Path::new("packs/foo"), false));
+
+    let codeowners = read_codeowners(temp_dir.path());
+    assert!(codeowners.contains("/packs/foo/app/new.rb @Bar"), "{}", codeowners);
+    assert!(!codeowners.contains("/packs/foo/app/shared.rb"), "{}", codeowners);
+    assert!(codeowners.contains("/packs/foo/**/** @Foo"), "{}", codeowners);
+    // packs/bar wasn't regenerated, so its stale annotation line is kept
+    assert!(codeowners.contains("/packs/bar/app/report.rb @Foo"), "{}", codeowners);
+    assert!(codeowners.contains("/packs/bar/**/** @Bar"), "{}", codeowners);
+    assert!(codeowners.contains("/config/teams/foo.yml @Foo"), "{}", codeowners);
+
+    // Regenerating the other subtree too catches the file up with a full generate
+    assert_no_run_errors(&runner::generate_subtree(&run_config, &temp_dir.path().join("packs/bar"), false));
+    let subtree_codeowners = read_codeowners(temp_dir.path());
+    assert_no_run_errors(&runner::generate(&run_config, false));
+    assert_eq!(subtree_codeowners, read_codeowners(temp_dir.path()));
+}
+
+#[test]
+fn test_generate_subtree_falls_back_to_full_generation_without_codeowners_file() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let run_config = build_project(temp_dir.path());
+
+    let result = runner::generate_subtree(&run_config, Path::new("packs/foo"), false);
+    assert!(result.io_errors.is_empty(), "{:?}", result.io_errors);
+    assert_eq!(
+        result.info_messages,
+        vec!["Regenerating the whole CODEOWNERS file because there is no CODEOWNERS file to merge into"]
+    );
+    assert!(read_codeowners(temp_dir.path()).contains("/packs/bar/**/** @Bar"));
+}
+
+#[test]
+fn test_generate_subtree_rejects_missing_directory() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let run_config = build_project(temp_dir.path());
+
+    let result = runner::generate_subtree(&run_config, Path::new("packs/missing"), false);
+    assert_eq!(result.io_errors, vec!["packs/missing is not a directory inside the project"]);
+}
@@ -45,3 +45,33 @@ where
     let staged = is_file_staged(&run_config.project_root, CODEOWNERS_REL);
     assert_eq!(staged, expected_staged, "unexpected staged state for CODEOWNERS");
 }
+
+#[test]
+fn test_generate_reports_missing_git() -> Result<(), Box<dyn std::error::Error>> {
+    use assert_cmd::prelude::*;
+    use predicates::prelude::*;
+
+    let temp_dir = setup_fixture_repo(Path::new(FIXTURE));
+
+    std::process::Command::cargo_bin("codeowners")?
+        .arg("--project-root")
+        .arg(temp_dir.path())
+        .arg("--no-cache")
+        .arg("generate")
+        .env("PATH", "")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("`git` executable not found on PATH"));
+
+    std::process::Command::cargo_bin("codeowners")?
+        .arg("--project-root")
+        .arg(temp_dir.path())
+        .arg("--no-cache")
+        .arg("generate")
+        .arg("--skip-stage")
+        .env("PATH", "")
+        .assert()
+        .success();
+
+    Ok(())
+}
@@ -0,0 +1,42 @@
+use std::path::Path;
+
+use codeowners::runner::{self, RunConfig};
+use tempfile::TempDir;
+
+mod common;
+use common::{assert_no_run_errors, build_run_config, git_add_all_files, setup_fixture_repo};
+
+fn build_project() -> (TempDir, RunConfig) {
+    let temp_dir = setup_fixture_repo(Path::new("tests/fixtures/ignored_owners_project"));
+    git_add_all_files(temp_dir.path());
+    let run_config = build_run_config(temp_dir.path(), ".github/CODEOWNERS");
+    (temp_dir, run_config)
+}
+
+#[test]
+fn test_ignored_owner_annotation_is_skipped_by_generate_and_validate() {
+    let (temp_dir, run_config) = build_project();
+
+    let result = runner::generate_and_validate(&run_config, vec![], false);
+    assert_no_run_errors(&result);
+
+    let codeowners = std::fs::read_to_string(temp_dir.path().join(".github/CODEOWNERS")).unwrap();
+    assert!(!codeowners.contains("report.rb"), "unexpected annotation entry:\n{}", codeowners);
+    assert!(!codeowners.contains("# Annotations at the top of file"));
+    assert!(codeowners.contains("/app/legacy/**/** @Foo"));
+}
+
+#[test]
+fn test_for_file_notes_ignored_owner_annotation() {
+    let (_temp_dir, run_config) = build_project();
+
+    let result = runner::for_file(&run_config, "app/legacy/report.rb", false, false);
+    assert_no_run_errors(&result);
+    assert_eq!(
+        result.info_messages,
+        vec![
+            "Team: Foo\nGithub Team: @Foo\nTeam YML: config/teams/foo.yml\nDescription:\n- Owner specified in `app/legacy/.codeowner`",
+            "Note: The annotation owner `DeprecatedTeam` is listed in `ignored_owners` and does not count towards ownership",
+        ]
+    );
+}
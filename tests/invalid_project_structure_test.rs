@@ -27,3 +27,20 @@ fn test_invalid_project_root() -> Result<(), Box<dyn Error>> {
 
     Ok(())
 }
+
+#[test]
+fn test_duplicate_team_across_team_file_globs() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("codeowners")?
+        .arg("--project-root")
+        .arg("tests/fixtures/duplicate_team")
+        .arg("--no-cache")
+        .arg("validate")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Duplicate team"))
+        .stderr(predicate::str::contains("Team name `Payroll` is declared by both"))
+        .stderr(predicate::str::contains("config/teams/a.yml"))
+        .stderr(predicate::str::contains("config/teams/legacy/a.yml"));
+
+    Ok(())
+}
@@ -72,6 +72,84 @@ fn test_validate() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+#[test]
+fn test_validate_color() -> Result<(), Box<dyn Error>> {
+    run_codeowners(
+        "invalid_project",
+        &["--color", "never", "validate"],
+        false,
+        OutputStream::Stdout,
+        predicate::str::contains("Some files are missing ownership").and(predicate::str::contains("\x1b[").not()),
+    )?;
+
+    run_codeowners(
+        "invalid_project",
+        &["--color", "always", "validate"],
+        false,
+        OutputStream::Stdout,
+        predicate::str::contains("\x1b[1;31mSome files are missing ownership\x1b[0m")
+            .and(predicate::str::contains("\nruby/app/services/multi_owned.rb\n owner: Payments")),
+    )?;
+
+    // JSON stays machine-readable whatever the color choice
+    run_codeowners(
+        "invalid_project",
+        &["--color", "always", "validate", "--json"],
+        false,
+        OutputStream::Stdout,
+        predicate::str::contains("\x1b[").not(),
+    )?;
+
+    Ok(())
+}
+
+#[test]
+fn test_validate_json_lists_every_error() -> Result<(), Box<dyn Error>> {
+    let output = std::process::Command::new(assert_cmd::cargo::cargo_bin("codeowners"))
+        .args([
+            "--project-root",
+            "tests/fixtures/invalid_project",
+            "--no-cache",
+            "validate",
+            "--json",
+        ])
+        .output()?;
+    assert!(!output.status.success());
+
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    let errors = report["errors"].as_array().expect("errors should be an array");
+    let kinds: Vec<&str> = errors.iter().map(|error| error["kind"].as_str().unwrap()).collect();
+    assert_eq!(
+        kinds,
+        vec![
+            "codeownership_file_is_stale",
+            "file_with_multiple_owners",
+            "file_with_multiple_owners",
+            "invalid_team",
+            "file_without_owner",
+        ]
+    );
+    assert_eq!(
+        errors[3],
+        serde_json::json!({
+            "category": "Found invalid team annotations",
+            "kind": "invalid_team",
+            "name": "Web3",
+            "path": "ruby/app/models/blockchain.rb",
+        })
+    );
+    assert_eq!(
+        errors[1]["owners"],
+        serde_json::json!([
+            {"team": "Payments", "sources": ["Owner annotation at the top of the file"]},
+            {"team": "Payroll", "sources": ["Owner specified in Team YML's `owned_gems`"]},
+        ])
+    );
+    assert_eq!(errors[4]["category"], "Some files are missing ownership");
+
+    Ok(())
+}
+
 #[test]
 fn test_for_file() -> Result<(), Box<dyn Error>> {
     run_codeowners(
@@ -84,6 +162,10 @@ fn test_for_file() -> Result<(), Box<dyn Error>> {
             Github Team: Unowned
             Team YML: 
             Description:
+            - annotation references unknown team `Web3`
+            - directory has no .codeowner up to the project root
+            - no package.yml or package.json ancestor
+            - not matched by any team's owned_globs
             "}),
     )?;
     Ok(())
@@ -101,7 +183,8 @@ fn test_for_file_json() -> Result<(), Box<dyn Error>> {
               "team_name": "Unowned",
               "github_team": "Unowned",
               "team_yml": "",
-              "description": []
+              "description": [],
+              "schema_version": 1
             }
             "#}),
     )?;
@@ -143,10 +226,26 @@ fn test_for_file_multiple_owners_json() -> Result<(), Box<dyn Error>> {
         OutputStream::Stdout,
         predicate::eq(indoc! {r#"
             {
-              "validation_errors": [
-                "Error: file is owned by multiple teams!",
-                "\nTeam: Payments\nGithub Team: @PaymentTeam\nTeam YML: config/teams/payments.yml\nDescription:\n- Owner annotation at the top of the file",
-                "\nTeam: Payroll\nGithub Team: @PayrollTeam\nTeam YML: config/teams/payroll.yml\nDescription:\n- Owner specified in `ruby/app/services/.codeowner`"
+              "error": "multiple_owners",
+              "owners": [
+                {
+                  "team_name": "Payments",
+                  "github_team": "@PaymentTeam",
+                  "team_yml": "config/teams/payments.yml",
+                  "description": [
+                    "Owner annotation at the top of the file"
+                  ],
+                  "schema_version": 1
+                },
+                {
+                  "team_name": "Payroll",
+                  "github_team": "@PayrollTeam",
+                  "team_yml": "config/teams/payroll.yml",
+                  "description": [
+                    "Owner specified in `ruby/app/services/.codeowner`"
+                  ],
+                  "schema_version": 1
+                }
               ]
             }
             "#}),
@@ -166,9 +265,97 @@ fn test_for_file_nonexistent_json() -> Result<(), Box<dyn Error>> {
               "team_name": "Unowned",
               "github_team": "Unowned",
               "team_yml": "",
-              "description": []
+              "description": [],
+              "schema_version": 1
             }
             "#}),
     )?;
     Ok(())
 }
+
+#[test]
+fn test_validate_github_annotations() -> Result<(), Box<dyn Error>> {
+    run_codeowners(
+        "invalid_project",
+        &["validate", "--format", "github-annotations"],
+        false,
+        OutputStream::Stdout,
+        predicate::eq(indoc! {"
+            ::error file=gems/payroll_calculator/calculator.rb::File has ownership declared in multiple ways: Payments, Payroll
+            ::error file=ruby/app/models/blockchain.rb,line=1::References an invalid team - 'Web3'
+            ::error file=ruby/app/services/multi_owned.rb::File has ownership declared in multiple ways: Payments, Payroll
+            ::error file=ruby/app/unowned.rb::File is missing ownership
+            ::error::CODEOWNERS out of date. Run `codeowners generate` to update the CODEOWNERS file
+        "}),
+    )?;
+    Ok(())
+}
+
+#[test]
+fn test_validate_sarif() -> Result<(), Box<dyn Error>> {
+    let output = std::process::Command::new(assert_cmd::cargo::cargo_bin("codeowners"))
+        .arg("--project-root")
+        .arg("tests/fixtures/invalid_project")
+        .arg("--no-cache")
+        .args(["validate", "--format", "sarif"])
+        .output()?;
+    assert!(!output.status.success());
+
+    let sarif: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(sarif["version"], "2.1.0");
+    let run = &sarif["runs"][0];
+    let rule_ids: Vec<&str> = run["tool"]["driver"]["rules"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|rule| rule["id"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        rule_ids,
+        vec!["invalid-team", "missing-ownership", "multiple-owners", "stale-codeowners"]
+    );
+
+    let results: Vec<(&str, &str, Option<u64>)> = run["results"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|result| {
+            let location = &result["locations"][0]["physicalLocation"];
+            (
+                result["ruleId"].as_str().unwrap(),
+                location["artifactLocation"]["uri"].as_str().unwrap(),
+                location["region"]["startLine"].as_u64(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        results,
+        vec![
+            ("stale-codeowners", ".github/CODEOWNERS", None),
+            ("multiple-owners", "gems/payroll_calculator/calculator.rb", None),
+            ("multiple-owners", "ruby/app/services/multi_owned.rb", None),
+            ("invalid-team", "ruby/app/models/blockchain.rb", Some(1)),
+            ("missing-ownership", "ruby/app/unowned.rb", None),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_validate_format_github_and_json() -> Result<(), Box<dyn Error>> {
+    run_codeowners(
+        "invalid_project",
+        &["validate", "--format", "github"],
+        false,
+        OutputStream::Stdout,
+        predicate::str::contains("::error file=ruby/app/unowned.rb::File is missing ownership\n"),
+    )?;
+    run_codeowners(
+        "invalid_project",
+        &["validate", "--format", "json"],
+        false,
+        OutputStream::Stdout,
+        predicate::str::contains("\"kind\": \"file_without_owner\"").and(predicate::str::contains("\"path\": \"ruby/app/unowned.rb\"")),
+    )?;
+    Ok(())
+}
@@ -0,0 +1,177 @@
+use std::path::Path;
+
+use codeowners::runner::{self, LintChecks, RunConfig};
+
+mod common;
+use common::{build_run_config, git_add_all_files, init_git_repo, write_file, write_team};
+
+fn build_project(temp_dir: &Path) -> RunConfig {
+    write_file(
+        temp_dir,
+        "config/code_ownership.yml",
+        "---\nowned_globs:\n  - \"app/**/*.rb\"\nteam_file_glob:\n  - config/teams/**/*.yml\n",
+    );
+    write_team(
+        temp_dir,
+        "Foo",
+        "owned_globs:\n  - app/services/**/*.rb\n  - app/removed/**/*.rb\nsubtracted_globs:\n  - app/services/legacy/**/*.rb\n",
+    );
+    write_file(temp_dir, "app/services/billing.rb", "class Billing; end\n");
+    init_git_repo(temp_dir);
+    git_add_all_files(temp_dir);
+    // Present on disk but never committed, so it doesn't keep `app/removed/**/*.rb` alive
+    write_file(temp_dir, "app/removed/untracked.rb", "class Untracked; end\n");
+
+    build_run_config(temp_dir, ".github/CODEOWNERS")
+}
+
+#[test]
+fn test_lint_teams_reports_orphaned_globs() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let run_config = build_project(temp_dir.path());
+
+    let result = runner::lint_teams(&run_config, LintChecks::all(), false);
+    assert!(result.io_errors.is_empty(), "{:?}", result.io_errors);
+    assert_eq!(
+        result.validation_errors,
+        vec![
+            "Found team globs that match no tracked files:",
+            "- config/teams/foo.yml (Foo): owned_globs `app/removed/**/*.rb`",
+            "- config/teams/foo.yml (Foo): subtracted_globs `app/services/legacy/**/*.rb`",
+        ]
+    );
+}
+
+#[test]
+fn test_lint_teams_json() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let run_config = build_project(temp_dir.path());
+
+    let result = runner::lint_teams(&run_config, LintChecks::all(), true);
+    assert!(result.validation_errors.is_empty());
+    let report: serde_json::Value = serde_json::from_str(&result.info_messages[0]).unwrap();
+    assert_eq!(
+        report,
+        serde_json::json!({
+            "orphaned_globs": [
+                {"team": "Foo", "team_yml": "config/teams/foo.yml", "key": "owned_globs", "glob": "app/removed/**/*.rb"},
+                {"team": "Foo", "team_yml": "config/teams/foo.yml", "key": "subtracted_globs", "glob": "app/services/legacy/**/*.rb"}
+            ],
+            "unused_teams": [],
+            "subtracted_globs": []
+        })
+    );
+}
+
+#[test]
+fn test_lint_teams_passes_when_every_glob_matches() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    write_file(temp_dir.path(), "app/services/legacy/old.rb", "class Old; end\n");
+    write_file(temp_dir.path(), "app/removed/kept.rb", "class Kept; end\n");
+    let run_config = build_project(temp_dir.path());
+
+    let result = runner::lint_teams(&run_config, LintChecks::all(), false);
+    assert!(result.validation_errors.is_empty(), "{:?}", result.validation_errors);
+    assert_eq!(
+        result.info_messages,
+        vec![
+            "Every team glob matches at least one tracked file.",
+            "Every team owns at least one file or glob.",
+            "Every subtracted glob removes part of an owned glob."
+        ]
+    );
+}
+
+fn add_unused_team(temp_dir: &Path) {
+    write_team(temp_dir, "Bar", "");
+    git_add_all_files(temp_dir);
+}
+
+#[test]
+fn test_lint_teams_reports_unused_teams() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let run_config = build_project(temp_dir.path());
+    add_unused_team(temp_dir.path());
+
+    let checks = LintChecks {
+        orphaned_globs: false,
+        unused: true,
+        subtracted_globs: false,
+    };
+    let result = runner::lint_teams(&run_config, checks, false);
+    assert!(result.io_errors.is_empty(), "{:?}", result.io_errors);
+    assert_eq!(
+        result.validation_errors,
+        vec![
+            "Found teams that own nothing besides their team file:",
+            "- config/teams/bar.yml (Bar)"
+        ]
+    );
+}
+
+#[test]
+fn test_lint_teams_unused_json_leaves_out_checks_not_run() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let run_config = build_project(temp_dir.path());
+    add_unused_team(temp_dir.path());
+
+    let checks = LintChecks {
+        orphaned_globs: false,
+        unused: true,
+        subtracted_globs: false,
+    };
+    let result = runner::lint_teams(&run_config, checks, true);
+    assert!(result.validation_errors.is_empty());
+    let report: serde_json::Value = serde_json::from_str(&result.info_messages[0]).unwrap();
+    assert_eq!(
+        report,
+        serde_json::json!({
+            "unused_teams": [{"team": "Bar", "team_yml": "config/teams/bar.yml"}]
+        })
+    );
+}
+
+fn lint_subtracted_globs(temp_dir: &Path, team_yml: &str) -> runner::RunResult {
+    let run_config = build_project(temp_dir);
+    write_file(temp_dir, "config/teams/foo.yml", team_yml);
+    let checks = LintChecks {
+        orphaned_globs: false,
+        unused: false,
+        subtracted_globs: true,
+    };
+    runner::lint_teams(&run_config, checks, false)
+}
+
+#[test]
+fn test_lint_teams_reports_subtracted_glob_cancelling_owned_glob() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let result = lint_subtracted_globs(
+        temp_dir.path(),
+        "name: Foo\ngithub:\n  team: \"@Foo\"\nowned_globs:\n  - packs/**\n  - app/**/*.rb\nsubtracted_globs:\n  - packs/**\n",
+    );
+    assert!(result.io_errors.is_empty(), "{:?}", result.io_errors);
+    assert_eq!(
+        result.validation_errors,
+        vec![
+            "Found subtracted_globs that cancel an owned glob or overlap none:",
+            "- config/teams/foo.yml (Foo): subtracted_globs `packs/**` cancels owned_globs `packs/**` entirely",
+        ]
+    );
+}
+
+#[test]
+fn test_lint_teams_reports_redundant_subtracted_glob() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let result = lint_subtracted_globs(
+        temp_dir.path(),
+        "name: Foo\ngithub:\n  team: \"@Foo\"\nowned_globs:\n  - app/**/*.rb\nsubtracted_globs:\n  - app/legacy/**/*.rb\n  - lib/**/*.rb\n",
+    );
+    assert!(result.io_errors.is_empty(), "{:?}", result.io_errors);
+    assert_eq!(
+        result.validation_errors,
+        vec![
+            "Found subtracted_globs that cancel an owned glob or overlap none:",
+            "- config/teams/foo.yml (Foo): subtracted_globs `lib/**/*.rb` overlaps no owned_globs entry",
+        ]
+    );
+}
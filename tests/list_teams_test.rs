@@ -0,0 +1,62 @@
+use std::{error::Error, process::Command};
+
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+
+mod common;
+use common::{OutputStream, run_codeowners};
+
+#[test]
+fn test_list_teams() -> Result<(), Box<dyn Error>> {
+    run_codeowners(
+        "valid_project",
+        &["list-teams"],
+        true,
+        OutputStream::Stdout,
+        predicate::eq(
+            "Payments\t@PaymentsTeam\tconfig/teams/payments.yml\nPayroll\t@PayrollTeam\tconfig/teams/payroll.yml\nUX\t@UX\tconfig/teams/ux.yml\n",
+        ),
+    )
+}
+
+#[test]
+fn test_list_teams_json() -> Result<(), Box<dyn Error>> {
+    let output = Command::cargo_bin("codeowners")?
+        .arg("--project-root")
+        .arg("tests/fixtures/valid_project")
+        .arg("--no-cache")
+        .arg("list-teams")
+        .arg("--json")
+        .output()?;
+    assert!(output.status.success());
+
+    let teams: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(
+        teams,
+        serde_json::json!([
+            {
+                "name": "Payments",
+                "github_team": "@PaymentsTeam",
+                "team_yml": "config/teams/payments.yml",
+                "owned_globs": 1,
+                "owned_gems": 0
+            },
+            {
+                "name": "Payroll",
+                "github_team": "@PayrollTeam",
+                "team_yml": "config/teams/payroll.yml",
+                "owned_globs": 0,
+                "owned_gems": 1
+            },
+            {
+                "name": "UX",
+                "github_team": "@UX",
+                "team_yml": "config/teams/ux.yml",
+                "owned_globs": 0,
+                "owned_gems": 1
+            }
+        ])
+    );
+
+    Ok(())
+}
@@ -0,0 +1,88 @@
+use std::path::Path;
+
+use codeowners::runner::{self, RunConfig};
+
+mod common;
+use common::{assert_no_run_errors, build_run_config, write_file};
+
+// Child's GitHub team is nested under a parent team; files refer to it by that full handle
+fn build_project(temp_dir: &Path, extra_config: &str) -> RunConfig {
+    write_file(
+        temp_dir,
+        "config/code_ownership.yml",
+        format!(
+            "---\nowned_globs:\n  - \"{{app,packs}}/**/*.rb\"\nteam_file_glob:\n  - config/teams/**/*.yml\n{}",
+            extra_config
+        ),
+    );
+    write_file(
+        temp_dir,
+        "config/teams/child.yml",
+        "name: Child\ngithub:\n  team: \"@org/parent/child\"\n  members:\n    - user\n",
+    );
+    write_file(
+        temp_dir,
+        "config/teams/parent.yml",
+        "name: Parent\ngithub:\n  team: \"@org/parent\"\n  members:\n    - user\n",
+    );
+    write_file(temp_dir, "app/models/charge.rb", "# @team @org/parent/child\nclass Charge; end\n");
+    write_file(temp_dir, "packs/refunds/package.yml", "owner: \"@org/parent/child\"\n");
+    write_file(temp_dir, "packs/refunds/app/models/refund.rb", "class Refund; end\n");
+    write_file(temp_dir, "app/services/.codeowner", "@org/parent/child\n");
+    write_file(temp_dir, "app/services/payout.rb", "class Payout; end\n");
+
+    build_run_config(temp_dir, ".github/CODEOWNERS")
+}
+
+#[test]
+fn test_nested_team_handles_own_files() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let run_config = build_project(temp_dir.path(), "");
+
+    assert_no_run_errors(&runner::generate_and_validate(&run_config, vec![], false));
+    let codeowners = std::fs::read_to_string(temp_dir.path().join(".github/CODEOWNERS")).unwrap();
+    for line in [
+        "/app/models/charge.rb @org/parent/child",
+        "/packs/refunds/**/** @org/parent/child",
+        "/app/services/**/** @org/parent/child",
+    ] {
+        assert!(codeowners.contains(line), "missing {}:\n{}", line, codeowners);
+    }
+
+    for file in [
+        "app/models/charge.rb",
+        "packs/refunds/app/models/refund.rb",
+        "app/services/payout.rb",
+    ] {
+        let team = runner::team_for_file(&run_config, file).unwrap().expect(file);
+        assert_eq!(team.name, "Child", "{}", file);
+        assert_eq!(team.github_team, "@org/parent/child", "{}", file);
+    }
+}
+
+#[test]
+fn test_annotation_by_handle_is_consistent_with_package_owner() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let run_config = build_project(temp_dir.path(), "");
+    write_file(
+        temp_dir.path(),
+        "packs/refunds/app/models/refund_reason.rb",
+        "# @team @org/parent/child\nclass RefundReason; end\n",
+    );
+
+    assert_no_run_errors(&runner::generate(&run_config, false));
+    assert_no_run_errors(&runner::validate_consistent_annotations(&run_config));
+}
+
+#[test]
+fn test_annotating_a_team_by_name_and_handle_is_not_a_conflict() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let run_config = build_project(temp_dir.path(), "strict_annotations: true\n");
+    write_file(
+        temp_dir.path(),
+        "app/models/charge.rb",
+        "# @team @org/parent/child\n# @team Child\nclass Charge; end\n",
+    );
+
+    assert_no_run_errors(&runner::generate_and_validate(&run_config, vec![], false));
+}
@@ -0,0 +1,49 @@
+use std::path::Path;
+
+use codeowners::runner::{self, RunConfig};
+use tempfile::TempDir;
+
+mod common;
+use common::{assert_no_run_errors, build_run_config, git_add_all_files, setup_fixture_repo};
+
+fn build_project() -> (TempDir, RunConfig) {
+    let temp_dir = setup_fixture_repo(Path::new("tests/fixtures/nested_package_project"));
+    git_add_all_files(temp_dir.path());
+    let run_config = build_run_config(temp_dir.path(), ".github/CODEOWNERS");
+    (temp_dir, run_config)
+}
+
+fn owners_for(run_config: &RunConfig, file_path: &str) -> Vec<(String, Vec<String>)> {
+    runner::owners_for_file(run_config, file_path)
+        .unwrap()
+        .into_iter()
+        .map(|owner| (owner.team.name, owner.sources.iter().map(|source| source.to_string()).collect()))
+        .collect()
+}
+
+#[test]
+fn test_ownerless_inner_package_inherits_outer_package_owner() {
+    let (temp_dir, run_config) = build_project();
+
+    assert_no_run_errors(&runner::generate_and_validate(&run_config, vec![], false));
+    let codeowners = std::fs::read_to_string(temp_dir.path().join(".github/CODEOWNERS")).unwrap();
+    assert!(codeowners.contains("/packs/payroll/**/** @Payroll"), "{}", codeowners);
+    assert!(!codeowners.contains("/packs/payroll/tax/"), "{}", codeowners);
+
+    let outer_package = "Owner defined in `packs/payroll/package.yml` with implicitly owned glob: `packs/payroll/**/**`".to_owned();
+    assert_eq!(
+        owners_for(&run_config, "packs/payroll/tax/app/tax.rb"),
+        vec![("Payroll".to_owned(), vec![outer_package.clone()])]
+    );
+    assert_eq!(
+        owners_for(&run_config, "packs/payroll/tax/forms/w2.rb"),
+        vec![("Payroll".to_owned(), vec![outer_package])]
+    );
+    assert_eq!(
+        owners_for(&run_config, "frontend/payroll/stubs/index.js"),
+        vec![(
+            "Payroll".to_owned(),
+            vec!["Owner defined in `frontend/payroll/package.json` with implicitly owned glob: `frontend/payroll/**/**`".to_owned()]
+        )]
+    );
+}
@@ -0,0 +1,90 @@
+use std::path::Path;
+
+use codeowners::runner;
+
+mod common;
+use common::{assert_no_run_errors, build_run_config, git_add_all_files, setup_fixture_repo};
+
+const FIXTURE: &str = "tests/fixtures/valid_project";
+const CODEOWNERS_REL: &str = ".github/CODEOWNERS";
+
+// Reverses both the sections and the lines within each section, leaving the disclaimer alone
+fn shuffle(codeowners: &str) -> String {
+    let (disclaimer, body) = codeowners.split_once("\n\n\n").unwrap();
+    let mut sections: Vec<Vec<&str>> = body
+        .split("\n\n")
+        .map(|section| {
+            let mut lines: Vec<&str> = section.lines().collect();
+            lines[1..].reverse();
+            lines
+        })
+        .collect();
+    sections.reverse();
+    let body = sections.iter().map(|lines| lines.join("\n")).collect::<Vec<_>>().join("\n\n");
+    format!("{}\n\n\n{}", disclaimer, body)
+}
+
+#[test]
+fn test_normalize_restores_canonical_order() {
+    let temp_dir = setup_fixture_repo(Path::new(FIXTURE));
+    git_add_all_files(temp_dir.path());
+    let run_config = build_run_config(temp_dir.path(), CODEOWNERS_REL);
+    let codeowners_path = temp_dir.path().join(CODEOWNERS_REL);
+    let canonical = std::fs::read_to_string(&codeowners_path).unwrap();
+
+    let shuffled = shuffle(&canonical);
+    assert_ne!(shuffled, canonical);
+    std::fs::write(&codeowners_path, &shuffled).unwrap();
+    assert!(!runner::validate(&run_config, vec![]).validation_errors.is_empty());
+
+    assert_no_run_errors(&runner::normalize(&run_config, false));
+    assert_eq!(std::fs::read_to_string(&codeowners_path).unwrap(), canonical);
+    assert_no_run_errors(&runner::validate(&run_config, vec![]));
+}
+
+#[test]
+fn test_normalize_to_stdout_leaves_file_untouched() {
+    let temp_dir = setup_fixture_repo(Path::new(FIXTURE));
+    let run_config = build_run_config(temp_dir.path(), CODEOWNERS_REL);
+    let codeowners_path = temp_dir.path().join(CODEOWNERS_REL);
+    let canonical = std::fs::read_to_string(&codeowners_path).unwrap();
+    let shuffled = shuffle(&canonical);
+    std::fs::write(&codeowners_path, &shuffled).unwrap();
+
+    let result = runner::normalize(&run_config, true);
+    assert_no_run_errors(&result);
+    assert_eq!(result.info_messages, vec![canonical.trim_end().to_owned()]);
+    assert_eq!(std::fs::read_to_string(&codeowners_path).unwrap(), shuffled);
+}
+
+#[test]
+fn test_normalize_drops_free_form_comments_inside_sections() {
+    let temp_dir = setup_fixture_repo(Path::new(FIXTURE));
+    git_add_all_files(temp_dir.path());
+    let run_config = build_run_config(temp_dir.path(), CODEOWNERS_REL);
+    let codeowners_path = temp_dir.path().join(CODEOWNERS_REL);
+    let canonical = std::fs::read_to_string(&codeowners_path).unwrap();
+
+    // A note under every section heading and another after each section's first entry, leaving the disclaimer alone
+    let (disclaimer, body) = canonical.split_once("\n\n\n").unwrap();
+    let mut commented = Vec::new();
+    let mut previous = "";
+    for line in body.lines() {
+        commented.push(line.to_owned());
+        if line.starts_with("# ") && previous.is_empty() {
+            commented.push("# Reviewed by the platform team".to_owned());
+        } else if line.starts_with('/') && previous.starts_with('#') {
+            commented.push("# Keep in sync with the team handbook".to_owned());
+        }
+        previous = line;
+    }
+    let commented = format!("{}\n\n\n{}\n", disclaimer, commented.join("\n"));
+    std::fs::write(&codeowners_path, &commented).unwrap();
+
+    let owner_before = runner::team_for_file_from_codeowners(&run_config, "ruby/app/models/payroll.rb").unwrap();
+    assert_no_run_errors(&runner::normalize(&run_config, false));
+    assert_eq!(std::fs::read_to_string(&codeowners_path).unwrap(), canonical);
+    assert_no_run_errors(&runner::validate(&run_config, vec![]));
+    let owner_after = runner::team_for_file_from_codeowners(&run_config, "ruby/app/models/payroll.rb").unwrap();
+    assert_eq!(owner_before.map(|team| team.name), owner_after.map(|team| team.name));
+}
@@ -0,0 +1,33 @@
+use std::path::Path;
+
+use codeowners::runner::{self, RunConfig};
+use tempfile::TempDir;
+
+mod common;
+use common::{assert_no_run_errors, build_run_config, git_add_all_files, setup_fixture_repo};
+
+fn build_project() -> (TempDir, RunConfig) {
+    let temp_dir = setup_fixture_repo(Path::new("tests/fixtures/owned_extensions_in_project"));
+    git_add_all_files(temp_dir.path());
+    let run_config = build_run_config(temp_dir.path(), ".github/CODEOWNERS");
+    (temp_dir, run_config)
+}
+
+#[test]
+fn test_team_owns_only_listed_extensions_in_dir() {
+    let (_temp_dir, run_config) = build_project();
+
+    assert_no_run_errors(&runner::generate_and_validate(&run_config, vec![], false));
+    let codeowners = std::fs::read_to_string(run_config.codeowners_file_path.as_ref().unwrap()).unwrap();
+    assert!(codeowners.contains("/config/locales/**/*.yml @I18n"), "{}", codeowners);
+    assert!(codeowners.contains("/config/locales/**/*.yaml @I18n"), "{}", codeowners);
+
+    for (file, team) in [
+        ("config/locales/en/app.yml", "I18n"),
+        ("config/locales/fr.yaml", "I18n"),
+        ("config/locales/loader.rb", "Platform"),
+    ] {
+        let owner = runner::team_for_file(&run_config, file).unwrap().unwrap();
+        assert_eq!(owner.name, team, "{}", file);
+    }
+}
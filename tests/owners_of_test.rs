@@ -0,0 +1,61 @@
+use indoc::indoc;
+use predicates::prelude::*;
+use std::{error::Error, path::Path};
+
+use codeowners::runner;
+
+mod common;
+use common::{OutputStream, build_run_config, run_codeowners};
+
+#[test]
+fn test_owners_of_streams_team_files() -> Result<(), Box<dyn Error>> {
+    run_codeowners(
+        "valid_project",
+        &["owners-of", "Payroll"],
+        true,
+        OutputStream::Stdout,
+        predicate::eq(indoc! {"
+            config/teams/payroll.yml
+            gems/payroll_calculator/calculator.rb
+            javascript/packages/PayrollFlow/index.tsx
+            ruby/app/models/payroll.rb
+            ruby/app/payments/foo/ownedby_payroll.rb
+            ruby/app/payroll/payroll.rb
+            ruby/app/views/foos/edit.erb
+            ruby/app/views/foos/new.html.erb
+        "}),
+    )?;
+
+    Ok(())
+}
+
+#[test]
+fn test_owners_of_unknown_team() -> Result<(), Box<dyn Error>> {
+    run_codeowners(
+        "valid_project",
+        &["owners-of", "Nope"],
+        false,
+        OutputStream::Stderr,
+        predicate::str::contains("Team not found: Nope"),
+    )?;
+
+    Ok(())
+}
+
+#[test]
+fn test_stream_team_files_calls_back_per_file() {
+    let run_config = build_run_config(Path::new("tests/fixtures/valid_project"), ".github/CODEOWNERS");
+
+    let mut streamed = Vec::new();
+    let result = runner::stream_team_files(&run_config, "Payments", |file| streamed.push(file.to_owned()));
+    common::assert_no_run_errors(&result);
+    assert_eq!(
+        streamed,
+        vec![
+            "config/teams/payments.yml",
+            "javascript/packages/list/page-admin.tsx",
+            "ruby/app/models/bank_account.rb",
+            "ruby/app/payments/nacha.rb",
+        ]
+    );
+}
@@ -0,0 +1,139 @@
+use std::path::Path;
+
+use codeowners::runner::{self, RunConfig};
+
+mod common;
+use common::{assert_no_run_errors, build_run_config, write_file, write_team};
+
+fn build_project(temp_dir: &Path, overrides: &str) -> RunConfig {
+    write_file(
+        temp_dir,
+        "config/code_ownership.yml",
+        format!(
+            "---\nowned_globs:\n  - \"{{app,packs}}/**/*.rb\"\nteam_file_glob:\n  - config/teams/**/*.yml\noverrides:\n{}",
+            overrides
+        ),
+    );
+    for name in ["Payments", "Payroll", "Platform"] {
+        write_team(temp_dir, name, "");
+    }
+    write_file(temp_dir, "packs/payroll/package.yml", "owner: Payroll\n");
+    write_file(temp_dir, "packs/payroll/app/models/pay.rb", "class Pay; end\n");
+    write_file(
+        temp_dir,
+        "packs/payroll/app/legacy/old_pay.rb",
+        "# @team Payments\nclass OldPay; end\n",
+    );
+
+    build_run_config(temp_dir, ".github/CODEOWNERS")
+}
+
+fn owner_and_sources(run_config: &RunConfig, file_path: &str) -> Vec<(String, Vec<String>)> {
+    runner::owners_for_file(run_config, file_path)
+        .unwrap()
+        .into_iter()
+        .map(|owner| (owner.team.name, owner.sources.iter().map(|source| source.to_string()).collect()))
+        .collect()
+}
+
+#[test]
+fn test_override_beats_package_owner_and_annotation() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let run_config = build_project(temp_dir.path(), "  - glob: packs/payroll/app/legacy/**\n    team: Platform\n");
+
+    assert_eq!(
+        owner_and_sources(&run_config, "packs/payroll/app/legacy/old_pay.rb"),
+        vec![(
+            "Platform".to_owned(),
+            vec!["Owner overridden by `packs/payroll/app/legacy/**` in the config's `overrides`".to_owned()]
+        )]
+    );
+    // Files the override doesn't match keep their package owner
+    assert_eq!(
+        owner_and_sources(&run_config, "packs/payroll/app/models/pay.rb")[0].0,
+        "Payroll".to_owned()
+    );
+}
+
+#[test]
+fn test_overrides_are_the_last_codeowners_section_and_validate() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let run_config = build_project(temp_dir.path(), "  - glob: packs/payroll/app/legacy/**\n    team: Platform\n");
+
+    assert_no_run_errors(&runner::generate(&run_config, false));
+    let codeowners = std::fs::read_to_string(temp_dir.path().join(".github/CODEOWNERS")).unwrap();
+    assert!(
+        codeowners.ends_with("# Ownership overrides\n/packs/payroll/app/legacy/** @Platform\n"),
+        "{}",
+        codeowners
+    );
+
+    // The override's file isn't reported as owned by the package and its annotation too
+    assert_no_run_errors(&runner::validate(&run_config, vec![]));
+}
+
+#[test]
+fn test_most_specific_override_wins() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let run_config = build_project(
+        temp_dir.path(),
+        "  - glob: packs/**\n    team: Platform\n  - glob: packs/payroll/app/legacy/**\n    team: Payroll\n",
+    );
+
+    assert_eq!(
+        owner_and_sources(&run_config, "packs/payroll/app/legacy/old_pay.rb")[0].0,
+        "Payroll".to_owned()
+    );
+    assert_eq!(
+        owner_and_sources(&run_config, "packs/payroll/app/models/pay.rb")[0].0,
+        "Platform".to_owned()
+    );
+
+    assert_no_run_errors(&runner::generate(&run_config, false));
+    assert_no_run_errors(&runner::validate(&run_config, vec![]));
+}
+
+#[test]
+fn test_equally_specific_overrides_for_different_teams_conflict() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let run_config = build_project(
+        temp_dir.path(),
+        "  - glob: packs/payroll/app/legacy/*.rb\n    team: Platform\n  - glob: packs/payroll/app/legacy/*_pay.rb\n    team: Payroll\n",
+    );
+
+    let owners: Vec<String> = owner_and_sources(&run_config, "packs/payroll/app/legacy/old_pay.rb")
+        .into_iter()
+        .map(|(team, _)| team)
+        .collect();
+    assert_eq!(owners, vec!["Payroll".to_owned(), "Platform".to_owned()]);
+
+    assert_no_run_errors(&runner::generate(&run_config, false));
+    let result = runner::validate(&run_config, vec![]);
+    assert!(
+        result
+            .validation_errors
+            .iter()
+            .any(|error| error.contains("packs/payroll/app/legacy/old_pay.rb")
+                && error.contains("Owner overridden by `packs/payroll/app/legacy/*.rb`")
+                && error.contains("Owner overridden by `packs/payroll/app/legacy/*_pay.rb`")),
+        "{:?}",
+        result.validation_errors
+    );
+}
+
+#[test]
+fn test_override_for_unknown_team_is_invalid() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let run_config = build_project(temp_dir.path(), "  - glob: packs/**\n    team: Nobody\n");
+
+    assert_no_run_errors(&runner::generate(&run_config, false));
+    let result = runner::validate(&run_config, vec![]);
+    assert!(
+        result
+            .validation_errors
+            .iter()
+            .any(|error| error.contains("overrides.packs/** is referencing an invalid team - 'Nobody'")),
+        "{:?}",
+        result.validation_errors
+    );
+}
@@ -0,0 +1,70 @@
+use std::path::Path;
+
+use codeowners::runner::{self, RunConfig};
+
+mod common;
+use common::{assert_no_run_errors, build_run_config, write_file, write_team};
+
+fn build_project(temp_dir: &Path, config_extra: &str) -> RunConfig {
+    write_file(
+        temp_dir,
+        "config/code_ownership.yml",
+        format!(
+            "---\nowned_globs:\n  - \"**/*.{{js,ts}}\"\njavascript_package_paths:\n  - \"frontend/packages/**\"\nteam_file_glob:\n  - config/teams/**/*.yml\n{}",
+            config_extra
+        ),
+    );
+    for team in ["Payroll", "Infra"] {
+        write_team(temp_dir, team, "");
+    }
+    write_file(
+        temp_dir,
+        "frontend/packages/paystubs/package.json",
+        r#"{"name": "paystubs", "maintainers": ["Payroll <payroll@example.com>", "Infra"]}"#,
+    );
+    write_file(temp_dir, "frontend/packages/paystubs/index.js", "export default {};\n");
+    write_file(
+        temp_dir,
+        "frontend/packages/build/package.json",
+        r#"{"name": "build", "metadata": {"owner": "Infra"}, "maintainers": [{"name": "Payroll"}]}"#,
+    );
+    write_file(temp_dir, "frontend/packages/build/index.ts", "export {};\n");
+
+    build_run_config(temp_dir, ".github/CODEOWNERS")
+}
+
+fn team_for(run_config: &RunConfig, file_path: &str) -> Vec<String> {
+    runner::owners_for_file(run_config, file_path)
+        .unwrap()
+        .into_iter()
+        .map(|owner| owner.team.name)
+        .collect()
+}
+
+#[test]
+fn test_owner_field_maps_first_maintainer_to_owner() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let run_config = build_project(temp_dir.path(), "owner_field: maintainers\n");
+
+    assert_no_run_errors(&runner::generate_and_validate(&run_config, vec![], false));
+    let codeowners = std::fs::read_to_string(temp_dir.path().join(".github/CODEOWNERS")).unwrap();
+    assert!(codeowners.contains("/frontend/packages/paystubs/**/** @Payroll"), "{}", codeowners);
+    // `metadata.owner` still wins over the configured field
+    assert!(codeowners.contains("/frontend/packages/build/**/** @Infra"), "{}", codeowners);
+
+    assert_eq!(team_for(&run_config, "frontend/packages/paystubs/index.js"), vec!["Payroll"]);
+    assert_eq!(team_for(&run_config, "frontend/packages/build/index.ts"), vec!["Infra"]);
+}
+
+#[test]
+fn test_maintainers_are_ignored_without_owner_field() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let run_config = build_project(temp_dir.path(), "");
+
+    assert!(team_for(&run_config, "frontend/packages/paystubs/index.js").is_empty());
+    assert_eq!(team_for(&run_config, "frontend/packages/build/index.ts"), vec!["Infra"]);
+
+    let mut run_config = run_config;
+    run_config.config_overrides = vec!["owner_field=maintainers".to_owned()];
+    assert_eq!(team_for(&run_config, "frontend/packages/paystubs/index.js"), vec!["Payroll"]);
+}
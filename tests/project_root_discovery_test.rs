@@ -0,0 +1,81 @@
+use assert_cmd::prelude::*;
+use indoc::indoc;
+use predicates::prelude::predicate;
+use std::{error::Error, path::Path, process::Command};
+
+mod common;
+
+use common::{git_add_all_files, setup_fixture_repo};
+
+#[test]
+fn test_discovers_project_root_from_nested_directory() -> Result<(), Box<dyn Error>> {
+    let temp_dir = setup_fixture_repo(Path::new("tests/fixtures/valid_project"));
+    let project_root = temp_dir.path();
+    git_add_all_files(project_root);
+
+    Command::cargo_bin("codeowners")?
+        .current_dir(project_root.join("ruby/app/models"))
+        .arg("--no-cache")
+        .arg("for-file")
+        .arg("ruby/app/models/payroll.rb")
+        .assert()
+        .success()
+        .stdout(predicate::eq(indoc! {"
+            Team: Payroll
+            Github Team: @PayrollTeam
+            Team YML: config/teams/payroll.yml
+            Description:
+            - Owner annotation at the top of the file
+        "}));
+
+    Command::cargo_bin("codeowners")?
+        .current_dir(project_root.join("javascript/packages"))
+        .arg("--no-cache")
+        .arg("validate")
+        .assert()
+        .success()
+        .stdout(predicate::eq(""));
+
+    Ok(())
+}
+
+#[test]
+fn test_explicit_project_root_skips_discovery() -> Result<(), Box<dyn Error>> {
+    let temp_dir = setup_fixture_repo(Path::new("tests/fixtures/valid_project"));
+    let project_root = temp_dir.path();
+    git_add_all_files(project_root);
+
+    Command::cargo_bin("codeowners")?
+        .current_dir(project_root.join("ruby/app/models"))
+        .arg("--project-root")
+        .arg(".")
+        .arg("--no-cache")
+        .arg("validate")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Can't open config file"));
+
+    Ok(())
+}
+
+#[test]
+fn test_absolute_config_path_sets_the_project_root() -> Result<(), Box<dyn Error>> {
+    let temp_dir = setup_fixture_repo(Path::new("tests/fixtures/valid_project"));
+    let project_root = temp_dir.path();
+    git_add_all_files(project_root);
+    let elsewhere = tempfile::tempdir()?;
+
+    // Run from a directory outside the project, which discovery from the current directory would never find
+    Command::cargo_bin("codeowners")?
+        .current_dir(elsewhere.path())
+        .arg("--config-path")
+        .arg(project_root.join("config/code_ownership.yml"))
+        .arg("--no-cache")
+        .arg("for-file")
+        .arg("ruby/app/models/payroll.rb")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Team: Payroll"));
+
+    Ok(())
+}
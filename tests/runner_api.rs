@@ -2,11 +2,8 @@ use std::path::Path;
 
 use codeowners::runner::{self, RunConfig};
 
-fn write_file(temp_dir: &Path, file_path: &str, content: &str) {
-    let file_path = temp_dir.join(file_path);
-    let _ = std::fs::create_dir_all(file_path.parent().unwrap());
-    std::fs::write(file_path, content).unwrap();
-}
+mod common;
+use common::{build_run_config, write_file, write_team};
 
 #[test]
 fn test_file_owners_for_file() {
@@ -33,13 +30,7 @@ team_file_glob:
         "# @team b\nclass DeepFile end;",
     );
 
-    let run_config = RunConfig {
-        project_root: temp_dir.path().to_path_buf(),
-        codeowners_file_path: Some(temp_dir.path().join(".github/CODEOWNERS").to_path_buf()),
-        config_path: temp_dir.path().join("config/code_ownership.yml").to_path_buf(),
-        no_cache: true,
-        executable_name: None,
-    };
+    let run_config = build_run_config(temp_dir.path(), ".github/CODEOWNERS");
 
     let file_owner = runner::file_owner_for_file(&run_config, "app/consumers/deep/nesting/nestdir/deep_file.rb")
         .unwrap()
@@ -49,6 +40,47 @@ team_file_glob:
     assert!(file_owner.team.path.to_string_lossy().ends_with("config/teams/b.yml"));
 }
 
+#[test]
+fn test_owners_for_files_matches_owners_for_file() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    write_file(
+        temp_dir.path(),
+        "config/code_ownership.yml",
+        "---\nowned_globs:\n  - \"{app,packs}/**/*.rb\"\nteam_file_glob:\n  - config/teams/**/*.yml\n",
+    );
+    for name in ["Foo", "Bar"] {
+        let team_yml = format!("name: {}\ngithub:\n  team: \"@{}\"\n  members:\n    - user\n", name, name);
+        write_file(temp_dir.path(), &format!("config/teams/{}.yml", name.to_lowercase()), &team_yml);
+    }
+    write_file(temp_dir.path(), "packs/foo/package.yml", "owner: Foo\n");
+    write_file(temp_dir.path(), "app/bar/.codeowner", "Bar\n");
+    let mut file_paths = Vec::new();
+    for i in 0..10 {
+        for dir in ["packs/foo/app", "app/bar"] {
+            let file_path = format!("{}/file_{}.rb", dir, i);
+            write_file(temp_dir.path(), &file_path, "class File; end\n");
+            file_paths.push(file_path);
+        }
+    }
+    write_file(temp_dir.path(), "app/bar/annotated.rb", "# @team Foo\nclass Annotated; end\n");
+    file_paths.push("app/bar/annotated.rb".to_owned());
+    file_paths.push("app/unowned.rb".to_owned());
+
+    let run_config = build_run_config(temp_dir.path(), ".github/CODEOWNERS");
+
+    let owners_by_file = runner::owners_for_files(&run_config, &file_paths).unwrap();
+    assert_eq!(owners_by_file.len(), file_paths.len());
+    for file_path in &file_paths {
+        let owners_alone = runner::owners_for_file(&run_config, file_path).unwrap();
+        let display = |owners: &[codeowners::ownership::FileOwner]| owners.iter().map(|o| o.to_string()).collect::<Vec<_>>();
+        assert_eq!(display(&owners_by_file[file_path]), display(&owners_alone), "{}", file_path);
+    }
+    assert_eq!(owners_by_file["packs/foo/app/file_3.rb"][0].team.name, "Foo");
+    assert_eq!(owners_by_file["app/bar/file_3.rb"][0].team.name, "Bar");
+    assert_eq!(owners_by_file["app/bar/annotated.rb"][0].team.name, "Foo");
+    assert!(owners_by_file["app/unowned.rb"].is_empty());
+}
+
 #[test]
 fn test_teams_for_files_from_codeowners() {
     let project_root = Path::new("tests/fixtures/valid_project");
@@ -58,17 +90,12 @@ fn test_teams_for_files_from_codeowners() {
         "ruby/app/models/bank_account.rb",
         "made/up/file.rb",
         "ruby/ignored_files/git_ignored.rb",
+        "ruby\\app\\models\\payroll.rb",
     ];
-    let run_config = RunConfig {
-        project_root: project_root.to_path_buf(),
-        codeowners_file_path: Some(project_root.join(".github/CODEOWNERS").to_path_buf()),
-        config_path: project_root.join("config/code_ownership.yml").to_path_buf(),
-        no_cache: true,
-        executable_name: None,
-    };
+    let run_config = build_run_config(project_root, ".github/CODEOWNERS");
     let teams =
         runner::teams_for_files_from_codeowners(&run_config, &file_paths.iter().map(|s| s.to_string()).collect::<Vec<String>>()).unwrap();
-    assert_eq!(teams.len(), 5);
+    assert_eq!(teams.len(), 6);
     assert_eq!(
         teams
             .get("javascript/packages/items/item.ts")
@@ -90,6 +117,15 @@ fn test_teams_for_files_from_codeowners() {
         Some("Payments")
     );
     assert_eq!(teams.get("made/up/file.rb").unwrap().as_ref().map(|t| t.name.as_str()), None);
+    // `\` only separates directories on Windows; elsewhere it's part of the file name
+    assert_eq!(
+        teams
+            .get("ruby\\app\\models\\payroll.rb")
+            .unwrap()
+            .as_ref()
+            .map(|t| t.name.as_str()),
+        if cfg!(windows) { Some("Payroll") } else { None }
+    );
     assert_eq!(
         teams
             .get("ruby/ignored_files/git_ignored.rb")
@@ -100,6 +136,72 @@ fn test_teams_for_files_from_codeowners() {
     );
 }
 
+#[test]
+fn test_for_file_all_codeowners_patterns() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    write_file(
+        temp_dir.path(),
+        "config/code_ownership.yml",
+        "---\nowned_globs:\n  - \"app/**/*.rb\"\nteam_file_glob:\n  - config/teams/**/*.yml\n",
+    );
+    write_file(
+        temp_dir.path(),
+        ".github/CODEOWNERS",
+        "# Team-specific owned globs\n/app/** @Platform\n/app/models/*.rb @Models\n/lib/** @Lib\n\n# Owner in .codeowner\n/app/models/**/** @Deep\n",
+    );
+    let run_config = build_run_config(temp_dir.path(), ".github/CODEOWNERS");
+
+    let result = runner::for_file_all_codeowners_patterns(&run_config, "app/models/user.rb", false);
+    assert_eq!(
+        result.info_messages,
+        vec![
+            "CODEOWNERS patterns matching app/models/user.rb, in file order:\n2: /app/** @Platform\n3: /app/models/*.rb @Models\n7: /app/models/**/** @Deep (applied by GitHub)"
+        ]
+    );
+
+    let result = runner::for_file_all_codeowners_patterns(&run_config, "./app/../app/models/user.rb", true);
+    let json: serde_json::Value = serde_json::from_str(&result.info_messages[0]).unwrap();
+    let line_numbers: Vec<u64> = json["matches"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|m| m["line_number"].as_u64().unwrap())
+        .collect();
+    assert_eq!(line_numbers, vec![2, 3, 7]);
+    assert_eq!(json["matches"][1]["owners"], serde_json::json!(["@Models"]));
+
+    let result = runner::for_file_all_codeowners_patterns(&run_config, "config/routes.rb", false);
+    assert_eq!(result.info_messages, vec!["No CODEOWNERS pattern matches config/routes.rb"]);
+}
+
+#[test]
+fn test_team_for_deleted_file_from_codeowners() {
+    let td = tempfile::tempdir().unwrap();
+    write_file(
+        td.path(),
+        "config/code_ownership.yml",
+        "---\nowned_globs:\n  - \"app/**/*.rb\"\nteam_file_glob:\n  - config/teams/**/*.yml\n",
+    );
+    write_team(td.path(), "Foo", "");
+    write_file(td.path(), "app/billing/.codeowner", "Foo\n");
+    write_file(td.path(), "app/billing/invoice.rb", "class Invoice; end\n");
+
+    let rc = build_run_config(td.path(), ".github/CODEOWNERS");
+    assert!(runner::generate(&rc, false).io_errors.is_empty());
+
+    // As in a pre-receive hook: the file (and its directory) are gone, but CODEOWNERS still knows the owner
+    std::fs::remove_dir_all(td.path().join("app/billing")).unwrap();
+    let absolute_path = td.path().join("app/billing/invoice.rb").to_string_lossy().into_owned();
+    for file_path in ["app/billing/invoice.rb", "./app/billing/invoice.rb", absolute_path.as_str()] {
+        let team = runner::team_for_file_from_codeowners(&rc, file_path).unwrap();
+        assert_eq!(team.map(|team| team.name), Some("Foo".to_owned()), "{}", file_path);
+    }
+
+    let res = runner::for_file(&rc, "app/billing/invoice.rb", true, false);
+    assert!(res.io_errors.is_empty(), "unexpected io errors: {:?}", res.io_errors);
+    assert!(res.info_messages[0].starts_with("Team: Foo\n"), "{:?}", res.info_messages);
+}
+
 #[test]
 fn test_for_team_reads_codeowners() {
     let td = tempfile::tempdir().unwrap();
@@ -118,21 +220,11 @@ javascript_package_paths:
     write_file(td.path(), "config/code_ownership.yml", DEFAULT_CODE_OWNERSHIP_YML);
 
     // team file for Foo
-    write_file(
-        td.path(),
-        "config/teams/foo.yml",
-        "name: Foo\ngithub:\n  team: \"@Foo\"\n  members:\n    - user\n",
-    );
+    write_team(td.path(), "Foo", "");
     // provide a CODEOWNERS file referencing @Foo
     write_file(td.path(), ".github/CODEOWNERS", "/app/** @Foo\n");
 
-    let rc = RunConfig {
-        project_root: td.path().to_path_buf(),
-        codeowners_file_path: Some(td.path().join(".github/CODEOWNERS")),
-        config_path: td.path().join("config/code_ownership.yml"),
-        no_cache: true,
-        executable_name: None,
-    };
+    let rc = build_run_config(td.path(), ".github/CODEOWNERS");
 
     // Ensure CODEOWNERS file matches generator output to avoid out-of-date errors
     let _ = runner::generate(&rc, false);
@@ -160,21 +252,15 @@ javascript_package_paths:
   - javascript/packages/**
 "#;
     write_file(td.path(), "config/code_ownership.yml", DEFAULT_CODE_OWNERSHIP_YML);
-    write_file(
+    write_team(
         td.path(),
-        "config/teams/foo.yml",
-        "name: Foo\ngithub:\n  team: \"@Foo\"\n  members:\n    - user\nowned_globs:\n  - \"app/**\"\n  - \"config/code_ownership.yml\"\n",
+        "Foo",
+        "owned_globs:\n  - \"app/**\"\n  - \"config/code_ownership.yml\"\n",
     );
     // create a file to be matched (no annotation to avoid multi-source ownership)
     write_file(td.path(), "app/x.rb", "puts :x\n");
 
-    let rc = RunConfig {
-        project_root: td.path().to_path_buf(),
-        codeowners_file_path: Some(td.path().join(".github/CODEOWNERS")),
-        config_path: td.path().join("config/code_ownership.yml"),
-        no_cache: true,
-        executable_name: None,
-    };
+    let rc = build_run_config(td.path(), ".github/CODEOWNERS");
 
     let gv = runner::generate_and_validate(&rc, vec![], true);
     assert!(gv.io_errors.is_empty(), "io: {:?}", gv.io_errors);
@@ -183,3 +269,136 @@ javascript_package_paths:
     let content = std::fs::read_to_string(td.path().join(".github/CODEOWNERS")).unwrap();
     assert!(!content.is_empty());
 }
+
+#[test]
+fn test_package_discovery_anywhere_finds_packages_outside_package_paths() {
+    let td = tempfile::tempdir().unwrap();
+    const CODE_OWNERSHIP_YML: &str = r#"---
+owned_globs:
+  - "lib/**/*.rb"
+ruby_package_paths:
+  - packs/**/*
+package_discovery: anywhere
+team_file_glob:
+  - config/teams/**/*.yml
+"#;
+    write_file(td.path(), "config/code_ownership.yml", CODE_OWNERSHIP_YML);
+    write_team(td.path(), "Foo", "");
+    write_file(td.path(), "lib/engines/billing/package.yml", "owner: Foo\n");
+    write_file(td.path(), "lib/engines/billing/app/invoice.rb", "class Invoice; end\n");
+
+    let rc = build_run_config(td.path(), ".github/CODEOWNERS");
+
+    let gv = runner::generate_and_validate(&rc, vec![], false);
+    assert!(gv.io_errors.is_empty(), "io: {:?}", gv.io_errors);
+    assert!(gv.validation_errors.is_empty(), "val: {:?}", gv.validation_errors);
+    let content = std::fs::read_to_string(td.path().join(".github/CODEOWNERS")).unwrap();
+    assert!(content.contains("/lib/engines/billing/**/** @Foo"));
+
+    let file_owner = runner::file_owner_for_file(&rc, "lib/engines/billing/app/invoice.rb")
+        .unwrap()
+        .unwrap();
+    assert_eq!(file_owner.team.name, "Foo");
+}
+
+#[test]
+fn test_max_depth_excludes_deep_files_from_generation() {
+    let td = tempfile::tempdir().unwrap();
+    const CODE_OWNERSHIP_YML: &str = r#"---
+owned_globs:
+  - "app/**/*.rb"
+team_file_glob:
+  - config/teams/**/*.yml
+"#;
+    write_file(td.path(), "config/code_ownership.yml", CODE_OWNERSHIP_YML);
+    write_team(td.path(), "Foo", "");
+    write_file(td.path(), "app/shallow.rb", "# @team Foo\nclass Shallow; end\n");
+    write_file(td.path(), "app/a/b/c/deep.rb", "# @team Foo\nclass Deep; end\n");
+
+    let rc = RunConfig {
+        max_depth: Some(3),
+        ..build_run_config(td.path(), ".github/CODEOWNERS")
+    };
+
+    let gv = runner::generate_and_validate(&rc, vec![], false);
+    assert!(gv.io_errors.is_empty(), "io: {:?}", gv.io_errors);
+    assert!(gv.validation_errors.is_empty(), "val: {:?}", gv.validation_errors);
+    let content = std::fs::read_to_string(td.path().join(".github/CODEOWNERS")).unwrap();
+    assert!(content.contains("/app/shallow.rb @Foo"));
+    assert!(!content.contains("deep.rb"), "deep file should be excluded:\n{}", content);
+}
+
+#[test]
+fn test_case_insensitive_paths_matches_mixed_case_files() {
+    let td = tempfile::tempdir().unwrap();
+    const CODE_OWNERSHIP_YML: &str = r#"---
+owned_globs:
+  - "app/**/*.rb"
+team_file_glob:
+  - config/teams/**/*.yml
+"#;
+    write_file(td.path(), "config/code_ownership.yml", CODE_OWNERSHIP_YML);
+    write_team(td.path(), "Foo", "owned_globs:\n  - \"app/services/**/*\"\n");
+    write_file(td.path(), "App/Models/x.rb", "# @team Foo\nclass X; end\n");
+    write_file(td.path(), "App/Services/y.rb", "class Y; end\n");
+
+    let rc = build_run_config(td.path(), ".github/CODEOWNERS");
+
+    for file in ["App/Models/x.rb", "App/Services/y.rb"] {
+        assert!(
+            runner::file_owner_for_file(&rc, file).unwrap().is_none(),
+            "{} should be unowned",
+            file
+        );
+    }
+    let generate = runner::generate(&rc, false);
+    assert!(generate.io_errors.is_empty(), "io: {:?}", generate.io_errors);
+    assert!(
+        !std::fs::read_to_string(td.path().join(".github/CODEOWNERS"))
+            .unwrap()
+            .contains("App/")
+    );
+
+    write_file(
+        td.path(),
+        "config/code_ownership.yml",
+        format!("{}case_insensitive_paths: true\n", CODE_OWNERSHIP_YML),
+    );
+
+    for file in ["App/Models/x.rb", "App/Services/y.rb"] {
+        let file_owner = runner::file_owner_for_file(&rc, file).unwrap().unwrap();
+        assert_eq!(file_owner.team.name, "Foo");
+    }
+    let generate = runner::generate(&rc, false);
+    assert!(generate.io_errors.is_empty(), "io: {:?}", generate.io_errors);
+    let content = std::fs::read_to_string(td.path().join(".github/CODEOWNERS")).unwrap();
+    assert!(content.contains("/App/Models/x.rb @Foo"), "{}", content);
+    let validate = runner::validate(&rc, vec![]);
+    assert!(validate.io_errors.is_empty(), "io: {:?}", validate.io_errors);
+    assert!(validate.validation_errors.is_empty(), "val: {:?}", validate.validation_errors);
+}
+
+#[test]
+fn test_explain_unowned() {
+    let project_root = Path::new("tests/fixtures/invalid_project");
+    let run_config = RunConfig {
+        codeowners_file_path: None,
+        ..build_run_config(project_root, ".github/CODEOWNERS")
+    };
+
+    let reasons = runner::explain_unowned(&run_config, "ruby/app/unowned.rb").unwrap();
+    assert_eq!(
+        reasons,
+        vec![
+            "no annotation found",
+            "directory has no .codeowner up to the project root",
+            "no package.yml or package.json ancestor",
+            "not matched by any team's owned_globs",
+        ]
+    );
+    assert!(
+        runner::explain_unowned(&run_config, "ruby/app/models/payroll.rb")
+            .unwrap()
+            .is_empty()
+    );
+}
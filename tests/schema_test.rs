@@ -0,0 +1,33 @@
+use assert_cmd::prelude::*;
+use std::{error::Error, process::Command};
+
+#[test]
+fn test_schema_for_file_describes_for_file_json() -> Result<(), Box<dyn Error>> {
+    let output = Command::cargo_bin("codeowners")?
+        .arg("--project-root")
+        .arg("tests/fixtures/valid_project")
+        .arg("schema")
+        .arg("for-file")
+        .output()?;
+    assert!(output.status.success());
+    let schema: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(schema["$defs"]["owner"]["properties"]["schema_version"]["const"], 1);
+
+    let output = Command::cargo_bin("codeowners")?
+        .arg("--project-root")
+        .arg("tests/fixtures/valid_project")
+        .arg("--no-cache")
+        .arg("for-file")
+        .arg("ruby/app/models/payroll.rb")
+        .arg("--json")
+        .output()?;
+    assert!(output.status.success());
+    let result: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(result["schema_version"], 1);
+
+    let required = schema["$defs"]["owner"]["required"].as_array().unwrap();
+    for field in required {
+        assert!(result.get(field.as_str().unwrap()).is_some(), "missing {}", field);
+    }
+    Ok(())
+}
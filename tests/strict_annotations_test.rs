@@ -0,0 +1,52 @@
+use std::path::Path;
+
+use codeowners::runner::{self, RunConfig};
+
+mod common;
+use common::{assert_no_run_errors, build_run_config, write_file, write_team};
+
+fn build_project(temp_dir: &Path, strict_annotations: bool) -> RunConfig {
+    let code_ownership_yml = format!(
+        "---\nowned_globs:\n  - \"app/**/*.rb\"\nteam_file_glob:\n  - config/teams/**/*.yml\nstrict_annotations: {}\n",
+        strict_annotations
+    );
+    write_file(temp_dir, "config/code_ownership.yml", &code_ownership_yml);
+    for name in ["Foo", "Bar"] {
+        write_team(temp_dir, name, "");
+    }
+    write_file(
+        temp_dir,
+        "app/report.rb",
+        "# @team Foo\n# typed: true\n// @team Bar\nclass Report; end\n",
+    );
+    write_file(temp_dir, "app/widget.rb", "# @team Foo\n# @team Foo\nclass Widget; end\n");
+
+    build_run_config(temp_dir, ".github/CODEOWNERS")
+}
+
+#[test]
+fn test_conflicting_annotations_first_wins_by_default() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let run_config = build_project(temp_dir.path(), false);
+
+    assert_no_run_errors(&runner::generate_and_validate(&run_config, vec![], false));
+    let codeowners = std::fs::read_to_string(temp_dir.path().join(".github/CODEOWNERS")).unwrap();
+    assert!(codeowners.contains("/app/report.rb @Foo"));
+}
+
+#[test]
+fn test_conflicting_annotations_fail_validation_in_strict_mode() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let run_config = build_project(temp_dir.path(), true);
+
+    let result = runner::generate_and_validate(&run_config, vec![], false);
+    assert_eq!(result.validation_errors.len(), 1, "{:?}", result.validation_errors);
+    let error = &result.validation_errors[0];
+    assert!(error.contains("Found conflicting annotations in file"), "{}", error);
+    assert!(
+        error.contains("- app/report.rb is annotated with multiple teams - 'Foo', 'Bar'"),
+        "{}",
+        error
+    );
+    assert!(!error.contains("app/widget.rb"), "{}", error);
+}
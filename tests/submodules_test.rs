@@ -0,0 +1,76 @@
+use std::path::Path;
+
+use codeowners::runner::{self, RunConfig};
+
+mod common;
+use common::{assert_no_run_errors, build_run_config, write_file, write_team};
+
+fn build_project(temp_dir: &Path, extra_config: &str) -> RunConfig {
+    write_file(
+        temp_dir,
+        "config/code_ownership.yml",
+        format!(
+            "---\nowned_globs:\n  - \"**/*.rb\"\nteam_file_glob:\n  - config/teams/**/*.yml\n{}",
+            extra_config
+        ),
+    );
+    write_team(temp_dir, "Payments", "owned_globs:\n  - \"app/**\"\n");
+    write_file(temp_dir, "app/charge.rb", "class Charge; end\n");
+    // A stubbed submodule working tree: declared in .gitmodules, with a `.git` file pointing at the superproject
+    write_file(
+        temp_dir,
+        ".gitmodules",
+        "[submodule \"vendor/payments-sdk\"]\n\tpath = vendor/payments-sdk\n\turl = https://example.com/payments-sdk.git\n",
+    );
+    write_file(
+        temp_dir,
+        "vendor/payments-sdk/.git",
+        "gitdir: ../../.git/modules/vendor/payments-sdk\n",
+    );
+    write_file(
+        temp_dir,
+        "vendor/payments-sdk/lib/client.rb",
+        "# @team Payments\nclass Client; end\n",
+    );
+
+    build_run_config(temp_dir, ".github/CODEOWNERS")
+}
+
+#[test]
+fn test_submodule_files_are_excluded() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let run_config = build_project(temp_dir.path(), "");
+
+    assert_no_run_errors(&runner::generate_and_validate(&run_config, vec![], false));
+    let codeowners = std::fs::read_to_string(temp_dir.path().join(".github/CODEOWNERS")).unwrap();
+    assert!(codeowners.contains("/app/** @Payments"), "{}", codeowners);
+    assert!(!codeowners.contains("payments-sdk"), "{}", codeowners);
+
+    assert!(
+        runner::owners_for_file(&run_config, "vendor/payments-sdk/lib/client.rb")
+            .unwrap()
+            .is_empty()
+    );
+    let reasons = runner::explain_unowned(&run_config, "vendor/payments-sdk/lib/client.rb").unwrap();
+    assert_eq!(
+        reasons[0],
+        "inside git submodule `vendor/payments-sdk`; set `include_submodules` to own its files"
+    );
+}
+
+#[test]
+fn test_include_submodules() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let run_config = build_project(temp_dir.path(), "include_submodules: true\n");
+
+    assert_no_run_errors(&runner::generate_and_validate(&run_config, vec![], false));
+    let codeowners = std::fs::read_to_string(temp_dir.path().join(".github/CODEOWNERS")).unwrap();
+    assert!(
+        codeowners.contains("/vendor/payments-sdk/lib/client.rb @Payments"),
+        "{}",
+        codeowners
+    );
+
+    let owners = runner::owners_for_file(&run_config, "vendor/payments-sdk/lib/client.rb").unwrap();
+    assert_eq!(owners[0].team.name, "Payments");
+}
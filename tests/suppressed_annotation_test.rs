@@ -0,0 +1,50 @@
+use std::path::Path;
+
+use codeowners::runner::{self, RunConfig};
+use tempfile::TempDir;
+
+mod common;
+use common::{assert_no_run_errors, build_run_config, git_add_all_files, setup_fixture_repo};
+
+fn build_project() -> (TempDir, RunConfig) {
+    let temp_dir = setup_fixture_repo(Path::new("tests/fixtures/suppressed_annotation_project"));
+    git_add_all_files(temp_dir.path());
+    let run_config = build_run_config(temp_dir.path(), ".github/CODEOWNERS");
+    (temp_dir, run_config)
+}
+
+#[test]
+fn test_validate_warns_about_annotations_under_unowned_globs() {
+    let (temp_dir, run_config) = build_project();
+
+    let result = runner::generate_and_validate(&run_config, vec![], false);
+    assert_no_run_errors(&result);
+    assert_eq!(
+        result.info_messages,
+        vec![
+            "Warning: these annotations are ignored because their files match `unowned_globs`:\n- app/generated/schema.rb (@team Payments)"
+        ]
+    );
+
+    // The annotation is still ignored for ownership
+    let codeowners = std::fs::read_to_string(temp_dir.path().join(".github/CODEOWNERS")).unwrap();
+    assert!(!codeowners.contains("schema.rb"), "{}", codeowners);
+}
+
+#[test]
+fn test_validate_json_lists_warnings() {
+    let (_temp_dir, run_config) = build_project();
+    assert_no_run_errors(&runner::generate(&run_config, false));
+
+    let result = runner::validate_json(&run_config);
+    assert_no_run_errors(&result);
+    let json: serde_json::Value = serde_json::from_str(&result.info_messages[0]).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({
+            "validation_errors": [],
+            "errors": [],
+            "warnings": [{"path": "app/generated/schema.rb", "team": "Payments"}],
+        })
+    );
+}
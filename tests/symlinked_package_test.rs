@@ -0,0 +1,67 @@
+#![cfg(unix)]
+
+use std::error::Error;
+use std::path::Path;
+use std::process::Command;
+
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+
+mod common;
+use common::{git_add_all_files, init_git_repo, write_file};
+
+// `packs/payroll/package.yml` is a symlink to a manifest shared by several packs
+fn build_project(project_root: &Path) {
+    write_file(
+        project_root,
+        "config/code_ownership.yml",
+        "---\nowned_globs:\n  - \"{packs,shared}/**/*.rb\"\nruby_package_paths:\n  - packs/*\n",
+    );
+    write_file(
+        project_root,
+        "config/teams/payroll.yml",
+        "name: Payroll\ngithub:\n  team: \"@PayrollTeam\"\n  members:\n    - user\n",
+    );
+    write_file(project_root, "shared/package.yml", "---\nowner: Payroll\n");
+    write_file(project_root, "packs/payroll/app/paycheck.rb", "class Paycheck; end\n");
+    std::os::unix::fs::symlink("../../shared/package.yml", project_root.join("packs/payroll/package.yml")).unwrap();
+
+    init_git_repo(project_root);
+    git_add_all_files(project_root);
+}
+
+fn codeowners(project_root: &Path) -> Result<Command, Box<dyn Error>> {
+    let mut cmd = Command::cargo_bin("codeowners")?;
+    cmd.arg("--project-root").arg(project_root).arg("--no-cache");
+    Ok(cmd)
+}
+
+#[test]
+fn test_symlinked_package_yml_reports_its_target() -> Result<(), Box<dyn Error>> {
+    let temp_dir = tempfile::tempdir()?;
+    build_project(temp_dir.path());
+
+    codeowners(temp_dir.path())?
+        .arg("for-file")
+        .arg("packs/payroll/app/paycheck.rb")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Team: Payroll"))
+        .stdout(predicate::str::contains(
+            "Owner defined in `shared/package.yml` with implicitly owned glob: `packs/payroll/**/**`",
+        ));
+
+    codeowners(temp_dir.path())?.arg("generate").assert().success();
+    let codeowners_file = std::fs::read_to_string(temp_dir.path().join(".github/CODEOWNERS"))?;
+    assert!(codeowners_file.contains("/packs/payroll/**/** @PayrollTeam"), "{}", codeowners_file);
+
+    codeowners(temp_dir.path())?
+        .arg("for-file")
+        .arg("--trace")
+        .arg("packs/payroll/app/paycheck.rb")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("shared/package.yml"));
+
+    Ok(())
+}
@@ -0,0 +1,51 @@
+use std::path::Path;
+
+use codeowners::runner::{self, RunConfig};
+
+mod common;
+use common::{assert_no_run_errors, build_run_config, git_add_all_files, init_git_repo, write_file};
+
+fn write_team_file(project_root: &Path, github_team: &str) {
+    write_file(
+        project_root,
+        "config/teams/payments.yml",
+        format!(
+            "name: Payments\ngithub:\n  team: \"{}\"\n  members:\n    - user\nowned_globs:\n  - \"app/payments/**/*\"\n",
+            github_team
+        ),
+    );
+}
+
+fn build_project(project_root: &Path) -> RunConfig {
+    write_file(
+        project_root,
+        "config/code_ownership.yml",
+        "---\nowned_globs:\n  - \"app/**/*.rb\"\n",
+    );
+    write_team_file(project_root, "@Payments");
+    write_file(project_root, "app/payments/charge.rb", "class Charge; end\n");
+
+    init_git_repo(project_root);
+    git_add_all_files(project_root);
+
+    build_run_config(project_root, ".github/CODEOWNERS")
+}
+
+fn github_team_from_codeowners(run_config: &RunConfig) -> String {
+    let teams = runner::teams_for_files_from_codeowners(run_config, &["app/payments/charge.rb".to_string()]).unwrap();
+    teams["app/payments/charge.rb"].as_ref().unwrap().github_team.clone()
+}
+
+#[test]
+fn test_sequential_runs_see_edited_team_files() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let run_config = build_project(temp_dir.path());
+
+    assert_no_run_errors(&runner::generate(&run_config, false));
+    assert_eq!(github_team_from_codeowners(&run_config), "@Payments");
+
+    // Same project root and team file globs, so a cache keyed on them would hand back the old team
+    write_team_file(temp_dir.path(), "@PaymentsPlatform");
+    assert_no_run_errors(&runner::generate(&run_config, false));
+    assert_eq!(github_team_from_codeowners(&run_config), "@PaymentsPlatform");
+}
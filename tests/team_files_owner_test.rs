@@ -0,0 +1,59 @@
+use std::path::Path;
+
+use codeowners::runner::{self, RunConfig};
+
+mod common;
+use common::{assert_no_run_errors, build_run_config, write_file, write_team};
+
+fn build_project(temp_dir: &Path, team_files_owner: &str) -> RunConfig {
+    write_file(
+        temp_dir,
+        "config/code_ownership.yml",
+        format!(
+            "---\nowned_globs:\n  - \"**/*.{{rb,yml}}\"\nteam_file_glob:\n  - config/teams/**/*.yml\nunowned_globs:\n  - config/code_ownership.yml\nteam_files_owner: {}\n",
+            team_files_owner
+        ),
+    );
+    write_team(temp_dir, "Infra", "");
+    write_team(temp_dir, "Payments", "");
+    write_file(temp_dir, "app/models/charge.rb", "# @team Payments\nclass Charge; end\n");
+
+    build_run_config(temp_dir, ".github/CODEOWNERS")
+}
+
+fn team_for(run_config: &RunConfig, file_path: &str) -> Vec<String> {
+    runner::owners_for_file(run_config, file_path)
+        .unwrap()
+        .into_iter()
+        .map(|owner| owner.team.name)
+        .collect()
+}
+
+#[test]
+fn test_team_files_owner_owns_every_team_file() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let run_config = build_project(temp_dir.path(), "Infra");
+
+    assert_no_run_errors(&runner::generate_and_validate(&run_config, vec![], false));
+    let codeowners = std::fs::read_to_string(temp_dir.path().join(".github/CODEOWNERS")).unwrap();
+    assert!(codeowners.contains("/config/teams/payments.yml @Infra"), "{}", codeowners);
+    assert!(codeowners.contains("/config/teams/infra.yml @Infra"), "{}", codeowners);
+    assert!(!codeowners.contains("/config/teams/payments.yml @Payments"), "{}", codeowners);
+
+    assert_eq!(team_for(&run_config, "config/teams/payments.yml"), vec!["Infra"]);
+    assert_eq!(team_for(&run_config, "app/models/charge.rb"), vec!["Payments"]);
+}
+
+#[test]
+fn test_team_files_owner_must_be_a_known_team() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let run_config = build_project(temp_dir.path(), "Nobody");
+
+    let result = runner::validate(&run_config, vec![]);
+    let errors = result.validation_errors.join("\n");
+    assert!(
+        errors.contains("team_files_owner is referencing an invalid team - 'Nobody'"),
+        "{}",
+        errors
+    );
+}
@@ -0,0 +1,46 @@
+use std::path::Path;
+
+use codeowners::runner::{self, RunConfig};
+use tempfile::TempDir;
+
+mod common;
+use common::{assert_no_run_errors, build_run_config, git_add_all_files, setup_fixture_repo};
+
+fn build_project() -> (TempDir, RunConfig) {
+    let temp_dir = setup_fixture_repo(Path::new("tests/fixtures/team_glob_braces_project"));
+    git_add_all_files(temp_dir.path());
+    let run_config = build_run_config(temp_dir.path(), ".github/CODEOWNERS");
+    (temp_dir, run_config)
+}
+
+fn team_for(run_config: &RunConfig, file_path: &str) -> Vec<String> {
+    runner::owners_for_file(run_config, file_path)
+        .unwrap()
+        .into_iter()
+        .map(|owner| owner.team.name)
+        .collect()
+}
+
+#[test]
+fn test_braced_team_glob_becomes_one_codeowners_line_per_alternative() {
+    let (temp_dir, run_config) = build_project();
+
+    assert_no_run_errors(&runner::generate(&run_config, false));
+    let codeowners = std::fs::read_to_string(temp_dir.path().join(".github/CODEOWNERS")).unwrap();
+    assert!(
+        codeowners.contains("# Team-specific owned globs\n/packs/bar/** @Payments\n/packs/foo/** @Payments\n"),
+        "{}",
+        codeowners
+    );
+    assert!(!codeowners.contains('{'), "{}", codeowners);
+}
+
+#[test]
+fn test_braced_team_glob_resolves_like_its_expansion() {
+    let (_temp_dir, run_config) = build_project();
+
+    assert_eq!(team_for(&run_config, "packs/foo/app/charge.rb"), vec!["Payments"]);
+    assert_eq!(team_for(&run_config, "packs/bar/app/refund.rb"), vec!["Payments"]);
+    assert!(team_for(&run_config, "packs/bar/legacy/old.rb").is_empty());
+    assert!(team_for(&run_config, "packs/baz/app/other.rb").is_empty());
+}
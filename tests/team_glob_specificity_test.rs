@@ -0,0 +1,111 @@
+use std::path::Path;
+
+use codeowners::runner::{self, RunConfig};
+
+mod common;
+use common::{build_run_config, write_file, write_team};
+
+fn build_project(temp_dir: &Path) -> RunConfig {
+    write_file(
+        temp_dir,
+        "config/code_ownership.yml",
+        "---\nowned_globs:\n  - \"packs/**/*.rb\"\nteam_file_glob:\n  - config/teams/**/*.yml\n",
+    );
+    let teams = [
+        ("Platform", "packs/**"),
+        ("Payroll", "packs/payroll/**"),
+        ("Billing", "packs/billing/**/*.rb"),
+        ("Clients", "packs/billing/lib/*.rb"),
+    ];
+    for (name, glob) in teams {
+        write_file(
+            temp_dir,
+            &format!("config/teams/{}.yml", name.to_lowercase()),
+            format!(
+                "name: {}\ngithub:\n  team: \"@{}\"\n  members:\n    - user\nowned_globs:\n  - \"{}\"\n",
+                name, name, glob
+            ),
+        );
+    }
+    for file in [
+        "packs/payroll/app/models/paycheck.rb",
+        "packs/billing/app/models/invoice.rb",
+        "packs/billing/lib/client.rb",
+        "packs/legacy/report.rb",
+    ] {
+        write_file(temp_dir, file, "class Thing; end\n");
+    }
+
+    build_run_config(temp_dir, ".github/CODEOWNERS")
+}
+
+#[test]
+fn test_more_specific_team_glob_wins() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let run_config = build_project(temp_dir.path());
+
+    let owner_of = |file_path: &str| {
+        let owners = runner::owners_for_file(&run_config, file_path).unwrap();
+        assert_eq!(owners.len(), 1, "{}: {:?}", file_path, owners);
+        owners[0].team.name.clone()
+    };
+    // Longer literal prefix
+    assert_eq!(owner_of("packs/payroll/app/models/paycheck.rb"), "Payroll");
+    assert_eq!(owner_of("packs/billing/app/models/invoice.rb"), "Billing");
+    // Same literal prefix, fewer wildcards
+    assert_eq!(owner_of("packs/billing/lib/client.rb"), "Clients");
+    assert_eq!(owner_of("packs/legacy/report.rb"), "Platform");
+}
+
+#[test]
+fn test_more_specific_team_glob_wins_in_validate() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let run_config = build_project(temp_dir.path());
+
+    let result = runner::generate_and_validate(&run_config, vec![], false);
+    assert!(result.validation_errors.is_empty(), "{:?}", result.validation_errors);
+    assert!(result.io_errors.is_empty(), "{:?}", result.io_errors);
+
+    let codeowners = std::fs::read_to_string(temp_dir.path().join(".github/CODEOWNERS")).unwrap();
+    let line_of = |line: &str| codeowners.find(line).unwrap_or_else(|| panic!("{} missing:\n{}", line, codeowners));
+    // CODEOWNERS applies the last matching line, so each broader glob has to come first
+    assert!(line_of("/packs/** @Platform") < line_of("/packs/payroll/** @Payroll"));
+    assert!(line_of("/packs/** @Platform") < line_of("/packs/billing/**/*.rb @Billing"));
+    assert!(line_of("/packs/billing/**/*.rb @Billing") < line_of("/packs/billing/lib/*.rb @Clients"));
+
+    let result = runner::validate(&run_config, vec![]);
+    assert!(result.validation_errors.is_empty(), "{:?}", result.validation_errors);
+    assert!(result.io_errors.is_empty(), "{:?}", result.io_errors);
+}
+
+#[test]
+fn test_equally_specific_team_globs_still_conflict() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let run_config = build_project(temp_dir.path());
+    write_team(temp_dir.path(), "Integrations", "owned_globs:\n  - \"packs/billing/lib/*b\"\n");
+
+    let result = runner::for_file(&run_config, "packs/billing/lib/client.rb", false, false);
+    assert!(
+        result
+            .validation_errors
+            .iter()
+            .any(|e| e.contains("file is owned by multiple teams")),
+        "{:?}",
+        result
+    );
+}
+
+#[test]
+fn test_trace_notes_overridden_team_globs() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let run_config = build_project(temp_dir.path());
+
+    let result = runner::for_file_trace(&run_config, "packs/payroll/app/models/paycheck.rb");
+    let trace = result.info_messages.join("\n");
+    assert!(
+        trace.contains("Team-specific owned globs: overridden by a more specific glob: `packs/**` (Platform)"),
+        "{}",
+        trace
+    );
+    assert!(trace.contains("Owner: Payroll"), "{}", trace);
+}
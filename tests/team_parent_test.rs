@@ -0,0 +1,83 @@
+use std::path::Path;
+
+use codeowners::runner::{self, RunConfig};
+
+mod common;
+use common::{assert_no_run_errors, build_run_config, write_file, write_team};
+
+fn build_project(temp_dir: &Path) -> RunConfig {
+    write_file(
+        temp_dir,
+        "config/code_ownership.yml",
+        "---\nowned_globs:\n  - \"{app,lib}/**/*.rb\"\nteam_file_glob:\n  - config/teams/**/*.yml\n",
+    );
+    write_team(temp_dir, "Platform", "subtracted_globs:\n  - \"app/infra/generated/**\"\n");
+    write_team(
+        temp_dir,
+        "Infra",
+        "parent: Platform\nowned_globs:\n  - \"app/infra/**\"\n  - \"lib/infra/**\"\n",
+    );
+    write_file(temp_dir, "app/infra/deploy.rb", "class Deploy; end\n");
+    write_file(temp_dir, "app/infra/generated/schema.rb", "class Schema; end\n");
+    write_file(temp_dir, "lib/infra/client.rb", "class Client; end\n");
+
+    build_run_config(temp_dir, ".github/CODEOWNERS")
+}
+
+#[test]
+fn test_child_team_inherits_parent_subtracted_globs() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let run_config = build_project(temp_dir.path());
+    write_file(
+        temp_dir.path(),
+        "app/infra/generated/schema.rb",
+        "# @team Platform\nclass Schema; end\n",
+    );
+
+    assert_no_run_errors(&runner::generate_and_validate(&run_config, vec![], false));
+    let codeowners = std::fs::read_to_string(temp_dir.path().join(".github/CODEOWNERS")).unwrap();
+    assert!(codeowners.contains("/app/infra/** @Infra"), "{}", codeowners);
+
+    // The subtracted glob inherited from Platform keeps Infra's glob off generated code
+    let owners = runner::owners_for_file(&run_config, "app/infra/generated/schema.rb").unwrap();
+    assert_eq!(owners.len(), 1);
+    assert_eq!(owners[0].team.name, "Platform");
+    let owners = runner::owners_for_file(&run_config, "app/infra/deploy.rb").unwrap();
+    assert_eq!(owners[0].team.name, "Infra");
+    assert!(!owners[0].team.subtracted_globs.is_empty());
+}
+
+#[test]
+fn test_child_team_wins_the_parent_owned_globs_it_inherits() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let run_config = build_project(temp_dir.path());
+    write_team(temp_dir.path(), "Platform", "owned_globs:\n  - \"lib/**\"\n");
+    write_file(temp_dir.path(), "lib/shared.rb", "class Shared; end\n");
+
+    assert_no_run_errors(&runner::generate_and_validate(&run_config, vec![], false));
+    let codeowners = std::fs::read_to_string(temp_dir.path().join(".github/CODEOWNERS")).unwrap();
+    assert!(codeowners.contains("/lib/** @Infra"), "{}", codeowners);
+    assert!(!codeowners.contains("/lib/** @Platform"), "{}", codeowners);
+    assert_no_run_errors(&runner::validate(&run_config, vec![]));
+
+    let owners = runner::owners_for_file(&run_config, "lib/shared.rb").unwrap();
+    assert_eq!(owners.len(), 1);
+    assert_eq!(owners[0].team.name, "Infra");
+    let owners = runner::owners_for_file(&run_config, "lib/infra/client.rb").unwrap();
+    assert_eq!(owners.len(), 1);
+    assert_eq!(owners[0].team.name, "Infra");
+}
+
+#[test]
+fn test_team_parent_cycle_is_an_error() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let run_config = build_project(temp_dir.path());
+    write_team(temp_dir.path(), "Platform", "parent: Infra\n");
+
+    let result = runner::generate(&run_config, false);
+    let errors = result.io_errors.join("\n");
+    assert!(errors.contains("Team inheritance cycle"), "{:?}", result);
+
+    let error = runner::owners_for_file(&run_config, "app/infra/deploy.rb").unwrap_err();
+    assert!(format!("{:?}", error).contains("Team inheritance cycle"), "{:?}", error);
+}
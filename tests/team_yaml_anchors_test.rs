@@ -0,0 +1,34 @@
+use std::path::Path;
+
+use codeowners::runner::{self, RunConfig};
+use tempfile::TempDir;
+
+mod common;
+use common::{assert_no_run_errors, build_run_config, git_add_all_files, setup_fixture_repo};
+
+fn build_project() -> (TempDir, RunConfig) {
+    let temp_dir = setup_fixture_repo(Path::new("tests/fixtures/team_yaml_anchors_project"));
+    git_add_all_files(temp_dir.path());
+    let run_config = build_run_config(temp_dir.path(), ".github/CODEOWNERS");
+    (temp_dir, run_config)
+}
+
+fn owner_of(run_config: &RunConfig, file: &str) -> String {
+    let result = runner::for_file(run_config, file, false, false);
+    assert_no_run_errors(&result);
+    result.info_messages.join("\n")
+}
+
+#[test]
+fn test_team_file_with_anchors_aliases_and_merge_keys() {
+    let (temp_dir, run_config) = build_project();
+
+    assert_no_run_errors(&runner::generate(&run_config, false));
+    let codeowners = std::fs::read_to_string(temp_dir.path().join(".github/CODEOWNERS")).unwrap();
+    assert!(codeowners.contains("/app/payroll/**/*.rb @PayrollTeam"), "{}", codeowners);
+    assert!(codeowners.contains("/app/tax/**/*.rb @PayrollTeam"), "{}", codeowners);
+
+    let output = owner_of(&run_config, "app/tax/form.rb");
+    assert!(output.starts_with("Team: Payroll\nGithub Team: @PayrollTeam"), "{}", output);
+    assert!(owner_of(&run_config, "app/tax/legacy/w2.rb").starts_with("Team: Unowned"));
+}
@@ -0,0 +1,66 @@
+use std::path::Path;
+
+use codeowners::runner::{self, RunConfig};
+
+mod common;
+use common::{assert_no_run_errors, build_run_config, git_add_all_files, init_git_repo, write_file, write_team};
+
+// A monorepo whose team files live at the root, outside the `services/billing` project
+fn build_monorepo(monorepo_root: &Path, teams_root: &str) -> RunConfig {
+    write_team(monorepo_root, "Payments", "owned_globs:\n  - \"app/payments/**/*\"\n");
+    write_team(monorepo_root, "Billing", "");
+
+    let service_root = monorepo_root.join("services/billing");
+    write_file(
+        &service_root,
+        "config/code_ownership.yml",
+        format!("---\nowned_globs:\n  - \"app/**/*.rb\"\nteams_root: {}\n", teams_root),
+    );
+    write_file(&service_root, "app/models/invoice.rb", "# @team Billing\nclass Invoice; end\n");
+    write_file(&service_root, "app/payments/charge.rb", "class Charge; end\n");
+
+    init_git_repo(monorepo_root);
+    git_add_all_files(monorepo_root);
+
+    build_run_config(&service_root, ".github/CODEOWNERS")
+}
+
+fn assert_resolves_shared_teams(run_config: &RunConfig) {
+    assert_no_run_errors(&runner::generate_and_validate(run_config, vec![], false));
+    let codeowners = std::fs::read_to_string(run_config.codeowners_file_path.as_ref().unwrap()).unwrap();
+    assert!(codeowners.contains("/app/models/invoice.rb @Billing"), "{}", codeowners);
+    assert!(codeowners.contains("/app/payments/**/* @Payments"), "{}", codeowners);
+    assert!(!codeowners.contains("config/teams"), "{}", codeowners);
+
+    let team = runner::team_for_file(run_config, "app/payments/charge.rb").unwrap().unwrap();
+    assert_eq!(team.name, "Payments");
+    let team = runner::team_for_file(run_config, "app/models/invoice.rb").unwrap().unwrap();
+    assert_eq!(team.name, "Billing");
+
+    let teams = runner::teams_for_files_from_codeowners(run_config, &["app/payments/charge.rb".to_string()]).unwrap();
+    assert_eq!(teams["app/payments/charge.rb"].as_ref().unwrap().name, "Payments");
+}
+
+#[test]
+fn test_teams_root_relative_to_monorepo_root() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let run_config = build_monorepo(temp_dir.path(), ".");
+    assert_resolves_shared_teams(&run_config);
+}
+
+#[test]
+fn test_teams_root_absolute() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let run_config = build_monorepo(temp_dir.path(), &temp_dir.path().display().to_string());
+    assert_resolves_shared_teams(&run_config);
+}
+
+#[test]
+fn test_without_teams_root_teams_are_not_found() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let run_config = build_monorepo(temp_dir.path(), "null");
+
+    let result = runner::validate(&run_config, vec![]);
+    assert!(!result.validation_errors.is_empty());
+    assert!(runner::team_for_file(&run_config, "app/payments/charge.rb").unwrap().is_none());
+}
@@ -0,0 +1,31 @@
+use std::path::Path;
+
+use codeowners::runner::{self, RunResult};
+
+mod common;
+use common::{build_run_config, git_add_all_files, setup_fixture_repo};
+
+fn generate_and_validate_with_threads(fixture: &str, threads: Option<usize>) -> (RunResult, String, RunResult) {
+    let temp_dir = setup_fixture_repo(&Path::new("tests/fixtures").join(fixture));
+    git_add_all_files(temp_dir.path());
+    let mut run_config = build_run_config(temp_dir.path(), ".github/CODEOWNERS");
+    run_config.threads = threads;
+
+    let result = runner::generate_and_validate(&run_config, vec![], false);
+    let codeowners = std::fs::read_to_string(temp_dir.path().join(".github/CODEOWNERS")).unwrap();
+    let for_team = runner::for_team(&run_config, "Payroll");
+    (result, codeowners, for_team)
+}
+
+#[test]
+fn test_single_threaded_run_matches_multi_threaded_run() {
+    for fixture in ["valid_project", "invalid_project"] {
+        let (single_result, single_codeowners, single_for_team) = generate_and_validate_with_threads(fixture, Some(1));
+        let (multi_result, multi_codeowners, multi_for_team) = generate_and_validate_with_threads(fixture, Some(4));
+
+        assert_eq!(single_codeowners, multi_codeowners, "{}", fixture);
+        assert_eq!(single_result.validation_errors, multi_result.validation_errors, "{}", fixture);
+        assert_eq!(single_result.io_errors, multi_result.io_errors, "{}", fixture);
+        assert_eq!(single_for_team.info_messages, multi_for_team.info_messages, "{}", fixture);
+    }
+}
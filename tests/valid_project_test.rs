@@ -1,12 +1,13 @@
 use assert_cmd::prelude::*;
 use indoc::indoc;
-use predicates::prelude::predicate;
+use predicates::prelude::{PredicateBooleanExt, predicate};
 use std::{error::Error, fs, path::Path, process::Command};
 
 mod common;
 
 use common::OutputStream;
 use common::run_codeowners;
+use common::setup_fixture_repo;
 
 #[test]
 fn test_validate() -> Result<(), Box<dyn Error>> {
@@ -37,6 +38,56 @@ fn test_generate() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+#[test]
+fn test_generate_with_exclude() -> Result<(), Box<dyn Error>> {
+    let temp_dir = tempfile::tempdir()?;
+    let codeowners_abs = temp_dir.path().join("CODEOWNERS");
+    let codeowners_str = codeowners_abs.to_str().unwrap();
+
+    run_codeowners(
+        "valid_project",
+        &[
+            "--codeowners-file-path",
+            codeowners_str,
+            "generate",
+            "--exclude",
+            "ruby/app/views/**",
+            "--exclude",
+            "javascript/packages/list/**",
+        ],
+        true,
+        OutputStream::Stdout,
+        predicate::eq(""),
+    )?;
+
+    let expected_codeowners: String = std::fs::read_to_string(Path::new("tests/fixtures/valid_project/.github/CODEOWNERS"))?
+        .lines()
+        .filter(|line| !line.starts_with("/ruby/app/views/") && !line.starts_with("/javascript/packages/list/"))
+        .map(|line| format!("{}\n", line))
+        .collect();
+    let actual_codeowners: String = std::fs::read_to_string(&codeowners_abs)?;
+
+    assert_eq!(expected_codeowners, actual_codeowners);
+
+    Ok(())
+}
+
+#[test]
+fn test_validate_with_exclude() -> Result<(), Box<dyn Error>> {
+    // The checked-in CODEOWNERS still owns the excluded files, so it's out of date for this run
+    run_codeowners(
+        "valid_project",
+        &["validate", "--exclude", "ruby/app/views/**"],
+        false,
+        OutputStream::Stdout,
+        predicate::str::contains("-/ruby/app/views/foos/edit.erb @PayrollTeam")
+            .and(predicate::str::contains("CODEOWNERS out of date"))
+            .and(predicate::str::contains("+/ruby/app/views").not()),
+    )?;
+
+    Ok(())
+}
+
 #[test]
 fn test_crosscheck_owners() -> Result<(), Box<dyn Error>> {
     run_codeowners(
@@ -46,6 +97,7 @@ fn test_crosscheck_owners() -> Result<(), Box<dyn Error>> {
         OutputStream::Stdout,
         predicate::eq(indoc! {"
             Success! All files match between CODEOWNERS and for-file command.
+            Checked 12 files: 12 matches, 0 mismatches. Winning sources: annotation=7, default=0, directory=2, extension=0, gem=2, glob=1, override=0, package=0, unowned=0, yml=0
         "}),
     )?;
 
@@ -71,6 +123,66 @@ fn test_for_file() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+#[test]
+fn test_for_file_normalizes_path() -> Result<(), Box<dyn Error>> {
+    for path in [
+        "./ruby/app/models/payroll.rb",
+        "ruby/app/models/../models/payroll.rb",
+        "ruby/app/models/payroll.rb/",
+    ] {
+        run_codeowners(
+            "valid_project",
+            &["for-file", path],
+            true,
+            OutputStream::Stdout,
+            predicate::eq(indoc! {"
+                Team: Payroll
+                Github Team: @PayrollTeam
+                Team YML: config/teams/payroll.yml
+                Description:
+                - Owner annotation at the top of the file
+            "}),
+        )?;
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_for_file_package_relative() -> Result<(), Box<dyn Error>> {
+    run_codeowners(
+        "valid_project",
+        &["for-file", "--package-relative", "javascript/packages/PayrollFlow", "index.tsx"],
+        true,
+        OutputStream::Stdout,
+        predicate::eq(indoc! {"
+            Team: Payroll
+            Github Team: @PayrollTeam
+            Team YML: config/teams/payroll.yml
+            Description:
+            - Owner annotation at the top of the file
+            - Owner defined in `javascript/packages/PayrollFlow/package.json` with implicitly owned glob: `javascript/packages/PayrollFlow/**/**`
+        "}),
+    )?;
+
+    run_codeowners(
+        "valid_project",
+        &[
+            "for-file",
+            "--package-relative",
+            "javascript/packages/PayrollFlow",
+            "--format",
+            "line",
+            "index.tsx",
+        ],
+        true,
+        OutputStream::Stdout,
+        predicate::str::starts_with("javascript/packages/PayrollFlow/index.tsx\tPayroll\t"),
+    )?;
+
+    Ok(())
+}
+
 #[test]
 fn test_for_file_json() -> Result<(), Box<dyn Error>> {
     run_codeowners(
@@ -85,7 +197,8 @@ fn test_for_file_json() -> Result<(), Box<dyn Error>> {
               "team_yml": "config/teams/payroll.yml",
               "description": [
                 "Owner annotation at the top of the file"
-              ]
+              ],
+              "schema_version": 1
             }
         "#}),
     )?;
@@ -137,7 +250,8 @@ fn test_for_file_full_path_json() -> Result<(), Box<dyn Error>> {
               "team_yml": "config/teams/payroll.yml",
               "description": [
                 "Owner annotation at the top of the file"
-              ]
+              ],
+              "schema_version": 1
             }
         "#}));
     Ok(())
@@ -178,6 +292,11 @@ fn test_fast_for_file_with_ignored_file() -> Result<(), Box<dyn Error>> {
             Github Team: Unowned
             Team YML: 
             Description:
+            - not matched by any owned_glob
+            - annotation for `Payments` only applies to files matched by owned_globs and not by unowned_globs
+            - directory has no .codeowner up to the project root
+            - no package.yml or package.json ancestor
+            - not matched by any team's owned_globs
         "}));
     Ok(())
 }
@@ -267,6 +386,161 @@ fn test_fast_for_file_same_team_multiple_ownerships() -> Result<(), Box<dyn Erro
     Ok(())
 }
 
+#[test]
+fn test_for_file_json_same_team_multiple_ownerships() -> Result<(), Box<dyn Error>> {
+    let output = Command::cargo_bin("codeowners")?
+        .arg("--project-root")
+        .arg("tests/fixtures/valid_project")
+        .arg("--no-cache")
+        .arg("for-file")
+        .arg("--json")
+        .arg("javascript/packages/PayrollFlow/index.tsx")
+        .output()?;
+    assert!(output.status.success());
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(
+        json["description"],
+        serde_json::json!([
+            "Owner annotation at the top of the file",
+            "Owner defined in `javascript/packages/PayrollFlow/package.json` with implicitly owned glob: `javascript/packages/PayrollFlow/**/**`"
+        ])
+    );
+    Ok(())
+}
+
+#[test]
+fn test_for_file_team_format() -> Result<(), Box<dyn Error>> {
+    run_codeowners(
+        "valid_project",
+        &["for-file", "--team-format", "plain", "ruby/app/models/payroll.rb"],
+        true,
+        OutputStream::Stdout,
+        predicate::eq(indoc! {"
+            Team: Payroll
+            Github Team: PayrollTeam
+            Team YML: config/teams/payroll.yml
+            Description:
+            - Owner annotation at the top of the file
+        "}),
+    )?;
+    run_codeowners(
+        "valid_project",
+        &["for-file", "--team-format", "at", "--format", "line", "ruby/app/models/payroll.rb"],
+        true,
+        OutputStream::Stdout,
+        predicate::eq("ruby/app/models/payroll.rb\tPayroll\t@PayrollTeam\tannotation\n"),
+    )?;
+    run_codeowners(
+        "valid_project",
+        &[
+            "for-file",
+            "--team-format",
+            "plain",
+            "--from-codeowners",
+            "--json",
+            "ruby/app/models/payroll.rb",
+        ],
+        true,
+        OutputStream::Stdout,
+        predicate::str::contains("\"github_team\": \"PayrollTeam\""),
+    )?;
+
+    Ok(())
+}
+
+#[test]
+fn test_for_file_team_name_from() -> Result<(), Box<dyn Error>> {
+    run_codeowners(
+        "valid_project",
+        &["for-file", "--team-name-from", "name", "ruby/app/models/payroll.rb"],
+        true,
+        OutputStream::Stdout,
+        predicate::str::starts_with("Team: Payroll\nGithub Team: @PayrollTeam\n"),
+    )?;
+    run_codeowners(
+        "valid_project",
+        &["for-file", "--team-name-from", "github", "ruby/app/models/payroll.rb"],
+        true,
+        OutputStream::Stdout,
+        predicate::str::starts_with("Team: @PayrollTeam\nGithub Team: @PayrollTeam\n"),
+    )?;
+    run_codeowners(
+        "valid_project",
+        &["for-file", "--team-name-from", "github", "--json", "ruby/app/models/payroll.rb"],
+        true,
+        OutputStream::Stdout,
+        predicate::str::contains("\"team_name\": \"@PayrollTeam\"").and(predicate::str::contains("\"github_team\": \"@PayrollTeam\"")),
+    )?;
+    run_codeowners(
+        "valid_project",
+        &[
+            "for-file",
+            "--team-name-from",
+            "github",
+            "--team-format",
+            "plain",
+            "--from-codeowners",
+            "--format",
+            "line",
+            "ruby/app/models/payroll.rb",
+        ],
+        true,
+        OutputStream::Stdout,
+        predicate::eq("ruby/app/models/payroll.rb\tPayrollTeam\tPayrollTeam\tcodeowners\n"),
+    )?;
+
+    Ok(())
+}
+
+#[test]
+fn test_for_file_absolute_paths() -> Result<(), Box<dyn Error>> {
+    run_codeowners(
+        "valid_project",
+        &["for-file", "ruby/app/models/payroll.rb"],
+        true,
+        OutputStream::Stdout,
+        predicate::str::contains("Team YML: config/teams/payroll.yml\n"),
+    )?;
+    run_codeowners(
+        "valid_project",
+        &["for-file", "--absolute-paths", "ruby/app/models/payroll.rb"],
+        true,
+        OutputStream::Stdout,
+        predicate::str::is_match(r"(?m)^Team YML: /\S+/config/teams/payroll\.yml$")?,
+    )?;
+    run_codeowners(
+        "valid_project",
+        &[
+            "for-file",
+            "--absolute-paths",
+            "--from-codeowners",
+            "--json",
+            "ruby/app/models/payroll.rb",
+        ],
+        true,
+        OutputStream::Stdout,
+        predicate::str::is_match(r#""team_yml": "/\S+/config/teams/payroll\.yml""#)?,
+    )?;
+    run_codeowners(
+        "valid_project",
+        &["for-file", "--absolute-paths", "--format", "line", "ruby/app/models/payroll.rb"],
+        true,
+        OutputStream::Stdout,
+        predicate::str::is_match(r"^/\S+/ruby/app/models/payroll\.rb\tPayroll\t@PayrollTeam\tannotation\n$")?,
+    )?;
+    // An unowned file has no team file to make absolute
+    run_codeowners(
+        "valid_project",
+        &["for-file", "--absolute-paths", "--json", "ruby/app/unowned.rb"],
+        true,
+        OutputStream::Stdout,
+        predicate::str::contains("\"team_yml\": \"\""),
+    )?;
+
+    Ok(())
+}
+
 #[test]
 fn test_for_file_with_2_ownerships() -> Result<(), Box<dyn Error>> {
     Command::cargo_bin("codeowners")?
@@ -289,6 +563,160 @@ fn test_for_file_with_2_ownerships() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+#[test]
+fn test_for_file_with_2_owning_teams_json() -> Result<(), Box<dyn Error>> {
+    let temp_dir = setup_fixture_repo(Path::new("tests/fixtures/valid_project"));
+    let project_root = temp_dir.path();
+    // The package.json still claims Payroll, so the annotation now introduces a second team
+    fs::write(
+        project_root.join("javascript/packages/PayrollFlow/index.tsx"),
+        "// @team Payments\n",
+    )?;
+
+    Command::cargo_bin("codeowners")?
+        .arg("--project-root")
+        .arg(project_root)
+        .arg("--no-cache")
+        .arg("for-file")
+        .arg("--json")
+        .arg("javascript/packages/PayrollFlow/index.tsx")
+        .assert()
+        .failure()
+        .stdout(predicate::eq(indoc! {r#"
+            {
+              "error": "multiple_owners",
+              "owners": [
+                {
+                  "team_name": "Payments",
+                  "github_team": "@PaymentsTeam",
+                  "team_yml": "config/teams/payments.yml",
+                  "description": [
+                    "Owner annotation at the top of the file"
+                  ],
+                  "schema_version": 1
+                },
+                {
+                  "team_name": "Payroll",
+                  "github_team": "@PayrollTeam",
+                  "team_yml": "config/teams/payroll.yml",
+                  "description": [
+                    "Owner defined in `javascript/packages/PayrollFlow/package.json` with implicitly owned glob: `javascript/packages/PayrollFlow/**/**`"
+                  ],
+                  "schema_version": 1
+                }
+              ]
+            }
+        "#}));
+
+    Ok(())
+}
+
+#[test]
+fn test_for_file_line_format() -> Result<(), Box<dyn Error>> {
+    run_codeowners(
+        "valid_project",
+        &["for-file", "--format", "line", "ruby/app/models/payroll.rb"],
+        true,
+        OutputStream::Stdout,
+        predicate::eq("ruby/app/models/payroll.rb\tPayroll\t@PayrollTeam\tannotation\n"),
+    )?;
+    run_codeowners(
+        "valid_project",
+        &["for-file", "--format", "line", "should_be_ignored/an_ignored_file.rb"],
+        true,
+        OutputStream::Stdout,
+        predicate::eq("should_be_ignored/an_ignored_file.rb\tUnowned\tUnowned\tunowned\n"),
+    )?;
+
+    Ok(())
+}
+
+#[test]
+fn test_for_file_line_format_with_2_owning_teams() -> Result<(), Box<dyn Error>> {
+    let temp_dir = setup_fixture_repo(Path::new("tests/fixtures/valid_project"));
+    let project_root = temp_dir.path();
+    fs::write(
+        project_root.join("javascript/packages/PayrollFlow/index.tsx"),
+        "// @team Payments\n",
+    )?;
+
+    Command::cargo_bin("codeowners")?
+        .arg("--project-root")
+        .arg(project_root)
+        .arg("--no-cache")
+        .arg("for-file")
+        .arg("--format")
+        .arg("line")
+        .arg("javascript/packages/PayrollFlow/index.tsx")
+        .assert()
+        .failure()
+        .stdout(predicate::eq(
+            "javascript/packages/PayrollFlow/index.tsx\tPayments,Payroll\t@PaymentsTeam,@PayrollTeam\tmultiple\n",
+        ));
+
+    Ok(())
+}
+
+#[test]
+fn test_for_file_on_package_directory() -> Result<(), Box<dyn Error>> {
+    run_codeowners(
+        "valid_project",
+        &["for-file", "javascript/packages/PayrollFlow", "--uniform"],
+        true,
+        OutputStream::Stdout,
+        predicate::eq(indoc! {"
+            Team: Payroll
+            Github Team: @PayrollTeam
+            Team YML: config/teams/payroll.yml
+            Description:
+            - Owner defined in `javascript/packages/PayrollFlow/package.json` with implicitly owned glob: `javascript/packages/PayrollFlow/**/**`
+            Files: uniform, all 1 owned by Payroll
+        "}),
+    )?;
+
+    Ok(())
+}
+
+#[test]
+fn test_for_file_on_mixed_directory() -> Result<(), Box<dyn Error>> {
+    run_codeowners(
+        "valid_project",
+        &["for-file", "ruby/app", "--uniform"],
+        true,
+        OutputStream::Stdout,
+        predicate::str::starts_with("Team: Unowned").and(predicate::str::ends_with(indoc! {"
+            Files: mixed
+            - Payments: 2
+            - Payroll: 5
+            - UX: 1
+        "})),
+    )?;
+
+    Ok(())
+}
+
+#[test]
+fn test_for_file_on_directory_rejects_file_only_flags() -> Result<(), Box<dyn Error>> {
+    for flags in [
+        &["--from-codeowners"][..],
+        &["--trace"],
+        &["--explain"],
+        &["--format", "line"],
+        &["--team-file", "config/teams/payroll.yml"],
+    ] {
+        let args: Vec<&str> = ["for-file", "ruby/app"].iter().chain(flags).copied().collect();
+        run_codeowners(
+            "valid_project",
+            &args,
+            false,
+            OutputStream::Stderr,
+            predicate::str::contains("--from-codeowners, --trace, --explain, --format line and --team-file only apply to files"),
+        )?;
+    }
+
+    Ok(())
+}
+
 #[test]
 fn test_for_team() -> Result<(), Box<dyn Error>> {
     run_codeowners(
@@ -330,6 +758,47 @@ fn test_for_team() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+#[test]
+fn test_for_team_with_source() -> Result<(), Box<dyn Error>> {
+    run_codeowners(
+        "valid_project",
+        &["for-team", "Payroll", "--with-source"],
+        true,
+        OutputStream::Stdout,
+        predicate::eq(indoc! {"
+            # Code Ownership Report for `Payroll` Team
+
+            ## Annotations at the top of file
+            /javascript/packages/PayrollFlow/index.tsx (Owner annotation at the top of the file)
+            /ruby/app/models/payroll.rb (Owner annotation at the top of the file)
+            /ruby/app/views/foos/edit.erb (Owner annotation at the top of the file)
+            /ruby/app/views/foos/new.html.erb (Owner annotation at the top of the file)
+
+            ## Team-specific owned globs
+            This team owns nothing in this category.
+
+            ## Owner in .codeowner
+            /javascript/packages/items/**/** (Owner specified in `javascript/packages/items/.codeowner`)
+            /ruby/app/payments/foo/**/** (Owner specified in `ruby/app/payments/foo/.codeowner`)
+            /ruby/app/payroll/**/** (Owner specified in `ruby/app/payroll/.codeowner`)
+
+            ## Owner metadata key in package.yml
+            /ruby/packages/payroll_flow/**/** (Owner defined in `ruby/packages/payroll_flow/package.yml` with implicitly owned glob: `ruby/packages/payroll_flow/**/**`)
+
+            ## Owner metadata key in package.json
+            /javascript/packages/PayrollFlow/**/** (Owner defined in `javascript/packages/PayrollFlow/package.json` with implicitly owned glob: `javascript/packages/PayrollFlow/**/**`)
+
+            ## Team YML ownership
+            /config/teams/payroll.yml (Teams own their configuration files)
+
+            ## Team owned gems
+            /gems/payroll_calculator/**/** (Owner specified in Team YML's `owned_gems`)
+        "}),
+    )?;
+
+    Ok(())
+}
+
 #[test]
 fn test_for_missing_team() -> Result<(), Box<dyn Error>> {
     Command::cargo_bin("codeowners")?
@@ -346,3 +815,15 @@ fn test_for_missing_team() -> Result<(), Box<dyn Error>> {
 
     Ok(())
 }
+
+#[test]
+fn test_validate_sarif_without_errors() -> Result<(), Box<dyn Error>> {
+    run_codeowners(
+        "valid_project",
+        &["validate", "--format", "sarif"],
+        true,
+        OutputStream::Stdout,
+        predicate::str::contains("\"version\": \"2.1.0\"").and(predicate::str::contains("\"results\": []")),
+    )?;
+    Ok(())
+}
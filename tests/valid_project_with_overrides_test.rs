@@ -36,3 +36,27 @@ fn test_crosscheck_owners() -> Result<(), Box<dyn Error>> {
 
     Ok(())
 }
+
+#[test]
+fn test_for_file_trace_package_owned() -> Result<(), Box<dyn Error>> {
+    run_codeowners(
+        "valid_project_with_overrides",
+        &["for-file", "--trace", "packs/games/app/services/stats.rb"],
+        true,
+        OutputStream::Stdout,
+        predicate::eq(indoc! {"
+            Mapper evaluation order for packs/games/app/services/stats.rb:
+            1. Annotations at the top of file: no claim
+            2. Owner in .codeowner: no claim
+            3. Owner metadata key in package.yml, package.json or Cargo.toml: claimed by Rockies (priority 3) - Owner defined in `packs/games/package.yml` with implicitly owned glob: `packs/games/**/**`
+            4. Team-specific owned globs: no claim
+            5. Team owned gems: no claim
+            6. Team YML ownership: no claim
+            7. Owner by file extension: no claim
+
+            Owner: Rockies
+            Reason: highest priority claim (priority 3) - Owner defined in `packs/games/package.yml` with implicitly owned glob: `packs/games/**/**`
+        "}),
+    )?;
+    Ok(())
+}
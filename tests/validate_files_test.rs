@@ -53,6 +53,33 @@ fn test_validate_with_no_files() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+#[test]
+fn test_validate_with_owned_files_detects_stale_codeowners() -> Result<(), Box<dyn Error>> {
+    let fixture_root = std::path::Path::new("tests/fixtures/valid_project");
+    let temp_dir = setup_fixture_repo(fixture_root);
+    let project_root = temp_dir.path();
+    git_add_all_files(project_root);
+
+    // A hand-edited line leaves the file's own owner intact but the CODEOWNERS file stale
+    let codeowners_path = project_root.join(".github/CODEOWNERS");
+    let mut codeowners = std::fs::read_to_string(&codeowners_path)?;
+    codeowners.push_str("/ruby/app/hand_edited.rb @PayrollTeam\n");
+    std::fs::write(&codeowners_path, codeowners)?;
+
+    Command::cargo_bin("codeowners")?
+        .arg("--project-root")
+        .arg(project_root)
+        .arg("--no-cache")
+        .arg("validate")
+        .arg("ruby/app/models/payroll.rb")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("CODEOWNERS out of date"))
+        .stdout(predicate::str::contains("-/ruby/app/hand_edited.rb @PayrollTeam"));
+
+    Ok(())
+}
+
 #[test]
 fn test_generate_and_validate_with_owned_files() -> Result<(), Box<dyn Error>> {
     let fixture_root = std::path::Path::new("tests/fixtures/valid_project");
@@ -123,6 +150,51 @@ fn test_validate_with_absolute_path() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+#[test]
+fn test_validate_with_nul_separated_paths_from_stdin() -> Result<(), Box<dyn Error>> {
+    let fixture_root = std::path::Path::new("tests/fixtures/valid_project");
+    let temp_dir = setup_fixture_repo(fixture_root);
+    let project_root = temp_dir.path();
+    git_add_all_files(project_root);
+
+    // A long changeset, as `git diff -z --name-only` prints it
+    let mut stdin = "ruby/app/models/payroll.rb\0ruby/app/models/bank_account.rb\0".repeat(5_000);
+    stdin.push_str("ruby/app/models/it's unowned.rb\0");
+    assert_cmd::Command::cargo_bin("codeowners")?
+        .arg("--project-root")
+        .arg(project_root)
+        .arg("--no-cache")
+        .arg("validate")
+        .arg("--stdin-paths0")
+        .write_stdin(stdin)
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("ruby/app/models/it's unowned.rb"));
+
+    Ok(())
+}
+
+#[test]
+fn test_validate_with_newline_separated_paths_from_stdin() -> Result<(), Box<dyn Error>> {
+    let fixture_root = std::path::Path::new("tests/fixtures/valid_project");
+    let temp_dir = setup_fixture_repo(fixture_root);
+    let project_root = temp_dir.path();
+    git_add_all_files(project_root);
+
+    assert_cmd::Command::cargo_bin("codeowners")?
+        .arg("--project-root")
+        .arg(project_root)
+        .arg("--no-cache")
+        .arg("validate")
+        .arg("--stdin-paths")
+        .write_stdin("ruby/app/models/payroll.rb\r\nruby/app/models/bank_account.rb\n\n")
+        .assert()
+        .success()
+        .stdout(predicate::eq(""));
+
+    Ok(())
+}
+
 #[test]
 fn test_validate_only_checks_codeowners_file() -> Result<(), Box<dyn Error>> {
     // This test demonstrates that `validate` with files only checks the CODEOWNERS file
@@ -376,3 +448,199 @@ fn test_validate_respects_unowned_globs() -> Result<(), Box<dyn Error>> {
 
     Ok(())
 }
+
+#[test]
+fn test_validate_with_json_file_list_from_stdin() -> Result<(), Box<dyn Error>> {
+    let fixture_root = std::path::Path::new("tests/fixtures/valid_project");
+    let temp_dir = setup_fixture_repo(fixture_root);
+    let project_root = temp_dir.path();
+    git_add_all_files(project_root);
+
+    // Paths that would need quoting on a command line are passed through as-is
+    assert_cmd::Command::cargo_bin("codeowners")?
+        .arg("--project-root")
+        .arg(project_root)
+        .arg("--no-cache")
+        .arg("validate")
+        .arg("--input-format")
+        .arg("json")
+        .write_stdin(r#"["ruby/app/models/payroll.rb", "ruby/app/unowned.rb", "ruby/app/models/it's unowned.rb"]"#)
+        .assert()
+        .failure()
+        .stdout(predicate::eq(
+            "Unowned files detected:\n  ruby/app/unowned.rb\n  ruby/app/models/it's unowned.rb\n",
+        ));
+
+    Ok(())
+}
+
+#[test]
+fn test_validate_with_json_file_list_from_input_file() -> Result<(), Box<dyn Error>> {
+    let fixture_root = std::path::Path::new("tests/fixtures/valid_project");
+    let temp_dir = setup_fixture_repo(fixture_root);
+    let project_root = temp_dir.path();
+    git_add_all_files(project_root);
+
+    let input_file = project_root.join("tmp/changed_files.json");
+    std::fs::create_dir_all(input_file.parent().unwrap())?;
+    std::fs::write(&input_file, r#"["ruby/app/models/payroll.rb", "ruby/app/models/bank_account.rb"]"#)?;
+
+    Command::cargo_bin("codeowners")?
+        .arg("--project-root")
+        .arg(project_root)
+        .arg("--no-cache")
+        .arg("validate")
+        .arg("--input-format")
+        .arg("json")
+        .arg("--input-file")
+        .arg(&input_file)
+        .assert()
+        .success()
+        .stdout(predicate::eq(""));
+
+    // An empty list has nothing to validate, unlike passing no files at all
+    std::fs::write(&input_file, "[]")?;
+    Command::cargo_bin("codeowners")?
+        .arg("--project-root")
+        .arg(project_root)
+        .arg("--no-cache")
+        .arg("validate")
+        .arg("--input-format")
+        .arg("json")
+        .arg("--input-file")
+        .arg(&input_file)
+        .assert()
+        .success();
+
+    std::fs::write(&input_file, r#"{"files": []}"#)?;
+    Command::cargo_bin("codeowners")?
+        .arg("--project-root")
+        .arg(project_root)
+        .arg("--no-cache")
+        .arg("validate")
+        .arg("--input-format")
+        .arg("json")
+        .arg("--input-file")
+        .arg(&input_file)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Expected a JSON array of file paths"));
+
+    Ok(())
+}
+
+#[test]
+fn test_validate_fail_fast_reports_first_unowned_file() -> Result<(), Box<dyn Error>> {
+    run_codeowners(
+        "valid_project",
+        &["validate", "ruby/app/unowned.rb", "ruby/app/also_unowned.rb"],
+        false,
+        OutputStream::Stdout,
+        predicate::str::contains("ruby/app/unowned.rb").and(predicate::str::contains("ruby/app/also_unowned.rb")),
+    )?;
+    run_codeowners(
+        "valid_project",
+        &["validate", "--fail-fast", "ruby/app/unowned.rb", "ruby/app/also_unowned.rb"],
+        false,
+        OutputStream::Stdout,
+        predicate::str::contains("ruby/app/unowned.rb").and(predicate::str::contains("ruby/app/also_unowned.rb").not()),
+    )?;
+
+    Ok(())
+}
+
+#[test]
+fn test_validate_fail_fast_stops_at_first_project_error() -> Result<(), Box<dyn Error>> {
+    let temp_dir = setup_fixture_repo(&std::path::Path::new("tests/fixtures").join("invalid_project"));
+    let project_root = temp_dir.path();
+    git_add_all_files(project_root);
+    // The audit log gets a line for every file whose owners were resolved
+    let validate = |args: &[&str], audit_log: &str| -> Result<(String, usize), Box<dyn Error>> {
+        let output = Command::cargo_bin("codeowners")?
+            .arg("--project-root")
+            .arg(project_root)
+            .arg("--no-cache")
+            .arg("validate")
+            .args(["--audit-log", audit_log])
+            .args(args)
+            .output()?;
+        assert!(!output.status.success());
+        let resolved = std::fs::read_to_string(project_root.join(audit_log))
+            .unwrap_or_default()
+            .lines()
+            .count();
+        Ok((String::from_utf8(output.stdout)?, resolved))
+    };
+
+    let (collected, collected_resolved) = validate(&["--collect-all"], "collected.ndjson")?;
+    assert!(collected.contains("Web3"));
+    assert!(collected.contains("multiple ways"));
+    assert!(collected_resolved > 0);
+
+    // The invalid team annotation is found before any file is resolved, so resolution is skipped entirely
+    let (failed_fast, failed_fast_resolved) = validate(&["--fail-fast"], "failed_fast.ndjson")?;
+    assert!(failed_fast.contains("Web3"));
+    assert!(!failed_fast.contains("multiple ways"));
+    assert_eq!(failed_fast_resolved, 0);
+
+    Ok(())
+}
+
+#[test]
+fn test_validate_team_fail_fast_stops_at_first_error() -> Result<(), Box<dyn Error>> {
+    let validate_team = |args: &[&str]| -> Result<String, Box<dyn Error>> {
+        let output = Command::cargo_bin("codeowners")?
+            .arg("--project-root")
+            .arg("tests/fixtures/invalid_project")
+            .arg("--no-cache")
+            .args(["validate", "--team", "Payments"])
+            .args(args)
+            .output()?;
+        assert!(!output.status.success());
+        Ok(String::from_utf8(output.stdout)?)
+    };
+
+    let multi_owned_files = ["gems/payroll_calculator/calculator.rb", "ruby/app/services/multi_owned.rb"];
+
+    let collected = validate_team(&[])?;
+    assert!(multi_owned_files.iter().all(|file| collected.contains(file)));
+    assert!(collected.contains("CODEOWNERS out of date"));
+
+    // Only one of the files is reported, and the CODEOWNERS check after the ownership check never runs
+    let failed_fast = validate_team(&["--fail-fast"])?;
+    assert_eq!(multi_owned_files.iter().filter(|file| failed_fast.contains(*file)).count(), 1);
+    assert!(!failed_fast.contains("CODEOWNERS out of date"));
+
+    Ok(())
+}
+
+#[test]
+fn test_validate_modes_are_exclusive() -> Result<(), Box<dyn Error>> {
+    for args in [
+        &["validate", "--fix", "--since-cache"][..],
+        &["validate", "--consistent-annotations", "--check-all-annotations"],
+        &["validate", "--fail-fast", "--since-cache"],
+        &["validate", "--fail-fast", "--collect-all"],
+        &["validate", "--json", "--format", "sarif"],
+        &["validate", "--fix", "--format", "json"],
+    ] {
+        Command::cargo_bin("codeowners")?
+            .args(args)
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("cannot be used with"));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_validate_yes_requires_fix() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("codeowners")?
+        .args(["validate", "--yes"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--fix"));
+
+    Ok(())
+}
@@ -0,0 +1,59 @@
+use std::path::Path;
+
+use codeowners::runner::{self, RunConfig};
+use tempfile::TempDir;
+
+mod common;
+use common::{build_run_config, git_add_all_files, setup_fixture_repo};
+
+fn build_project() -> (TempDir, RunConfig) {
+    let temp_dir = setup_fixture_repo(Path::new("tests/fixtures/validate_fix_project"));
+    git_add_all_files(temp_dir.path());
+    let run_config = build_run_config(temp_dir.path(), ".github/CODEOWNERS");
+    (temp_dir, run_config)
+}
+
+#[test]
+fn test_validate_fix_annotates_files_with_a_suggested_owner() {
+    let (temp_dir, run_config) = build_project();
+
+    let result = runner::validate_fix(&run_config, |_| true);
+    assert!(result.io_errors.is_empty(), "{:?}", result.io_errors);
+    assert_eq!(
+        std::fs::read_to_string(temp_dir.path().join("lib/billing/invoice.rb")).unwrap(),
+        "# @team Billing\nclass Invoice; end\n"
+    );
+    assert_eq!(
+        std::fs::read_to_string(temp_dir.path().join("lib/billing/cli.rb")).unwrap(),
+        "#!/usr/bin/env ruby\n# @team Billing\nputs Invoice.new\n"
+    );
+    assert!(
+        result
+            .info_messages
+            .contains(&"Annotated lib/billing/invoice.rb with `# @team Billing`".to_owned()),
+        "{:?}",
+        result.info_messages
+    );
+
+    // Files without a directory or package owner stay unowned and keep validation failing
+    assert_eq!(result.validation_errors, vec!["Unowned files detected:", "  script/orphan.rb"]);
+    assert_eq!(
+        std::fs::read_to_string(temp_dir.path().join("script/orphan.rb")).unwrap(),
+        "puts 1\n"
+    );
+}
+
+#[test]
+fn test_validate_fix_leaves_declined_files_untouched() {
+    let (temp_dir, run_config) = build_project();
+
+    let result = runner::validate_fix(&run_config, |suggestion| suggestion.path.ends_with("cli.rb"));
+    assert_eq!(
+        std::fs::read_to_string(temp_dir.path().join("lib/billing/invoice.rb")).unwrap(),
+        "class Invoice; end\n"
+    );
+    assert_eq!(
+        result.validation_errors,
+        vec!["Unowned files detected:", "  lib/billing/invoice.rb", "  script/orphan.rb"]
+    );
+}
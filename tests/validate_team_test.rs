@@ -0,0 +1,90 @@
+use std::path::Path;
+
+use codeowners::runner::{self, RunConfig};
+
+mod common;
+use common::{assert_no_run_errors, build_run_config, write_file, write_team};
+
+fn build_project(temp_dir: &Path) -> RunConfig {
+    const CODE_OWNERSHIP_YML: &str = r#"---
+owned_globs:
+  - "app/**/*.rb"
+team_file_glob:
+  - config/teams/**/*.yml
+"#;
+    write_file(temp_dir, "config/code_ownership.yml", CODE_OWNERSHIP_YML);
+    write_team(temp_dir, "Foo", "owned_globs:\n  - \"app/foo/**/*\"\n");
+    write_team(temp_dir, "Bar", "");
+    write_file(temp_dir, "app/foo/widget.rb", "class Widget; end\n");
+    write_file(temp_dir, "app/bar/.codeowner", "Bar\n");
+    write_file(temp_dir, "app/bar/report.rb", "class Report; end\n");
+
+    let run_config = build_run_config(temp_dir, ".github/CODEOWNERS");
+    assert_no_run_errors(&runner::generate(&run_config, false));
+
+    run_config
+}
+
+#[test]
+fn test_validate_team_ignores_unrelated_unowned_file() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let run_config = build_project(temp_dir.path());
+    write_file(temp_dir.path(), "app/misc/orphan.rb", "class Orphan; end\n");
+
+    let result = runner::validate(&run_config, vec![]);
+    assert!(
+        result.validation_errors.iter().any(|e| e.contains("app/misc/orphan.rb")),
+        "expected full validation to report the orphan: {:?}",
+        result.validation_errors
+    );
+
+    assert_no_run_errors(&runner::validate_team(&run_config, "Foo"));
+    assert_no_run_errors(&runner::validate_team(&run_config, "Bar"));
+}
+
+#[test]
+fn test_validate_team_reports_errors_in_team_directories() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let run_config = build_project(temp_dir.path());
+    write_file(temp_dir.path(), "app/bar/invalid.rb", "# @team Nope\nclass Invalid; end\n");
+
+    let result = runner::validate_team(&run_config, "Bar");
+    assert_eq!(result.validation_errors.len(), 1);
+    assert!(
+        result.validation_errors[0].contains("app/bar/invalid.rb is referencing an invalid team - 'Nope'"),
+        "{}",
+        result.validation_errors[0]
+    );
+
+    assert_no_run_errors(&runner::validate_team(&run_config, "Foo"));
+}
+
+#[test]
+fn test_validate_team_reports_multiple_owners_involving_team() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let run_config = build_project(temp_dir.path());
+    write_file(temp_dir.path(), "app/foo/widget.rb", "# @team Bar\nclass Widget; end\n");
+
+    for team in ["Foo", "Bar"] {
+        let result = runner::validate_team(&run_config, team);
+        assert!(
+            result
+                .validation_errors
+                .iter()
+                .any(|e| e.contains("Code ownership should only be defined for each file in one way") && e.contains("app/foo/widget.rb")),
+            "{}: {:?}",
+            team,
+            result.validation_errors
+        );
+    }
+}
+
+#[test]
+fn test_validate_team_unknown_team() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let run_config = build_project(temp_dir.path());
+
+    let result = runner::validate_team(&run_config, "Nope");
+    assert_eq!(result.validation_errors.len(), 1);
+    assert_eq!(result.validation_errors[0].trim(), "Team `Nope` not found");
+}
@@ -0,0 +1,83 @@
+use std::path::Path;
+
+use codeowners::runner::{self, RunConfig};
+
+mod common;
+use common::{assert_no_run_errors, build_run_config, write_file, write_team};
+
+fn build_project(temp_dir: &Path, payroll_owned_gems: &str) -> RunConfig {
+    const CODE_OWNERSHIP_YML: &str = r#"---
+owned_globs:
+  - "{app,gems}/**/*.rb"
+team_file_glob:
+  - config/teams/**/*.yml
+vendored_gems_path: gems
+"#;
+    write_file(temp_dir, "config/code_ownership.yml", CODE_OWNERSHIP_YML);
+    write_file(
+        temp_dir,
+        "config/teams/payroll.yml",
+        format!(
+            "name: Payroll\ngithub:\n  team: \"@Payroll\"\n  members:\n    - user\nruby:\n  owned_gems: {}\n",
+            payroll_owned_gems
+        ),
+    );
+    write_team(temp_dir, "Pets", "");
+    write_file(temp_dir, "gems/dog/.codeowner", "Pets\n");
+    write_file(temp_dir, "gems/dog/lib/dog.rb", "class Dog; end\n");
+
+    build_run_config(temp_dir, ".github/CODEOWNERS")
+}
+
+#[test]
+fn test_gem_declares_its_own_owner() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let run_config = build_project(temp_dir.path(), "[]");
+
+    assert_no_run_errors(&runner::generate_and_validate(&run_config, vec![], false));
+    let codeowners = std::fs::read_to_string(temp_dir.path().join(".github/CODEOWNERS")).unwrap();
+    assert!(codeowners.contains("/gems/dog/**/** @Pets"), "{}", codeowners);
+
+    let result = runner::for_file(&run_config, "gems/dog/lib/dog.rb", false, false);
+    assert_no_run_errors(&result);
+    let output = result.info_messages.join("\n");
+    assert!(output.starts_with("Team: Pets"), "{}", output);
+    assert!(output.contains("Owner declared inside the vendored gem `gems/dog`"), "{}", output);
+}
+
+#[test]
+fn test_owned_gems_take_precedence_over_gem_owner() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let run_config = build_project(temp_dir.path(), "[dog]");
+
+    assert_no_run_errors(&runner::generate_and_validate(&run_config, vec![], false));
+    let codeowners = std::fs::read_to_string(temp_dir.path().join(".github/CODEOWNERS")).unwrap();
+    assert!(codeowners.contains("/gems/dog/**/** @Payroll"), "{}", codeowners);
+    assert!(!codeowners.contains("/gems/dog/**/** @Pets"), "{}", codeowners);
+
+    let result = runner::for_file(&run_config, "gems/dog/lib/dog.rb", false, false);
+    let output = result.info_messages.join("\n");
+    assert!(output.starts_with("Team: Payroll"), "{}", output);
+}
+
+#[test]
+fn test_owned_gems_glob_matches_every_gem() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let run_config = build_project(temp_dir.path(), "[\"payroll_*\"]");
+    write_file(temp_dir.path(), "gems/payroll_tax/lib/tax.rb", "class Tax; end\n");
+    write_file(temp_dir.path(), "gems/payroll_checks/lib/check.rb", "class Check; end\n");
+
+    assert_no_run_errors(&runner::generate_and_validate(&run_config, vec![], false));
+    let codeowners = std::fs::read_to_string(temp_dir.path().join(".github/CODEOWNERS")).unwrap();
+    assert!(codeowners.contains("/gems/payroll_tax/**/** @Payroll"), "{}", codeowners);
+    assert!(codeowners.contains("/gems/payroll_checks/**/** @Payroll"), "{}", codeowners);
+    assert!(codeowners.contains("/gems/dog/**/** @Pets"), "{}", codeowners);
+
+    for file in ["gems/payroll_tax/lib/tax.rb", "gems/payroll_checks/lib/check.rb"] {
+        let result = runner::for_file(&run_config, file, false, false);
+        assert_no_run_errors(&result);
+        let output = result.info_messages.join("\n");
+        assert!(output.starts_with("Team: Payroll"), "{}", output);
+        assert!(output.contains("Owner specified in Team YML's `owned_gems`"), "{}", output);
+    }
+}